@@ -11,4 +11,10 @@ impl Config {
             api_secret: std::env::var("BINGX_API_SECRET").unwrap_or_default(),
         }
     }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
 } 
\ No newline at end of file
@@ -0,0 +1,471 @@
+use crate::exchange::clock::ServerClock;
+use crate::exchange::session::{self, Session, SessionRecorder, SessionReplayer};
+use crate::exchange::{
+    array_kline, de_f64, normalize_symbol, ApiError, to_venue_symbol, Exchange, ExchangeOrder, LimitOrder, OrderFill, OrderStatus, OrderUpdate,
+};
+use crate::logging::redact_url;
+use crate::strategy::{MarketDepth, MarketTicker};
+use crate::types::{Interval, Kline, MarketType, OrderSide, Venue};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::{Client, Method, Url};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::Path;
+use tracing::{debug, warn};
+
+const API_BASE_URL: &str = "https://fapi.binance.com";
+// 时间戳超出 recvWindow 时 Binance 返回的错误码
+const CODE_TIMESTAMP_ERROR: i32 = -1021;
+
+// 出错时的响应体 {code, msg}
+#[derive(Debug, Deserialize)]
+struct BinanceError {
+    code: i32,
+    #[serde(default)]
+    msg: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ServerTimeData {
+    #[serde(rename = "serverTime")]
+    server_time: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct DepthData {
+    bids: Vec<[String; 2]>,
+    asks: Vec<[String; 2]>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Ticker24hData {
+    #[serde(deserialize_with = "de_f64")]
+    price_change_percent: f64,
+    #[serde(deserialize_with = "de_f64")]
+    last_price: f64,
+    #[serde(deserialize_with = "de_f64")]
+    high_price: f64,
+    #[serde(deserialize_with = "de_f64")]
+    low_price: f64,
+    #[serde(deserialize_with = "de_f64")]
+    volume: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BookTickerData {
+    #[serde(deserialize_with = "de_f64")]
+    bid_price: f64,
+    #[serde(deserialize_with = "de_f64")]
+    ask_price: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct PriceData {
+    #[serde(deserialize_with = "de_f64")]
+    price: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OrderData {
+    order_id: i64,
+    #[serde(default, deserialize_with = "de_f64")]
+    avg_price: f64,
+    #[serde(deserialize_with = "de_f64")]
+    orig_qty: f64,
+    #[serde(default, deserialize_with = "de_f64")]
+    executed_qty: f64,
+}
+
+// 查询订单/撤单返回的订单详情
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OrderDetail {
+    order_id: i64,
+    symbol: String,
+    side: String,
+    #[serde(default, deserialize_with = "de_f64")]
+    price: f64,
+    #[serde(deserialize_with = "de_f64")]
+    orig_qty: f64,
+    #[serde(default, deserialize_with = "de_f64")]
+    executed_qty: f64,
+    #[serde(default, deserialize_with = "de_f64")]
+    avg_price: f64,
+    status: String,
+}
+
+impl From<OrderDetail> for OrderUpdate {
+    fn from(detail: OrderDetail) -> Self {
+        OrderUpdate {
+            order_id: detail.order_id.to_string(),
+            symbol: normalize_symbol(&detail.symbol),
+            side: if detail.side == "SELL" { OrderSide::Sell } else { OrderSide::Buy },
+            price: detail.price,
+            quantity: detail.orig_qty,
+            filled_quantity: detail.executed_qty,
+            avg_price: detail.avg_price,
+            status: OrderStatus::parse(&detail.status),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BalanceData {
+    asset: String,
+    #[serde(deserialize_with = "de_f64")]
+    available_balance: f64,
+}
+
+// Binance U本位合约 (/fapi) 客户端, 单向持仓模式
+pub struct BinanceFuturesClient {
+    client: Client,
+    api_secret: String,
+    base_url: String,
+    session: Option<Session>,
+    clock: ServerClock,
+}
+
+impl BinanceFuturesClient {
+    pub fn new(api_key: String, api_secret: String) -> Self {
+        Self::with_base_url(api_key, api_secret, API_BASE_URL)
+    }
+
+    // 指定接口地址 (例如指向本地模拟服务器)
+    pub fn with_base_url(api_key: String, api_secret: String, base_url: &str) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-MBX-APIKEY", HeaderValue::from_str(&api_key).unwrap());
+
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .unwrap();
+
+        Self {
+            client,
+            api_secret,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: None,
+            clock: ServerClock::default(),
+        }
+    }
+
+    // 签名请求的有效时间窗口 (毫秒)
+    pub fn with_recv_window(mut self, recv_window: u64) -> Self {
+        self.clock.set_recv_window(recv_window);
+        self
+    }
+
+    pub fn time_offset(&self) -> i64 {
+        self.clock.offset()
+    }
+
+    // 录制模式: 所有请求/响应 (签名已脱敏) 写入会话文件
+    pub fn record_to(mut self, path: impl AsRef<Path>) -> std::io::Result<Self> {
+        self.session = Some(Session::Record(SessionRecorder::create(path)?));
+        Ok(self)
+    }
+
+    // 回放模式: 不访问网络, 按顺序返回会话文件中录制的响应
+    pub fn replay_from(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let mut client = Self::new(String::new(), String::new());
+        client.session = Some(Session::Replay(SessionReplayer::load(path)?));
+        Ok(client)
+    }
+
+    // 回放模式下尚未使用的录制条数
+    pub fn replay_remaining(&self) -> Option<usize> {
+        match &self.session {
+            Some(Session::Replay(replayer)) => Some(replayer.remaining()),
+            _ => None,
+        }
+    }
+
+    // 查询服务器时间并更新本地偏移, 返回新的偏移量
+    pub async fn sync_time(&self) -> Result<i64, Box<dyn Error + Send + Sync>> {
+        self.clock.sync("binance", async {
+            let data: ServerTimeData = self.request_once(Method::GET, "/fapi/v1/time", BTreeMap::new(), false).await?;
+            Ok(data.server_time)
+        }).await
+    }
+
+    // Binance 对已编码的查询串整体签名
+    fn sign(&self, query: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.api_secret.as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(query.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    // 通用请求: 签名请求的时间戳被拒绝时重新校时并重试一次
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        params: BTreeMap<String, String>,
+        signed: bool,
+    ) -> Result<T, Box<dyn Error + Send + Sync>> {
+        match self.request_once(method.clone(), path, params.clone(), signed).await {
+            Err(e) if signed
+                && e.downcast_ref::<ApiError>().is_some_and(|api| api.code == CODE_TIMESTAMP_ERROR) =>
+            {
+                warn!(path, error = %e, "时间戳被拒绝, 重新同步服务器时间");
+                self.sync_time().await?;
+                self.request_once(method, path, params, signed).await
+            }
+            result => result,
+        }
+    }
+
+    // 单次请求: 成功时响应体即数据, 失败时 HTTP 状态码非 2xx 且响应体为 {code, msg}
+    async fn request_once<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        mut params: BTreeMap<String, String>,
+        signed: bool,
+    ) -> Result<T, Box<dyn Error + Send + Sync>> {
+        let mut url = Url::parse(&format!("{}{}", self.base_url, path))?;
+        if signed {
+            params.insert("recvWindow".to_string(), self.clock.recv_window().to_string());
+            params.insert("timestamp".to_string(), self.clock.timestamp().to_string());
+        }
+        url.query_pairs_mut().extend_pairs(&params);
+        if signed {
+            let signature = self.sign(url.query().unwrap_or_default());
+            url.query_pairs_mut().append_pair("signature", &signature);
+        }
+        if url.query() == Some("") {
+            url.set_query(None);
+        }
+
+        debug!(venue = "binance", %method, url = %redact_url(url.as_str()), "发送请求");
+        let request = self.client.request(method.clone(), url.clone());
+        let (status, response_text) = session::send(self.session.as_ref(), &self.clock, &method, url.as_str(), request).await?;
+
+        if !(200..300).contains(&status) {
+            let error: BinanceError = serde_json::from_str(&response_text)
+                .unwrap_or(BinanceError { code: status as i32, msg: response_text });
+            warn!(venue = "binance", path, code = error.code, msg = %error.msg, "接口返回错误");
+            return Err(ApiError { code: error.code, msg: error.msg }.into());
+        }
+
+        Ok(serde_json::from_str(&response_text)?)
+    }
+
+    fn symbol_params(symbol: &str) -> BTreeMap<String, String> {
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), to_venue_symbol(Venue::Binance, symbol));
+        params
+    }
+
+    // 触发价单 (止盈/止损), 触发后以市价平掉整个仓位
+    async fn place_close_position_order(
+        &self,
+        symbol: &str,
+        side: &OrderSide,
+        order_type: &str,
+        stop_price: f64,
+    ) -> Result<OrderData, Box<dyn Error + Send + Sync>> {
+        let mut params = Self::symbol_params(symbol);
+        params.insert("side".to_string(), side_str(side).to_string());
+        params.insert("type".to_string(), order_type.to_string());
+        params.insert("stopPrice".to_string(), format!("{}", stop_price));
+        params.insert("closePosition".to_string(), "true".to_string());
+        params.insert("workingType".to_string(), "MARK_PRICE".to_string());
+        self.request(Method::POST, "/fapi/v1/order", params, true).await
+    }
+}
+
+fn side_str(side: &OrderSide) -> &'static str {
+    match side {
+        OrderSide::Buy => "BUY",
+        OrderSide::Sell => "SELL",
+    }
+}
+
+// Binance 只接入U本位合约
+fn ensure_swap(market: MarketType) -> Result<(), Box<dyn Error + Send + Sync>> {
+    match market {
+        MarketType::Swap => Ok(()),
+        MarketType::Spot => Err("Binance 适配器仅支持U本位合约".into()),
+    }
+}
+
+#[async_trait]
+impl Exchange for BinanceFuturesClient {
+    fn venue(&self) -> Venue {
+        Venue::Binance
+    }
+
+    fn now_millis(&self) -> i64 {
+        self.clock.now()
+    }
+
+    fn replay_remaining(&self) -> Option<usize> {
+        BinanceFuturesClient::replay_remaining(self)
+    }
+
+    async fn sync_time_if_stale(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if self.clock.is_stale() {
+            self.sync_time().await?;
+        }
+        Ok(())
+    }
+
+    async fn get_klines(
+        &self,
+        symbol: &str,
+        market: MarketType,
+        interval: Interval,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Kline>, Box<dyn Error + Send + Sync>> {
+        ensure_swap(market)?;
+        let mut params = Self::symbol_params(symbol);
+        params.insert("interval".to_string(), interval.as_str().to_string());
+        if let Some(start) = start_time {
+            params.insert("startTime".to_string(), start.timestamp_millis().to_string());
+        }
+        if let Some(end) = end_time {
+            params.insert("endTime".to_string(), end.timestamp_millis().to_string());
+        }
+        if let Some(limit_val) = limit {
+            params.insert("limit".to_string(), limit_val.to_string());
+        }
+
+        // 每根K线为数组: [开盘时间, 开, 高, 低, 收, 成交量, 收盘时间, ...], 按时间升序
+        let data: Vec<Vec<serde_json::Value>> = self.request(Method::GET, "/fapi/v1/klines", params, false).await?;
+
+        Ok(data.iter().map(|k| array_kline(k)).collect())
+    }
+
+    async fn get_market_depth(&self, symbol: &str, market: MarketType, limit: Option<u32>) -> Result<MarketDepth, Box<dyn Error + Send + Sync>> {
+        ensure_swap(market)?;
+        let mut params = Self::symbol_params(symbol);
+        if let Some(limit_val) = limit {
+            params.insert("limit".to_string(), limit_val.to_string());
+        }
+        let data: DepthData = self.request(Method::GET, "/fapi/v1/depth", params, false).await?;
+
+        let levels = |side: &[[String; 2]]| -> Vec<(f64, f64)> {
+            side.iter()
+                .filter_map(|level| Some((level[0].parse().ok()?, level[1].parse().ok()?)))
+                .collect()
+        };
+        Ok(MarketDepth {
+            asks: levels(&data.asks),
+            bids: levels(&data.bids),
+        })
+    }
+
+    // 24小时行情不含买一卖一, 需额外查询 bookTicker
+    async fn get_market_ticker(&self, symbol: &str, market: MarketType) -> Result<MarketTicker, Box<dyn Error + Send + Sync>> {
+        ensure_swap(market)?;
+        let t: Ticker24hData = self.request(Method::GET, "/fapi/v1/ticker/24hr", Self::symbol_params(symbol), false).await?;
+        let book: BookTickerData = self.request(Method::GET, "/fapi/v1/ticker/bookTicker", Self::symbol_params(symbol), false).await?;
+        Ok(MarketTicker {
+            price_change_percent: t.price_change_percent,
+            high_price: t.high_price,
+            low_price: t.low_price,
+            last_price: t.last_price,
+            volume: t.volume,
+            bid_price: book.bid_price,
+            ask_price: book.ask_price,
+        })
+    }
+
+    async fn get_price(&self, symbol: &str, market: MarketType) -> Result<f64, Box<dyn Error + Send + Sync>> {
+        ensure_swap(market)?;
+        let data: PriceData = self.request(Method::GET, "/fapi/v1/ticker/price", Self::symbol_params(symbol), false).await?;
+        Ok(data.price)
+    }
+
+    async fn place_order(&self, order: ExchangeOrder) -> Result<OrderFill, Box<dyn Error + Send + Sync>> {
+        ensure_swap(order.market)?;
+        let mut params = Self::symbol_params(&order.symbol);
+        params.insert("side".to_string(), side_str(&order.side).to_string());
+        params.insert("type".to_string(), "MARKET".to_string());
+        params.insert("quantity".to_string(), format!("{}", order.quantity));
+        params.insert("newOrderRespType".to_string(), "RESULT".to_string());
+        if order.reduce_only {
+            params.insert("reduceOnly".to_string(), "true".to_string());
+        }
+        let data: OrderData = self.request(Method::POST, "/fapi/v1/order", params, true).await?;
+
+        // Binance 开仓单不能附带止盈止损, 开仓后分别挂平仓触发单; 失败只记录不影响已成交的开仓
+        let close_side = match order.side {
+            OrderSide::Buy => OrderSide::Sell,
+            OrderSide::Sell => OrderSide::Buy,
+        };
+        let protective = [
+            ("TAKE_PROFIT_MARKET", order.take_profit),
+            ("STOP_MARKET", order.stop_loss),
+        ];
+        for (order_type, stop_price) in protective {
+            if let Some(stop_price) = stop_price {
+                if let Err(e) = self.place_close_position_order(&order.symbol, &close_side, order_type, stop_price).await {
+                    warn!(symbol = %order.symbol, order_type, stop_price, error = %e, "挂止盈止损单失败");
+                }
+            }
+        }
+
+        Ok(OrderFill {
+            order_id: data.order_id.to_string(),
+            symbol: normalize_symbol(&order.symbol),
+            side: order.side,
+            quantity: if data.executed_qty > 0.0 { data.executed_qty } else { data.orig_qty },
+            price: data.avg_price,
+        })
+    }
+
+    async fn get_balance(&self, asset: &str, market: MarketType) -> Result<f64, Box<dyn Error + Send + Sync>> {
+        ensure_swap(market)?;
+        let balances: Vec<BalanceData> = self.request(Method::GET, "/fapi/v2/balance", BTreeMap::new(), true).await?;
+        Ok(balances.iter()
+            .find(|b| b.asset == asset)
+            .map(|b| b.available_balance)
+            .unwrap_or_default())
+    }
+
+    async fn place_limit_order(&self, order: LimitOrder) -> Result<OrderUpdate, Box<dyn Error + Send + Sync>> {
+        ensure_swap(order.market)?;
+        let mut params = Self::symbol_params(&order.symbol);
+        params.insert("side".to_string(), side_str(&order.side).to_string());
+        params.insert("type".to_string(), "LIMIT".to_string());
+        params.insert("timeInForce".to_string(), "GTC".to_string());
+        params.insert("price".to_string(), format!("{}", order.price));
+        params.insert("quantity".to_string(), format!("{}", order.quantity));
+        if order.reduce_only {
+            params.insert("reduceOnly".to_string(), "true".to_string());
+        }
+        let detail: OrderDetail = self.request(Method::POST, "/fapi/v1/order", params, true).await?;
+        Ok(detail.into())
+    }
+
+    async fn get_order(&self, symbol: &str, market: MarketType, order_id: &str) -> Result<OrderUpdate, Box<dyn Error + Send + Sync>> {
+        ensure_swap(market)?;
+        let mut params = Self::symbol_params(symbol);
+        params.insert("orderId".to_string(), order_id.to_string());
+        let detail: OrderDetail = self.request(Method::GET, "/fapi/v1/order", params, true).await?;
+        Ok(detail.into())
+    }
+
+    async fn cancel_order(&self, symbol: &str, market: MarketType, order_id: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        ensure_swap(market)?;
+        let mut params = Self::symbol_params(symbol);
+        params.insert("orderId".to_string(), order_id.to_string());
+        let _: OrderDetail = self.request(Method::DELETE, "/fapi/v1/order", params, true).await?;
+        Ok(())
+    }
+}
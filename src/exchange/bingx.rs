@@ -1,30 +1,63 @@
 use crate::types::{
-    Interval, Kline, OrderRequest, OrderResponse,
-    OrderSide
+    Interval, Kline, MarketType, OrderRequest, OrderResponseData,
+    OrderSide, OrderType, PositionSide, Venue
 };
+use crate::exchange::clock::ServerClock;
+use crate::exchange::{normalize_symbol, ApiError, Exchange, ExchangeOrder, LimitOrder, OrderFill, OrderStatus, OrderUpdate};
+use crate::exchange::spot::SpotOrderRequest;
+use crate::strategy::{MarketDepth, MarketTicker};
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use reqwest::Client;
+use crate::exchange::session::{self, Session, SessionRecorder, SessionReplayer};
+use crate::logging::redact_url;
+use reqwest::{Client, Method};
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use std::error::Error;
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
 use std::collections::BTreeMap;
+use std::path::Path;
 use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
 use reqwest::Url;
-use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use tracing::{debug, warn};
 
 const API_BASE_URL: &str = "https://open-api-vst.bingx.com";
 
 pub struct BingXClient {
     client: Client,
     api_secret: String,
+    base_url: String,
+    session: Option<Session>,
+    clock: ServerClock,
 }
 
+// 所有接口共用的响应外层 {code, msg, data}
 #[derive(Debug, Deserialize)]
-struct KlineResponse {
+struct ApiEnvelope {
     code: i32,
+    #[serde(default)]
     msg: String,
-    data: Vec<KlineData>,
+    #[serde(default)]
+    data: serde_json::Value,
+}
+
+// 是否需要签名
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Auth {
+    Public,
+    Signed,
+}
+
+#[derive(Debug, Deserialize)]
+struct ServerTimeData {
+    #[serde(rename = "serverTime")]
+    server_time: i64,
+}
+
+// 时间戳超出 recvWindow 或与服务器时间偏差过大时, BingX 返回的错误信息包含 timestamp
+fn is_timestamp_error(msg: &str) -> bool {
+    msg.to_ascii_lowercase().contains("timestamp")
 }
 
 #[derive(Debug, Deserialize)]
@@ -37,6 +70,30 @@ struct KlineData {
     time: i64,
 }
 
+// 查询订单/撤单返回的订单详情 (数值字段为字符串)
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderDetail {
+    pub order_id: i64,
+    pub symbol: String,
+    pub side: String,
+    #[serde(default)]
+    pub position_side: String,
+    #[serde(rename = "type")]
+    pub order_type: String,
+    pub price: String,
+    pub orig_qty: String,
+    pub executed_qty: String,
+    #[serde(default)]
+    pub avg_price: String,
+    pub status: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrderDetailData {
+    order: OrderDetail,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct DepthData {
     #[serde(rename = "T")]
@@ -49,13 +106,6 @@ pub struct DepthData {
     pub bids_coin: Vec<[String; 2]>,
 }
 
-#[derive(Debug, Deserialize)]
-struct DepthResponse {
-    code: i32,
-    msg: String,
-    data: Option<DepthData>,
-}
-
 #[derive(Debug, Deserialize)]
 pub struct TickerData {
     pub symbol: String,
@@ -90,15 +140,9 @@ pub struct TickerData {
     pub ask_qty: String,
 }
 
-#[derive(Debug, Deserialize)]
-struct TickerResponse {
-    code: i32,
-    msg: String,
-    data: TickerResponseData,
-}
-
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
 enum TickerResponseData {
     Single(TickerData),
     Multiple(Vec<TickerData>),
@@ -106,6 +150,11 @@ enum TickerResponseData {
 
 impl BingXClient {
     pub fn new(api_key: String, api_secret: String) -> Self {
+        Self::with_base_url(api_key, api_secret, API_BASE_URL)
+    }
+
+    // 指定接口地址 (例如指向本地模拟服务器)
+    pub fn with_base_url(api_key: String, api_secret: String, base_url: &str) -> Self {
         let mut headers = HeaderMap::new();
         headers.insert("X-BX-APIKEY", HeaderValue::from_str(&api_key).unwrap());
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
@@ -118,10 +167,88 @@ impl BingXClient {
         Self {
             client,
             api_secret,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: None,
+            clock: ServerClock::default(),
+        }
+    }
+
+    // 签名请求的有效时间窗口 (毫秒)
+    pub fn with_recv_window(mut self, recv_window: u64) -> Self {
+        self.clock.set_recv_window(recv_window);
+        self
+    }
+
+    pub fn recv_window(&self) -> u64 {
+        self.clock.recv_window()
+    }
+
+    // 当前使用的服务器时间偏移 (毫秒)
+    pub fn time_offset(&self) -> i64 {
+        self.clock.offset()
+    }
+
+    // 按服务器时间校正后的当前时间戳
+    pub fn timestamp(&self) -> i64 {
+        self.clock.timestamp()
+    }
+
+    // 查询服务器时间并更新本地偏移, 返回新的偏移量
+    pub async fn sync_time(&self) -> Result<i64, Box<dyn Error + Send + Sync>> {
+        self.clock.sync("bingx", async {
+            let data: ServerTimeData = self.request_once(
+                Method::GET,
+                "/openApi/swap/v2/server/time",
+                BTreeMap::new(),
+                Auth::Public,
+            ).await?;
+            Ok(data.server_time)
+        }).await
+    }
+
+    // 距上次校时超过间隔 (或从未校时) 时重新同步
+    pub async fn sync_time_if_stale(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if self.clock.is_stale() {
+            self.sync_time().await?;
+        }
+        Ok(())
+    }
+
+    // 录制模式: 所有请求/响应 (签名已脱敏) 写入会话文件
+    pub fn record_to(mut self, path: impl AsRef<Path>) -> std::io::Result<Self> {
+        self.session = Some(Session::Record(SessionRecorder::create(path)?));
+        Ok(self)
+    }
+
+    // 回放模式: 不访问网络, 按顺序返回会话文件中录制的响应
+    pub fn replay_from(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let mut client = Self::new(String::new(), String::new());
+        client.session = Some(Session::Replay(SessionReplayer::load(path)?));
+        Ok(client)
+    }
+
+    // 回放模式下尚未使用的录制条数
+    pub fn replay_remaining(&self) -> Option<usize> {
+        match &self.session {
+            Some(Session::Replay(replayer)) => Some(replayer.remaining()),
+            _ => None,
+        }
+    }
+
+    // 发送请求并返回响应文本 (录制/回放在此统一处理)
+    async fn send(&self, method: Method, url: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
+        debug!(%method, url = %redact_url(url), "发送请求");
+
+        let mut request = self.client.request(method.clone(), url);
+        if method == Method::POST {
+            request = request.header(CONTENT_TYPE, "application/x-www-form-urlencoded");
         }
+        let (_, response_text) = session::send(self.session.as_ref(), &self.clock, &method, url, request).await?;
+        Ok(response_text)
     }
 
-    fn sign(&self, params: &mut BTreeMap<String, String>) -> String {
+    // 按键排序拼接参数后做 HMAC-SHA256 签名
+    fn sign(&self, params: &BTreeMap<String, String>) -> String {
         let query = params
             .iter()
             .map(|(k, v)| format!("{}={}", k, v))
@@ -134,6 +261,57 @@ impl BingXClient {
         hex::encode(mac.finalize().into_bytes())
     }
 
+    // 通用请求: 签名请求的时间戳被拒绝时重新校时并重试一次
+    pub(crate) async fn request<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        params: BTreeMap<String, String>,
+        auth: Auth,
+    ) -> Result<T, Box<dyn Error + Send + Sync>> {
+        match self.request_once(method.clone(), path, params.clone(), auth).await {
+            Err(e) if auth == Auth::Signed
+                && e.downcast_ref::<ApiError>().is_some_and(|api| is_timestamp_error(&api.msg)) =>
+            {
+                warn!(path, error = %e, "时间戳被拒绝, 重新同步服务器时间");
+                self.sync_time().await?;
+                self.request_once(method, path, params, auth).await
+            }
+            result => result,
+        }
+    }
+
+    // 单次请求: 编码参数、签名 (签名请求附带时间戳)、发送并解析 {code, msg, data} 中的 data
+    async fn request_once<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        mut params: BTreeMap<String, String>,
+        auth: Auth,
+    ) -> Result<T, Box<dyn Error + Send + Sync>> {
+        if auth == Auth::Signed {
+            params.insert("recvWindow".to_string(), self.clock.recv_window().to_string());
+            params.insert("timestamp".to_string(), self.timestamp().to_string());
+        }
+
+        let mut url = Url::parse(&format!("{}{}", self.base_url, path))?;
+        url.query_pairs_mut().extend_pairs(&params);
+        if auth == Auth::Signed {
+            let signature = self.sign(&params);
+            url.query_pairs_mut().append_pair("signature", &signature);
+        }
+
+        let response_text = self.send(method, url.as_str()).await?;
+        let envelope: ApiEnvelope = serde_json::from_str(&response_text)?;
+
+        if envelope.code != 0 {
+            warn!(path, code = envelope.code, msg = %envelope.msg, "接口返回错误");
+            return Err(ApiError { code: envelope.code, msg: envelope.msg }.into());
+        }
+
+        Ok(serde_json::from_value(envelope.data)?)
+    }
+
     pub async fn get_klines(
         &self,
         symbol: &str,
@@ -141,44 +319,32 @@ impl BingXClient {
         start_time: Option<DateTime<Utc>>,
         end_time: Option<DateTime<Utc>>,
         limit: Option<u32>,
-    ) -> Result<Vec<Kline>, Box<dyn Error>> {
-        let mut url = format!(
-            "{}/openApi/swap/v3/quote/klines?symbol={}&interval={}",
-            API_BASE_URL,
-            symbol,
-            serde_json::to_string(&interval)?.replace("\"", "")
-        );
-
+    ) -> Result<Vec<Kline>, Box<dyn Error + Send + Sync>> {
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), symbol.to_string());
+        params.insert("interval".to_string(), interval.as_str().to_string());
         if let Some(start) = start_time {
-            url.push_str(&format!("&startTime={}", start.timestamp_millis()));
+            params.insert("startTime".to_string(), start.timestamp_millis().to_string());
         }
         if let Some(end) = end_time {
-            url.push_str(&format!("&endTime={}", end.timestamp_millis()));
+            params.insert("endTime".to_string(), end.timestamp_millis().to_string());
         }
         if let Some(limit_val) = limit {
-            url.push_str(&format!("&limit={}", limit_val));
+            params.insert("limit".to_string(), limit_val.to_string());
         }
 
-        url.push_str(&format!("&timestamp={}", Utc::now().timestamp_millis()));
+        let data: Vec<KlineData> = self.request(
+            Method::GET,
+            "/openApi/swap/v3/quote/klines",
+            params,
+            Auth::Public,
+        ).await?;
 
-        println!("请求URL: {}", url);
-
-        let response = self.client.get(&url).send().await?;
-        let response_text = response.text().await?;
-        println!("API响应: {}", response_text);
-
-        let kline_response: KlineResponse = serde_json::from_str(&response_text)?;
-
-        if kline_response.code != 0 {
-            return Err(format!("API错误: {}", kline_response.msg).into());
-        }
-
-        let klines = kline_response
-            .data
+        let klines = data
             .into_iter()
             .map(|k| Kline {
                 open_time: k.time,
-                close_time: k.time,  // BingX API 只提供了一个时间戳
+                close_time: interval.close_time(k.time),  // BingX 只返回开盘时间, 收盘时间按周期推算
                 open: k.open.parse().unwrap_or_default(),
                 high: k.high.parse().unwrap_or_default(),
                 low: k.low.parse().unwrap_or_default(),
@@ -190,20 +356,34 @@ impl BingXClient {
         Ok(klines)
     }
 
-    pub async fn place_order(&self, order: OrderRequest) -> Result<OrderResponse, Box<dyn Error>> {
+    pub async fn place_order(&self, order: OrderRequest) -> Result<OrderResponseData, Box<dyn Error + Send + Sync>> {
         // 构造基本参数
-        let timestamp = Utc::now().timestamp_millis();
         let mut params = BTreeMap::new();
         params.insert("symbol".to_string(), order.symbol.clone());
         params.insert("side".to_string(), match order.side {
             OrderSide::Buy => "BUY".to_string(),
             OrderSide::Sell => "SELL".to_string(),
         });
-        params.insert("positionSide".to_string(), "LONG".to_string());
-        params.insert("type".to_string(), "MARKET".to_string());
+        params.insert("positionSide".to_string(), match order.position_side {
+            Some(PositionSide::Both) => "BOTH".to_string(),
+            Some(PositionSide::Short) => "SHORT".to_string(),
+            Some(PositionSide::Long) | None => "LONG".to_string(),
+        });
+        if order.reduce_only == Some(true) {
+            params.insert("reduceOnly".to_string(), "true".to_string());
+        }
+        match order.order_type {
+            OrderType::Limit => {
+                let price = order.price.ok_or("限价单缺少价格")?;
+                params.insert("type".to_string(), "LIMIT".to_string());
+                params.insert("price".to_string(), format!("{}", price));
+                params.insert("timeInForce".to_string(), "GTC".to_string());
+            }
+            _ => {
+                params.insert("type".to_string(), "MARKET".to_string());
+            }
+        }
         params.insert("quantity".to_string(), format!("{}", order.quantity));
-        params.insert("timestamp".to_string(), timestamp.to_string());
-        params.insert("recvWindow".to_string(), "5000".to_string());
 
         // 添加止盈止损
         if let Some(take_profit) = order.take_profit {
@@ -213,83 +393,46 @@ impl BingXClient {
             params.insert("stopLoss".to_string(), stop_loss);
         }
 
-        // 生成签名字符串
-        let param_str = {
-            let mut keys: Vec<&String> = params.keys().collect();
-            keys.sort();
-            let encoded_params: Vec<String> = keys.iter()
-                .map(|k| {
-                    let value = params.get(*k).unwrap();
-                    format!("{}={}", k, value)
-                })
-                .collect();
-            encoded_params.join("&")
-        };
-
-        println!("签名前的字符串: {}", param_str);
-
-        // 计算签名
-        let mut mac = Hmac::<Sha256>::new_from_slice(self.api_secret.as_bytes())
-            .expect("HMAC can take key of any size");
-        mac.update(param_str.as_bytes());
-        let signature = hex::encode(mac.finalize().into_bytes());
-
-        // 构造URL并编码参数
-        let mut url = Url::parse(&format!("{}/openApi/swap/v2/trade/order", API_BASE_URL))?;
-        
-        // 添加编码后的参数
-        for (key, value) in &params {
-            url.query_pairs_mut().append_pair(key, value);
-        }
-        
-        // 添加签名
-        url.query_pairs_mut().append_pair("signature", &signature);
-
-        println!("\n下单请求URL: {}", url.as_str());
-        println!("签名: {}", signature);
-
-        // 发送请求
-        let response = self.client
-            .post(url)
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .send()
-            .await?;
-
-        let response_text = response.text().await?;
-        println!("API响应: {}", response_text);
-
-        let order_response: OrderResponse = serde_json::from_str(&response_text)?;
-        
-        if order_response.code != 0 {
-            println!("\n下单结果:");
-            println!("响应代码: {}", order_response.code);
-            println!("响应消息: {}", order_response.msg);
-            return Err(order_response.msg.into());
-        }
-
-        Ok(order_response)
+        self.request(
+            Method::POST,
+            "/openApi/swap/v2/trade/order",
+            params,
+            Auth::Signed,
+        ).await
     }
 
-    pub async fn get_latest_price(&self, symbol: &str) -> Result<f64, Box<dyn Error>> {
-        let url = format!(
-            "{}/openApi/swap/v1/ticker/price?symbol={}&timestamp={}",
-            API_BASE_URL,
-            symbol,
-            Utc::now().timestamp_millis()
-        );
-
-        println!("获取价格URL: {}", url);
-        let response = self.client.get(&url).send().await?;
-        let response_text = response.text().await?;
-        println!("价格响应: {}", response_text);
+    // 查询订单
+    pub async fn query_order(&self, symbol: &str, order_id: i64) -> Result<OrderDetail, Box<dyn Error + Send + Sync>> {
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), symbol.to_string());
+        params.insert("orderId".to_string(), order_id.to_string());
+
+        let data: OrderDetailData = self.request(
+            Method::GET,
+            "/openApi/swap/v2/trade/order",
+            params,
+            Auth::Signed,
+        ).await?;
+        Ok(data.order)
+    }
 
-        #[derive(Debug, Deserialize)]
-        struct PriceResponse {
-            code: i32,
-            msg: String,
-            data: Option<PriceData>,
-        }
+    // 撤销订单
+    pub async fn cancel_order(&self, symbol: &str, order_id: i64) -> Result<OrderDetail, Box<dyn Error + Send + Sync>> {
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), symbol.to_string());
+        params.insert("orderId".to_string(), order_id.to_string());
+
+        let data: OrderDetailData = self.request(
+            Method::DELETE,
+            "/openApi/swap/v2/trade/order",
+            params,
+            Auth::Signed,
+        ).await?;
+        Ok(data.order)
+    }
 
+    pub async fn get_latest_price(&self, symbol: &str) -> Result<f64, Box<dyn Error + Send + Sync>> {
+        #[allow(dead_code)]
         #[derive(Debug, Deserialize)]
         struct PriceData {
             symbol: String,
@@ -298,44 +441,40 @@ impl BingXClient {
             timestamp: i64,
         }
 
-        let price_response: PriceResponse = serde_json::from_str(&response_text)?;
-        if price_response.code != 0 {
-            return Err(format!("API错误: {}", price_response.msg).into());
-        }
-
-        if let Some(price_data) = price_response.data {
-            Ok(price_data.price.parse()?)
-        } else {
-            Err("无价格数据".into())
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), symbol.to_string());
+
+        let data: Option<PriceData> = self.request(
+            Method::GET,
+            "/openApi/swap/v1/ticker/price",
+            params,
+            Auth::Public,
+        ).await?;
+
+        match data {
+            Some(price_data) => Ok(price_data.price.parse()?),
+            None => Err("无价格数据".into()),
         }
     }
 
-    pub async fn get_depth(&self, symbol: &str, limit: Option<u32>) -> Result<DepthData, Box<dyn Error>> {
-        let mut url = format!(
-            "{}/openApi/swap/v2/quote/depth?symbol={}&timestamp={}",
-            API_BASE_URL,
-            symbol,
-            Utc::now().timestamp_millis()
-        );
-
+    pub async fn get_depth(&self, symbol: &str, limit: Option<u32>) -> Result<DepthData, Box<dyn Error + Send + Sync>> {
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), symbol.to_string());
         if let Some(limit_val) = limit {
-            url.push_str(&format!("&limit={}", limit_val));
+            params.insert("limit".to_string(), limit_val.to_string());
         }
 
-        println!("获取深度信息URL: {}", url);
-        let response = self.client.get(&url).send().await?;
-        let response_text = response.text().await?;
-        println!("深度信息响应: {}", response_text);
+        let data: Option<DepthData> = self.request(
+            Method::GET,
+            "/openApi/swap/v2/quote/depth",
+            params,
+            Auth::Public,
+        ).await?;
 
-        let depth_response: DepthResponse = serde_json::from_str(&response_text)?;
-        if depth_response.code != 0 {
-            return Err(format!("API错误: {}", depth_response.msg).into());
-        }
-
-        depth_response.data.ok_or_else(|| "无深度数据".into())
+        data.ok_or_else(|| "无深度数据".into())
     }
 
-    pub async fn print_depth_info(&self, symbol: &str, limit: Option<u32>) -> Result<(), Box<dyn Error>> {
+    pub async fn print_depth_info(&self, symbol: &str, limit: Option<u32>) -> Result<(), Box<dyn Error + Send + Sync>> {
         let depth = self.get_depth(symbol, limit).await?;
         
         println!("\n深度信息 - {}:", symbol);
@@ -364,34 +503,26 @@ impl BingXClient {
         Ok(())
     }
 
-    pub async fn get_ticker(&self, symbol: Option<&str>) -> Result<Vec<TickerData>, Box<dyn Error>> {
-        let mut url = format!(
-            "{}/openApi/swap/v2/quote/ticker?timestamp={}",
-            API_BASE_URL,
-            Utc::now().timestamp_millis()
-        );
-
+    pub async fn get_ticker(&self, symbol: Option<&str>) -> Result<Vec<TickerData>, Box<dyn Error + Send + Sync>> {
+        let mut params = BTreeMap::new();
         if let Some(sym) = symbol {
-            url.push_str(&format!("&symbol={}", sym));
+            params.insert("symbol".to_string(), sym.to_string());
         }
 
-        println!("获取24小时行情URL: {}", url);
-        let response = self.client.get(&url).send().await?;
-        let response_text = response.text().await?;
-        println!("24小时行情响应: {}", response_text);
-
-        let ticker_response: TickerResponse = serde_json::from_str(&response_text)?;
-        if ticker_response.code != 0 {
-            return Err(format!("API错误: {}", ticker_response.msg).into());
-        }
+        let data: TickerResponseData = self.request(
+            Method::GET,
+            "/openApi/swap/v2/quote/ticker",
+            params,
+            Auth::Public,
+        ).await?;
 
-        Ok(match ticker_response.data {
+        Ok(match data {
             TickerResponseData::Single(ticker) => vec![ticker],
             TickerResponseData::Multiple(tickers) => tickers,
         })
     }
 
-    pub async fn print_ticker_info(&self, symbol: Option<&str>) -> Result<(), Box<dyn Error>> {
+    pub async fn print_ticker_info(&self, symbol: Option<&str>) -> Result<(), Box<dyn Error + Send + Sync>> {
         let tickers = self.get_ticker(symbol).await?;
         
         for ticker in tickers {
@@ -440,4 +571,250 @@ impl BingXClient {
         
         Ok(())
     }
+}
+
+#[async_trait]
+impl Exchange for BingXClient {
+    fn venue(&self) -> Venue {
+        Venue::BingX
+    }
+
+    fn now_millis(&self) -> i64 {
+        self.clock.now()
+    }
+
+    fn replay_remaining(&self) -> Option<usize> {
+        BingXClient::replay_remaining(self)
+    }
+
+    async fn sync_time_if_stale(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        BingXClient::sync_time_if_stale(self).await
+    }
+
+    async fn get_klines(
+        &self,
+        symbol: &str,
+        market: MarketType,
+        interval: Interval,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Kline>, Box<dyn Error + Send + Sync>> {
+        let symbol = normalize_symbol(symbol);
+        match market {
+            MarketType::Swap => BingXClient::get_klines(self, &symbol, interval, start_time, end_time, limit).await,
+            MarketType::Spot => self.get_spot_klines(&symbol, interval, start_time, end_time, limit).await,
+        }
+    }
+
+    async fn get_market_depth(&self, symbol: &str, market: MarketType, limit: Option<u32>) -> Result<MarketDepth, Box<dyn Error + Send + Sync>> {
+        let symbol = normalize_symbol(symbol);
+        match market {
+            MarketType::Swap => {
+                let depth_data = self.get_depth(&symbol, limit).await?;
+                let levels = |side: &[[String; 2]]| -> Vec<(f64, f64)> {
+                    side.iter()
+                        .filter_map(|level| {
+                            let price = level[0].parse().ok()?;
+                            let quantity = level[1].parse().ok()?;
+                            Some((price, quantity))
+                        })
+                        .collect()
+                };
+                Ok(MarketDepth {
+                    asks: levels(&depth_data.asks),
+                    bids: levels(&depth_data.bids),
+                })
+            }
+            MarketType::Spot => {
+                let depth = self.get_spot_depth(&symbol, limit).await?;
+                Ok(MarketDepth { asks: depth.asks, bids: depth.bids })
+            }
+        }
+    }
+
+    async fn get_market_ticker(&self, symbol: &str, market: MarketType) -> Result<MarketTicker, Box<dyn Error + Send + Sync>> {
+        let symbol = normalize_symbol(symbol);
+        match market {
+            MarketType::Swap => {
+                let tickers = self.get_ticker(Some(&symbol)).await?;
+                let t = tickers.first().ok_or("无行情数据")?;
+                Ok(MarketTicker {
+                    price_change_percent: t.price_change_percent.parse().unwrap_or_default(),
+                    high_price: t.high_price.parse().unwrap_or_default(),
+                    low_price: t.low_price.parse().unwrap_or_default(),
+                    last_price: t.last_price.parse().unwrap_or_default(),
+                    volume: t.volume.parse().unwrap_or_default(),
+                    bid_price: t.bid_price.parse().unwrap_or_default(),
+                    ask_price: t.ask_price.parse().unwrap_or_default(),
+                })
+            }
+            MarketType::Spot => {
+                let tickers = self.get_spot_ticker(Some(&symbol)).await?;
+                let t = tickers.first().ok_or("无行情数据")?;
+                Ok(MarketTicker {
+                    price_change_percent: t.price_change_percent,
+                    high_price: t.high_price,
+                    low_price: t.low_price,
+                    last_price: t.last_price,
+                    volume: t.volume,
+                    bid_price: t.bid_price,
+                    ask_price: t.ask_price,
+                })
+            }
+        }
+    }
+
+    async fn get_price(&self, symbol: &str, market: MarketType) -> Result<f64, Box<dyn Error + Send + Sync>> {
+        match market {
+            MarketType::Swap => self.get_latest_price(&normalize_symbol(symbol)).await,
+            MarketType::Spot => Ok(self.get_market_ticker(symbol, market).await?.last_price),
+        }
+    }
+
+    async fn place_order(&self, order: ExchangeOrder) -> Result<OrderFill, Box<dyn Error + Send + Sync>> {
+        let symbol = normalize_symbol(&order.symbol);
+
+        if order.market == MarketType::Spot {
+            let data = self.place_spot_order(SpotOrderRequest {
+                symbol: symbol.clone(),
+                side: order.side.clone(),
+                quantity: order.quantity,
+            }).await?;
+            let filled = if data.executed_qty > 0.0 { data.executed_qty } else { order.quantity };
+            let price = if data.executed_qty > 0.0 && data.cummulative_quote_qty > 0.0 {
+                data.cummulative_quote_qty / data.executed_qty
+            } else {
+                data.price
+            };
+            return Ok(OrderFill {
+                order_id: data.order_id.to_string(),
+                symbol,
+                side: order.side,
+                quantity: filled,
+                price,
+            });
+        }
+
+        // 构造止盈止损JSON
+        let take_profit = order.take_profit.map(|stop_price| serde_json::json!({
+            "type": "TAKE_PROFIT_MARKET",
+            "stopPrice": stop_price,
+            "workingType": "MARK_PRICE",
+            "closePosition": true
+        }).to_string());
+
+        let stop_loss = order.stop_loss.map(|stop_price| serde_json::json!({
+            "type": "STOP_MARKET",
+            "stopPrice": stop_price,
+            "workingType": "MARK_PRICE",
+            "closePosition": true
+        }).to_string());
+
+        // 双向持仓: 开多/平多为 LONG, 开空/平空为 SHORT; 平仓方向与开仓相反, 天然只减仓
+        let position_side = match (&order.side, order.reduce_only) {
+            (OrderSide::Buy, false) | (OrderSide::Sell, true) => PositionSide::Long,
+            (OrderSide::Sell, false) | (OrderSide::Buy, true) => PositionSide::Short,
+        };
+
+        let request = OrderRequest {
+            symbol: symbol.clone(),
+            order_type: OrderType::Market,
+            side: order.side.clone(),
+            quantity: order.quantity,
+            timestamp: self.timestamp(),
+            price: None,
+            stop_price: None,
+            working_type: None,
+            take_profit,
+            stop_loss,
+            position_side: Some(position_side),
+            reduce_only: None,
+        };
+
+        let data = BingXClient::place_order(self, request).await?.order;
+        Ok(OrderFill {
+            order_id: data.order_id.to_string(),
+            symbol,
+            side: order.side,
+            quantity: data.quantity,
+            price: data.price,
+        })
+    }
+
+    async fn get_balance(&self, asset: &str, market: MarketType) -> Result<f64, Box<dyn Error + Send + Sync>> {
+        match market {
+            MarketType::Spot => self.get_spot_free_balance(asset).await,
+            MarketType::Swap => Err("BingX 合约余额查询暂未支持".into()),
+        }
+    }
+
+    async fn place_limit_order(&self, order: LimitOrder) -> Result<OrderUpdate, Box<dyn Error + Send + Sync>> {
+        ensure_swap_orders(order.market)?;
+        let symbol = normalize_symbol(&order.symbol);
+        let position_side = match (&order.side, order.reduce_only) {
+            (OrderSide::Buy, false) | (OrderSide::Sell, true) => PositionSide::Long,
+            (OrderSide::Sell, false) | (OrderSide::Buy, true) => PositionSide::Short,
+        };
+
+        let request = OrderRequest {
+            symbol: symbol.clone(),
+            order_type: OrderType::Limit,
+            side: order.side.clone(),
+            quantity: order.quantity,
+            timestamp: self.timestamp(),
+            price: Some(order.price),
+            stop_price: None,
+            working_type: None,
+            take_profit: None,
+            stop_loss: None,
+            position_side: Some(position_side),
+            reduce_only: None,
+        };
+
+        let data = BingXClient::place_order(self, request).await?.order;
+        Ok(OrderUpdate {
+            order_id: data.order_id.to_string(),
+            symbol,
+            side: order.side,
+            price: order.price,
+            quantity: order.quantity,
+            filled_quantity: 0.0,
+            avg_price: 0.0,
+            status: OrderStatus::New,
+        })
+    }
+
+    async fn get_order(&self, symbol: &str, market: MarketType, order_id: &str) -> Result<OrderUpdate, Box<dyn Error + Send + Sync>> {
+        ensure_swap_orders(market)?;
+        let detail = self.query_order(&normalize_symbol(symbol), order_id.parse()?).await?;
+        Ok(order_update(detail))
+    }
+
+    async fn cancel_order(&self, symbol: &str, market: MarketType, order_id: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        ensure_swap_orders(market)?;
+        BingXClient::cancel_order(self, &normalize_symbol(symbol), order_id.parse()?).await?;
+        Ok(())
+    }
+}
+
+// 限价单只接入永续合约
+fn ensure_swap_orders(market: MarketType) -> Result<(), Box<dyn Error + Send + Sync>> {
+    match market {
+        MarketType::Swap => Ok(()),
+        MarketType::Spot => Err("BingX 现货暂不支持限价挂单".into()),
+    }
+}
+
+fn order_update(detail: OrderDetail) -> OrderUpdate {
+    OrderUpdate {
+        order_id: detail.order_id.to_string(),
+        symbol: normalize_symbol(&detail.symbol),
+        side: if detail.side == "SELL" { OrderSide::Sell } else { OrderSide::Buy },
+        price: detail.price.parse().unwrap_or_default(),
+        quantity: detail.orig_qty.parse().unwrap_or_default(),
+        filled_quantity: detail.executed_qty.parse().unwrap_or_default(),
+        avg_price: detail.avg_price.parse().unwrap_or_default(),
+        status: OrderStatus::parse(&detail.status),
+    }
 } 
\ No newline at end of file
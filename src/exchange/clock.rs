@@ -0,0 +1,91 @@
+use chrono::Utc;
+use std::error::Error;
+use std::future::Future;
+use std::sync::atomic::{AtomicI64, Ordering};
+use tracing::info;
+
+// 默认的签名请求有效时间窗口 (毫秒)
+pub const DEFAULT_RECV_WINDOW: u64 = 5000;
+// 超过该时间未同步则重新校时 (毫秒)
+const TIME_SYNC_INTERVAL_MS: i64 = 30 * 60 * 1000;
+
+// 与交易所服务器的时间偏移及签名请求的时间窗口, 各交易所客户端共用
+pub struct ServerClock {
+    recv_window: u64,
+    offset: AtomicI64,       // 服务器时间 - 本地时间 (毫秒)
+    last_sync: AtomicI64,    // 上次校时的本地时间, 0 表示从未校时
+    replay_time: AtomicI64,  // 回放会话中最近一次响应的录制时间, 0 表示未回放
+}
+
+impl Default for ServerClock {
+    fn default() -> Self {
+        Self::new(DEFAULT_RECV_WINDOW)
+    }
+}
+
+impl ServerClock {
+    pub fn new(recv_window: u64) -> Self {
+        Self {
+            recv_window,
+            offset: AtomicI64::new(0),
+            last_sync: AtomicI64::new(0),
+            replay_time: AtomicI64::new(0),
+        }
+    }
+
+    pub fn recv_window(&self) -> u64 {
+        self.recv_window
+    }
+
+    pub fn set_recv_window(&mut self, recv_window: u64) {
+        self.recv_window = recv_window;
+    }
+
+    pub fn offset(&self) -> i64 {
+        self.offset.load(Ordering::Relaxed)
+    }
+
+    // 当前本地时间 (毫秒), 回放会话时为最近一次回放响应的录制时间
+    pub fn now(&self) -> i64 {
+        match self.replay_time.load(Ordering::Relaxed) {
+            0 => Utc::now().timestamp_millis(),
+            replayed => replayed,
+        }
+    }
+
+    // 回放到录制于 time 的响应 (未记录时间的旧会话保持本地时间)
+    pub(crate) fn replay_at(&self, time: i64) {
+        if time > 0 {
+            self.replay_time.store(time, Ordering::Relaxed);
+        }
+    }
+
+    // 按服务器时间校正后的当前时间戳
+    pub fn timestamp(&self) -> i64 {
+        self.now() + self.offset()
+    }
+
+    // 从未校时或距上次校时超过间隔
+    pub fn is_stale(&self) -> bool {
+        let last = self.last_sync.load(Ordering::Relaxed);
+        last == 0 || self.now() - last > TIME_SYNC_INTERVAL_MS
+    }
+
+    // 查询服务器时间 (毫秒) 并更新偏移, 返回新的偏移量
+    pub async fn sync<F>(&self, venue: &str, server_time: F) -> Result<i64, Box<dyn Error + Send + Sync>>
+    where
+        F: Future<Output = Result<i64, Box<dyn Error + Send + Sync>>>,
+    {
+        let before = self.now();
+        let server_time = server_time.await?;
+        let after = self.now();
+
+        // 以请求往返的中点作为服务器返回时间对应的本地时间
+        let offset = server_time - (before + after) / 2;
+        self.offset.store(offset, Ordering::Relaxed);
+        self.last_sync.store(after, Ordering::Relaxed);
+        info!(venue, offset_ms = offset, round_trip_ms = after - before, "服务器时间已同步");
+
+        Ok(offset)
+    }
+}
@@ -0,0 +1,1181 @@
+use crate::strategy::{MarketDepth, MarketTicker};
+use crate::types::{Interval, Kline, MarketType};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::Url;
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+// 模拟服务器使用的错误码 (与 BingX 返回格式一致)
+pub const CODE_SIGNATURE_ERROR: i32 = 100001;
+pub const CODE_API_KEY_ERROR: i32 = 100413;
+pub const CODE_INVALID_PARAM: i32 = 109400;
+pub const CODE_SYMBOL_NOT_FOUND: i32 = 109414;
+pub const CODE_NOT_FOUND: i32 = 100400;
+pub const CODE_INSUFFICIENT_BALANCE: i32 = 100490;
+pub const CODE_ORDER_NOT_FOUND: i32 = 80016;
+
+// Binance 模拟服务器使用的错误码
+pub const BINANCE_CODE_TIMESTAMP_ERROR: i32 = -1021;
+pub const BINANCE_CODE_SIGNATURE_ERROR: i32 = -1022;
+pub const BINANCE_CODE_INVALID_SYMBOL: i32 = -1121;
+pub const BINANCE_CODE_API_KEY_ERROR: i32 = -2015;
+pub const BINANCE_CODE_ORDER_NOT_FOUND: i32 = -2013;
+
+// 收到的请求记录
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub params: BTreeMap<String, String>,
+    pub api_key: Option<String>,
+}
+
+// 模拟的订单: 市价单立即成交, 限价单挂单直到价格穿过挂单价
+#[derive(Debug, Clone)]
+pub struct MockOrder {
+    pub order_id: i64,
+    pub market: MarketType,
+    pub symbol: String,
+    pub side: String,
+    pub position_side: String,
+    pub order_type: String,
+    pub quantity: f64,
+    pub price: f64,
+    pub take_profit: Option<String>,
+    pub stop_loss: Option<String>,
+    pub reduce_only: bool,
+    pub status: String,         // NEW / PARTIALLY_FILLED / FILLED / CANCELED / EXPIRED
+    pub filled_quantity: f64,   // 已成交数量
+}
+
+impl MockOrder {
+    pub fn is_open(&self) -> bool {
+        matches!(self.status.as_str(), "NEW" | "PARTIALLY_FILLED")
+    }
+}
+
+// 注入的故障
+#[derive(Debug, Clone)]
+pub enum MockFault {
+    // 返回 HTTP 200 但 code 非 0
+    ApiError { code: i32, msg: String },
+    // 返回指定的 HTTP 状态码
+    HttpStatus(u16),
+    // 返回无法解析的响应体
+    MalformedBody,
+}
+
+// 模拟的交易所接口风格
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Dialect {
+    BingX,
+    Binance,
+}
+
+struct MockState {
+    dialect: Dialect,
+    api_key: String,
+    api_secret: String,
+    latency: Duration,
+    clock_offset: i64,
+    klines: HashMap<String, Vec<Kline>>,
+    interval_klines: HashMap<(String, String), Vec<Kline>>,  // (交易对, 周期) 单独设置的K线
+    depths: HashMap<String, MarketDepth>,
+    tickers: HashMap<String, MarketTicker>,
+    prices: HashMap<String, f64>,
+    balances: HashMap<String, f64>,
+    faults: HashMap<String, VecDeque<MockFault>>,
+    scripted: HashMap<String, VecDeque<Value>>,
+    orders: Vec<MockOrder>,
+    requests: Vec<RecordedRequest>,
+    next_order_id: i64,
+}
+
+impl MockState {
+    fn server_time(&self) -> i64 {
+        Utc::now().timestamp_millis() + self.clock_offset
+    }
+
+    // 当前价格: 优先使用显式设置的价格, 其次行情最新价, 最后是最近一根K线收盘价
+    fn price_of(&self, symbol: &str) -> Option<f64> {
+        self.prices.get(symbol).copied()
+            .or_else(|| self.tickers.get(symbol).map(|t| t.last_price))
+            .or_else(|| self.klines.get(symbol).and_then(|k| k.last()).map(|k| k.close))
+    }
+
+    // 请求周期的K线: 优先使用按周期设置的K线, 否则使用通用K线
+    fn klines_for(&self, symbol: &str, request: &HttpRequest) -> &[Kline] {
+        request.params.get("interval")
+            .and_then(|interval| self.interval_klines.get(&(symbol.to_string(), interval.clone())))
+            .or_else(|| self.klines.get(symbol))
+            .map(|k| k.as_slice())
+            .unwrap_or_default()
+    }
+
+    // 价格穿过挂单价的限价单按挂单价成交
+    fn match_limit_orders(&mut self, symbol: &str) {
+        let price = match self.price_of(symbol) {
+            Some(price) => price,
+            None => return,
+        };
+        for order in self.orders.iter_mut() {
+            if order.symbol != symbol || order.order_type != "LIMIT" || !order.is_open() {
+                continue;
+            }
+            let crossed = match order.side.as_str() {
+                "BUY" => price <= order.price,
+                _ => price >= order.price,
+            };
+            if crossed {
+                order.status = "FILLED".to_string();
+                order.filled_quantity = order.quantity;
+            }
+        }
+    }
+
+    fn knows_symbol(&self, symbol: &str) -> bool {
+        self.klines.contains_key(symbol)
+            || self.interval_klines.keys().any(|(s, _)| s == symbol)
+            || self.depths.contains_key(symbol)
+            || self.tickers.contains_key(symbol)
+            || self.prices.contains_key(symbol)
+    }
+}
+
+// 本地 BingX 永续合约接口模拟服务器
+//
+// 覆盖客户端使用的 K线、深度、24小时行情、最新价格和下单接口,
+// 校验 HMAC 签名, 并支持脚本化响应、故障注入和延迟注入。
+pub struct MockBingXServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+    handle: JoinHandle<()>,
+}
+
+impl MockBingXServer {
+    pub async fn start(api_key: &str, api_secret: &str) -> std::io::Result<Self> {
+        Self::start_dialect(Dialect::BingX, api_key, api_secret).await
+    }
+
+    async fn start_dialect(dialect: Dialect, api_key: &str, api_secret: &str) -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(MockState {
+            dialect,
+            api_key: api_key.to_string(),
+            api_secret: api_secret.to_string(),
+            latency: Duration::ZERO,
+            clock_offset: 0,
+            klines: HashMap::new(),
+            interval_klines: HashMap::new(),
+            depths: HashMap::new(),
+            tickers: HashMap::new(),
+            prices: HashMap::new(),
+            balances: HashMap::new(),
+            faults: HashMap::new(),
+            scripted: HashMap::new(),
+            orders: Vec::new(),
+            requests: Vec::new(),
+            next_order_id: 1,
+        }));
+
+        let server_state = state.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                let (stream, _) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(_) => continue,
+                };
+                let state = server_state.clone();
+                tokio::spawn(async move {
+                    let _ = handle_connection(stream, state).await;
+                });
+            }
+        });
+
+        Ok(Self { addr, state, handle })
+    }
+
+    // 供 BingXClient::with_base_url 使用的地址
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    // 设置K线 (按时间升序)
+    pub fn set_klines(&self, symbol: &str, klines: Vec<Kline>) {
+        self.state.lock().unwrap().klines.insert(symbol.to_string(), klines);
+    }
+
+    // 设置指定周期的K线 (未设置的周期返回 set_klines 的数据)
+    pub fn set_interval_klines(&self, symbol: &str, interval: Interval, klines: Vec<Kline>) {
+        self.state.lock().unwrap()
+            .interval_klines
+            .insert((symbol.to_string(), interval.to_string()), klines);
+    }
+
+    // 追加一根K线
+    pub fn push_kline(&self, symbol: &str, kline: Kline) {
+        self.state.lock().unwrap()
+            .klines
+            .entry(symbol.to_string())
+            .or_default()
+            .push(kline);
+    }
+
+    pub fn set_depth(&self, symbol: &str, depth: MarketDepth) {
+        self.state.lock().unwrap().depths.insert(symbol.to_string(), depth);
+    }
+
+    pub fn set_ticker(&self, symbol: &str, ticker: MarketTicker) {
+        self.state.lock().unwrap().tickers.insert(symbol.to_string(), ticker);
+    }
+
+    // 设置最新价, 价格穿过的限价挂单随之成交
+    pub fn set_price(&self, symbol: &str, price: f64) {
+        let mut state = self.state.lock().unwrap();
+        state.prices.insert(symbol.to_string(), price);
+        state.match_limit_orders(symbol);
+    }
+
+    // 现货账户可用余额
+    pub fn set_balance(&self, asset: &str, free: f64) {
+        self.state.lock().unwrap().balances.insert(asset.to_string(), free);
+    }
+
+    pub fn balance(&self, asset: &str) -> f64 {
+        self.state.lock().unwrap().balances.get(asset).copied().unwrap_or_default()
+    }
+
+    // 每个请求在响应前等待的时间
+    pub fn set_latency(&self, latency: Duration) {
+        self.state.lock().unwrap().latency = latency;
+    }
+
+    // 挂单部分成交 quantity (不超过下单数量), 订单仍在挂单中
+    pub fn partially_fill(&self, order_id: i64, quantity: f64) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(order) = state.orders.iter_mut().find(|o| o.order_id == order_id && o.is_open()) {
+            order.filled_quantity = quantity.min(order.quantity);
+            order.status = "PARTIALLY_FILLED".to_string();
+        }
+    }
+
+    // 挂单被交易所取消 (例如超时过期), 已成交部分保留
+    pub fn expire_order(&self, order_id: i64) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(order) = state.orders.iter_mut().find(|o| o.order_id == order_id && o.is_open()) {
+            order.status = "EXPIRED".to_string();
+        }
+    }
+
+    // 模拟服务器时钟与本地时钟的偏差 (毫秒), 用于测试校时
+    pub fn set_clock_offset(&self, offset_ms: i64) {
+        self.state.lock().unwrap().clock_offset = offset_ms;
+    }
+
+    // 下一次请求该路径时返回故障 (可多次调用排队)
+    pub fn inject_fault(&self, path: &str, fault: MockFault) {
+        self.state.lock().unwrap()
+            .faults
+            .entry(path.to_string())
+            .or_default()
+            .push_back(fault);
+    }
+
+    // 下一次请求该路径时原样返回给定的响应体 (可多次调用排队)
+    pub fn script_response(&self, path: &str, body: Value) {
+        self.state.lock().unwrap()
+            .scripted
+            .entry(path.to_string())
+            .or_default()
+            .push_back(body);
+    }
+
+    pub fn orders(&self) -> Vec<MockOrder> {
+        self.state.lock().unwrap().orders.clone()
+    }
+
+    // 仍在挂单中的订单
+    pub fn open_orders(&self) -> Vec<MockOrder> {
+        self.state.lock().unwrap()
+            .orders
+            .iter()
+            .filter(|o| o.is_open())
+            .cloned()
+            .collect()
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    // 指定路径收到的请求数
+    pub fn request_count(&self, path: &str) -> usize {
+        self.state.lock().unwrap()
+            .requests
+            .iter()
+            .filter(|r| r.path == path)
+            .count()
+    }
+}
+
+impl Drop for MockBingXServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+// 本地 Binance U本位合约 (/fapi) 接口模拟服务器
+//
+// 与 MockBingXServer 共用行情/余额设置、故障注入和请求记录接口,
+// 交易对使用 Binance 格式 (例如 "BTCUSDT"), 出错时按 Binance 规则返回 HTTP 4xx 和 {code, msg}。
+pub struct MockBinanceServer {
+    inner: MockBingXServer,
+}
+
+impl MockBinanceServer {
+    pub async fn start(api_key: &str, api_secret: &str) -> std::io::Result<Self> {
+        let inner = MockBingXServer::start_dialect(Dialect::Binance, api_key, api_secret).await?;
+        Ok(Self { inner })
+    }
+}
+
+impl Deref for MockBinanceServer {
+    type Target = MockBingXServer;
+
+    fn deref(&self) -> &MockBingXServer {
+        &self.inner
+    }
+}
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    raw_query: String,
+    params: BTreeMap<String, String>,
+    api_key: Option<String>,
+}
+
+struct HttpResponse {
+    status: u16,
+    body: String,
+}
+
+impl HttpResponse {
+    fn json(body: Value) -> Self {
+        Self { status: 200, body: body.to_string() }
+    }
+
+    fn api_error(code: i32, msg: &str) -> Self {
+        Self::json(json!({ "code": code, "msg": msg, "data": null }))
+    }
+
+    // Binance 出错时返回 HTTP 4xx, 响应体只有 {code, msg}
+    fn binance_error(status: u16, code: i32, msg: &str) -> Self {
+        Self { status, body: json!({ "code": code, "msg": msg }).to_string() }
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, state: Arc<Mutex<MockState>>) -> std::io::Result<()> {
+    let request = match read_request(&mut stream).await? {
+        Some(request) => request,
+        None => return Ok(()),
+    };
+
+    let latency = state.lock().unwrap().latency;
+    if !latency.is_zero() {
+        tokio::time::sleep(latency).await;
+    }
+
+    let response = route(&request, &mut state.lock().unwrap());
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        _ => "Error",
+    };
+    let raw = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason,
+        response.body.len(),
+        response.body
+    );
+    stream.write_all(raw.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<Option<HttpRequest>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    // 读取请求头
+    let header_end = loop {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let target = request_line.next().unwrap_or("/").to_string();
+
+    let mut headers = HashMap::new();
+    for line in lines {
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+
+    // 读取请求体
+    let content_length: usize = headers.get("content-length")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    while buf.len() < header_end + content_length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    let body = String::from_utf8_lossy(&buf[header_end..]).to_string();
+
+    let url = Url::parse(&format!("http://mock{}", target))
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    let mut params: BTreeMap<String, String> = url.query_pairs().into_owned().collect();
+    if !body.is_empty() && !body.trim_start().starts_with('{') {
+        if let Ok(form) = Url::parse(&format!("http://mock/?{}", body)) {
+            params.extend(form.query_pairs().into_owned());
+        }
+    }
+
+    Ok(Some(HttpRequest {
+        method,
+        path: url.path().to_string(),
+        raw_query: url.query().unwrap_or_default().to_string(),
+        params,
+        api_key: headers.get("x-bx-apikey").or_else(|| headers.get("x-mbx-apikey")).cloned(),
+    }))
+}
+
+// 按 BingX 规则校验签名: 除 signature 外的参数按键排序拼接后做 HMAC-SHA256
+fn verify_signature(request: &HttpRequest, state: &MockState) -> Result<(), HttpResponse> {
+    if request.api_key.as_deref() != Some(state.api_key.as_str()) {
+        return Err(HttpResponse::api_error(CODE_API_KEY_ERROR, "Incorrect apiKey"));
+    }
+
+    let signature = request.params.get("signature")
+        .ok_or_else(|| HttpResponse::api_error(CODE_SIGNATURE_ERROR, "Missing signature"))?;
+
+    let payload = request.params
+        .iter()
+        .filter(|(k, _)| k.as_str() != "signature")
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<String>>()
+        .join("&");
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(state.api_secret.as_bytes())
+        .expect("HMAC can take key of any size");
+    mac.update(payload.as_bytes());
+    let expected = hex::encode(mac.finalize().into_bytes());
+
+    if *signature != expected {
+        return Err(HttpResponse::api_error(CODE_SIGNATURE_ERROR, "Signature verification failed"));
+    }
+
+    // 时间戳必须落在 [服务器时间 - recvWindow, 服务器时间 + 1s] 内
+    let timestamp: i64 = request.params.get("timestamp")
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| HttpResponse::api_error(CODE_INVALID_PARAM, "timestamp is required"))?;
+    let recv_window: i64 = request.params.get("recvWindow")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(5000);
+    let now = state.server_time();
+    if timestamp > now + 1000 || timestamp < now - recv_window {
+        return Err(HttpResponse::api_error(
+            CODE_INVALID_PARAM,
+            "timestamp is invalid, please check your system time",
+        ));
+    }
+    Ok(())
+}
+
+fn route(request: &HttpRequest, state: &mut MockState) -> HttpResponse {
+    state.requests.push(RecordedRequest {
+        method: request.method.clone(),
+        path: request.path.clone(),
+        params: request.params.clone(),
+        api_key: request.api_key.clone(),
+    });
+
+    // 故障注入优先于其他响应
+    if let Some(fault) = state.faults.get_mut(&request.path).and_then(|q| q.pop_front()) {
+        return match fault {
+            MockFault::ApiError { code, msg } if state.dialect == Dialect::Binance => {
+                HttpResponse::binance_error(400, code, &msg)
+            }
+            MockFault::ApiError { code, msg } => HttpResponse::api_error(code, &msg),
+            MockFault::HttpStatus(status) => HttpResponse {
+                status,
+                body: json!({ "code": status, "msg": "injected http error" }).to_string(),
+            },
+            MockFault::MalformedBody => HttpResponse { status: 200, body: "<html>oops".to_string() },
+        };
+    }
+
+    if let Some(body) = state.scripted.get_mut(&request.path).and_then(|q| q.pop_front()) {
+        return HttpResponse::json(body);
+    }
+
+    if state.dialect == Dialect::Binance {
+        return binance_route(request, state);
+    }
+
+    // 带签名的请求一律校验
+    let is_trade = request.path.contains("/trade/") || request.path.contains("/account/");
+    if is_trade || request.params.contains_key("signature") {
+        if let Err(response) = verify_signature(request, state) {
+            return response;
+        }
+    }
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/openApi/swap/v2/server/time") => HttpResponse::json(json!({
+            "code": 0,
+            "msg": "",
+            "data": { "serverTime": state.server_time() }
+        })),
+        ("GET", "/openApi/swap/v3/quote/klines") => klines_response(request, state),
+        ("GET", "/openApi/swap/v2/quote/depth") => depth_response(request, state),
+        ("GET", "/openApi/swap/v2/quote/ticker") => ticker_response(request, state),
+        ("GET", "/openApi/swap/v1/ticker/price") => price_response(request, state),
+        ("POST", "/openApi/swap/v2/trade/order") => order_response(request, state),
+        ("GET", "/openApi/swap/v2/trade/order") => order_query_response(request, state, false),
+        ("DELETE", "/openApi/swap/v2/trade/order") => order_query_response(request, state, true),
+        ("GET", "/openApi/spot/v2/market/kline") => spot_klines_response(request, state),
+        ("GET", "/openApi/spot/v1/market/depth") => spot_depth_response(request, state),
+        ("GET", "/openApi/spot/v1/ticker/24hr") => spot_ticker_response(request, state),
+        ("GET", "/openApi/spot/v1/account/balance") => spot_balance_response(state),
+        ("POST", "/openApi/spot/v1/trade/order") => spot_order_response(request, state),
+        _ => HttpResponse {
+            status: 404,
+            body: json!({ "code": CODE_NOT_FOUND, "msg": "this api is not exist" }).to_string(),
+        },
+    }
+}
+
+fn symbol_param<'a>(request: &'a HttpRequest, state: &MockState) -> Result<&'a str, HttpResponse> {
+    let symbol = request.params.get("symbol")
+        .ok_or_else(|| HttpResponse::api_error(CODE_SYMBOL_NOT_FOUND, "symbol is required"))?;
+    if !state.knows_symbol(symbol) {
+        return Err(HttpResponse::api_error(CODE_SYMBOL_NOT_FOUND, "symbol not exist"));
+    }
+    Ok(symbol)
+}
+
+fn klines_response(request: &HttpRequest, state: &MockState) -> HttpResponse {
+    let symbol = match symbol_param(request, state) {
+        Ok(symbol) => symbol,
+        Err(response) => return response,
+    };
+    // BingX 按时间倒序返回, 最新的K线在前
+    let klines = state.klines_for(symbol, request);
+    let data: Vec<Value> = filtered_klines(request, klines)
+        .into_iter()
+        .rev()
+        .map(|k| json!({
+            "open": k.open.to_string(),
+            "close": k.close.to_string(),
+            "high": k.high.to_string(),
+            "low": k.low.to_string(),
+            "volume": k.volume.to_string(),
+            "time": k.open_time,
+        }))
+        .collect();
+
+    HttpResponse::json(json!({ "code": 0, "msg": "", "data": data }))
+}
+
+fn depth_response(request: &HttpRequest, state: &MockState) -> HttpResponse {
+    let symbol = match symbol_param(request, state) {
+        Ok(symbol) => symbol,
+        Err(response) => return response,
+    };
+    let limit: usize = request.params.get("limit").and_then(|v| v.parse().ok()).unwrap_or(20);
+    let depth = match state.depths.get(symbol) {
+        Some(depth) => depth,
+        None => return HttpResponse::json(json!({ "code": 0, "msg": "", "data": null })),
+    };
+
+    let levels = |side: &[(f64, f64)]| -> Vec<[String; 2]> {
+        side.iter()
+            .take(limit)
+            .map(|(price, qty)| [price.to_string(), qty.to_string()])
+            .collect()
+    };
+    let coin_levels = |side: &[(f64, f64)]| -> Vec<[String; 2]> {
+        side.iter()
+            .take(limit)
+            .map(|(price, qty)| [price.to_string(), (price * qty).to_string()])
+            .collect()
+    };
+
+    HttpResponse::json(json!({
+        "code": 0,
+        "msg": "",
+        "data": {
+            "T": state.server_time(),
+            "asks": levels(&depth.asks),
+            "bids": levels(&depth.bids),
+            "asksCoin": coin_levels(&depth.asks),
+            "bidsCoin": coin_levels(&depth.bids),
+        }
+    }))
+}
+
+fn ticker_json(symbol: &str, t: &MarketTicker) -> Value {
+    let open_price = t.last_price / (1.0 + t.price_change_percent / 100.0);
+    let now = Utc::now().timestamp_millis();
+    json!({
+        "symbol": symbol,
+        "priceChange": (t.last_price - open_price).to_string(),
+        "priceChangePercent": t.price_change_percent.to_string(),
+        "lastPrice": t.last_price.to_string(),
+        "lastQty": "1",
+        "highPrice": t.high_price.to_string(),
+        "lowPrice": t.low_price.to_string(),
+        "volume": t.volume.to_string(),
+        "quoteVolume": (t.volume * t.last_price).to_string(),
+        "openPrice": open_price.to_string(),
+        "openTime": now - 24 * 60 * 60 * 1000,
+        "closeTime": now,
+        "bidPrice": t.bid_price.to_string(),
+        "bidQty": "1",
+        "askPrice": t.ask_price.to_string(),
+        "askQty": "1",
+    })
+}
+
+fn ticker_response(request: &HttpRequest, state: &MockState) -> HttpResponse {
+    let data = match request.params.get("symbol") {
+        Some(symbol) => match state.tickers.get(symbol) {
+            Some(ticker) => ticker_json(symbol, ticker),
+            None => return HttpResponse::api_error(CODE_SYMBOL_NOT_FOUND, "symbol not exist"),
+        },
+        None => Value::Array(
+            state.tickers.iter()
+                .map(|(symbol, ticker)| ticker_json(symbol, ticker))
+                .collect(),
+        ),
+    };
+    HttpResponse::json(json!({ "code": 0, "msg": "", "data": data }))
+}
+
+fn price_response(request: &HttpRequest, state: &MockState) -> HttpResponse {
+    let symbol = match symbol_param(request, state) {
+        Ok(symbol) => symbol,
+        Err(response) => return response,
+    };
+    match state.price_of(symbol) {
+        Some(price) => HttpResponse::json(json!({
+            "code": 0,
+            "msg": "",
+            "data": {
+                "symbol": symbol,
+                "price": price.to_string(),
+                "time": state.server_time(),
+            }
+        })),
+        None => HttpResponse::json(json!({ "code": 0, "msg": "", "data": null })),
+    }
+}
+
+fn order_response(request: &HttpRequest, state: &mut MockState) -> HttpResponse {
+    let symbol = match symbol_param(request, state) {
+        Ok(symbol) => symbol.to_string(),
+        Err(response) => return response,
+    };
+    let param = |key: &str| request.params.get(key).cloned().unwrap_or_default();
+    let quantity: f64 = match param("quantity").parse() {
+        Ok(quantity) if quantity > 0.0 => quantity,
+        _ => return HttpResponse::api_error(CODE_INVALID_PARAM, "invalid quantity"),
+    };
+
+    // 限价单按挂单价挂出, 其余订单按当前价立即成交
+    let limit_order = param("type") == "LIMIT";
+    let price = if limit_order {
+        match param("price").parse() {
+            Ok(price) if price > 0.0 => price,
+            _ => return HttpResponse::api_error(CODE_INVALID_PARAM, "invalid price"),
+        }
+    } else {
+        state.price_of(&symbol).unwrap_or_default()
+    };
+
+    let order = MockOrder {
+        order_id: state.next_order_id,
+        market: MarketType::Swap,
+        symbol: symbol.clone(),
+        side: param("side"),
+        position_side: param("positionSide"),
+        order_type: param("type"),
+        quantity,
+        price,
+        take_profit: request.params.get("takeProfit").cloned(),
+        stop_loss: request.params.get("stopLoss").cloned(),
+        // 双向持仓下卖出 LONG / 买入 SHORT 为平仓
+        reduce_only: param("reduceOnly") == "true"
+            || matches!((param("side").as_str(), param("positionSide").as_str()), ("SELL", "LONG") | ("BUY", "SHORT")),
+        status: if limit_order { "NEW" } else { "FILLED" }.to_string(),
+        filled_quantity: if limit_order { 0.0 } else { quantity },
+    };
+    state.next_order_id += 1;
+
+    let data = json!({
+        "order": {
+            "orderId": order.order_id,
+            "orderID": order.order_id.to_string(),
+            "symbol": order.symbol,
+            "positionSide": order.position_side,
+            "side": order.side,
+            "type": order.order_type,
+            "price": order.price,
+            "quantity": order.quantity,
+            "stopPrice": 0.0,
+            "workingType": "MARK_PRICE",
+            "clientOrderID": "",
+            "timeInForce": "GTC",
+            "priceRate": 0.0,
+            "stopLoss": order.stop_loss.clone().unwrap_or_default(),
+            "takeProfit": order.take_profit.clone().unwrap_or_default(),
+            "reduceOnly": false,
+            "activationPrice": 0.0,
+            "closePosition": "",
+            "stopGuaranteed": "",
+        }
+    });
+    state.orders.push(order);
+    state.match_limit_orders(&symbol);
+
+    HttpResponse::json(json!({ "code": 0, "msg": "", "data": data }))
+}
+
+// 订单详情, 成交数量与均价按状态给出 (BingX 与 Binance 字段相同, 数值均为字符串)
+fn order_detail_json(order: &MockOrder) -> Value {
+    let filled = order.filled_quantity > 0.0;
+    json!({
+        "orderId": order.order_id,
+        "symbol": order.symbol,
+        "side": order.side,
+        "positionSide": order.position_side,
+        "type": order.order_type,
+        "price": order.price.to_string(),
+        "origQty": order.quantity.to_string(),
+        "executedQty": order.filled_quantity.to_string(),
+        "avgPrice": if filled { order.price.to_string() } else { "0".to_string() },
+        "status": order.status,
+        "reduceOnly": order.reduce_only,
+    })
+}
+
+// 按 orderId 查找订单; cancel 为 true 时撤销仍在挂单中的订单
+fn find_order<'a>(request: &HttpRequest, state: &'a mut MockState, symbol: &str, cancel: bool) -> Option<&'a MockOrder> {
+    let order_id: i64 = request.params.get("orderId")?.parse().ok()?;
+    state.match_limit_orders(symbol);
+    let order = state.orders.iter_mut().find(|o| o.order_id == order_id && o.symbol == symbol)?;
+    if cancel && order.is_open() {
+        order.status = "CANCELED".to_string();
+    }
+    Some(order)
+}
+
+fn order_query_response(request: &HttpRequest, state: &mut MockState, cancel: bool) -> HttpResponse {
+    let symbol = match symbol_param(request, state) {
+        Ok(symbol) => symbol.to_string(),
+        Err(response) => return response,
+    };
+    match find_order(request, state, &symbol, cancel) {
+        Some(order) if cancel && order.status != "CANCELED" => {
+            HttpResponse::api_error(CODE_INVALID_PARAM, "order is not open")
+        }
+        Some(order) => HttpResponse::json(json!({
+            "code": 0,
+            "msg": "",
+            "data": { "order": order_detail_json(order) }
+        })),
+        None => HttpResponse::api_error(CODE_ORDER_NOT_FOUND, "order not exist"),
+    }
+}
+
+fn filtered_klines<'a>(request: &HttpRequest, klines: &'a [Kline]) -> Vec<&'a Kline> {
+    let start: Option<i64> = request.params.get("startTime").and_then(|v| v.parse().ok());
+    let end: Option<i64> = request.params.get("endTime").and_then(|v| v.parse().ok());
+    let limit: usize = request.params.get("limit").and_then(|v| v.parse().ok()).unwrap_or(500);
+
+    let mut selected: Vec<&Kline> = klines.iter()
+        .rev()
+        .filter(|k| start.is_none_or(|s| k.open_time >= s))
+        .filter(|k| end.is_none_or(|e| k.open_time <= e))
+        .take(limit)
+        .collect();
+    selected.reverse();
+    selected
+}
+
+// 现货K线按时间升序返回, 每根为数组
+fn spot_klines_response(request: &HttpRequest, state: &MockState) -> HttpResponse {
+    let symbol = match symbol_param(request, state) {
+        Ok(symbol) => symbol,
+        Err(response) => return response,
+    };
+    let klines = state.klines_for(symbol, request);
+    let data: Vec<Value> = filtered_klines(request, klines)
+        .into_iter()
+        .map(|k| json!([k.open_time, k.open, k.high, k.low, k.close, k.volume, k.close_time, k.volume * k.close]))
+        .collect();
+
+    HttpResponse::json(json!({ "code": 0, "msg": "", "data": data }))
+}
+
+fn spot_depth_response(request: &HttpRequest, state: &MockState) -> HttpResponse {
+    let symbol = match symbol_param(request, state) {
+        Ok(symbol) => symbol,
+        Err(response) => return response,
+    };
+    let limit: usize = request.params.get("limit").and_then(|v| v.parse().ok()).unwrap_or(20);
+    let depth = match state.depths.get(symbol) {
+        Some(depth) => depth,
+        None => return HttpResponse::json(json!({ "code": 0, "msg": "", "data": null })),
+    };
+    let levels = |side: &[(f64, f64)]| -> Vec<[String; 2]> {
+        side.iter()
+            .take(limit)
+            .map(|(price, qty)| [price.to_string(), qty.to_string()])
+            .collect()
+    };
+
+    HttpResponse::json(json!({
+        "code": 0,
+        "msg": "",
+        "data": {
+            "ts": state.server_time(),
+            "asks": levels(&depth.asks),
+            "bids": levels(&depth.bids),
+        }
+    }))
+}
+
+fn spot_ticker_json(symbol: &str, t: &MarketTicker) -> Value {
+    json!({
+        "symbol": symbol,
+        "priceChangePercent": format!("{}%", t.price_change_percent),
+        "lastPrice": t.last_price,
+        "highPrice": t.high_price,
+        "lowPrice": t.low_price,
+        "volume": t.volume,
+        "quoteVolume": t.volume * t.last_price,
+        "bidPrice": t.bid_price,
+        "askPrice": t.ask_price,
+    })
+}
+
+// 现货行情总是返回数组
+fn spot_ticker_response(request: &HttpRequest, state: &MockState) -> HttpResponse {
+    let data: Vec<Value> = match request.params.get("symbol") {
+        Some(symbol) => match state.tickers.get(symbol) {
+            Some(ticker) => vec![spot_ticker_json(symbol, ticker)],
+            None => return HttpResponse::api_error(CODE_SYMBOL_NOT_FOUND, "symbol not exist"),
+        },
+        None => state.tickers.iter()
+            .map(|(symbol, ticker)| spot_ticker_json(symbol, ticker))
+            .collect(),
+    };
+    HttpResponse::json(json!({ "code": 0, "msg": "", "data": data }))
+}
+
+fn spot_balance_response(state: &MockState) -> HttpResponse {
+    let balances: Vec<Value> = state.balances.iter()
+        .map(|(asset, free)| json!({ "asset": asset, "free": free.to_string(), "locked": "0" }))
+        .collect();
+    HttpResponse::json(json!({ "code": 0, "msg": "", "data": { "balances": balances } }))
+}
+
+// 现货市价单按当前价格立即成交并更新余额
+fn spot_order_response(request: &HttpRequest, state: &mut MockState) -> HttpResponse {
+    let symbol = match symbol_param(request, state) {
+        Ok(symbol) => symbol.to_string(),
+        Err(response) => return response,
+    };
+    let (base, quote) = match symbol.split_once('-') {
+        Some((base, quote)) => (base.to_string(), quote.to_string()),
+        None => return HttpResponse::api_error(CODE_INVALID_PARAM, "invalid symbol"),
+    };
+    let side = request.params.get("side").cloned().unwrap_or_default();
+    let quantity: f64 = match request.params.get("quantity").and_then(|v| v.parse().ok()) {
+        Some(quantity) if quantity > 0.0 => quantity,
+        _ => return HttpResponse::api_error(CODE_INVALID_PARAM, "invalid quantity"),
+    };
+    let price = state.price_of(&symbol).unwrap_or_default();
+    let cost = quantity * price;
+
+    let base_free = state.balances.get(&base).copied().unwrap_or_default();
+    let quote_free = state.balances.get(&quote).copied().unwrap_or_default();
+    match side.as_str() {
+        "BUY" if quote_free >= cost => {
+            state.balances.insert(quote, quote_free - cost);
+            state.balances.insert(base, base_free + quantity);
+        }
+        "SELL" if base_free >= quantity => {
+            state.balances.insert(base, base_free - quantity);
+            state.balances.insert(quote, quote_free + cost);
+        }
+        "BUY" | "SELL" => return HttpResponse::api_error(CODE_INSUFFICIENT_BALANCE, "Insufficient balance"),
+        _ => return HttpResponse::api_error(CODE_INVALID_PARAM, "invalid side"),
+    }
+
+    let order = MockOrder {
+        order_id: state.next_order_id,
+        market: MarketType::Spot,
+        symbol: symbol.clone(),
+        side: side.clone(),
+        position_side: String::new(),
+        order_type: request.params.get("type").cloned().unwrap_or_default(),
+        quantity,
+        price,
+        take_profit: None,
+        stop_loss: None,
+        reduce_only: false,
+        status: "FILLED".to_string(),
+        filled_quantity: quantity,
+    };
+    state.next_order_id += 1;
+
+    let data = json!({
+        "symbol": symbol,
+        "orderId": order.order_id,
+        "transactTime": state.server_time(),
+        "price": price.to_string(),
+        "origQty": quantity.to_string(),
+        "executedQty": quantity.to_string(),
+        "cummulativeQuoteQty": cost.to_string(),
+        "status": "FILLED",
+        "type": order.order_type,
+        "side": side,
+    });
+    state.orders.push(order);
+
+    HttpResponse::json(json!({ "code": 0, "msg": "", "data": data }))
+}
+
+// 按 Binance 规则校验签名: 对签名前的原始查询串做 HMAC-SHA256
+fn binance_verify_signature(request: &HttpRequest, state: &MockState) -> Result<(), HttpResponse> {
+    if request.api_key.as_deref() != Some(state.api_key.as_str()) {
+        return Err(HttpResponse::binance_error(401, BINANCE_CODE_API_KEY_ERROR, "Invalid API-key, IP, or permissions for action."));
+    }
+
+    let (payload, signature) = request.raw_query.rsplit_once("&signature=")
+        .ok_or_else(|| HttpResponse::binance_error(400, BINANCE_CODE_SIGNATURE_ERROR, "Signature for this request is not valid."))?;
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(state.api_secret.as_bytes())
+        .expect("HMAC can take key of any size");
+    mac.update(payload.as_bytes());
+    if signature != hex::encode(mac.finalize().into_bytes()) {
+        return Err(HttpResponse::binance_error(400, BINANCE_CODE_SIGNATURE_ERROR, "Signature for this request is not valid."));
+    }
+
+    let timestamp: i64 = request.params.get("timestamp")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_default();
+    let recv_window: i64 = request.params.get("recvWindow")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(5000);
+    let now = state.server_time();
+    if timestamp > now + 1000 || timestamp < now - recv_window {
+        return Err(HttpResponse::binance_error(
+            400,
+            BINANCE_CODE_TIMESTAMP_ERROR,
+            "Timestamp for this request is outside of the recvWindow.",
+        ));
+    }
+    Ok(())
+}
+
+fn binance_route(request: &HttpRequest, state: &mut MockState) -> HttpResponse {
+    let signed = request.path.ends_with("/order") || request.path.ends_with("/balance");
+    if signed || request.params.contains_key("signature") {
+        if let Err(response) = binance_verify_signature(request, state) {
+            return response;
+        }
+    }
+
+    if request.path == "/fapi/v1/time" {
+        return HttpResponse::json(json!({ "serverTime": state.server_time() }));
+    }
+    if request.path == "/fapi/v2/balance" {
+        let balances: Vec<Value> = state.balances.iter()
+            .map(|(asset, free)| json!({
+                "accountAlias": "mock",
+                "asset": asset,
+                "balance": free.to_string(),
+                "availableBalance": free.to_string(),
+            }))
+            .collect();
+        return HttpResponse::json(json!(balances));
+    }
+
+    let symbol = match request.params.get("symbol") {
+        Some(symbol) if state.knows_symbol(symbol) => symbol.clone(),
+        _ => return HttpResponse::binance_error(400, BINANCE_CODE_INVALID_SYMBOL, "Invalid symbol."),
+    };
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/fapi/v1/klines") => {
+            let klines = state.klines_for(&symbol, request);
+            let data: Vec<Value> = filtered_klines(request, klines)
+                .into_iter()
+                .map(|k| json!([
+                    k.open_time,
+                    k.open.to_string(),
+                    k.high.to_string(),
+                    k.low.to_string(),
+                    k.close.to_string(),
+                    k.volume.to_string(),
+                    k.close_time,
+                    (k.close * k.volume).to_string(),
+                    1,
+                    "0",
+                    "0",
+                    "0"
+                ]))
+                .collect();
+            HttpResponse::json(json!(data))
+        }
+        ("GET", "/fapi/v1/depth") => {
+            let depth = state.depths.get(&symbol).cloned().unwrap_or(MarketDepth { asks: vec![], bids: vec![] });
+            let levels = |side: &[(f64, f64)]| -> Vec<Value> {
+                side.iter().map(|(p, q)| json!([p.to_string(), q.to_string()])).collect()
+            };
+            HttpResponse::json(json!({
+                "lastUpdateId": 1,
+                "E": state.server_time(),
+                "T": state.server_time(),
+                "bids": levels(&depth.bids),
+                "asks": levels(&depth.asks),
+            }))
+        }
+        ("GET", "/fapi/v1/ticker/24hr") => match state.tickers.get(&symbol) {
+            Some(t) => HttpResponse::json(json!({
+                "symbol": symbol,
+                "priceChangePercent": t.price_change_percent.to_string(),
+                "lastPrice": t.last_price.to_string(),
+                "highPrice": t.high_price.to_string(),
+                "lowPrice": t.low_price.to_string(),
+                "volume": t.volume.to_string(),
+                "quoteVolume": (t.volume * t.last_price).to_string(),
+            })),
+            None => HttpResponse::binance_error(400, BINANCE_CODE_INVALID_SYMBOL, "Invalid symbol."),
+        },
+        ("GET", "/fapi/v1/ticker/bookTicker") => match state.tickers.get(&symbol) {
+            Some(t) => HttpResponse::json(json!({
+                "symbol": symbol,
+                "bidPrice": t.bid_price.to_string(),
+                "bidQty": "1",
+                "askPrice": t.ask_price.to_string(),
+                "askQty": "1",
+            })),
+            None => HttpResponse::binance_error(400, BINANCE_CODE_INVALID_SYMBOL, "Invalid symbol."),
+        },
+        ("GET", "/fapi/v1/ticker/price") => HttpResponse::json(json!({
+            "symbol": symbol,
+            "price": state.price_of(&symbol).unwrap_or_default().to_string(),
+            "time": state.server_time(),
+        })),
+        ("POST", "/fapi/v1/order") => binance_order_response(request, state, symbol),
+        (method @ ("GET" | "DELETE"), "/fapi/v1/order") => {
+            let cancel = method == "DELETE";
+            match find_order(request, state, &symbol, cancel) {
+                Some(order) if cancel && order.status != "CANCELED" => {
+                    HttpResponse::binance_error(400, BINANCE_CODE_ORDER_NOT_FOUND, "Unknown order sent.")
+                }
+                Some(order) => HttpResponse::json(order_detail_json(order)),
+                None => HttpResponse::binance_error(400, BINANCE_CODE_ORDER_NOT_FOUND, "Order does not exist."),
+            }
+        }
+        _ => HttpResponse::binance_error(404, -1, "Not found"),
+    }
+}
+
+// 市价单立即按当前价成交; 限价单按挂单价挂出; 触发单 (closePosition) 只挂单不成交
+fn binance_order_response(request: &HttpRequest, state: &mut MockState, symbol: String) -> HttpResponse {
+    let param = |key: &str| request.params.get(key).cloned().unwrap_or_default();
+    let order_type = param("type");
+    let close_position = param("closePosition") == "true";
+    let quantity: f64 = param("quantity").parse().unwrap_or_default();
+    if quantity <= 0.0 && !close_position {
+        return HttpResponse::binance_error(400, -1102, "Mandatory parameter 'quantity' was not sent.");
+    }
+
+    let market_order = order_type == "MARKET";
+    let price = match order_type.as_str() {
+        "MARKET" => state.price_of(&symbol).unwrap_or_default(),
+        "LIMIT" => match param("price").parse() {
+            Ok(price) if price > 0.0 => price,
+            _ => return HttpResponse::binance_error(400, -1102, "Mandatory parameter 'price' was not sent."),
+        },
+        _ => param("stopPrice").parse().unwrap_or_default(),
+    };
+    let order = MockOrder {
+        order_id: state.next_order_id,
+        market: MarketType::Swap,
+        symbol: symbol.clone(),
+        side: param("side"),
+        position_side: request.params.get("positionSide").cloned().unwrap_or_else(|| "BOTH".to_string()),
+        order_type: order_type.clone(),
+        quantity,
+        price,
+        take_profit: None,
+        stop_loss: None,
+        reduce_only: param("reduceOnly") == "true" || close_position,
+        status: if market_order { "FILLED" } else { "NEW" }.to_string(),
+        filled_quantity: if market_order { quantity } else { 0.0 },
+    };
+    state.next_order_id += 1;
+
+    let body = json!({
+        "orderId": order.order_id,
+        "symbol": symbol,
+        "status": if market_order { "FILLED" } else { "NEW" },
+        "avgPrice": if market_order { price.to_string() } else { "0".to_string() },
+        "origQty": quantity.to_string(),
+        "executedQty": if market_order { quantity.to_string() } else { "0".to_string() },
+        "side": order.side,
+        "positionSide": order.position_side,
+        "type": order_type,
+        "stopPrice": param("stopPrice"),
+        "closePosition": close_position,
+        "price": if order_type == "LIMIT" { price.to_string() } else { "0".to_string() },
+        "updateTime": state.server_time(),
+    });
+    state.orders.push(order);
+    state.match_limit_orders(&symbol);
+    HttpResponse::json(body)
+}
@@ -1,17 +1,286 @@
+pub mod binance;
 pub mod bingx;
+pub mod clock;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+pub mod session;
+pub mod spot;
 
-#[allow(unused_imports)]
-use crate::types::{OrderBook, Trade, Position};
+use crate::strategy::{MarketDepth, MarketTicker};
+use crate::types::{Interval, Kline, MarketType, OrderSide, Venue};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer};
+use std::error::Error;
+use std::sync::Arc;
 
-#[allow(dead_code)]
-pub trait Exchange {
-    async fn get_price(&self, symbol: &str) -> Result<f64, Box<dyn std::error::Error>>;
-    async fn place_order(&self, symbol: &str, side: OrderSide, quantity: f64, price: f64) -> Result<String, Box<dyn std::error::Error>>;
-    async fn get_balance(&self) -> Result<f64, Box<dyn std::error::Error>>;
+// 识别无分隔符交易对 (如 BTCUSDT) 时尝试的计价币
+const KNOWN_QUOTES: &[&str] = &["USDT", "USDC", "FDUSD", "BUSD", "BTC", "ETH"];
+
+// 接口返回业务错误码时的错误 (BingX 为 code != 0, Binance 为 HTTP 非 2xx)
+#[derive(Debug, thiserror::Error)]
+#[error("API错误 ({code}): {msg}")]
+pub struct ApiError {
+    pub code: i32,
+    pub msg: String,
+}
+
+// 交易所无关的开仓请求
+#[derive(Debug, Clone)]
+pub struct ExchangeOrder {
+    pub symbol: String,           // 统一格式, 例如 "BTC-USDT"
+    pub market: MarketType,
+    pub side: OrderSide,
+    pub quantity: f64,
+    pub take_profit: Option<f64>, // 止盈触发价 (仅合约)
+    pub stop_loss: Option<f64>,   // 止损触发价 (仅合约)
+    pub reduce_only: bool,        // 只减仓 (平仓单, 仅合约)
+}
+
+// 下单结果
+#[derive(Debug, Clone)]
+pub struct OrderFill {
+    pub order_id: String,
+    pub symbol: String,
+    pub side: OrderSide,
+    pub quantity: f64,  // 成交数量 (未知时为下单数量)
+    pub price: f64,     // 成交均价 (未知时为 0)
+}
+
+// 交易所无关的限价挂单请求 (GTC)
+#[derive(Debug, Clone, PartialEq)]
+pub struct LimitOrder {
+    pub symbol: String,
+    pub market: MarketType,
+    pub side: OrderSide,
+    pub price: f64,
+    pub quantity: f64,
+    pub reduce_only: bool,  // 只减仓 (平仓单)
+}
+
+// 订单状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderStatus {
+    New,
+    PartiallyFilled,
+    Filled,
+    Canceled,
+    Rejected,
+    Expired,
+}
+
+impl OrderStatus {
+    // 交易所返回的状态字符串 (BingX 与 Binance 相同)
+    pub fn parse(status: &str) -> Self {
+        match status.to_ascii_uppercase().as_str() {
+            "PARTIALLY_FILLED" => OrderStatus::PartiallyFilled,
+            "FILLED" => OrderStatus::Filled,
+            "CANCELED" | "CANCELLED" => OrderStatus::Canceled,
+            "REJECTED" => OrderStatus::Rejected,
+            "EXPIRED" => OrderStatus::Expired,
+            _ => OrderStatus::New,
+        }
+    }
+
+    // 仍在挂单中
+    pub fn is_open(&self) -> bool {
+        matches!(self, OrderStatus::New | OrderStatus::PartiallyFilled)
+    }
+}
+
+// 挂单的最新状态
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderUpdate {
+    pub order_id: String,
+    pub symbol: String,
+    pub side: OrderSide,
+    pub price: f64,            // 挂单价
+    pub quantity: f64,         // 下单数量
+    pub filled_quantity: f64,  // 已成交数量
+    pub avg_price: f64,        // 成交均价 (未成交时为 0)
+    pub status: OrderStatus,
+}
+
+impl OrderUpdate {
+    // 成交均价, 未返回时按挂单价计算
+    pub fn fill_price(&self) -> f64 {
+        if self.avg_price > 0.0 { self.avg_price } else { self.price }
+    }
+}
+
+// 交易所抽象: 所有交易对参数与返回值都使用统一格式 "BASE-QUOTE"
+#[async_trait]
+pub trait Exchange: Send + Sync {
+    fn venue(&self) -> Venue;
+
+    // 当前时间 (毫秒), 回放会话时为最近一次响应的录制时间
+    fn now_millis(&self) -> i64 {
+        Utc::now().timestamp_millis()
+    }
+
+    // 回放会话中尚未使用的录制条数 (不在回放时为 None)
+    fn replay_remaining(&self) -> Option<usize> {
+        None
+    }
+
+    // 距上次校时过久时重新同步服务器时间 (不需要校时的交易所无需实现)
+    async fn sync_time_if_stale(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn get_klines(
+        &self,
+        symbol: &str,
+        market: MarketType,
+        interval: Interval,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Kline>, Box<dyn Error + Send + Sync>>;
+
+    async fn get_market_depth(&self, symbol: &str, market: MarketType, limit: Option<u32>) -> Result<MarketDepth, Box<dyn Error + Send + Sync>>;
+
+    async fn get_market_ticker(&self, symbol: &str, market: MarketType) -> Result<MarketTicker, Box<dyn Error + Send + Sync>>;
+
+    async fn get_price(&self, symbol: &str, market: MarketType) -> Result<f64, Box<dyn Error + Send + Sync>>;
+
+    async fn place_order(&self, order: ExchangeOrder) -> Result<OrderFill, Box<dyn Error + Send + Sync>>;
+
+    // 指定资产的可用余额
+    async fn get_balance(&self, asset: &str, market: MarketType) -> Result<f64, Box<dyn Error + Send + Sync>>;
+
+    // 挂限价单 (GTC), 返回挂单状态
+    async fn place_limit_order(&self, _order: LimitOrder) -> Result<OrderUpdate, Box<dyn Error + Send + Sync>> {
+        Err(format!("{:?} 暂不支持限价单", self.venue()).into())
+    }
+
+    // 查询订单状态
+    async fn get_order(&self, _symbol: &str, _market: MarketType, _order_id: &str) -> Result<OrderUpdate, Box<dyn Error + Send + Sync>> {
+        Err(format!("{:?} 暂不支持查询订单", self.venue()).into())
+    }
+
+    // 撤销挂单
+    async fn cancel_order(&self, _symbol: &str, _market: MarketType, _order_id: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Err(format!("{:?} 暂不支持撤单", self.venue()).into())
+    }
+}
+
+// 共享的客户端 (例如同时用于交易管理与行情查询)
+#[async_trait]
+impl<E: Exchange + ?Sized> Exchange for Arc<E> {
+    fn venue(&self) -> Venue {
+        self.as_ref().venue()
+    }
+
+    fn now_millis(&self) -> i64 {
+        self.as_ref().now_millis()
+    }
+
+    fn replay_remaining(&self) -> Option<usize> {
+        self.as_ref().replay_remaining()
+    }
+
+    async fn sync_time_if_stale(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.as_ref().sync_time_if_stale().await
+    }
+
+    async fn get_klines(
+        &self,
+        symbol: &str,
+        market: MarketType,
+        interval: Interval,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Kline>, Box<dyn Error + Send + Sync>> {
+        self.as_ref().get_klines(symbol, market, interval, start_time, end_time, limit).await
+    }
+
+    async fn get_market_depth(&self, symbol: &str, market: MarketType, limit: Option<u32>) -> Result<MarketDepth, Box<dyn Error + Send + Sync>> {
+        self.as_ref().get_market_depth(symbol, market, limit).await
+    }
+
+    async fn get_market_ticker(&self, symbol: &str, market: MarketType) -> Result<MarketTicker, Box<dyn Error + Send + Sync>> {
+        self.as_ref().get_market_ticker(symbol, market).await
+    }
+
+    async fn get_price(&self, symbol: &str, market: MarketType) -> Result<f64, Box<dyn Error + Send + Sync>> {
+        self.as_ref().get_price(symbol, market).await
+    }
+
+    async fn place_order(&self, order: ExchangeOrder) -> Result<OrderFill, Box<dyn Error + Send + Sync>> {
+        self.as_ref().place_order(order).await
+    }
+
+    async fn get_balance(&self, asset: &str, market: MarketType) -> Result<f64, Box<dyn Error + Send + Sync>> {
+        self.as_ref().get_balance(asset, market).await
+    }
+
+    async fn place_limit_order(&self, order: LimitOrder) -> Result<OrderUpdate, Box<dyn Error + Send + Sync>> {
+        self.as_ref().place_limit_order(order).await
+    }
+
+    async fn get_order(&self, symbol: &str, market: MarketType, order_id: &str) -> Result<OrderUpdate, Box<dyn Error + Send + Sync>> {
+        self.as_ref().get_order(symbol, market, order_id).await
+    }
+
+    async fn cancel_order(&self, symbol: &str, market: MarketType, order_id: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.as_ref().cancel_order(symbol, market, order_id).await
+    }
+}
+
+// 统一格式 "BTC-USDT" 转换为交易所格式
+pub fn to_venue_symbol(venue: Venue, symbol: &str) -> String {
+    match venue {
+        Venue::BingX => normalize_symbol(symbol),
+        Venue::Binance => normalize_symbol(symbol).replace('-', ""),
+    }
 }
 
-#[allow(dead_code)]
-pub enum OrderSide {
-    Buy,
-    Sell,
+// 交易所格式 ("BTCUSDT", "btc_usdt", "BTC/USDT") 转换为统一格式 "BTC-USDT"
+pub fn normalize_symbol(symbol: &str) -> String {
+    let upper = symbol.trim().to_ascii_uppercase().replace(['_', '/'], "-");
+    if upper.contains('-') {
+        return upper;
+    }
+    KNOWN_QUOTES.iter()
+        .find(|quote| upper.len() > quote.len() && upper.ends_with(*quote))
+        .map(|quote| format!("{}-{}", &upper[..upper.len() - quote.len()], quote))
+        .unwrap_or(upper)
+}
+
+// 数值字段可能是数字或字符串 (现货涨跌幅还带 "%")
+pub(crate) fn de_f64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumOrStr {
+        Num(f64),
+        Str(String),
+    }
+
+    match NumOrStr::deserialize(deserializer)? {
+        NumOrStr::Num(n) => Ok(n),
+        NumOrStr::Str(s) => s.trim_end_matches('%').parse().map_err(serde::de::Error::custom),
+    }
+}
+
+// 数组格式的K线: [开盘时间, 开, 高, 低, 收, 成交量, 收盘时间, ...], 数值可能是数字或字符串
+pub(crate) fn array_kline(values: &[serde_json::Value]) -> Kline {
+    let number = |v: Option<&serde_json::Value>| -> f64 {
+        match v {
+            Some(serde_json::Value::Number(n)) => n.as_f64().unwrap_or_default(),
+            Some(serde_json::Value::String(s)) => s.parse().unwrap_or_default(),
+            _ => 0.0,
+        }
+    };
+
+    Kline {
+        open_time: number(values.first()) as i64,
+        open: number(values.get(1)),
+        high: number(values.get(2)),
+        low: number(values.get(3)),
+        close: number(values.get(4)),
+        volume: number(values.get(5)),
+        close_time: number(values.get(6)) as i64,
+    }
 } 
\ No newline at end of file
@@ -0,0 +1,158 @@
+use crate::exchange::clock::ServerClock;
+use crate::logging::{REDACTED, SECRET_PARAMS};
+use chrono::Utc;
+use reqwest::{Method, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::error::Error;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::trace;
+
+// 每次请求都会变化的参数, 回放匹配时忽略
+const VOLATILE_PARAMS: &[&str] = &["signature", "timestamp", "recvWindow", "startTime", "endTime"];
+
+// 一次请求/响应记录 (会话文件中每行一条 JSON)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionEntry {
+    pub method: String,
+    pub path: String,
+    pub params: BTreeMap<String, String>,
+    pub status: u16,
+    pub body: String,
+    #[serde(default)]
+    pub time: i64,  // 收到响应时的本地时间 (毫秒), 旧版会话文件中为 0
+}
+
+impl SessionEntry {
+    pub fn new(method: &str, url: &str, status: u16, body: String) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let url = Url::parse(url)?;
+        let params = url.query_pairs()
+            .map(|(k, v)| {
+                let value = if SECRET_PARAMS.contains(&k.as_ref()) {
+                    REDACTED.to_string()
+                } else {
+                    v.into_owned()
+                };
+                (k.into_owned(), value)
+            })
+            .collect();
+
+        Ok(Self {
+            method: method.to_string(),
+            path: url.path().to_string(),
+            params,
+            status,
+            body,
+            time: Utc::now().timestamp_millis(),
+        })
+    }
+
+    // 回放匹配键: 方法 + 路径 + 稳定参数
+    fn replay_key(&self) -> String {
+        let stable = self.params
+            .iter()
+            .filter(|(k, _)| !VOLATILE_PARAMS.contains(&k.as_str()))
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<String>>()
+            .join("&");
+        format!("{} {}?{}", self.method, self.path, stable)
+    }
+}
+
+// 录制模式: 将每个请求/响应追加写入会话文件
+pub struct SessionRecorder {
+    file: Mutex<File>,
+}
+
+impl SessionRecorder {
+    pub fn create(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(path)?;
+        Ok(Self { file: Mutex::new(file) })
+    }
+
+    pub fn record(&self, entry: &SessionEntry) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let line = serde_json::to_string(entry)?;
+        let mut file = self.file.lock().unwrap();
+        writeln!(file, "{}", line)?;
+        file.flush()?;
+        Ok(())
+    }
+}
+
+// 回放模式: 按录制顺序返回相同请求的响应
+pub struct SessionReplayer {
+    entries: Mutex<HashMap<String, VecDeque<SessionEntry>>>,
+}
+
+impl SessionReplayer {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut entries: HashMap<String, VecDeque<SessionEntry>> = HashMap::new();
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: SessionEntry = serde_json::from_str(&line)
+                .map_err(|e| format!("会话文件第 {} 行格式错误: {}", index + 1, e))?;
+            entries.entry(entry.replay_key()).or_default().push_back(entry);
+        }
+
+        Ok(Self { entries: Mutex::new(entries) })
+    }
+
+    pub fn next_response(&self, method: &str, url: &str) -> Result<SessionEntry, Box<dyn Error + Send + Sync>> {
+        let request = SessionEntry::new(method, url, 0, String::new())?;
+        let key = request.replay_key();
+        self.entries.lock().unwrap()
+            .get_mut(&key)
+            .and_then(|queue| queue.pop_front())
+            .ok_or_else(|| format!("会话记录中没有匹配的请求: {}", key).into())
+    }
+
+    // 尚未被回放的记录数
+    pub fn remaining(&self) -> usize {
+        self.entries.lock().unwrap().values().map(|q| q.len()).sum()
+    }
+}
+
+pub enum Session {
+    Record(SessionRecorder),
+    Replay(SessionReplayer),
+}
+
+// 发送请求并返回 (HTTP 状态码, 响应体), 各交易所客户端的录制/回放在此统一处理
+// 回放时不访问网络, 并把时钟拨到该响应的录制时间, 使K线收盘等判断与录制时一致
+pub(crate) async fn send(
+    session: Option<&Session>,
+    clock: &ServerClock,
+    method: &Method,
+    url: &str,
+    request: RequestBuilder,
+) -> Result<(u16, String), Box<dyn Error + Send + Sync>> {
+    if let Some(Session::Replay(replayer)) = session {
+        let entry = replayer.next_response(method.as_str(), url)?;
+        trace!(status = entry.status, body = %entry.body, "回放响应");
+        clock.replay_at(entry.time);
+        return Ok((entry.status, entry.body));
+    }
+
+    let response = request.send().await?;
+    let status = response.status().as_u16();
+    let response_text = response.text().await?;
+    trace!(status, body = %response_text, "收到响应");
+
+    if let Some(Session::Record(recorder)) = session {
+        recorder.record(&SessionEntry::new(method.as_str(), url, status, response_text.clone())?)?;
+    }
+
+    Ok((status, response_text))
+}
@@ -0,0 +1,200 @@
+use crate::exchange::bingx::{Auth, BingXClient};
+use crate::exchange::{array_kline, de_f64};
+use crate::types::{Interval, Kline, OrderSide};
+use chrono::{DateTime, Utc};
+use reqwest::Method;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+
+#[derive(Debug, Clone)]
+pub struct SpotDepth {
+    pub timestamp: i64,
+    pub asks: Vec<(f64, f64)>,  // (价格, 数量)
+    pub bids: Vec<(f64, f64)>,  // (价格, 数量)
+}
+
+#[derive(Debug, Deserialize)]
+struct SpotDepthData {
+    #[serde(default)]
+    ts: i64,
+    asks: Vec<[String; 2]>,
+    bids: Vec<[String; 2]>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotTicker {
+    pub symbol: String,
+    #[serde(deserialize_with = "de_f64")]
+    pub price_change_percent: f64,
+    #[serde(deserialize_with = "de_f64")]
+    pub last_price: f64,
+    #[serde(deserialize_with = "de_f64")]
+    pub high_price: f64,
+    #[serde(deserialize_with = "de_f64")]
+    pub low_price: f64,
+    #[serde(deserialize_with = "de_f64")]
+    pub volume: f64,
+    #[serde(deserialize_with = "de_f64")]
+    pub quote_volume: f64,
+    #[serde(deserialize_with = "de_f64")]
+    pub bid_price: f64,
+    #[serde(deserialize_with = "de_f64")]
+    pub ask_price: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpotBalance {
+    pub asset: String,
+    #[serde(deserialize_with = "de_f64")]
+    pub free: f64,
+    #[serde(deserialize_with = "de_f64")]
+    pub locked: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpotBalanceData {
+    balances: Vec<SpotBalance>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotOrderData {
+    pub symbol: String,
+    pub order_id: i64,
+    #[serde(deserialize_with = "de_f64")]
+    pub price: f64,
+    #[serde(deserialize_with = "de_f64")]
+    pub orig_qty: f64,
+    #[serde(deserialize_with = "de_f64")]
+    pub executed_qty: f64,
+    #[serde(deserialize_with = "de_f64")]
+    pub cummulative_quote_qty: f64,
+    pub status: String,
+    #[serde(rename = "type")]
+    pub order_type: String,
+    pub side: String,
+}
+
+// 现货市价单
+#[derive(Debug, Clone)]
+pub struct SpotOrderRequest {
+    pub symbol: String,
+    pub side: OrderSide,
+    pub quantity: f64,
+}
+
+// BingX 现货接口 (/openApi/spot/...)
+impl BingXClient {
+    pub async fn get_spot_klines(
+        &self,
+        symbol: &str,
+        interval: Interval,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Kline>, Box<dyn Error + Send + Sync>> {
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), symbol.to_string());
+        params.insert("interval".to_string(), interval.as_str().to_string());
+        if let Some(start) = start_time {
+            params.insert("startTime".to_string(), start.timestamp_millis().to_string());
+        }
+        if let Some(end) = end_time {
+            params.insert("endTime".to_string(), end.timestamp_millis().to_string());
+        }
+        if let Some(limit_val) = limit {
+            params.insert("limit".to_string(), limit_val.to_string());
+        }
+
+        // 每根K线为数组: [开盘时间, 开, 高, 低, 收, 成交量, 收盘时间, 成交额]
+        let data: Vec<Vec<serde_json::Value>> = self.request(
+            Method::GET,
+            "/openApi/spot/v2/market/kline",
+            params,
+            Auth::Public,
+        ).await?;
+
+        Ok(data.iter().map(|k| array_kline(k)).collect())
+    }
+
+    pub async fn get_spot_depth(&self, symbol: &str, limit: Option<u32>) -> Result<SpotDepth, Box<dyn Error + Send + Sync>> {
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), symbol.to_string());
+        if let Some(limit_val) = limit {
+            params.insert("limit".to_string(), limit_val.to_string());
+        }
+
+        let data: Option<SpotDepthData> = self.request(
+            Method::GET,
+            "/openApi/spot/v1/market/depth",
+            params,
+            Auth::Public,
+        ).await?;
+        let data = data.ok_or("无深度数据")?;
+
+        let levels = |side: &[[String; 2]]| -> Vec<(f64, f64)> {
+            side.iter()
+                .filter_map(|level| Some((level[0].parse().ok()?, level[1].parse().ok()?)))
+                .collect()
+        };
+
+        Ok(SpotDepth {
+            timestamp: data.ts,
+            asks: levels(&data.asks),
+            bids: levels(&data.bids),
+        })
+    }
+
+    pub async fn get_spot_ticker(&self, symbol: Option<&str>) -> Result<Vec<SpotTicker>, Box<dyn Error + Send + Sync>> {
+        let mut params = BTreeMap::new();
+        if let Some(sym) = symbol {
+            params.insert("symbol".to_string(), sym.to_string());
+        }
+
+        self.request(
+            Method::GET,
+            "/openApi/spot/v1/ticker/24hr",
+            params,
+            Auth::Public,
+        ).await
+    }
+
+    pub async fn get_spot_balances(&self) -> Result<Vec<SpotBalance>, Box<dyn Error + Send + Sync>> {
+        let data: SpotBalanceData = self.request(
+            Method::GET,
+            "/openApi/spot/v1/account/balance",
+            BTreeMap::new(),
+            Auth::Signed,
+        ).await?;
+        Ok(data.balances)
+    }
+
+    // 指定资产的可用余额, 账户中没有该资产时为 0
+    pub async fn get_spot_free_balance(&self, asset: &str) -> Result<f64, Box<dyn Error + Send + Sync>> {
+        let balances = self.get_spot_balances().await?;
+        Ok(balances.iter()
+            .find(|b| b.asset == asset)
+            .map(|b| b.free)
+            .unwrap_or_default())
+    }
+
+    pub async fn place_spot_order(&self, order: SpotOrderRequest) -> Result<SpotOrderData, Box<dyn Error + Send + Sync>> {
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), order.symbol.clone());
+        params.insert("side".to_string(), match order.side {
+            OrderSide::Buy => "BUY".to_string(),
+            OrderSide::Sell => "SELL".to_string(),
+        });
+        params.insert("type".to_string(), "MARKET".to_string());
+        params.insert("quantity".to_string(), format!("{}", order.quantity));
+
+        self.request(
+            Method::POST,
+            "/openApi/spot/v1/trade/order",
+            params,
+            Auth::Signed,
+        ).await
+    }
+}
@@ -0,0 +1,132 @@
+pub mod moving_average;
+pub mod oscillators;
+pub mod trend;
+pub mod volatility;
+pub mod volume;
+
+use crate::types::Kline;
+
+pub use moving_average::{EMA, SMA, WMA};
+pub use oscillators::{MACDIndicator, StochasticOutput, Stochastic, RSI};
+pub use trend::{ADXOutput, ADX};
+pub use volatility::{BollingerBands, BollingerOutput, SuperTrend, SuperTrendOutput, ATR};
+pub use volume::{OBV, VWAP};
+
+// 流式指标: 每根K线调用一次 update, 计算量与历史长度无关
+pub trait Indicator {
+    type Input;
+    type Output: Copy;
+
+    // 输入一个新数据, 预热完成后返回最新值
+    fn update(&mut self, input: Self::Input) -> Option<Self::Output>;
+
+    // 最新值 (预热期间为 None)
+    fn value(&self) -> Option<Self::Output>;
+
+    // 产生第一个值所需的输入数量
+    fn warm_up(&self) -> usize;
+
+    // 清空状态, 重新开始预热
+    fn reset(&mut self);
+
+    fn is_ready(&self) -> bool {
+        self.value().is_some()
+    }
+}
+
+// 需要最高/最低价和成交量的指标使用的K线数据
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bar {
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+impl Bar {
+    pub fn new(high: f64, low: f64, close: f64, volume: f64) -> Self {
+        Self { high, low, close, volume }
+    }
+
+    // 典型价格 (最高 + 最低 + 收盘) / 3
+    pub fn typical_price(&self) -> f64 {
+        (self.high + self.low + self.close) / 3.0
+    }
+}
+
+impl From<&Kline> for Bar {
+    fn from(kline: &Kline) -> Self {
+        Self::new(kline.high, kline.low, kline.close, kline.volume)
+    }
+}
+
+// 固定容量的环形缓冲区, 写满后覆盖最旧的数据
+#[derive(Debug, Clone)]
+pub struct RingBuffer<T> {
+    data: Vec<T>,
+    capacity: usize,
+    head: usize,  // 最旧元素的位置
+}
+
+impl<T: Copy> RingBuffer<T> {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            data: Vec::with_capacity(capacity),
+            capacity,
+            head: 0,
+        }
+    }
+
+    // 追加新元素, 缓冲区已满时返回被覆盖的最旧元素
+    pub fn push(&mut self, value: T) -> Option<T> {
+        if self.data.len() < self.capacity {
+            self.data.push(value);
+            return None;
+        }
+        let evicted = std::mem::replace(&mut self.data[self.head], value);
+        self.head = (self.head + 1) % self.capacity;
+        Some(evicted)
+    }
+
+    // 按时间顺序访问, 0 为最旧
+    pub fn get(&self, index: usize) -> Option<T> {
+        if index >= self.data.len() {
+            return None;
+        }
+        Some(self.data[(self.head + index) % self.data.len()])
+    }
+
+    pub fn first(&self) -> Option<T> {
+        self.get(0)
+    }
+
+    pub fn last(&self) -> Option<T> {
+        self.len().checked_sub(1).and_then(|i| self.get(i))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        (0..self.data.len()).filter_map(move |i| self.get(i))
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.data.len() == self.capacity
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.head = 0;
+    }
+}
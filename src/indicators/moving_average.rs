@@ -0,0 +1,154 @@
+use super::{Indicator, RingBuffer};
+
+// 简单移动平均
+#[derive(Debug, Clone)]
+pub struct SMA {
+    period: usize,
+    window: RingBuffer<f64>,
+    sum: f64,
+}
+
+impl SMA {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            window: RingBuffer::new(period),
+            sum: 0.0,
+        }
+    }
+}
+
+impl Indicator for SMA {
+    type Input = f64;
+    type Output = f64;
+
+    fn update(&mut self, price: f64) -> Option<f64> {
+        self.sum += price;
+        if let Some(evicted) = self.window.push(price) {
+            self.sum -= evicted;
+        }
+        self.value()
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.window.is_full().then(|| self.sum / self.period as f64)
+    }
+
+    fn warm_up(&self) -> usize {
+        self.period
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.sum = 0.0;
+    }
+}
+
+// 指数移动平均, 用前 period 个价格的简单平均作为初始值
+#[derive(Debug, Clone)]
+pub struct EMA {
+    period: usize,
+    multiplier: f64,
+    count: usize,
+    seed_sum: f64,
+    value: Option<f64>,
+}
+
+impl EMA {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            multiplier: 2.0 / (period as f64 + 1.0),
+            count: 0,
+            seed_sum: 0.0,
+            value: None,
+        }
+    }
+}
+
+impl Indicator for EMA {
+    type Input = f64;
+    type Output = f64;
+
+    fn update(&mut self, price: f64) -> Option<f64> {
+        self.count += 1;
+        self.value = match self.value {
+            Some(ema) => Some((price - ema) * self.multiplier + ema),
+            None => {
+                self.seed_sum += price;
+                (self.count == self.period).then(|| self.seed_sum / self.period as f64)
+            }
+        };
+        self.value
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.value
+    }
+
+    fn warm_up(&self) -> usize {
+        self.period
+    }
+
+    fn reset(&mut self) {
+        self.count = 0;
+        self.seed_sum = 0.0;
+        self.value = None;
+    }
+}
+
+// 线性加权移动平均, 最新价格权重为 period, 最旧为 1
+#[derive(Debug, Clone)]
+pub struct WMA {
+    period: usize,
+    window: RingBuffer<f64>,
+    sum: f64,           // 窗口内价格之和
+    weighted_sum: f64,  // 窗口内加权和
+}
+
+impl WMA {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            window: RingBuffer::new(period),
+            sum: 0.0,
+            weighted_sum: 0.0,
+        }
+    }
+}
+
+impl Indicator for WMA {
+    type Input = f64;
+    type Output = f64;
+
+    fn update(&mut self, price: f64) -> Option<f64> {
+        if self.window.is_full() {
+            // 窗口滑动: 所有旧价格权重减一, 新价格权重为 period
+            self.weighted_sum += self.period as f64 * price - self.sum;
+            self.sum += price - self.window.push(price).unwrap_or_default();
+        } else {
+            self.window.push(price);
+            self.weighted_sum += self.window.len() as f64 * price;
+            self.sum += price;
+        }
+        self.value()
+    }
+
+    fn value(&self) -> Option<f64> {
+        let denominator = (self.period * (self.period + 1)) as f64 / 2.0;
+        self.window.is_full().then(|| self.weighted_sum / denominator)
+    }
+
+    fn warm_up(&self) -> usize {
+        self.period
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.sum = 0.0;
+        self.weighted_sum = 0.0;
+    }
+}
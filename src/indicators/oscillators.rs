@@ -0,0 +1,224 @@
+use std::collections::VecDeque;
+use crate::types::MACD;
+use super::{Bar, Indicator, EMA, SMA};
+
+// MACD: 快慢 EMA 之差, 信号线为 MACD 线的 EMA
+#[derive(Debug, Clone)]
+pub struct MACDIndicator {
+    fast: EMA,
+    slow: EMA,
+    signal: EMA,
+    value: Option<MACD>,
+}
+
+impl MACDIndicator {
+    pub fn new(fast_period: usize, slow_period: usize, signal_period: usize) -> Self {
+        Self {
+            fast: EMA::new(fast_period),
+            slow: EMA::new(slow_period),
+            signal: EMA::new(signal_period),
+            value: None,
+        }
+    }
+}
+
+impl Indicator for MACDIndicator {
+    type Input = f64;
+    type Output = MACD;
+
+    fn update(&mut self, price: f64) -> Option<MACD> {
+        let fast = self.fast.update(price);
+        let slow = self.slow.update(price);
+        if let (Some(fast), Some(slow)) = (fast, slow) {
+            let macd = fast - slow;
+            if let Some(signal) = self.signal.update(macd) {
+                self.value = Some(MACD {
+                    macd,
+                    signal,
+                    histogram: macd - signal,
+                });
+            }
+        }
+        self.value
+    }
+
+    fn value(&self) -> Option<MACD> {
+        self.value
+    }
+
+    fn warm_up(&self) -> usize {
+        self.fast.warm_up().max(self.slow.warm_up()) + self.signal.warm_up() - 1
+    }
+
+    fn reset(&mut self) {
+        self.fast.reset();
+        self.slow.reset();
+        self.signal.reset();
+        self.value = None;
+    }
+}
+
+// 相对强弱指数 (Wilder 平滑)
+#[derive(Debug, Clone)]
+pub struct RSI {
+    period: usize,
+    previous: Option<f64>,
+    changes: usize,
+    avg_gain: f64,
+    avg_loss: f64,
+    value: Option<f64>,
+}
+
+impl RSI {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            previous: None,
+            changes: 0,
+            avg_gain: 0.0,
+            avg_loss: 0.0,
+            value: None,
+        }
+    }
+}
+
+impl Indicator for RSI {
+    type Input = f64;
+    type Output = f64;
+
+    fn update(&mut self, price: f64) -> Option<f64> {
+        // 第一个数据只作为基准
+        let previous = self.previous.replace(price)?;
+
+        let change = price - previous;
+        let (gain, loss) = (change.max(0.0), (-change).max(0.0));
+        let period = self.period as f64;
+        self.changes += 1;
+
+        if self.changes <= self.period {
+            // 预热期: 累计前 period 个涨跌幅的简单平均
+            self.avg_gain += gain / period;
+            self.avg_loss += loss / period;
+            if self.changes < self.period {
+                return None;
+            }
+        } else {
+            self.avg_gain = (self.avg_gain * (period - 1.0) + gain) / period;
+            self.avg_loss = (self.avg_loss * (period - 1.0) + loss) / period;
+        }
+
+        self.value = Some(if self.avg_loss == 0.0 {
+            if self.avg_gain == 0.0 { 50.0 } else { 100.0 }
+        } else {
+            100.0 - 100.0 / (1.0 + self.avg_gain / self.avg_loss)
+        });
+        self.value
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.value
+    }
+
+    fn warm_up(&self) -> usize {
+        self.period + 1
+    }
+
+    fn reset(&mut self) {
+        self.previous = None;
+        self.changes = 0;
+        self.avg_gain = 0.0;
+        self.avg_loss = 0.0;
+        self.value = None;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StochasticOutput {
+    pub k: f64,  // %K
+    pub d: f64,  // %D (%K 的简单移动平均)
+}
+
+// 随机指标 KD, 用单调队列维护窗口内最高/最低价
+#[derive(Debug, Clone)]
+pub struct Stochastic {
+    k_period: usize,
+    index: usize,
+    highs: VecDeque<(usize, f64)>,  // 单调递减
+    lows: VecDeque<(usize, f64)>,   // 单调递增
+    d: SMA,
+    value: Option<StochasticOutput>,
+}
+
+impl Stochastic {
+    pub fn new(k_period: usize, d_period: usize) -> Self {
+        Self {
+            k_period: k_period.max(1),
+            index: 0,
+            highs: VecDeque::new(),
+            lows: VecDeque::new(),
+            d: SMA::new(d_period),
+            value: None,
+        }
+    }
+}
+
+impl Indicator for Stochastic {
+    type Input = Bar;
+    type Output = StochasticOutput;
+
+    fn update(&mut self, bar: Bar) -> Option<StochasticOutput> {
+        let index = self.index;
+        self.index += 1;
+
+        while self.highs.back().is_some_and(|&(_, high)| high <= bar.high) {
+            self.highs.pop_back();
+        }
+        self.highs.push_back((index, bar.high));
+        while self.lows.back().is_some_and(|&(_, low)| low >= bar.low) {
+            self.lows.pop_back();
+        }
+        self.lows.push_back((index, bar.low));
+
+        // 移除窗口外的数据
+        let window_start = (index + 1).saturating_sub(self.k_period);
+        while self.highs.front().is_some_and(|&(i, _)| i < window_start) {
+            self.highs.pop_front();
+        }
+        while self.lows.front().is_some_and(|&(i, _)| i < window_start) {
+            self.lows.pop_front();
+        }
+
+        if self.index < self.k_period {
+            return None;
+        }
+
+        let highest = self.highs.front().map(|&(_, high)| high).unwrap_or(bar.high);
+        let lowest = self.lows.front().map(|&(_, low)| low).unwrap_or(bar.low);
+        let k = if highest > lowest {
+            (bar.close - lowest) / (highest - lowest) * 100.0
+        } else {
+            50.0
+        };
+
+        if let Some(d) = self.d.update(k) {
+            self.value = Some(StochasticOutput { k, d });
+        }
+        self.value
+    }
+
+    fn value(&self) -> Option<StochasticOutput> {
+        self.value
+    }
+
+    fn warm_up(&self) -> usize {
+        self.k_period + self.d.warm_up() - 1
+    }
+
+    fn reset(&mut self) {
+        self.index = 0;
+        self.highs.clear();
+        self.lows.clear();
+        self.d.reset();
+        self.value = None;
+    }
+}
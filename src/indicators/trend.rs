@@ -0,0 +1,106 @@
+use super::{Bar, Indicator};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ADXOutput {
+    pub adx: f64,
+    pub plus_di: f64,   // +DI
+    pub minus_di: f64,  // -DI
+}
+
+// 平均趋向指数 (Wilder 平滑)
+#[derive(Debug, Clone)]
+pub struct ADX {
+    period: usize,
+    previous: Option<Bar>,
+    count: usize,        // 已计算的方向变动数量
+    smoothed_tr: f64,
+    smoothed_plus_dm: f64,
+    smoothed_minus_dm: f64,
+    dx_count: usize,
+    adx_seed_sum: f64,
+    value: Option<ADXOutput>,
+}
+
+impl ADX {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            previous: None,
+            count: 0,
+            smoothed_tr: 0.0,
+            smoothed_plus_dm: 0.0,
+            smoothed_minus_dm: 0.0,
+            dx_count: 0,
+            adx_seed_sum: 0.0,
+            value: None,
+        }
+    }
+}
+
+impl Indicator for ADX {
+    type Input = Bar;
+    type Output = ADXOutput;
+
+    fn update(&mut self, bar: Bar) -> Option<ADXOutput> {
+        // 第一个数据只作为基准
+        let previous = self.previous.replace(bar)?;
+
+        let up_move = bar.high - previous.high;
+        let down_move = previous.low - bar.low;
+        let plus_dm = if up_move > down_move && up_move > 0.0 { up_move } else { 0.0 };
+        let minus_dm = if down_move > up_move && down_move > 0.0 { down_move } else { 0.0 };
+        let tr = (bar.high - bar.low)
+            .max((bar.high - previous.close).abs())
+            .max((bar.low - previous.close).abs());
+
+        let period = self.period as f64;
+        self.count += 1;
+        if self.count <= self.period {
+            self.smoothed_tr += tr;
+            self.smoothed_plus_dm += plus_dm;
+            self.smoothed_minus_dm += minus_dm;
+            if self.count < self.period {
+                return None;
+            }
+        } else {
+            self.smoothed_tr += tr - self.smoothed_tr / period;
+            self.smoothed_plus_dm += plus_dm - self.smoothed_plus_dm / period;
+            self.smoothed_minus_dm += minus_dm - self.smoothed_minus_dm / period;
+        }
+
+        let (plus_di, minus_di) = if self.smoothed_tr > 0.0 {
+            (100.0 * self.smoothed_plus_dm / self.smoothed_tr, 100.0 * self.smoothed_minus_dm / self.smoothed_tr)
+        } else {
+            (0.0, 0.0)
+        };
+        let di_sum = plus_di + minus_di;
+        let dx = if di_sum > 0.0 { 100.0 * (plus_di - minus_di).abs() / di_sum } else { 0.0 };
+
+        self.dx_count += 1;
+        let adx = match self.value {
+            Some(previous) => (previous.adx * (period - 1.0) + dx) / period,
+            None => {
+                self.adx_seed_sum += dx;
+                if self.dx_count < self.period {
+                    return None;
+                }
+                self.adx_seed_sum / period
+            }
+        };
+
+        self.value = Some(ADXOutput { adx, plus_di, minus_di });
+        self.value
+    }
+
+    fn value(&self) -> Option<ADXOutput> {
+        self.value
+    }
+
+    fn warm_up(&self) -> usize {
+        2 * self.period
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.period);
+    }
+}
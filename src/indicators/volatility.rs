@@ -0,0 +1,250 @@
+use super::{Bar, Indicator, RingBuffer};
+
+// 真实波动幅度
+fn true_range(bar: &Bar, previous_close: Option<f64>) -> f64 {
+    let range = bar.high - bar.low;
+    match previous_close {
+        Some(close) => range
+            .max((bar.high - close).abs())
+            .max((bar.low - close).abs()),
+        None => range,
+    }
+}
+
+// 平均真实波幅 (Wilder 平滑)
+#[derive(Debug, Clone)]
+pub struct ATR {
+    period: usize,
+    previous_close: Option<f64>,
+    count: usize,
+    seed_sum: f64,
+    value: Option<f64>,
+}
+
+impl ATR {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            previous_close: None,
+            count: 0,
+            seed_sum: 0.0,
+            value: None,
+        }
+    }
+}
+
+impl Indicator for ATR {
+    type Input = Bar;
+    type Output = f64;
+
+    fn update(&mut self, bar: Bar) -> Option<f64> {
+        let tr = true_range(&bar, self.previous_close.replace(bar.close));
+        let period = self.period as f64;
+        self.count += 1;
+
+        self.value = match self.value {
+            Some(atr) => Some((atr * (period - 1.0) + tr) / period),
+            None => {
+                self.seed_sum += tr;
+                (self.count == self.period).then(|| self.seed_sum / period)
+            }
+        };
+        self.value
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.value
+    }
+
+    fn warm_up(&self) -> usize {
+        self.period
+    }
+
+    fn reset(&mut self) {
+        self.previous_close = None;
+        self.count = 0;
+        self.seed_sum = 0.0;
+        self.value = None;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BollingerOutput {
+    pub upper: f64,
+    pub middle: f64,
+    pub lower: f64,
+}
+
+impl BollingerOutput {
+    // 带宽 (上下轨距离占中轨的比例)
+    pub fn bandwidth(&self) -> f64 {
+        if self.middle == 0.0 {
+            return 0.0;
+        }
+        (self.upper - self.lower) / self.middle
+    }
+
+    // %B: 价格在通道中的位置, 0 为下轨, 1 为上轨
+    pub fn percent_b(&self, price: f64) -> f64 {
+        if self.upper == self.lower {
+            return 0.5;
+        }
+        (price - self.lower) / (self.upper - self.lower)
+    }
+}
+
+// 布林带, 标准差用滑动窗口的 Welford 算法维护 (总体标准差)
+#[derive(Debug, Clone)]
+pub struct BollingerBands {
+    period: usize,
+    multiplier: f64,
+    window: RingBuffer<f64>,
+    mean: f64,
+    m2: f64,  // 与均值差的平方和
+}
+
+impl BollingerBands {
+    pub fn new(period: usize, multiplier: f64) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            multiplier,
+            window: RingBuffer::new(period),
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+}
+
+impl Indicator for BollingerBands {
+    type Input = f64;
+    type Output = BollingerOutput;
+
+    fn update(&mut self, price: f64) -> Option<BollingerOutput> {
+        match self.window.push(price) {
+            Some(evicted) => {
+                let old_mean = self.mean;
+                self.mean += (price - evicted) / self.period as f64;
+                self.m2 += (price - evicted) * (price - self.mean + evicted - old_mean);
+            }
+            None => {
+                let delta = price - self.mean;
+                self.mean += delta / self.window.len() as f64;
+                self.m2 += delta * (price - self.mean);
+            }
+        }
+        self.value()
+    }
+
+    fn value(&self) -> Option<BollingerOutput> {
+        if !self.window.is_full() {
+            return None;
+        }
+        let std_dev = (self.m2.max(0.0) / self.period as f64).sqrt();
+        Some(BollingerOutput {
+            upper: self.mean + self.multiplier * std_dev,
+            middle: self.mean,
+            lower: self.mean - self.multiplier * std_dev,
+        })
+    }
+
+    fn warm_up(&self) -> usize {
+        self.period
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.mean = 0.0;
+        self.m2 = 0.0;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SuperTrendOutput {
+    pub value: f64,      // 上升趋势时为下轨, 下降趋势时为上轨
+    pub uptrend: bool,
+}
+
+// SuperTrend: 以 (最高 + 最低) / 2 ± multiplier * ATR 为通道, 收盘价突破通道时翻转趋势
+#[derive(Debug, Clone)]
+pub struct SuperTrend {
+    multiplier: f64,
+    atr: ATR,
+    previous_close: Option<f64>,
+    upper: f64,
+    lower: f64,
+    value: Option<SuperTrendOutput>,
+}
+
+impl SuperTrend {
+    pub fn new(period: usize, multiplier: f64) -> Self {
+        Self {
+            multiplier,
+            atr: ATR::new(period),
+            previous_close: None,
+            upper: 0.0,
+            lower: 0.0,
+            value: None,
+        }
+    }
+}
+
+impl Indicator for SuperTrend {
+    type Input = Bar;
+    type Output = SuperTrendOutput;
+
+    fn update(&mut self, bar: Bar) -> Option<SuperTrendOutput> {
+        let previous_close = self.previous_close.replace(bar.close);
+        let atr = self.atr.update(bar)?;
+
+        let middle = (bar.high + bar.low) / 2.0;
+        let basic_upper = middle + self.multiplier * atr;
+        let basic_lower = middle - self.multiplier * atr;
+
+        let previous = match self.value {
+            Some(previous) => previous,
+            None => {
+                self.upper = basic_upper;
+                self.lower = basic_lower;
+                let uptrend = bar.close >= middle;
+                let value = if uptrend { basic_lower } else { basic_upper };
+                self.value = Some(SuperTrendOutput { value, uptrend });
+                return self.value;
+            }
+        };
+
+        // 通道只向趋势方向收紧, 上一根收盘价突破时重置
+        let previous_close = previous_close.unwrap_or(bar.close);
+        if basic_upper < self.upper || previous_close > self.upper {
+            self.upper = basic_upper;
+        }
+        if basic_lower > self.lower || previous_close < self.lower {
+            self.lower = basic_lower;
+        }
+
+        let uptrend = if previous.uptrend {
+            bar.close >= self.lower
+        } else {
+            bar.close > self.upper
+        };
+        let value = if uptrend { self.lower } else { self.upper };
+        self.value = Some(SuperTrendOutput { value, uptrend });
+        self.value
+    }
+
+    fn value(&self) -> Option<SuperTrendOutput> {
+        self.value
+    }
+
+    fn warm_up(&self) -> usize {
+        self.atr.warm_up()
+    }
+
+    fn reset(&mut self) {
+        self.atr.reset();
+        self.previous_close = None;
+        self.upper = 0.0;
+        self.lower = 0.0;
+        self.value = None;
+    }
+}
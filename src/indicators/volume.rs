@@ -0,0 +1,96 @@
+use super::{Bar, Indicator, RingBuffer};
+
+// 能量潮: 收盘上涨累加成交量, 下跌扣减成交量
+#[derive(Debug, Clone, Default)]
+pub struct OBV {
+    previous_close: Option<f64>,
+    value: Option<f64>,
+}
+
+impl OBV {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Indicator for OBV {
+    type Input = Bar;
+    type Output = f64;
+
+    fn update(&mut self, bar: Bar) -> Option<f64> {
+        let obv = self.value.unwrap_or_default();
+        self.value = Some(match self.previous_close.replace(bar.close) {
+            Some(close) if bar.close > close => obv + bar.volume,
+            Some(close) if bar.close < close => obv - bar.volume,
+            _ => obv,
+        });
+        self.value
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.value
+    }
+
+    fn warm_up(&self) -> usize {
+        1
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+// 滚动成交量加权平均价 (按典型价格计算)
+#[derive(Debug, Clone)]
+pub struct VWAP {
+    window: RingBuffer<(f64, f64)>,  // (价格 * 成交量, 成交量)
+    price_volume: f64,
+    volume: f64,
+    value: Option<f64>,
+}
+
+impl VWAP {
+    pub fn new(period: usize) -> Self {
+        Self {
+            window: RingBuffer::new(period),
+            price_volume: 0.0,
+            volume: 0.0,
+            value: None,
+        }
+    }
+}
+
+impl Indicator for VWAP {
+    type Input = Bar;
+    type Output = f64;
+
+    fn update(&mut self, bar: Bar) -> Option<f64> {
+        let entry = (bar.typical_price() * bar.volume, bar.volume);
+        self.price_volume += entry.0;
+        self.volume += entry.1;
+        if let Some((price_volume, volume)) = self.window.push(entry) {
+            self.price_volume -= price_volume;
+            self.volume -= volume;
+        }
+
+        if self.window.is_full() && self.volume > 0.0 {
+            self.value = Some(self.price_volume / self.volume);
+        }
+        self.value
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.value
+    }
+
+    fn warm_up(&self) -> usize {
+        self.window.capacity()
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.price_volume = 0.0;
+        self.volume = 0.0;
+        self.value = None;
+    }
+}
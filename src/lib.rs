@@ -0,0 +1,7 @@
+pub mod config;
+pub mod exchange;
+pub mod indicators;
+pub mod logging;
+pub mod strategy;
+pub mod types;
+pub mod trading;
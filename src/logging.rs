@@ -0,0 +1,117 @@
+use reqwest::Url;
+use std::env;
+use std::path::PathBuf;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::EnvFilter;
+
+// 日志中替换敏感参数的占位符
+pub const REDACTED: &str = "<redacted>";
+
+// 需要脱敏的请求参数
+pub const SECRET_PARAMS: &[&str] = &["signature", "apiKey", "secretKey"];
+
+// 默认日志级别: 本程序 info, 依赖库 warn
+const DEFAULT_FILTER: &str = "warn,crypto_trading_bot=info";
+
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    pub filter: String,          // EnvFilter 指令, 支持按模块设置级别
+    pub json: bool,              // 输出 JSON 格式
+    pub dir: Option<PathBuf>,    // 日志目录, 设置后写入滚动日志文件
+    pub rotation: Rotation,      // 日志文件滚动周期
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            filter: DEFAULT_FILTER.to_string(),
+            json: false,
+            dir: None,
+            rotation: Rotation::DAILY,
+        }
+    }
+}
+
+impl LogConfig {
+    // 从环境变量读取:
+    //   BOT_LOG          日志级别, 例如 "info,crypto_trading_bot::exchange=debug"
+    //   BOT_LOG_FORMAT   "json" 输出 JSON
+    //   BOT_LOG_DIR      日志目录
+    //   BOT_LOG_ROTATION "minutely" / "hourly" / "daily" / "never"
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Ok(filter) = env::var("BOT_LOG") {
+            config.filter = filter;
+        }
+        config.json = env::var("BOT_LOG_FORMAT")
+            .map(|f| f.eq_ignore_ascii_case("json"))
+            .unwrap_or(false);
+        config.dir = env::var("BOT_LOG_DIR").ok().map(PathBuf::from);
+        if let Ok(rotation) = env::var("BOT_LOG_ROTATION") {
+            config.rotation = match rotation.to_ascii_lowercase().as_str() {
+                "minutely" => Rotation::MINUTELY,
+                "hourly" => Rotation::HOURLY,
+                "never" => Rotation::NEVER,
+                _ => Rotation::DAILY,
+            };
+        }
+        config
+    }
+}
+
+// 初始化全局日志, 返回的 guard 需要在程序退出前保持存活以刷新文件缓冲
+pub fn init_logging(config: &LogConfig) -> Option<WorkerGuard> {
+    let filter = EnvFilter::try_new(&config.filter)
+        .unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+
+    let (writer, guard) = match &config.dir {
+        Some(dir) => {
+            let appender = RollingFileAppender::new(config.rotation.clone(), dir, "trading_bot.log");
+            let (non_blocking, guard) = tracing_appender::non_blocking(appender);
+            (BoxMakeWriter::new(non_blocking), Some(guard))
+        }
+        None => (BoxMakeWriter::new(std::io::stderr), None),
+    };
+
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_target(true)
+        .with_ansi(config.dir.is_none())
+        .with_writer(writer);
+
+    let result = if config.json {
+        builder.json().try_init()
+    } else {
+        builder.try_init()
+    };
+    if let Err(e) = result {
+        eprintln!("日志初始化失败: {}", e);
+    }
+
+    guard
+}
+
+// 替换 URL 中的签名等敏感参数
+pub fn redact_url(url: &str) -> String {
+    let mut parsed = match Url::parse(url) {
+        Ok(parsed) => parsed,
+        Err(_) => return url.to_string(),
+    };
+    let pairs: Vec<(String, String)> = parsed.query_pairs()
+        .map(|(k, v)| {
+            let value = if SECRET_PARAMS.contains(&k.as_ref()) {
+                REDACTED.to_string()
+            } else {
+                v.into_owned()
+            };
+            (k.into_owned(), value)
+        })
+        .collect();
+    if pairs.is_empty() {
+        return parsed.to_string();
+    }
+    parsed.query_pairs_mut().clear().extend_pairs(pairs);
+    parsed.to_string()
+}
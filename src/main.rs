@@ -1,20 +1,20 @@
-mod config;
-mod exchange;
-mod strategy;
-mod types;
-mod trading;
-
-use chrono::{Duration, TimeZone, Utc};
+use chrono::{TimeZone, Utc};
 use dotenv::dotenv;
-use exchange::bingx::BingXClient;
-use types::{Interval, OrderType, OrderSide, OrderRequest, CurrencyConfig};
-use trading::TradingManager;
+use crypto_trading_bot::exchange::bingx::BingXClient;
+use crypto_trading_bot::logging::{init_logging, LogConfig};
+use crypto_trading_bot::exchange::binance::BinanceFuturesClient;
+use crypto_trading_bot::types::{self, OrderSide, CurrencyConfig, MarketType, Regime, StrategyConfig, Venue};
+use crypto_trading_bot::strategy::{PluginCommand, StrategyRegistry};
+use crypto_trading_bot::trading::TradingManager;
 use std::env;
-use tokio::time::{sleep, Duration as TokioDuration};
-use serde_json;
+use std::error::Error;
+use std::sync::Arc;
+
+// 预设币种成交后的冷却时间 (秒)
+const PRESET_COOLDOWN_SECS: u64 = 15 * 60;
 
 async fn init_currencies() -> Vec<CurrencyConfig> {
-    vec![
+    let presets = vec![
         CurrencyConfig::new(
             "BTC-USDT",
             "BTC",
@@ -95,7 +95,11 @@ async fn init_currencies() -> Vec<CurrencyConfig> {
             5.0,      // 最小名义价值
             20,       // 杠杆倍数
         ),
-    ]
+    ];
+
+    presets.into_iter()
+        .map(|config| config.with_cooldown(PRESET_COOLDOWN_SECS))
+        .collect()
 }
 
 async fn print_currency_status(manager: &TradingManager) {
@@ -122,6 +126,36 @@ async fn print_currency_status(manager: &TradingManager) {
         println!("  数量精度: {}", status.config.qty_precision);
         println!("  最小名义价值: {}", status.config.min_notional);
         println!("  杠杆倍数: {}", status.config.leverage);
+        println!("  市场类型: {:?}", status.config.market);
+        println!("  交易所: {:?}", status.config.venue);
+        match &status.owner {
+            Some(owner) => println!("  策略: 由 {} 的策略管理 (对冲腿)", owner),
+            None => println!("  策略: {}", status.config.strategy),
+        }
+        if !status.config.legs.is_empty() {
+            let legs: Vec<&str> = status.config.legs.iter().map(|leg| leg.symbol.as_str()).collect();
+            println!("  对冲腿: {}", legs.join(", "));
+        }
+        println!("  冷却时间: {} 秒", status.config.cooldown_secs);
+        if !status.config.regimes.is_empty() {
+            let regimes: Vec<String> = status.config.regimes.iter().map(|regime| regime.to_string()).collect();
+            println!("  允许开仓的市场状态: {}", regimes.join(", "));
+        }
+        if let Some(reading) = manager.regime(&symbol).await {
+            println!("  市场状态: {}", reading);
+        }
+        println!("  策略预热: {}/{} 根K线{}",
+            status.warm_up.loaded,
+            status.warm_up.required,
+            if status.warm_up.ready { " (已就绪)" } else { " (预热中)" }
+        );
+        if let Some(last_trade_at) = status.last_trade_at {
+            println!("  最近成交: {}",
+                Utc.timestamp_millis_opt(last_trade_at)
+                    .unwrap()
+                    .format("%Y-%m-%d %H:%M:%S")
+            );
+        }
         
         // 显示持仓信息
         if let Some(position) = status.current_position {
@@ -134,23 +168,113 @@ async fn print_currency_status(manager: &TradingManager) {
         } else {
             println!("\n当前无持仓");
         }
+
+        // 显示策略内部状态和限价挂单
+        let state = manager.strategy_state(&symbol).await;
+        if !state.is_empty() {
+            println!("\n策略状态:");
+            for line in state {
+                println!("  {}", line);
+            }
+        }
+        let resting = manager.resting_orders(&symbol).await;
+        if !resting.is_empty() {
+            println!("\n限价挂单:");
+            for order in resting {
+                println!("  {:?} {} @ {} ({}, 订单号 {})",
+                    order.intent.side, order.intent.quantity, order.intent.price, order.intent.key, order.order_id);
+            }
+        }
     }
 }
 
-async fn init_manager() -> TradingManager {
+// 返回交易管理器及其使用的 BingX 客户端 (菜单中的行情查询共用该客户端)
+async fn init_manager() -> (TradingManager, Arc<BingXClient>) {
+    // 回放模式: 使用会话文件中的录制数据离线运行
+    if let Ok(path) = env::var("BINGX_REPLAY_SESSION") {
+        println!("回放会话文件: {}", path);
+        let client = Arc::new(BingXClient::replay_from(&path).expect("加载会话文件失败"));
+        let mut manager = TradingManager::new(client.clone());
+        if let Ok(path) = env::var("BINANCE_REPLAY_SESSION") {
+            println!("回放 Binance 会话文件: {}", path);
+            manager = manager.with_exchange(BinanceFuturesClient::replay_from(&path).expect("加载会话文件失败"));
+        }
+        return (manager, client);
+    }
+
     let api_key = env::var("BINGX_API_KEY").expect("未设置 BINGX_API_KEY");
     let api_secret = env::var("BINGX_API_SECRET").expect("未设置 BINGX_API_SECRET");
     
-    let client = BingXClient::new(api_key, api_secret);
-    TradingManager::new(client)
+    let mut client = BingXClient::new(api_key, api_secret);
+
+    // 签名请求的有效时间窗口 (毫秒)
+    if let Some(recv_window) = env::var("BINGX_RECV_WINDOW").ok().and_then(|v| v.parse().ok()) {
+        client = client.with_recv_window(recv_window);
+    }
+
+    // 录制模式: 保存所有请求/响应, 便于复现问题
+    if let Ok(path) = env::var("BINGX_RECORD_SESSION") {
+        println!("录制会话到: {}", path);
+        client = client.record_to(&path).expect("创建会话文件失败");
+    }
+
+    // 与服务器时间同步, 避免本地时钟漂移导致签名请求被拒绝
+    match client.sync_time().await {
+        Ok(offset) => println!("服务器时间偏移: {} ms", offset),
+        Err(e) => println!("同步服务器时间失败: {}", e),
+    }
+
+    let client = Arc::new(client);
+    let mut manager = TradingManager::new(client.clone());
+
+    // 可选: Binance U本位合约 (币种配置中指定交易所为 binance 时使用)
+    if let (Ok(key), Ok(secret)) = (env::var("BINANCE_API_KEY"), env::var("BINANCE_API_SECRET")) {
+        let mut binance = BinanceFuturesClient::new(key, secret);
+        if let Ok(path) = env::var("BINANCE_RECORD_SESSION") {
+            println!("录制 Binance 会话到: {}", path);
+            binance = binance.record_to(&path).expect("创建会话文件失败");
+        }
+        match binance.sync_time().await {
+            Ok(offset) => println!("Binance 服务器时间偏移: {} ms", offset),
+            Err(e) => println!("同步 Binance 服务器时间失败: {}", e),
+        }
+        manager = manager.with_exchange(binance);
+    }
+
+    (manager, client)
 }
 
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     dotenv().ok();
+    let _log_guard = init_logging(&LogConfig::from_env());
     println!("加密货币交易机器人启动中...");
     
-    let manager = init_manager().await;
+    let (mut manager, client) = init_manager().await;
+
+    // 可选: 从规则文件加载自定义策略 (格式见 strategy::rules)
+    let mut registry = StrategyRegistry::with_builtin();
+    if let Ok(path) = env::var("STRATEGY_RULES_FILE") {
+        match registry.load_rules(&path) {
+            Ok(names) => println!("已加载规则策略: {}", names.join(", ")),
+            Err(e) => println!("加载规则策略失败: {}", e),
+        }
+    }
+
+    // 可选: 外部进程策略插件, 格式 "名称=命令 参数...;名称=命令 ..." (协议见 strategy::plugin)
+    if let Ok(plugins) = env::var("STRATEGY_PLUGINS") {
+        for entry in plugins.split(';').map(str::trim).filter(|entry| !entry.is_empty()) {
+            match entry.split_once('=').map(|(name, command)| (name.trim(), command.parse::<PluginCommand>())) {
+                Some((name, Ok(command))) if !name.is_empty() => {
+                    println!("注册策略插件: {} ({})", name, command);
+                    registry.register_plugin(name, command)?;
+                }
+                Some((_, Err(e))) => println!("策略插件配置错误: {} ({})", entry, e),
+                _ => println!("策略插件配置错误: {} (应为 名称=命令)", entry),
+            }
+        }
+    }
+    manager = manager.with_strategy_registry(registry);
     
     loop {
         println!("\n请选择操作:");
@@ -176,7 +300,9 @@ async fn main() {
                 
                 for currency in currencies {
                     println!("添加币种: {}", currency.symbol);
-                    manager.add_currency(currency).await;
+                    if let Err(e) = manager.add_currency(currency).await {
+                        println!("添加失败: {}", e);
+                    }
                 }
                 println!("预设币种初始化完成!");
             }
@@ -185,14 +311,30 @@ async fn main() {
                 print_currency_status(&manager).await;
             }
             "3" => {
-                println!("请输入币种信息 (格式: 交易对,基础币,计价币,最小数量,价格精度,数量精度,最小名义价值,杠杆倍数)");
-                println!("例如: BTC-USDT,BTC,USDT,0.001,1,3,5.0,20");
+                println!("请输入币种信息 (格式: 交易对,基础币,计价币,最小数量,价格精度,数量精度,最小名义价值,杠杆倍数[,市场类型 swap/spot[,交易所 bingx/binance]])");
+                println!("例如: BTC-USDT,BTC,USDT,0.001,1,3,5.0,20 或 BTC-USDT,BTC,USDT,0.001,2,6,5.0,1,spot 或 BTCUSDT,BTC,USDT,0.001,1,3,5.0,20,swap,binance");
                 
                 let mut input = String::new();
                 std::io::stdin().read_line(&mut input).expect("读取输入失败");
                 
                 let parts: Vec<&str> = input.trim().split(',').collect();
-                if parts.len() == 8 {
+                if (8..=10).contains(&parts.len()) {
+                    let market = match parts.get(8).map(|m| m.parse::<MarketType>()) {
+                        Some(Ok(market)) => market,
+                        Some(Err(e)) => {
+                            println!("{}", e);
+                            continue;
+                        }
+                        None => MarketType::Swap,
+                    };
+                    let venue = match parts.get(9).map(|v| v.parse::<Venue>()) {
+                        Some(Ok(venue)) => venue,
+                        Some(Err(e)) => {
+                            println!("{}", e);
+                            continue;
+                        }
+                        None => Venue::BingX,
+                    };
                     let config = CurrencyConfig::new(
                         parts[0],
                         parts[1],
@@ -202,10 +344,47 @@ async fn main() {
                         parts[5].parse().unwrap_or(3),
                         parts[6].parse().unwrap_or(5.0),
                         parts[7].parse().unwrap_or(20),
+                    ).with_market(market).with_venue(venue);
+
+                    // 选择策略
+                    println!(
+                        "请输入策略 (可用: {}; 格式: 名称[:参数=值;...][(子策略|...)], 例如 macd:fast=8;slow=21;signal=5 或 majority(macd|donchian|mean_reversion), 直接回车使用 macd):",
+                        manager.strategy_registry().names().join(", ")
                     );
-                    
-                    manager.add_currency(config).await;
-                    println!("币种添加成功!");
+                    let mut strategy_input = String::new();
+                    std::io::stdin().read_line(&mut strategy_input).expect("读取输入失败");
+                    let config = if strategy_input.trim().is_empty() {
+                        config
+                    } else {
+                        match strategy_input.trim().parse::<StrategyConfig>() {
+                            Ok(strategy) => config.with_strategy(strategy),
+                            Err(e) => {
+                                println!("{}", e);
+                                continue;
+                            }
+                        }
+                    };
+
+                    // 选择允许开仓的市场状态
+                    println!("请输入允许开仓的市场状态 (可选: trending/ranging/high_volatility/illiquid, 以逗号分隔, 直接回车不限制):");
+                    let mut regime_input = String::new();
+                    std::io::stdin().read_line(&mut regime_input).expect("读取输入失败");
+                    let regimes: Result<Vec<Regime>, String> = regime_input.split(',')
+                        .filter(|regime| !regime.trim().is_empty())
+                        .map(|regime| regime.parse())
+                        .collect();
+                    let config = match regimes {
+                        Ok(regimes) => config.with_regimes(regimes),
+                        Err(e) => {
+                            println!("{}", e);
+                            continue;
+                        }
+                    };
+
+                    match manager.add_currency(config).await {
+                        Ok(()) => println!("币种添加成功!"),
+                        Err(e) => println!("添加失败: {}", e),
+                    }
                 } else {
                     println!("输入格式错误!");
                 }
@@ -247,7 +426,7 @@ async fn main() {
                 };
 
                 // 获取当前价格
-                match manager.get_client().get_latest_price(symbol).await {
+                match client.get_latest_price(symbol).await {
                     Ok(price) => {
                         println!("当前价格: {}", price);
                         if let Err(e) = manager.place_order(symbol, side, price).await {
@@ -263,7 +442,7 @@ async fn main() {
                 std::io::stdin().read_line(&mut symbol).expect("读取输入失败");
                 
                 println!("获取市场深度信息...");
-                match manager.get_client().print_depth_info(symbol.trim(), Some(20)).await {
+                match client.print_depth_info(symbol.trim(), Some(20)).await {
                     Ok(_) => println!("\n深度信息获取成功"),
                     Err(e) => println!("获取深度信息失败: {}", e),
                 }
@@ -274,7 +453,7 @@ async fn main() {
                 std::io::stdin().read_line(&mut symbol).expect("读取输入失败");
                 
                 println!("获取24小时行情信息...");
-                match manager.get_client().print_ticker_info(Some(symbol.trim())).await {
+                match client.print_ticker_info(Some(symbol.trim())).await {
                     Ok(_) => println!("\n24小时行情获取成功"),
                     Err(e) => println!("获取24小时行情失败: {}", e),
                 }
@@ -290,6 +469,8 @@ async fn main() {
             _ => println!("无效的选择"),
         }
     }
+
+    Ok(())
 }
 
 // ... rest of the code stays the same ...
\ No newline at end of file
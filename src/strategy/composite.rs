@@ -0,0 +1,310 @@
+use crate::strategy::{Action, BoxedStrategy, Decision, StrategyContext, TradingStrategy};
+use crate::types::{CurrencyConfig, Interval, Kline, OrderSide};
+use std::fmt;
+use std::sync::Mutex;
+
+// 子策略结论的组合方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CombineMode {
+    Unanimous,      // 全部子策略同向才开仓/平仓
+    Majority,       // 超过半数子策略同向
+    Weighted(f64),  // 同向子策略的 权重 × 置信度 之和 / 总权重 达到阈值
+    Filter,         // 第一个子策略只作方向过滤, 第二个子策略的开仓须与其最近的方向一致
+}
+
+impl CombineMode {
+    // 注册表中的策略名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            CombineMode::Unanimous => "unanimous",
+            CombineMode::Majority => "majority",
+            CombineMode::Weighted(_) => "weighted",
+            CombineMode::Filter => "filtered",
+        }
+    }
+}
+
+impl fmt::Display for CombineMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CombineMode::Unanimous => write!(f, "全票"),
+            CombineMode::Majority => write!(f, "多数"),
+            CombineMode::Weighted(threshold) => write!(f, "加权 (阈值 {})", threshold),
+            CombineMode::Filter => write!(f, "过滤"),
+        }
+    }
+}
+
+// 子策略对当前持仓/方向的投票
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Vote {
+    Long,
+    Short,
+    Exit,
+    Abstain,
+}
+
+// 组合策略: 同一交易对运行多个子策略, 按 CombineMode 合并它们的决策。
+// 止损/止盈取同意的子策略中最保守的价格, 开仓数量取第一个指定了数量的同意者。
+// 子策略的限价挂单不会被转发 (网格、定投等挂单策略应单独运行)。
+pub struct CompositeStrategy {
+    mode: CombineMode,
+    children: Vec<BoxedStrategy>,
+    weights: Vec<f64>,
+    symbol: String,
+    bias: Option<bool>,  // 过滤模式: 过滤子策略最近一次的开仓方向 (true 为多)
+    votes: Mutex<Vec<Vote>>,  // 最近一次评估时各子策略的投票, 用于只回报给同意者
+}
+
+impl CompositeStrategy {
+    pub fn new(mode: CombineMode, children: Vec<BoxedStrategy>) -> Self {
+        Self {
+            weights: vec![1.0; children.len()],
+            mode,
+            children,
+            symbol: String::new(),
+            bias: None,
+            votes: Mutex::new(Vec::new()),
+        }
+    }
+
+    // 加权模式下各子策略的权重 (按顺序, 缺少的为 1)
+    pub fn with_weights(mut self, weights: Vec<f64>) -> Self {
+        for (i, weight) in weights.into_iter().enumerate().take(self.children.len()) {
+            self.weights[i] = weight;
+        }
+        self
+    }
+
+    pub fn mode(&self) -> CombineMode {
+        self.mode
+    }
+
+    pub fn children(&self) -> &[BoxedStrategy] {
+        &self.children
+    }
+
+    // 过滤模式下当前允许的方向 (true 为多), 未产生方向时为 None
+    pub fn bias(&self) -> Option<bool> {
+        self.bias
+    }
+
+    fn primary(&self) -> Interval {
+        self.intervals().first().copied().unwrap_or(Interval::FiveMinutes)
+    }
+
+    // 持仓时, 平仓或反向开仓都算作离场票
+    fn vote(decision: &Decision, holding: Option<&OrderSide>) -> Vote {
+        match (decision.action, holding) {
+            (Action::Exit, Some(_)) => Vote::Exit,
+            (Action::EnterLong, Some(OrderSide::Sell)) | (Action::EnterShort, Some(OrderSide::Buy)) => Vote::Exit,
+            (Action::EnterLong, _) => Vote::Long,
+            (Action::EnterShort, _) => Vote::Short,
+            _ => Vote::Abstain,
+        }
+    }
+
+    // 合并后的开仓/平仓决策, 附带同意者的止损止盈和数量
+    fn combine(&self, target: Vote, decisions: &[&Decision], confidence: f64, reasons: Vec<String>) -> Decision {
+        let decision = match target {
+            Vote::Long => Decision::enter_long(confidence),
+            Vote::Short => Decision::enter_short(confidence),
+            _ => Decision::exit(confidence),
+        };
+        let long = target == Vote::Long;
+        let tighter = |a: f64, b: f64| if long { a.max(b) } else { a.min(b) };
+        let nearer = |a: f64, b: f64| if long { a.min(b) } else { a.max(b) };
+        let mut decision = decision.with_reasons(reasons);
+        if matches!(target, Vote::Long | Vote::Short) {
+            decision.stop_loss = decisions.iter().filter_map(|d| d.stop_loss).reduce(tighter);
+            decision.take_profit = decisions.iter().filter_map(|d| d.take_profit).reduce(nearer);
+            decision.quantity = decisions.iter().find_map(|d| d.quantity);
+        }
+        decision.verdicts = decisions.iter().flat_map(|d| d.verdicts.clone()).collect();
+        decision
+    }
+}
+
+impl TradingStrategy for CompositeStrategy {
+    fn name(&self) -> &str {
+        self.mode.name()
+    }
+
+    fn bind_currency(&mut self, config: &CurrencyConfig) {
+        self.symbol = config.symbol.clone();
+        for child in &mut self.children {
+            child.bind_currency(config);
+        }
+    }
+
+    fn required_legs(&self) -> usize {
+        self.children.iter().map(|child| child.required_legs()).max().unwrap_or(0)
+    }
+
+    fn add_price(&mut self, price: f64) {
+        for child in &mut self.children {
+            child.add_price(price);
+        }
+    }
+
+    // 第一个子策略的主周期为组合策略的主周期
+    fn intervals(&self) -> Vec<Interval> {
+        let mut intervals: Vec<Interval> = Vec::new();
+        for interval in self.children.iter().flat_map(|child| child.intervals()) {
+            if !intervals.contains(&interval) {
+                intervals.push(interval);
+            }
+        }
+        intervals
+    }
+
+    fn lookback(&self, interval: Interval) -> usize {
+        self.children.iter().map(|child| child.lookback(interval)).max().unwrap_or(0)
+    }
+
+    fn uses_regime(&self) -> bool {
+        self.children.iter().any(|child| child.uses_regime())
+    }
+
+    fn is_ready(&self) -> bool {
+        self.children.iter().all(|child| child.is_ready())
+    }
+
+    fn add_candle(&mut self, interval: Interval, kline: &Kline) {
+        for child in &mut self.children {
+            child.add_candle(interval, kline);
+        }
+        // 过滤子策略每根主周期K线评估一次 (不含持仓和盘口), 记住最近的开仓方向
+        if self.mode == CombineMode::Filter && interval == self.primary() {
+            if let Some(filter) = self.children.first() {
+                match filter.evaluate(&StrategyContext::new(&self.symbol, kline.close)).action {
+                    Action::EnterLong => self.bias = Some(true),
+                    Action::EnterShort => self.bias = Some(false),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn add_leg_candle(&mut self, symbol: &str, interval: Interval, kline: &Kline) {
+        for child in &mut self.children {
+            child.add_leg_candle(symbol, interval, kline);
+        }
+    }
+
+    fn evaluate(&self, ctx: &StrategyContext) -> Decision {
+        let holding = ctx.position.map(|position| &position.side);
+        let decisions: Vec<Decision> = self.children.iter().map(|child| child.evaluate(ctx)).collect();
+        let mut votes: Vec<Vote> = decisions.iter().map(|decision| Self::vote(decision, holding)).collect();
+        if self.mode == CombineMode::Filter {
+            // 过滤子策略只决定方向, 不参与成交回报
+            if let Some(vote) = votes.first_mut() {
+                *vote = Vote::Abstain;
+            }
+        }
+        *self.votes.lock().unwrap_or_else(|e| e.into_inner()) = votes.clone();
+        let reasons: Vec<String> = self.children.iter()
+            .zip(&decisions)
+            .map(|(child, decision)| format!(
+                "[{}] {}: {}",
+                child.name(),
+                decision.action,
+                decision.reasons.join("; ")
+            ))
+            .collect();
+
+        if self.mode == CombineMode::Filter {
+            let (filter, signal) = match (self.children.first(), decisions.get(1)) {
+                (Some(filter), Some(signal)) => (filter, signal),
+                _ => return Decision::hold().with_reasons(reasons).with_reason("过滤模式需要两个子策略"),
+            };
+            return match votes[1] {
+                // 离场不受过滤
+                Vote::Exit => self.combine(Vote::Exit, &[signal], signal.confidence, reasons),
+                Vote::Long | Vote::Short if self.bias == Some(votes[1] == Vote::Long) => {
+                    self.combine(votes[1], &[signal], signal.confidence, reasons)
+                }
+                Vote::Long | Vote::Short => Decision::hold().with_reasons(reasons).with_reason(format!(
+                    "{} 方向过滤未确认 ({})",
+                    filter.name(),
+                    match self.bias {
+                        Some(true) => "只做多",
+                        Some(false) => "只做空",
+                        None => "尚无方向",
+                    }
+                )),
+                Vote::Abstain => Decision::hold().with_reasons(reasons),
+            };
+        }
+
+        let agreeing = |target: Vote| -> Vec<&Decision> {
+            decisions.iter().zip(&votes).filter(|(_, vote)| **vote == target).map(|(d, _)| d).collect()
+        };
+        let candidates = match holding {
+            Some(_) => vec![Vote::Exit],
+            None => vec![Vote::Long, Vote::Short],
+        };
+        for target in candidates {
+            let agree = agreeing(target);
+            if agree.is_empty() {
+                continue;
+            }
+            let (passed, confidence, summary) = match self.mode {
+                CombineMode::Weighted(threshold) => {
+                    let total: f64 = self.weights.iter().sum();
+                    let score = votes.iter()
+                        .zip(&decisions)
+                        .zip(&self.weights)
+                        .filter(|((vote, _), _)| **vote == target)
+                        .map(|((_, decision), weight)| weight * decision.confidence)
+                        .sum::<f64>() / total.max(f64::EPSILON);
+                    (score >= threshold, score, format!("加权得分 {:.2} (阈值 {})", score, threshold))
+                }
+                _ => {
+                    let passed = match self.mode {
+                        CombineMode::Unanimous => agree.len() == self.children.len(),
+                        _ => agree.len() * 2 > self.children.len(),
+                    };
+                    let confidence = agree.iter().map(|d| d.confidence).sum::<f64>() / agree.len() as f64;
+                    (passed, confidence, format!("{}/{} 个子策略同意", agree.len(), self.children.len()))
+                }
+            };
+            if passed {
+                return self.combine(target, &agree, confidence, reasons)
+                    .with_reason(format!("{}通过: {}", self.mode, summary));
+            }
+        }
+        Decision::hold().with_reasons(reasons).with_reason(format!("子策略未达成{}一致", self.mode))
+    }
+
+    // 只回报给投票与已执行决策一致的子策略
+    fn on_signal(&mut self, decision: &Decision, price: f64, timestamp: i64) {
+        let executed = match decision.action {
+            Action::EnterLong => Vote::Long,
+            Action::EnterShort => Vote::Short,
+            Action::Exit => Vote::Exit,
+            Action::Hold => return,
+        };
+        let votes = std::mem::take(self.votes.get_mut().unwrap_or_else(|e| e.into_inner()));
+        for (child, vote) in self.children.iter_mut().zip(votes) {
+            if vote == executed {
+                child.on_signal(decision, price, timestamp);
+            }
+        }
+    }
+
+    fn describe(&self) -> Vec<String> {
+        let mut lines = vec![format!("组合方式: {}", self.mode)];
+        if self.mode == CombineMode::Filter {
+            lines.push(format!("过滤方向: {}", match self.bias {
+                Some(true) => "多",
+                Some(false) => "空",
+                None => "-",
+            }));
+        }
+        for child in &self.children {
+            lines.extend(child.describe().into_iter().map(|line| format!("[{}] {}", child.name(), line)));
+        }
+        lines
+    }
+}
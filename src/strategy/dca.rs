@@ -0,0 +1,294 @@
+use crate::exchange::{OrderStatus, OrderUpdate};
+use crate::strategy::{Decision, OrderIntent, StrategyContext, TradingStrategy};
+use crate::types::{CurrencyConfig, Interval};
+use tracing::{debug, info};
+
+// 安全单: 相对底仓成交价的价格与数量
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SafetyOrder {
+    pub price: f64,
+    pub quantity: f64,
+    pub filled: bool,
+}
+
+// 当前一轮已成交的底仓与安全单
+#[derive(Debug, Clone)]
+struct DCAEntry {
+    base_price: f64,    // 底仓成交价, 安全单价格以此为基准
+    quantity: f64,      // 累计成交数量
+    cost: f64,          // 累计成交金额
+    filled: Vec<bool>,  // 各安全单是否已成交
+}
+
+impl DCAEntry {
+    fn average_price(&self) -> f64 {
+        self.cost / self.quantity
+    }
+}
+
+// 定投加仓 (只做多): 以最新收盘价挂底仓买单, 成交后按偏离幅度向下挂出安全单,
+// 每张安全单的偏离幅度按 step_scale、数量按 volume_scale 递增;
+// 止盈单按持仓均价 + take_profit_pct 挂出, 均价变化时重新挂单, 止盈成交后开始下一轮。
+pub struct DCAStrategy {
+    base_qty: f64,         // 底仓数量 (0 为币种最小下单量)
+    safety_qty: f64,       // 第一张安全单数量 (0 与底仓相同)
+    safety_orders: usize,  // 安全单数量
+    deviation_pct: f64,    // 第一张安全单相对底仓的偏离 (%)
+    step_scale: f64,       // 后续安全单偏离幅度的倍数
+    volume_scale: f64,     // 后续安全单数量的倍数
+    take_profit_pct: f64,  // 止盈幅度 (相对持仓均价, %)
+    interval: Interval,
+    currency: Option<CurrencyConfig>,
+    last_price: Option<f64>,
+    cycle: usize,          // 当前轮次, 挂单标识带上轮次以忽略上一轮迟到的成交
+    entry: Option<DCAEntry>,
+    completed_cycles: usize,
+    realized_profit: f64,  // 已止盈各轮的累计利润 (未扣手续费)
+}
+
+impl DCAStrategy {
+    pub fn new(safety_orders: usize, deviation_pct: f64, take_profit_pct: f64) -> Self {
+        Self {
+            base_qty: 0.0,
+            safety_qty: 0.0,
+            safety_orders,
+            deviation_pct,
+            step_scale: 1.0,
+            volume_scale: 1.0,
+            take_profit_pct,
+            interval: Interval::FiveMinutes,
+            currency: None,
+            last_price: None,
+            cycle: 1,
+            entry: None,
+            completed_cycles: 0,
+            realized_profit: 0.0,
+        }
+    }
+
+    // 底仓与第一张安全单的数量
+    pub fn with_quantities(mut self, base_qty: f64, safety_qty: f64) -> Self {
+        self.base_qty = base_qty;
+        self.safety_qty = safety_qty;
+        self
+    }
+
+    // 安全单偏离幅度与数量的递增倍数
+    pub fn with_scaling(mut self, step_scale: f64, volume_scale: f64) -> Self {
+        self.step_scale = step_scale;
+        self.volume_scale = volume_scale;
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Interval) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    // 持仓均价, 未开仓为 None
+    pub fn average_price(&self) -> Option<f64> {
+        self.entry.as_ref().map(DCAEntry::average_price)
+    }
+
+    // 累计持仓数量
+    pub fn position_quantity(&self) -> f64 {
+        self.entry.as_ref().map_or(0.0, |entry| entry.quantity)
+    }
+
+    // 按持仓均价计算的止盈价
+    pub fn take_profit_price(&self) -> Option<f64> {
+        self.average_price().map(|average| self.round_price(average * (1.0 + self.take_profit_pct / 100.0)))
+    }
+
+    pub fn completed_cycles(&self) -> usize {
+        self.completed_cycles
+    }
+
+    pub fn realized_profit(&self) -> f64 {
+        self.realized_profit
+    }
+
+    // 当前一轮的安全单阶梯, 底仓未成交时为空
+    pub fn ladder(&self) -> Vec<SafetyOrder> {
+        let entry = match &self.entry {
+            Some(entry) => entry,
+            None => return Vec::new(),
+        };
+        let first_qty = if self.safety_qty > 0.0 { self.safety_qty } else { self.base_quantity() };
+        let mut deviation = 0.0;
+        let mut step = self.deviation_pct;
+        let mut quantity = first_qty;
+        (0..self.safety_orders)
+            .map(|level| {
+                deviation += step;
+                let order = SafetyOrder {
+                    price: self.round_price(entry.base_price * (1.0 - deviation / 100.0)),
+                    quantity: self.floor_qty(quantity),
+                    filled: entry.filled[level],
+                };
+                step *= self.step_scale;
+                quantity *= self.volume_scale;
+                order
+            })
+            .collect()
+    }
+
+    fn base_quantity(&self) -> f64 {
+        let min_qty = self.currency.as_ref().map_or(0.0, |c| c.min_qty);
+        if self.base_qty > 0.0 { self.floor_qty(self.base_qty) } else { min_qty }
+    }
+
+    fn round_price(&self, price: f64) -> f64 {
+        self.currency.as_ref().map_or(price, |c| c.round_price(price))
+    }
+
+    // 按数量精度向下取整, 不低于最小下单量
+    fn floor_qty(&self, quantity: f64) -> f64 {
+        match &self.currency {
+            Some(c) => c.floor_qty(quantity).max(c.min_qty),
+            None => quantity,
+        }
+    }
+
+    fn key(&self, kind: &str) -> String {
+        format!("c{}-{}", self.cycle, kind)
+    }
+}
+
+impl TradingStrategy for DCAStrategy {
+    fn name(&self) -> &str {
+        "dca"
+    }
+
+    fn bind_currency(&mut self, config: &CurrencyConfig) {
+        self.currency = Some(config.clone());
+    }
+
+    fn add_price(&mut self, price: f64) {
+        self.last_price = Some(price);
+    }
+
+    fn intervals(&self) -> Vec<Interval> {
+        vec![self.interval]
+    }
+
+    fn evaluate(&self, _ctx: &StrategyContext) -> Decision {
+        let reason = match (self.average_price(), self.take_profit_price()) {
+            (Some(average), Some(target)) => format!(
+                "第 {} 轮: 均价 {:.4}, 止盈 {:.4}, 安全单 {}/{}",
+                self.cycle,
+                average,
+                target,
+                self.ladder().iter().filter(|order| order.filled).count(),
+                self.safety_orders,
+            ),
+            _ => format!("第 {} 轮: 等待底仓成交", self.cycle),
+        };
+        Decision::hold().with_reason(reason)
+    }
+
+    fn places_resting_orders(&self) -> bool {
+        true
+    }
+
+    fn resting_orders(&self) -> Vec<OrderIntent> {
+        let entry = match (&self.entry, self.last_price) {
+            (Some(entry), _) => entry,
+            // 未开仓时按最新收盘价挂底仓买单, 未成交时随收盘价重新挂单
+            (None, Some(price)) => {
+                return vec![OrderIntent::buy(self.key("base"), self.round_price(price), self.base_quantity())];
+            }
+            (None, None) => return Vec::new(),
+        };
+
+        let mut orders: Vec<OrderIntent> = self.ladder().iter()
+            .enumerate()
+            .filter(|(_, order)| !order.filled)
+            .map(|(level, order)| OrderIntent::buy(self.key(&format!("so{}", level + 1)), order.price, order.quantity))
+            .collect();
+        if let Some(target) = self.take_profit_price() {
+            orders.push(OrderIntent::sell(self.key("tp"), target, entry.quantity).reduce_only());
+        }
+        orders
+    }
+
+    fn on_order_filled(&mut self, intent: &OrderIntent, fill: &OrderUpdate) {
+        let kind = match intent.key.strip_prefix(&format!("c{}-", self.cycle)) {
+            Some(kind) => kind.to_string(),
+            None => {
+                debug!(key = %intent.key, cycle = self.cycle, "忽略上一轮的成交");
+                return;
+            }
+        };
+        let quantity = if fill.filled_quantity > 0.0 { fill.filled_quantity } else { intent.quantity };
+        let price = fill.fill_price();
+
+        if kind == "base" {
+            info!(cycle = self.cycle, price, quantity, "定投底仓成交");
+            self.entry = Some(DCAEntry {
+                base_price: price,
+                quantity,
+                cost: price * quantity,
+                filled: vec![false; self.safety_orders],
+            });
+        } else if kind == "tp" {
+            // 止盈单在均价变化时会被撤单重挂, 撤单或过期前的部分成交只减少持仓
+            let finished = match self.entry.as_mut() {
+                Some(entry) => {
+                    let finished = fill.status == OrderStatus::Filled || quantity >= entry.quantity;
+                    let quantity = quantity.min(entry.quantity);
+                    let cost = entry.cost * (quantity / entry.quantity);
+                    let profit = price * quantity - cost;
+                    entry.quantity -= quantity;
+                    entry.cost -= cost;
+                    self.realized_profit += profit;
+                    if finished {
+                        info!(cycle = self.cycle, price, profit, realized_profit = self.realized_profit, "定投止盈成交");
+                    } else {
+                        info!(cycle = self.cycle, price, quantity, remaining = entry.quantity, "定投止盈部分成交");
+                    }
+                    finished
+                }
+                None => false,
+            };
+            if finished {
+                self.entry = None;
+                self.completed_cycles += 1;
+                self.cycle += 1;
+            }
+        } else if let Some(level) = kind.strip_prefix("so").and_then(|level| level.parse::<usize>().ok()) {
+            if let Some(entry) = self.entry.as_mut().filter(|entry| (1..=entry.filled.len()).contains(&level)) {
+                entry.filled[level - 1] = true;
+                entry.quantity += quantity;
+                entry.cost += price * quantity;
+                info!(cycle = self.cycle, level, price, quantity, average = entry.average_price(), "定投安全单成交");
+            }
+        }
+    }
+
+    fn describe(&self) -> Vec<String> {
+        let mut lines = vec![format!(
+            "定投第 {} 轮, 已完成 {} 轮, 累计利润 {:.4}",
+            self.cycle, self.completed_cycles, self.realized_profit
+        )];
+        match (&self.entry, self.take_profit_price()) {
+            (Some(entry), Some(target)) => {
+                lines.push(format!(
+                    "持仓 {} @ 均价 {:.4}, 止盈价 {:.4}",
+                    entry.quantity, entry.average_price(), target
+                ));
+                for (level, order) in self.ladder().iter().enumerate() {
+                    lines.push(format!(
+                        "安全单 {}: {} @ {} ({})",
+                        level + 1,
+                        order.quantity,
+                        order.price,
+                        if order.filled { "已成交" } else { "挂单中" },
+                    ));
+                }
+            }
+            _ => lines.push("等待底仓成交".to_string()),
+        }
+        lines
+    }
+}
@@ -0,0 +1,149 @@
+use crate::strategy::filters::FilterVerdict;
+use std::fmt;
+
+// 策略建议的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    EnterLong,   // 开多 (现货为买入)
+    EnterShort,  // 开空 (现货为卖出持有的币)
+    Exit,        // 平掉当前持仓
+    Hold,        // 不操作
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Action::EnterLong => "开多",
+            Action::EnterShort => "开空",
+            Action::Exit => "平仓",
+            Action::Hold => "观望",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+// 已发出的信号记录
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalRecord {
+    pub timestamp: i64,   // 毫秒
+    pub action: Action,
+    pub price: f64,
+    pub confidence: f64,
+}
+
+// 需要阻塞等待的决策 (例如等待外部进程回复), 管理器在释放策略锁后于阻塞线程池中等待
+pub struct DeferredDecision(Box<dyn FnOnce() -> Decision + Send>);
+
+impl DeferredDecision {
+    pub fn new(wait: impl FnOnce() -> Decision + Send + 'static) -> Self {
+        Self(Box::new(wait))
+    }
+
+    // 阻塞当前线程直到得到决策
+    pub fn wait(self) -> Decision {
+        (self.0)()
+    }
+}
+
+impl fmt::Debug for DeferredDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DeferredDecision")
+    }
+}
+
+// 策略的结构化决策
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decision {
+    pub action: Action,
+    pub confidence: f64,            // 置信度 0.0 - 1.0
+    pub stop_loss: Option<f64>,     // 建议止损价
+    pub take_profit: Option<f64>,   // 建议止盈价
+    pub reasons: Vec<String>,       // 可读的决策依据
+    pub close_fraction: f64,        // 平仓比例 (仅 Exit, 1.0 为全部平仓)
+    pub verdicts: Vec<FilterVerdict>,  // 信号过滤器的结论
+    pub quantity: Option<f64>,      // 指定开仓数量 (None 时按置信度计算)
+    pub legs: Vec<(String, Decision)>, // 多交易对策略对附加交易对的决策, 在主交易对之后执行
+}
+
+impl Decision {
+    pub fn new(action: Action, confidence: f64) -> Self {
+        Self {
+            action,
+            confidence: confidence.clamp(0.0, 1.0),
+            stop_loss: None,
+            take_profit: None,
+            reasons: Vec::new(),
+            close_fraction: 1.0,
+            verdicts: Vec::new(),
+            quantity: None,
+            legs: Vec::new(),
+        }
+    }
+
+    pub fn hold() -> Self {
+        Self::new(Action::Hold, 0.0)
+    }
+
+    pub fn enter_long(confidence: f64) -> Self {
+        Self::new(Action::EnterLong, confidence)
+    }
+
+    pub fn enter_short(confidence: f64) -> Self {
+        Self::new(Action::EnterShort, confidence)
+    }
+
+    pub fn exit(confidence: f64) -> Self {
+        Self::new(Action::Exit, confidence)
+    }
+
+    // 部分平仓, fraction 为持仓的平仓比例 (0.0 - 1.0]
+    pub fn reduce(fraction: f64, confidence: f64) -> Self {
+        let mut decision = Self::new(Action::Exit, confidence);
+        decision.close_fraction = fraction.clamp(0.0, 1.0);
+        decision
+    }
+
+    pub fn with_stop_loss(mut self, price: f64) -> Self {
+        self.stop_loss = Some(price);
+        self
+    }
+
+    pub fn with_take_profit(mut self, price: f64) -> Self {
+        self.take_profit = Some(price);
+        self
+    }
+
+    pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reasons.push(reason.into());
+        self
+    }
+
+    pub fn with_reasons(mut self, reasons: Vec<String>) -> Self {
+        self.reasons.extend(reasons);
+        self
+    }
+
+    pub fn with_verdicts(mut self, verdicts: Vec<FilterVerdict>) -> Self {
+        self.verdicts.extend(verdicts);
+        self
+    }
+
+    pub fn with_quantity(mut self, quantity: f64) -> Self {
+        self.quantity = Some(quantity);
+        self
+    }
+
+    pub fn with_leg(mut self, symbol: &str, decision: Decision) -> Self {
+        self.legs.push((symbol.to_string(), decision));
+        self
+    }
+
+    // 主交易对或任一附加交易对需要操作
+    pub fn is_actionable(&self) -> bool {
+        self.action != Action::Hold || self.legs.iter().any(|(_, leg)| leg.is_actionable())
+    }
+
+    pub fn is_entry(&self) -> bool {
+        matches!(self.action, Action::EnterLong | Action::EnterShort)
+    }
+}
@@ -0,0 +1,209 @@
+use crate::indicators::{Bar, Indicator, RingBuffer, ATR};
+use crate::strategy::{Decision, StrategyContext, TradingStrategy};
+use crate::types::{CurrencyConfig, Interval, Kline, OrderSide};
+
+// 唐奇安通道 (不含最新一根K线)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Channel {
+    pub upper: f64,
+    pub lower: f64,
+}
+
+// 唐奇安通道突破 (趋势跟踪): 收盘价突破前 entry_period 根K线的最高价开多, 跌破最低价开空;
+// 持多时跌破前 exit_period 根K线的最低价平仓 (持空反之), 反向突破时由管理器先平仓再反手。
+// 止损/止盈按 ATR 倍数设置, 开仓数量按波动率缩放:
+// 指定 risk 时每笔亏损到止损约为 risk (计价货币), 否则 ATR 占价格的比例超过 target_vol_pct 时按比例减仓。
+pub struct DonchianStrategy {
+    entry_period: usize,
+    exit_period: usize,
+    stop_atr: f64,         // 止损距离 (ATR 倍数)
+    target_atr: f64,       // 止盈距离 (ATR 倍数)
+    risk: f64,             // 每笔风险金额 (计价货币, 0 为按波动率缩放)
+    target_vol_pct: f64,   // 目标波动率: ATR 占价格的比例 (%)
+    interval: Interval,
+    currency: Option<CurrencyConfig>,
+    bars: RingBuffer<Bar>,
+    atr: ATR,
+}
+
+impl DonchianStrategy {
+    pub fn new(entry_period: usize, exit_period: usize, atr_period: usize) -> Self {
+        let entry_period = entry_period.max(1);
+        let exit_period = exit_period.max(1);
+        Self {
+            entry_period,
+            exit_period,
+            stop_atr: 2.0,
+            target_atr: 6.0,
+            risk: 0.0,
+            target_vol_pct: 1.0,
+            interval: Interval::FiveMinutes,
+            currency: None,
+            bars: RingBuffer::new(entry_period.max(exit_period) + 1),
+            atr: ATR::new(atr_period),
+        }
+    }
+
+    // 止损/止盈距离 (ATR 倍数)
+    pub fn with_atr_risk(mut self, stop_atr: f64, target_atr: f64) -> Self {
+        self.stop_atr = stop_atr;
+        self.target_atr = target_atr;
+        self
+    }
+
+    // 仓位: 每笔风险金额 (0 为不使用) 与目标波动率 (%)
+    pub fn with_sizing(mut self, risk: f64, target_vol_pct: f64) -> Self {
+        self.risk = risk;
+        self.target_vol_pct = target_vol_pct;
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Interval) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    // 前 period 根K线 (不含最新一根) 的通道, 数据不足时为 None
+    pub fn channel(&self, period: usize) -> Option<Channel> {
+        let previous = self.bars.len().checked_sub(1)?;
+        if previous < period {
+            return None;
+        }
+        let (upper, lower) = (previous - period..previous)
+            .filter_map(|i| self.bars.get(i))
+            .fold((f64::MIN, f64::MAX), |(upper, lower), bar| (upper.max(bar.high), lower.min(bar.low)));
+        Some(Channel { upper, lower })
+    }
+
+    pub fn atr(&self) -> Option<f64> {
+        self.atr.value()
+    }
+
+    // 按波动率计算的开仓数量 (未绑定币种配置时为 None, 由管理器按置信度计算)
+    pub fn position_size(&self, price: f64, confidence: f64) -> Option<f64> {
+        let currency = self.currency.as_ref()?;
+        let atr = self.atr()?;
+        if atr <= 0.0 || price <= 0.0 {
+            return None;
+        }
+        let quantity = if self.risk > 0.0 {
+            self.risk / (self.stop_atr * atr)
+        } else {
+            let atr_pct = atr / price * 100.0;
+            currency.order_quantity(confidence) * (self.target_vol_pct / atr_pct).min(1.0)
+        };
+        Some(currency.floor_qty(quantity).max(currency.min_qty))
+    }
+
+    fn push(&mut self, bar: Bar) {
+        self.atr.update(bar);
+        self.bars.push(bar);
+    }
+
+    fn enter(&self, long: bool, price: f64, channel: Channel, atr: f64) -> Decision {
+        let (breakout, sign) = if long { (price - channel.upper, 1.0) } else { (channel.lower - price, -1.0) };
+        // 突破幅度达到 1 个 ATR 时置信度为 1
+        let confidence = (0.5 + 0.5 * breakout / atr).clamp(0.0, 1.0);
+        let decision = if long { Decision::enter_long(confidence) } else { Decision::enter_short(confidence) };
+        let mut decision = decision
+            .with_stop_loss(price - sign * self.stop_atr * atr)
+            .with_take_profit(price + sign * self.target_atr * atr)
+            .with_reason(format!(
+                "收盘价 {:.4} {} {} 根K线通道 {:.4}, ATR {:.4}",
+                price,
+                if long { "突破" } else { "跌破" },
+                self.entry_period,
+                if long { channel.upper } else { channel.lower },
+                atr
+            ));
+        if let Some(quantity) = self.position_size(price, confidence) {
+            decision = decision.with_quantity(quantity);
+        }
+        decision
+    }
+}
+
+impl TradingStrategy for DonchianStrategy {
+    fn name(&self) -> &str {
+        "donchian"
+    }
+
+    fn bind_currency(&mut self, config: &CurrencyConfig) {
+        self.currency = Some(config.clone());
+    }
+
+    // 只有收盘价时按最高价 = 最低价 = 收盘价处理
+    fn add_price(&mut self, price: f64) {
+        self.push(Bar::new(price, price, price, 0.0));
+    }
+
+    fn intervals(&self) -> Vec<Interval> {
+        vec![self.interval]
+    }
+
+    fn lookback(&self, interval: Interval) -> usize {
+        if interval == self.interval {
+            (self.entry_period.max(self.exit_period) + 1).max(self.atr.warm_up())
+        } else {
+            0
+        }
+    }
+
+    fn is_ready(&self) -> bool {
+        self.atr.is_ready() && self.channel(self.entry_period.max(self.exit_period)).is_some()
+    }
+
+    fn add_candle(&mut self, interval: Interval, kline: &Kline) {
+        if interval == self.interval {
+            self.push(Bar::from(kline));
+        }
+    }
+
+    fn evaluate(&self, ctx: &StrategyContext) -> Decision {
+        let (entry, exit, atr) = match (self.channel(self.entry_period), self.channel(self.exit_period), self.atr()) {
+            (Some(entry), Some(exit), Some(atr)) => (entry, exit, atr),
+            _ => return Decision::hold().with_reason(format!("通道数据不足 ({} 根K线)", self.bars.len())),
+        };
+        // 以最新收盘K线判断突破, 与回放历史K线时的结果一致
+        let price = self.bars.last().map_or(ctx.price, |bar| bar.close);
+
+        let holding = ctx.position.map(|position| position.side.clone());
+        if price > entry.upper && holding != Some(OrderSide::Buy) {
+            return self.enter(true, price, entry, atr);
+        }
+        if price < entry.lower && holding != Some(OrderSide::Sell) {
+            return self.enter(false, price, entry, atr);
+        }
+        match holding {
+            Some(OrderSide::Buy) if price < exit.lower => Decision::exit(1.0).with_reason(format!(
+                "收盘价 {:.4} 跌破 {} 根K线低点 {:.4}, 多头离场", price, self.exit_period, exit.lower
+            )),
+            Some(OrderSide::Sell) if price > exit.upper => Decision::exit(1.0).with_reason(format!(
+                "收盘价 {:.4} 突破 {} 根K线高点 {:.4}, 空头离场", price, self.exit_period, exit.upper
+            )),
+            Some(_) => Decision::hold().with_reason(format!(
+                "持仓中, 离场通道 {:.4} - {:.4}", exit.lower, exit.upper
+            )),
+            None => Decision::hold().with_reason(format!(
+                "收盘价 {:.4} 位于通道 {:.4} - {:.4} 内", price, entry.lower, entry.upper
+            )),
+        }
+    }
+
+    fn describe(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(channel) = self.channel(self.entry_period) {
+            lines.push(format!("入场通道 ({}): {:.4} - {:.4}", self.entry_period, channel.lower, channel.upper));
+        }
+        if let Some(channel) = self.channel(self.exit_period) {
+            lines.push(format!("离场通道 ({}): {:.4} - {:.4}", self.exit_period, channel.lower, channel.upper));
+        }
+        if let (Some(atr), Some(bar)) = (self.atr(), self.bars.last()) {
+            lines.push(format!("ATR {:.4} ({:.2}%)", atr, atr / bar.close * 100.0));
+            if let Some(quantity) = self.position_size(bar.close, 1.0) {
+                lines.push(format!("建议开仓数量: {}", quantity));
+            }
+        }
+        lines
+    }
+}
@@ -0,0 +1,332 @@
+use crate::strategy::{MarketDepth, MarketTicker, StrategyContext};
+use crate::types::StrategyConfig;
+use std::error::Error;
+use std::fmt;
+
+// 过滤器支持的策略参数 (按币种在策略配置中设置)
+pub const FILTER_PARAMS: &[&str] = &[
+    "depth_filter",        // 1 启用 / 0 关闭深度过滤
+    "depth_band_pct",      // 统计挂单的价格范围 (%)
+    "depth_ratio",         // 买卖压力倍数阈值
+    "ticker_filter",       // 1 启用 / 0 关闭行情过滤
+    "min_change_pct",      // 24h 涨跌幅阈值 (%)
+    "low_zone_pct",        // 日内低位区间 (%)
+    "high_zone_pct",       // 日内高位区间 (%)
+    "max_volatility_pct",  // 日内波动超过该值时只在更极端的位置开仓 (%)
+    "max_spread_pct",      // 买卖价差上限 (%)
+];
+
+// 单个过滤器的结论
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterVerdict {
+    pub filter: String,
+    pub long: bool,    // 是否确认做多
+    pub short: bool,   // 是否确认做空
+    pub reason: String,
+}
+
+impl FilterVerdict {
+    pub fn confirms(&self, is_long: bool) -> bool {
+        if is_long { self.long } else { self.short }
+    }
+}
+
+impl fmt::Display for FilterVerdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mark = |ok: bool| if ok { "✓" } else { "✗" };
+        write!(f, "{}[多{} 空{}] {}", self.filter, mark(self.long), mark(self.short), self.reason)
+    }
+}
+
+// 信号过滤器: 根据行情上下文确认或否决开仓方向
+pub trait SignalFilter {
+    fn name(&self) -> &str;
+    // 缺少所需数据时返回 None
+    fn check(&self, ctx: &StrategyContext) -> Option<FilterVerdict>;
+}
+
+pub type BoxedFilter = Box<dyn SignalFilter + Send + Sync>;
+
+// 深度过滤: 比较当前价附近的买卖挂单量
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthFilter {
+    pub band_pct: f64,
+    pub pressure_ratio: f64,
+}
+
+impl Default for DepthFilter {
+    fn default() -> Self {
+        Self {
+            band_pct: 1.0,
+            pressure_ratio: 1.2,
+        }
+    }
+}
+
+impl DepthFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_band_pct(mut self, band_pct: f64) -> Self {
+        self.band_pct = band_pct;
+        self
+    }
+
+    pub fn with_pressure_ratio(mut self, pressure_ratio: f64) -> Self {
+        self.pressure_ratio = pressure_ratio;
+        self
+    }
+
+    pub fn analyze(&self, depth: &MarketDepth, current_price: f64) -> FilterVerdict {
+        let band = self.band_pct / 100.0;
+        let bid_volume: f64 = depth.bids.iter()
+            .filter(|(price, _)| *price > current_price * (1.0 - band))
+            .map(|(_, quantity)| quantity)
+            .sum();
+        let ask_volume: f64 = depth.asks.iter()
+            .filter(|(price, _)| *price < current_price * (1.0 + band))
+            .map(|(_, quantity)| quantity)
+            .sum();
+
+        let buy_pressure = if ask_volume > 0.0 { bid_volume / ask_volume } else { 0.0 };
+        let sell_pressure = if bid_volume > 0.0 { ask_volume / bid_volume } else { 0.0 };
+
+        FilterVerdict {
+            filter: self.name().to_string(),
+            long: buy_pressure > self.pressure_ratio,
+            short: sell_pressure > self.pressure_ratio,
+            reason: format!(
+                "{}%范围内买单 {:.4} / 卖单 {:.4}, 买压 {:.2} 倍, 卖压 {:.2} 倍 (阈值 {:.2})",
+                self.band_pct, bid_volume, ask_volume, buy_pressure, sell_pressure, self.pressure_ratio
+            ),
+        }
+    }
+}
+
+impl SignalFilter for DepthFilter {
+    fn name(&self) -> &str {
+        "深度"
+    }
+
+    fn check(&self, ctx: &StrategyContext) -> Option<FilterVerdict> {
+        ctx.depth.map(|depth| self.analyze(depth, ctx.price))
+    }
+}
+
+// 24 小时行情过滤: 涨跌幅、日内位置、波动率和价差
+#[derive(Debug, Clone, PartialEq)]
+pub struct TickerFilter {
+    pub min_change_pct: f64,
+    pub low_zone_pct: f64,
+    pub high_zone_pct: f64,
+    pub max_volatility_pct: f64,
+    pub max_spread_pct: f64,
+}
+
+impl Default for TickerFilter {
+    fn default() -> Self {
+        Self {
+            min_change_pct: 0.2,
+            low_zone_pct: 20.0,
+            high_zone_pct: 80.0,
+            max_volatility_pct: 2.0,
+            max_spread_pct: 0.1,
+        }
+    }
+}
+
+impl TickerFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_change_pct(mut self, min_change_pct: f64) -> Self {
+        self.min_change_pct = min_change_pct;
+        self
+    }
+
+    pub fn with_zones(mut self, low_zone_pct: f64, high_zone_pct: f64) -> Self {
+        self.low_zone_pct = low_zone_pct;
+        self.high_zone_pct = high_zone_pct;
+        self
+    }
+
+    pub fn with_max_volatility_pct(mut self, max_volatility_pct: f64) -> Self {
+        self.max_volatility_pct = max_volatility_pct;
+        self
+    }
+
+    pub fn with_max_spread_pct(mut self, max_spread_pct: f64) -> Self {
+        self.max_spread_pct = max_spread_pct;
+        self
+    }
+
+    pub fn analyze(&self, ticker: &MarketTicker) -> FilterVerdict {
+        let volatility = (ticker.high_price - ticker.low_price) / ticker.low_price * 100.0;
+        let price_position = (ticker.last_price - ticker.low_price) /
+            (ticker.high_price - ticker.low_price) * 100.0;
+        let spread = (ticker.ask_price - ticker.bid_price) / ticker.bid_price * 100.0;
+
+        let mut bullish = false;
+        let mut bearish = false;
+
+        // 价格变动趋势
+        if ticker.price_change_percent > self.min_change_pct {
+            bullish = true;
+        } else if ticker.price_change_percent < -self.min_change_pct {
+            bearish = true;
+        }
+
+        // 日内位置: 接近低点偏多, 接近高点偏空
+        if price_position < self.low_zone_pct {
+            bullish = true;
+        } else if price_position > self.high_zone_pct {
+            bearish = true;
+        }
+
+        // 波动较大时只在更靠近区间两端的位置开仓
+        if volatility > self.max_volatility_pct {
+            bullish = bullish && price_position < self.low_zone_pct * 2.0;
+            bearish = bearish && price_position > 100.0 - (100.0 - self.high_zone_pct) * 2.0;
+        }
+
+        // 价差过大表示流动性不足
+        if spread > self.max_spread_pct {
+            bullish = false;
+            bearish = false;
+        }
+
+        FilterVerdict {
+            filter: self.name().to_string(),
+            long: bullish,
+            short: bearish,
+            reason: format!(
+                "24h涨跌 {:.2}%, 日内位置 {:.1}%, 波动 {:.2}%, 价差 {:.3}%",
+                ticker.price_change_percent, price_position, volatility, spread
+            ),
+        }
+    }
+}
+
+impl SignalFilter for TickerFilter {
+    fn name(&self) -> &str {
+        "行情"
+    }
+
+    fn check(&self, ctx: &StrategyContext) -> Option<FilterVerdict> {
+        ctx.ticker.map(|ticker| self.analyze(ticker))
+    }
+}
+
+// 一组过滤器的综合结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterOutcome {
+    pub verdicts: Vec<FilterVerdict>,
+    pub rejected: Vec<String>,  // 未确认的过滤器
+    pub score: f64,             // 0.0 - 1.0, 缺少数据的过滤器计 0.5
+}
+
+impl FilterOutcome {
+    pub fn confirmed(&self) -> bool {
+        self.rejected.is_empty()
+    }
+}
+
+// 可组合的过滤器集合
+#[derive(Default)]
+pub struct FilterSet {
+    filters: Vec<BoxedFilter>,
+}
+
+impl FilterSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 默认的深度 + 行情过滤
+    pub fn standard() -> Self {
+        Self::new()
+            .with_filter(DepthFilter::new())
+            .with_filter(TickerFilter::new())
+    }
+
+    pub fn with_filter(mut self, filter: impl SignalFilter + Send + Sync + 'static) -> Self {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.filters.iter().map(|f| f.name()).collect()
+    }
+
+    // 检查开仓方向, 缺少数据的过滤器视为确认
+    pub fn evaluate(&self, ctx: &StrategyContext, is_long: bool) -> FilterOutcome {
+        let mut verdicts = Vec::new();
+        let mut rejected = Vec::new();
+        let mut total = 0.0;
+
+        for filter in &self.filters {
+            match filter.check(ctx) {
+                Some(verdict) => {
+                    if verdict.confirms(is_long) {
+                        total += 1.0;
+                    } else {
+                        rejected.push(verdict.filter.clone());
+                    }
+                    verdicts.push(verdict);
+                }
+                None => {
+                    total += 0.5;
+                    verdicts.push(FilterVerdict {
+                        filter: filter.name().to_string(),
+                        long: true,
+                        short: true,
+                        reason: format!("无{}数据, 跳过确认", filter.name()),
+                    });
+                }
+            }
+        }
+
+        let score = if self.filters.is_empty() { 1.0 } else { total / self.filters.len() as f64 };
+        FilterOutcome { verdicts, rejected, score }
+    }
+
+    // 按策略参数构造过滤器, 参数缺省时使用默认值
+    pub fn from_config(config: &StrategyConfig) -> Result<Self, Box<dyn Error>> {
+        let mut set = Self::new();
+        let positive = |key: &str, default: f64| -> Result<f64, Box<dyn Error>> {
+            let value = config.param(key, default);
+            if value <= 0.0 {
+                return Err(format!("策略 {} 的参数 {} 必须大于 0: {}", config.name, key, value).into());
+            }
+            Ok(value)
+        };
+
+        if config.param("depth_filter", 1.0) != 0.0 {
+            let defaults = DepthFilter::default();
+            set = set.with_filter(DepthFilter::new()
+                .with_band_pct(positive("depth_band_pct", defaults.band_pct)?)
+                .with_pressure_ratio(positive("depth_ratio", defaults.pressure_ratio)?));
+        }
+
+        if config.param("ticker_filter", 1.0) != 0.0 {
+            let defaults = TickerFilter::default();
+            let low = config.param("low_zone_pct", defaults.low_zone_pct);
+            let high = config.param("high_zone_pct", defaults.high_zone_pct);
+            if !(0.0..=50.0).contains(&low) || !(50.0..=100.0).contains(&high) {
+                return Err(format!("策略 {} 的日内区间参数无效: 低位 {} / 高位 {}", config.name, low, high).into());
+            }
+            set = set.with_filter(TickerFilter::new()
+                .with_change_pct(positive("min_change_pct", defaults.min_change_pct)?)
+                .with_zones(low, high)
+                .with_max_volatility_pct(positive("max_volatility_pct", defaults.max_volatility_pct)?)
+                .with_max_spread_pct(positive("max_spread_pct", defaults.max_spread_pct)?));
+        }
+        Ok(set)
+    }
+}
@@ -0,0 +1,196 @@
+use crate::exchange::OrderUpdate;
+use crate::strategy::{Decision, OrderIntent, StrategyContext, TradingStrategy};
+use crate::types::Interval;
+use tracing::{info, warn};
+
+// 某一格已成交的买单, 等待在上一条网格线卖出
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GridFill {
+    quantity: f64,
+    price: f64,
+}
+
+// 网格交易 (只做多): 在 [lower, upper] 内等距划分网格线,
+// 当前价下方的每条网格线挂买单, 买单成交后在上一条网格线挂卖单, 卖单成交后重新挂回买单。
+// 收盘价超出区间 stop_pct 以上时停止网格, 撤销全部挂单并平掉持仓。
+pub struct GridStrategy {
+    lines: Vec<f64>,               // 网格线 (升序)
+    quantity: f64,                 // 每格下单数量 (0 为币种最小下单量)
+    stop_pct: f64,                 // 安全止损: 超出区间的幅度 (%)
+    interval: Interval,
+    last_price: Option<f64>,
+    fills: Vec<Option<GridFill>>,  // 每格的持仓, 下标 i 对应 lines[i] - lines[i + 1]
+    grid_profit: f64,              // 已完成买卖回合的累计利润 (计价货币, 未扣手续费)
+    round_trips: usize,
+    stopped: Option<String>,       // 安全止损原因
+}
+
+impl GridStrategy {
+    // levels 为网格线数量 (至少 2 条, 即 levels - 1 格)
+    pub fn new(lower: f64, upper: f64, levels: usize) -> Self {
+        let levels = levels.max(2);
+        let step = (upper - lower) / (levels - 1) as f64;
+        let lines: Vec<f64> = (0..levels).map(|i| lower + step * i as f64).collect();
+        Self {
+            fills: vec![None; levels - 1],
+            lines,
+            quantity: 0.0,
+            stop_pct: 5.0,
+            interval: Interval::FiveMinutes,
+            last_price: None,
+            grid_profit: 0.0,
+            round_trips: 0,
+            stopped: None,
+        }
+    }
+
+    // 每格下单数量
+    pub fn with_quantity(mut self, quantity: f64) -> Self {
+        self.quantity = quantity;
+        self
+    }
+
+    // 收盘价超出区间该幅度 (%) 时停止网格
+    pub fn with_stop_pct(mut self, stop_pct: f64) -> Self {
+        self.stop_pct = stop_pct;
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Interval) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn lines(&self) -> &[f64] {
+        &self.lines
+    }
+
+    // 已完成买卖回合的累计利润
+    pub fn grid_profit(&self) -> f64 {
+        self.grid_profit
+    }
+
+    pub fn round_trips(&self) -> usize {
+        self.round_trips
+    }
+
+    // 已买入、等待卖出的格数
+    pub fn filled_cells(&self) -> usize {
+        self.fills.iter().filter(|fill| fill.is_some()).count()
+    }
+
+    // 安全止损原因, 未触发为 None
+    pub fn stop_reason(&self) -> Option<&str> {
+        self.stopped.as_deref()
+    }
+
+    fn lower(&self) -> f64 {
+        self.lines[0]
+    }
+
+    fn upper(&self) -> f64 {
+        self.lines[self.lines.len() - 1]
+    }
+
+    // 解析挂单标识 "buy-3" / "sell-3"
+    fn parse_key(key: &str) -> Option<(&str, usize)> {
+        let (kind, cell) = key.split_once('-')?;
+        Some((kind, cell.parse().ok()?))
+    }
+}
+
+impl TradingStrategy for GridStrategy {
+    fn name(&self) -> &str {
+        "grid"
+    }
+
+    fn add_price(&mut self, price: f64) {
+        self.last_price = Some(price);
+        if self.stopped.is_some() {
+            return;
+        }
+        let lower_stop = self.lower() * (1.0 - self.stop_pct / 100.0);
+        let upper_stop = self.upper() * (1.0 + self.stop_pct / 100.0);
+        let reason = if price < lower_stop {
+            format!("价格 {:.4} 跌破网格止损价 {:.4}", price, lower_stop)
+        } else if price > upper_stop {
+            format!("价格 {:.4} 突破网格上沿 {:.4}", price, upper_stop)
+        } else {
+            return;
+        };
+        warn!(price, reason = %reason, "网格安全止损, 停止挂单");
+        self.stopped = Some(reason);
+    }
+
+    fn intervals(&self) -> Vec<Interval> {
+        vec![self.interval]
+    }
+
+    fn evaluate(&self, ctx: &StrategyContext) -> Decision {
+        match &self.stopped {
+            Some(reason) if ctx.position.is_some() => Decision::exit(1.0).with_reason(reason.clone()),
+            Some(reason) => Decision::hold().with_reason(reason.clone()),
+            None => Decision::hold().with_reason(format!(
+                "网格运行中: 持仓 {}/{} 格, 网格利润 {:.4}",
+                self.filled_cells(),
+                self.fills.len(),
+                self.grid_profit,
+            )),
+        }
+    }
+
+    fn places_resting_orders(&self) -> bool {
+        true
+    }
+
+    fn resting_orders(&self) -> Vec<OrderIntent> {
+        let price = match self.last_price {
+            Some(price) if self.stopped.is_none() => price,
+            _ => return Vec::new(),
+        };
+        self.fills.iter()
+            .enumerate()
+            .filter_map(|(cell, fill)| match fill {
+                Some(fill) => Some(OrderIntent::sell(format!("sell-{}", cell), self.lines[cell + 1], fill.quantity).reduce_only()),
+                // 只在当前价下方挂买单, 避免限价单立即以吃单成交
+                None if self.lines[cell] < price => Some(OrderIntent::buy(format!("buy-{}", cell), self.lines[cell], self.quantity)),
+                None => None,
+            })
+            .collect()
+    }
+
+    fn on_order_filled(&mut self, intent: &OrderIntent, fill: &OrderUpdate) {
+        let (kind, cell) = match Self::parse_key(&intent.key) {
+            Some((kind, cell)) if cell < self.fills.len() => (kind, cell),
+            _ => return,
+        };
+        let quantity = if fill.filled_quantity > 0.0 { fill.filled_quantity } else { intent.quantity };
+        match kind {
+            "buy" => {
+                self.fills[cell] = Some(GridFill { quantity, price: fill.fill_price() });
+                info!(cell, price = fill.fill_price(), quantity, "网格买单成交");
+            }
+            "sell" => {
+                if let Some(bought) = self.fills[cell].take() {
+                    let profit = (fill.fill_price() - bought.price) * quantity;
+                    self.grid_profit += profit;
+                    self.round_trips += 1;
+                    info!(cell, price = fill.fill_price(), profit, grid_profit = self.grid_profit, "网格卖单成交");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn describe(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("网格区间: {:.4} - {:.4} ({} 格)", self.lower(), self.upper(), self.fills.len()),
+            format!("网格持仓: {}/{} 格", self.filled_cells(), self.fills.len()),
+            format!("网格利润: {:.4} ({} 次买卖)", self.grid_profit, self.round_trips),
+        ];
+        if let Some(reason) = &self.stopped {
+            lines.push(format!("已停止: {}", reason));
+        }
+        lines
+    }
+}
@@ -0,0 +1,210 @@
+use crate::indicators::{BollingerBands, BollingerOutput, Indicator, RSI};
+use crate::strategy::{Action, Decision, FilterSet, SignalLog, StrategyContext, TradingStrategy};
+use crate::types::{Interval, OrderSide};
+
+// 均值回归 (适合震荡行情): 收盘价触及布林带下轨且 RSI 超卖时开多, 触及上轨且 RSI 超买时开空;
+// 回到中轨或持仓超过 max_bars 根K线 (时间止损) 时平仓, 止盈价为开仓时的中轨。
+// 可选的深度过滤 (DepthFilter) 用买卖挂单的失衡确认开仓方向。
+pub struct MeanReversionStrategy {
+    bands: BollingerBands,
+    rsi: RSI,
+    oversold: f64,
+    overbought: f64,
+    max_bars: usize,      // 时间止损: 最长持仓K线数 (0 为不限)
+    stop_pct: f64,        // 止损幅度 (%)
+    interval: Interval,
+    filters: FilterSet,   // 默认不过滤
+    last_close: Option<f64>,
+    bars: usize,          // 已输入的K线数
+    entered_at: Option<usize>,
+    signal_history: SignalLog,
+}
+
+impl MeanReversionStrategy {
+    pub fn new(period: usize, std_dev: f64, rsi_period: usize) -> Self {
+        Self {
+            bands: BollingerBands::new(period, std_dev),
+            rsi: RSI::new(rsi_period),
+            oversold: 30.0,
+            overbought: 70.0,
+            max_bars: 0,
+            stop_pct: 2.0,
+            interval: Interval::FiveMinutes,
+            filters: FilterSet::new(),
+            last_close: None,
+            bars: 0,
+            entered_at: None,
+            signal_history: SignalLog::default(),
+        }
+    }
+
+    // RSI 超卖/超买阈值
+    pub fn with_rsi_levels(mut self, oversold: f64, overbought: f64) -> Self {
+        self.oversold = oversold;
+        self.overbought = overbought;
+        self
+    }
+
+    // 时间止损: 持仓超过 max_bars 根K线时平仓
+    pub fn with_max_bars(mut self, max_bars: usize) -> Self {
+        self.max_bars = max_bars;
+        self
+    }
+
+    pub fn with_stop_pct(mut self, stop_pct: f64) -> Self {
+        self.stop_pct = stop_pct;
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Interval) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    // 开仓确认过滤器 (例如深度失衡)
+    pub fn with_filters(mut self, filters: FilterSet) -> Self {
+        self.filters = filters;
+        self
+    }
+
+    pub fn bands(&self) -> Option<BollingerOutput> {
+        self.bands.value()
+    }
+
+    pub fn rsi(&self) -> Option<f64> {
+        self.rsi.value()
+    }
+
+    pub fn signal_history(&self) -> &SignalLog {
+        &self.signal_history
+    }
+
+    // 已持仓的K线数, 未开仓为 None
+    pub fn bars_held(&self) -> Option<usize> {
+        self.entered_at.map(|at| self.bars.saturating_sub(at))
+    }
+
+    fn enter(&self, ctx: &StrategyContext, is_long: bool, price: f64, bands: BollingerOutput, rsi: f64) -> Decision {
+        let reason = format!(
+            "收盘价 {:.4} 触及布林带{} {:.4}, RSI {:.1} {}",
+            price,
+            if is_long { "下轨" } else { "上轨" },
+            if is_long { bands.lower } else { bands.upper },
+            rsi,
+            if is_long { "超卖" } else { "超买" },
+        );
+        let outcome = self.filters.evaluate(ctx, is_long);
+        if !outcome.confirmed() {
+            return Decision::hold()
+                .with_reason(reason)
+                .with_reason(format!("{}未确认", outcome.rejected.join("、")))
+                .with_verdicts(outcome.verdicts);
+        }
+        // RSI 越极端置信度越高
+        let extremity = if is_long {
+            (self.oversold - rsi) / self.oversold.max(1.0)
+        } else {
+            (rsi - self.overbought) / (100.0 - self.overbought).max(1.0)
+        };
+        let confidence = 0.5 + 0.5 * extremity.clamp(0.0, 1.0);
+        let stop = self.stop_pct / 100.0;
+        let decision = if is_long {
+            Decision::enter_long(confidence).with_stop_loss(price * (1.0 - stop))
+        } else {
+            Decision::enter_short(confidence).with_stop_loss(price * (1.0 + stop))
+        };
+        decision
+            .with_take_profit(bands.middle)
+            .with_reason(reason)
+            .with_verdicts(outcome.verdicts)
+    }
+}
+
+impl TradingStrategy for MeanReversionStrategy {
+    fn name(&self) -> &str {
+        "mean_reversion"
+    }
+
+    fn add_price(&mut self, price: f64) {
+        self.bands.update(price);
+        self.rsi.update(price);
+        self.last_close = Some(price);
+        self.bars += 1;
+    }
+
+    fn intervals(&self) -> Vec<Interval> {
+        vec![self.interval]
+    }
+
+    fn lookback(&self, interval: Interval) -> usize {
+        if interval == self.interval {
+            self.bands.warm_up().max(self.rsi.warm_up())
+        } else {
+            0
+        }
+    }
+
+    fn is_ready(&self) -> bool {
+        self.bands.is_ready() && self.rsi.is_ready()
+    }
+
+    fn evaluate(&self, ctx: &StrategyContext) -> Decision {
+        let (bands, rsi, price) = match (self.bands(), self.rsi(), self.last_close) {
+            (Some(bands), Some(rsi), Some(price)) => (bands, rsi, price),
+            _ => return Decision::hold().with_reason(format!("布林带/RSI 数据不足 ({} 根K线)", self.bars)),
+        };
+        let summary = format!(
+            "收盘价 {:.4}, 布林带 {:.4} / {:.4} / {:.4}, RSI {:.1}",
+            price, bands.lower, bands.middle, bands.upper, rsi
+        );
+
+        match ctx.position.map(|position| &position.side) {
+            Some(OrderSide::Buy) if price >= bands.middle => {
+                return Decision::exit(1.0).with_reason(summary).with_reason("多头回到中轨, 平仓");
+            }
+            Some(OrderSide::Sell) if price <= bands.middle => {
+                return Decision::exit(1.0).with_reason(summary).with_reason("空头回到中轨, 平仓");
+            }
+            Some(_) => {
+                return match self.bars_held() {
+                    Some(held) if self.max_bars > 0 && held >= self.max_bars => Decision::exit(1.0)
+                        .with_reason(summary)
+                        .with_reason(format!("持仓 {} 根K线未回归, 时间止损", held)),
+                    _ => Decision::hold().with_reason(summary).with_reason("持仓中, 等待回到中轨"),
+                };
+            }
+            None => {}
+        }
+
+        if price <= bands.lower && rsi <= self.oversold {
+            self.enter(ctx, true, price, bands, rsi)
+        } else if price >= bands.upper && rsi >= self.overbought {
+            self.enter(ctx, false, price, bands, rsi)
+        } else {
+            Decision::hold().with_reason(summary).with_reason("未触及布林带或 RSI 未确认")
+        }
+    }
+
+    fn on_signal(&mut self, decision: &Decision, price: f64, timestamp: i64) {
+        match decision.action {
+            Action::EnterLong | Action::EnterShort => self.entered_at = Some(self.bars),
+            Action::Exit => self.entered_at = None,
+            Action::Hold => return,
+        }
+        self.signal_history.record(decision, price, timestamp);
+    }
+
+    fn describe(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(bands) = self.bands() {
+            lines.push(format!("布林带: {:.4} / {:.4} / {:.4}", bands.lower, bands.middle, bands.upper));
+        }
+        if let Some(rsi) = self.rsi() {
+            lines.push(format!("RSI {:.1} (超卖 {} / 超买 {})", rsi, self.oversold, self.overbought));
+        }
+        if let Some(held) = self.bars_held() {
+            lines.push(format!("持仓 {} 根K线", held));
+        }
+        lines
+    }
+}
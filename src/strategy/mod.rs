@@ -1,4 +1,40 @@
-use crate::types::MACD;
+pub mod composite;
+pub mod dca;
+pub mod decision;
+pub mod donchian;
+pub mod filters;
+pub mod grid;
+pub mod mean_reversion;
+pub mod pairs;
+pub mod plugin;
+pub mod regime;
+pub mod registry;
+pub mod rules;
+
+use crate::exchange::OrderUpdate;
+use crate::indicators::{Indicator, MACDIndicator, RingBuffer, EMA};
+use crate::types::{CurrencyConfig, Interval, Kline, OrderSide, Position, MACD};
+use chrono::Utc;
+use std::collections::{BTreeMap, VecDeque};
+use tracing::debug;
+
+// 保留的信号记录条数
+const MAX_SIGNAL_HISTORY: usize = 100;
+// 指标周期上限 (与单次最多回填的K线数量一致)
+pub const MAX_PERIOD: usize = 1000;
+
+pub use composite::{CombineMode, CompositeStrategy};
+pub use dca::DCAStrategy;
+pub use decision::{Action, Decision, DeferredDecision, SignalRecord};
+pub use donchian::{Channel, DonchianStrategy};
+pub use filters::{DepthFilter, FilterSet, FilterVerdict, SignalFilter, TickerFilter};
+pub use grid::GridStrategy;
+pub use mean_reversion::MeanReversionStrategy;
+pub use pairs::{PairsStrategy, SpreadStats};
+pub use plugin::{PluginCommand, PluginStrategy};
+pub use regime::{RegimeDetector, RegimeReading};
+pub use registry::{BoxedStrategy, CompositeFactory, StrategyRegistry};
+pub use rules::{parse_rules, RuleDefinition, RuleError, RuleStrategy};
 
 // 定义市场深度数据结构
 #[derive(Debug, Clone)]
@@ -19,10 +55,237 @@ pub struct MarketTicker {
     pub ask_price: f64,             // 卖一价
 }
 
-#[allow(dead_code)]
+// 附加交易对的最新价格与持仓
+#[derive(Debug, Clone)]
+pub struct LegState {
+    pub symbol: String,
+    pub price: f64,                 // 最新收盘价
+    pub position: Option<Position>,
+}
+
+// 策略评估时的行情与持仓上下文
+#[derive(Debug, Clone, Copy)]
+pub struct StrategyContext<'a> {
+    pub symbol: &'a str,
+    pub price: f64,                          // 最新收盘价
+    pub depth: Option<&'a MarketDepth>,
+    pub ticker: Option<&'a MarketTicker>,
+    pub position: Option<&'a Position>,      // 当前持仓 (无持仓为 None)
+    pub timestamp: i64,                      // 评估时间 (毫秒)
+    pub candles: Option<&'a BTreeMap<Interval, &'a [Kline]>>,  // 各周期已收盘K线 (已按主周期对齐)
+    pub legs: &'a [LegState],                // 附加交易对 (多交易对策略)
+    pub regime: Option<&'a RegimeReading>,   // 当前市场状态 (识别数据不足时为 None)
+}
+
+impl<'a> StrategyContext<'a> {
+    pub fn new(symbol: &'a str, price: f64) -> Self {
+        Self {
+            symbol,
+            price,
+            depth: None,
+            ticker: None,
+            position: None,
+            timestamp: Utc::now().timestamp_millis(),
+            candles: None,
+            legs: &[],
+            regime: None,
+        }
+    }
+
+    pub fn with_legs(mut self, legs: &'a [LegState]) -> Self {
+        self.legs = legs;
+        self
+    }
+
+    // 指定附加交易对的状态
+    pub fn leg(&self, symbol: &str) -> Option<&'a LegState> {
+        self.legs.iter().find(|leg| leg.symbol == symbol)
+    }
+
+    pub fn with_candles(mut self, candles: Option<&'a BTreeMap<Interval, &'a [Kline]>>) -> Self {
+        self.candles = candles;
+        self
+    }
+
+    // 指定周期的已收盘K线 (按时间升序, 不含主周期最新K线之后收盘的K线)
+    pub fn candles(&self, interval: Interval) -> &'a [Kline] {
+        self.candles
+            .and_then(|candles| candles.get(&interval))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn with_timestamp(mut self, timestamp: i64) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    pub fn with_depth(mut self, depth: Option<&'a MarketDepth>) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    pub fn with_ticker(mut self, ticker: Option<&'a MarketTicker>) -> Self {
+        self.ticker = ticker;
+        self
+    }
+
+    pub fn with_position(mut self, position: Option<&'a Position>) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_regime(mut self, regime: Option<&'a RegimeReading>) -> Self {
+        self.regime = regime;
+        self
+    }
+}
+
+// 挂单型策略希望保持的限价单
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderIntent {
+    pub key: String,       // 策略内唯一标识, 成交回调时原样返回
+    pub side: OrderSide,
+    pub price: f64,
+    pub quantity: f64,     // 0 表示按币种最小下单量
+    pub reduce_only: bool, // 平仓单
+}
+
+impl OrderIntent {
+    pub fn buy(key: impl Into<String>, price: f64, quantity: f64) -> Self {
+        Self { key: key.into(), side: OrderSide::Buy, price, quantity, reduce_only: false }
+    }
+
+    pub fn sell(key: impl Into<String>, price: f64, quantity: f64) -> Self {
+        Self { key: key.into(), side: OrderSide::Sell, price, quantity, reduce_only: false }
+    }
+
+    pub fn reduce_only(mut self) -> Self {
+        self.reduce_only = true;
+        self
+    }
+}
+
 pub trait TradingStrategy {
-    fn should_buy(&self, price: f64, depth: Option<&MarketDepth>, ticker: Option<&MarketTicker>) -> bool;
-    fn should_sell(&self, price: f64, depth: Option<&MarketDepth>, ticker: Option<&MarketTicker>) -> bool;
+    // 策略名称 (与注册表中的名称一致)
+    fn name(&self) -> &str;
+    // 添加币种时传入币种配置, 供需要最小下单量和精度的策略使用
+    fn bind_currency(&mut self, _config: &CurrencyConfig) {}
+    // 需要的附加交易对数量 (CurrencyConfig::legs), 多交易对策略覆盖
+    fn required_legs(&self) -> usize {
+        0
+    }
+    // 输入最新收盘价
+    fn add_price(&mut self, price: f64);
+    // 需要的K线周期, 第一个为触发评估的主周期
+    fn intervals(&self) -> Vec<Interval> {
+        vec![Interval::FiveMinutes]
+    }
+    // 各周期需要回看的K线数量, 添加币种时按此回填历史数据
+    fn lookback(&self, _interval: Interval) -> usize {
+        0
+    }
+    // 是否读取市场状态 (StrategyContext::regime), 是则添加币种时同时回填识别所需的K线
+    fn uses_regime(&self) -> bool {
+        false
+    }
+    // 预热是否完成, 完成前不会评估实时信号
+    fn is_ready(&self) -> bool {
+        true
+    }
+    // 输入新收盘的K线 (多个周期按收盘时间顺序), 默认只把主周期收盘价交给 add_price
+    fn add_candle(&mut self, interval: Interval, kline: &Kline) {
+        if self.intervals().first() == Some(&interval) {
+            self.add_price(kline.close);
+        }
+    }
+    // 输入附加交易对新收盘的K线
+    fn add_leg_candle(&mut self, _symbol: &str, _interval: Interval, _kline: &Kline) {}
+    // 根据当前价格、深度、24小时行情和持仓给出决策 (可请求平仓)
+    fn evaluate(&self, ctx: &StrategyContext) -> Decision;
+    // 评估需要阻塞等待时返回待完成的决策, 管理器在策略锁之外等待 (默认 None, 在锁内调用 evaluate)
+    fn evaluate_deferred(&self, _ctx: &StrategyContext) -> Option<DeferredDecision> {
+        None
+    }
+    // 决策被执行后回调, 供策略记录已发出的信号
+    fn on_signal(&mut self, _decision: &Decision, _price: f64, _timestamp: i64) {}
+    // 希望保持挂出的限价单, 管理器每轮挂出缺少的、撤销不再需要的 (默认不挂单)
+    fn resting_orders(&self) -> Vec<OrderIntent> {
+        Vec::new()
+    }
+    // 是否通过 resting_orders 挂限价单 (添加币种时检查交易市场是否支持限价单)
+    fn places_resting_orders(&self) -> bool {
+        false
+    }
+    // 限价单成交回调
+    fn on_order_filled(&mut self, _intent: &OrderIntent, _fill: &OrderUpdate) {}
+    // 状态面板显示的策略内部状态, 每项一行
+    fn describe(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+// 已执行信号的记录, 超过容量时丢弃最早的 (各策略共用)
+#[derive(Debug, Clone)]
+pub struct SignalLog {
+    records: VecDeque<SignalRecord>,
+    capacity: usize,
+}
+
+impl Default for SignalLog {
+    fn default() -> Self {
+        Self::new(MAX_SIGNAL_HISTORY)
+    }
+}
+
+impl SignalLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            records: VecDeque::with_capacity(capacity.min(MAX_SIGNAL_HISTORY)),
+            capacity: capacity.max(1),
+        }
+    }
+
+    // 记录被执行的决策 (观望不记录)
+    pub fn record(&mut self, decision: &Decision, price: f64, timestamp: i64) {
+        if decision.action == Action::Hold {
+            return;
+        }
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(SignalRecord {
+            timestamp,
+            action: decision.action,
+            price,
+            confidence: decision.confidence,
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn last(&self) -> Option<&SignalRecord> {
+        self.records.back()
+    }
+
+    // 按时间顺序遍历
+    pub fn iter(&self) -> impl Iterator<Item = &SignalRecord> {
+        self.records.iter()
+    }
+}
+
+impl std::ops::Index<usize> for SignalLog {
+    type Output = SignalRecord;
+
+    fn index(&self, index: usize) -> &SignalRecord {
+        &self.records[index]
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -33,82 +296,99 @@ enum Signal {
 }
 
 pub struct MACDStrategy {
-    price_history: Vec<f64>,
-    fast_period: usize,   // 快线周期 (通常是12)
-    slow_period: usize,   // 慢线周期 (通常是26)
-    signal_period: usize, // 信号线周期 (通常是9)
-    last_signal: Option<Signal>,
-    macd_history: Vec<MACD>,
+    macd: MACDIndicator,  // 快线 12 / 慢线 26 / 信号线 9
+    price_count: usize,
+    last_signal: Option<Signal>,         // 柱状图反向翻转前不再发出同向信号
+    signal_history: SignalLog,
+    macd_history: RingBuffer<MACD>,
+    stop_loss_pct: f64,    // 建议止损幅度 (%)
+    take_profit_pct: f64,  // 建议止盈幅度 (%)
+    interval: Interval,    // 计算 MACD 的周期
+    trend_filter: Option<TrendFilter>,
+    filters: FilterSet,    // 开仓前的深度/行情确认
+}
+
+// 大周期趋势过滤: 只在收盘价位于 EMA 同侧时顺势开仓
+struct TrendFilter {
+    interval: Interval,
+    ema: EMA,
+    last_close: Option<f64>,
 }
 
 impl MACDStrategy {
     pub fn new(fast_period: usize, slow_period: usize, signal_period: usize) -> Self {
         Self {
-            price_history: Vec::new(),
-            fast_period,
-            slow_period,
-            signal_period,
+            macd: MACDIndicator::new(fast_period, slow_period, signal_period),
+            price_count: 0,
             last_signal: None,
-            macd_history: Vec::new(),
+            signal_history: SignalLog::default(),
+            macd_history: RingBuffer::new((signal_period * 2).max(3)),
+            stop_loss_pct: 5.0,
+            take_profit_pct: 10.0,
+            interval: Interval::FiveMinutes,
+            trend_filter: None,
+            filters: FilterSet::standard(),
         }
     }
 
-    pub fn add_price(&mut self, price: f64) {
-        self.price_history.push(price);
-        if self.price_history.len() > self.slow_period * 2 {
-            self.price_history.remove(0);
-        }
-        self.update_macd();
+    // 计算 MACD 使用的K线周期 (默认 5 分钟)
+    pub fn with_interval(mut self, interval: Interval) -> Self {
+        self.interval = interval;
+        self
     }
 
-    fn calculate_ema(&self, period: usize, prices: &[f64]) -> Option<f64> {
-        if prices.len() < period {
-            return None;
-        }
-
-        let multiplier = 2.0 / (period as f64 + 1.0);
-        let mut ema = prices[0];
+    // 替换开仓确认使用的过滤器
+    pub fn with_filters(mut self, filters: FilterSet) -> Self {
+        self.filters = filters;
+        self
+    }
 
-        for price in prices.iter().skip(1) {
-            ema = price * multiplier + ema * (1.0 - multiplier);
-        }
+    // 启用大周期趋势过滤, 例如 1 小时 EMA50
+    pub fn with_trend_filter(mut self, interval: Interval, ema_period: usize) -> Self {
+        self.trend_filter = Some(TrendFilter {
+            interval,
+            ema: EMA::new(ema_period),
+            last_close: None,
+        });
+        self
+    }
 
-        Some(ema)
+    // 已发出的信号 (按时间顺序, 最多保留最近 100 条)
+    pub fn signal_history(&self) -> &SignalLog {
+        &self.signal_history
     }
 
-    fn update_macd(&mut self) {
-        if self.price_history.len() < self.slow_period {
-            return;
-        }
+    // 是否在等待柱状图翻转 (期间不会重复发出同向开仓信号)
+    pub fn awaiting_reset(&self) -> bool {
+        self.last_signal.is_some()
+    }
 
-        // 计算MACD线
-        let fast_ema = self.calculate_ema(self.fast_period, &self.price_history)
-            .unwrap_or_default();
-        let slow_ema = self.calculate_ema(self.slow_period, &self.price_history)
-            .unwrap_or_default();
-        let macd_line = fast_ema - slow_ema;
-
-        // 计算信号线
-        let mut macd_values = self.macd_history.iter()
-            .map(|m| m.macd)
-            .collect::<Vec<f64>>();
-        macd_values.push(macd_line);
-
-        let signal_line = self.calculate_ema(self.signal_period, &macd_values)
-            .unwrap_or_default();
-
-        // 计算MACD柱状图
-        let histogram = macd_line - signal_line;
-
-        self.macd_history.push(MACD {
-            macd: macd_line,
-            signal: signal_line,
-            histogram,
-        });
+    // 建议的止损/止盈幅度 (%)
+    pub fn with_risk(mut self, stop_loss_pct: f64, take_profit_pct: f64) -> Self {
+        self.stop_loss_pct = stop_loss_pct;
+        self.take_profit_pct = take_profit_pct;
+        self
+    }
 
-        // 保持历史数据在合理范围内
-        if self.macd_history.len() > self.signal_period * 2 {
-            self.macd_history.remove(0);
+    fn update_macd(&mut self, price: f64) {
+        let previous = self.macd_history.last().map(|m| m.histogram);
+        let histogram = match self.macd.update(price) {
+            Some(macd) => {
+                self.macd_history.push(macd);
+                macd.histogram
+            }
+            None => return,
+        };
+
+        // 柱状图穿越到与信号相反的一侧后复位 (做多后由正转负, 做空后由负转正), 允许再次发出同向信号
+        let flipped = match (self.last_signal.as_ref(), previous) {
+            (Some(Signal::Buy), Some(previous)) => previous >= 0.0 && histogram < 0.0,
+            (Some(Signal::Sell), Some(previous)) => previous <= 0.0 && histogram > 0.0,
+            _ => false,
+        };
+        if flipped {
+            debug!(?self.last_signal, histogram, "MACD 柱状图翻转, 信号复位");
+            self.last_signal = None;
         }
     }
 
@@ -118,9 +398,9 @@ impl MACDStrategy {
             return None;
         }
 
-        let current = self.macd_history.last().unwrap();
-        let previous = self.macd_history.get(self.macd_history.len() - 2).unwrap();
-        let prev_prev = self.macd_history.get(self.macd_history.len() - 3).unwrap();
+        let current = self.macd_history.last()?;
+        let previous = self.macd_history.get(self.macd_history.len() - 2)?;
+        let prev_prev = self.macd_history.get(self.macd_history.len() - 3)?;
 
         // 计算柱状图变化率
         let curr_change = current.histogram - previous.histogram;
@@ -180,138 +460,184 @@ impl MACDStrategy {
 
         Some((current.histogram - previous.histogram).abs())
     }
+}
 
-    // 分析深度数据
-    fn analyze_depth(&self, depth: &MarketDepth, current_price: f64) -> (bool, bool) {
-        let mut buy_pressure = 0.0;
-        let mut sell_pressure = 0.0;
-        
-        // 计算买卖压力
-        let bid_volume: f64 = depth.bids.iter()
-            .filter(|(price, _)| *price > current_price * 0.99) // 考虑接近当前价格的订单
-            .map(|(_, quantity)| quantity)
-            .sum();
-            
-        let ask_volume: f64 = depth.asks.iter()
-            .filter(|(price, _)| *price < current_price * 1.01) // 考虑接近当前价格的订单
-            .map(|(_, quantity)| quantity)
-            .sum();
-            
-        // 计算买卖压力比例
-        if ask_volume > 0.0 {
-            buy_pressure = bid_volume / ask_volume;
-        }
-        if bid_volume > 0.0 {
-            sell_pressure = ask_volume / bid_volume;
+impl TradingStrategy for MACDStrategy {
+    fn name(&self) -> &str {
+        "macd"
+    }
+
+    fn add_price(&mut self, price: f64) {
+        self.price_count += 1;
+        self.update_macd(price);
+    }
+
+    fn intervals(&self) -> Vec<Interval> {
+        let mut intervals = vec![self.interval];
+        if let Some(filter) = &self.trend_filter {
+            if filter.interval != self.interval {
+                intervals.push(filter.interval);
+            }
         }
-        
-        // 判断是否有显著的买卖压力
-        let strong_buy = buy_pressure > 1.2;  // 买单量超过卖单量20%
-        let strong_sell = sell_pressure > 1.2; // 卖单量超过买单量20%
-        
-        (strong_buy, strong_sell)
+        intervals
     }
 
-    // 分析24小时行情数据
-    fn analyze_ticker(&self, ticker: &MarketTicker) -> (bool, bool) {
-        // 计算日内波动率
-        let volatility = (ticker.high_price - ticker.low_price) / ticker.low_price * 100.0;
-        
-        // 计算价格在日内区间的位置 (0-100%)
-        let price_position = (ticker.last_price - ticker.low_price) / 
-            (ticker.high_price - ticker.low_price) * 100.0;
-            
-        // 计算买卖压力
-        let spread = (ticker.ask_price - ticker.bid_price) / ticker.bid_price * 100.0;
-        
-        // 判断市场状态
-        let mut bullish = false;
-        let mut bearish = false;
-        
-        // 价格变动趋势判断
-        if ticker.price_change_percent > 0.2 {  // 上涨超过0.2%
-            bullish = true;
-        } else if ticker.price_change_percent < -0.2 {  // 下跌超过0.2%
-            bearish = true;
+    fn lookback(&self, interval: Interval) -> usize {
+        let mut bars = 0;
+        if interval == self.interval {
+            // 动量判断需要 3 个 MACD 值
+            bars = self.macd.warm_up() + 2;
         }
-        
-        // 价格位置判断
-        if price_position < 20.0 {  // 接近日内低点
-            bullish = true;
-        } else if price_position > 80.0 {  // 接近日内高点
-            bearish = true;
+        if let Some(filter) = self.trend_filter.as_ref().filter(|f| f.interval == interval) {
+            bars = bars.max(filter.ema.warm_up());
         }
-        
-        // 波动率判断
-        if volatility > 2.0 {  // 日内波动超过2%，增加风险意识
-            bullish = bullish && price_position < 40.0;  // 只在较低位置做多
-            bearish = bearish && price_position > 60.0;  // 只在较高位置做空
+        bars
+    }
+
+    fn is_ready(&self) -> bool {
+        self.macd_history.len() >= 3
+            && self.trend_filter.as_ref().is_none_or(|filter| filter.ema.is_ready())
+    }
+
+    fn add_candle(&mut self, interval: Interval, kline: &Kline) {
+        if let Some(filter) = self.trend_filter.as_mut().filter(|f| f.interval == interval) {
+            filter.ema.update(kline.close);
+            filter.last_close = Some(kline.close);
         }
-        
-        // 买卖压力判断
-        if spread > 0.1 {  // 买卖价差过大，表示流动性不足
-            bullish = false;
-            bearish = false;
+        if interval == self.interval {
+            self.add_price(kline.close);
         }
-        
-        (bullish, bearish)
     }
-}
 
-impl TradingStrategy for MACDStrategy {
-    fn should_buy(&self, price: f64, depth: Option<&MarketDepth>, ticker: Option<&MarketTicker>) -> bool {
-        let signal = self.check_momentum();
-        let strength = self.momentum_strength();
-        
-        // 检查深度数据
-        let depth_confirms = depth.map(|d| {
-            let (strong_buy, _) = self.analyze_depth(d, price);
-            strong_buy
-        }).unwrap_or(true);
-        
-        // 检查24小时行情
-        let ticker_confirms = ticker.map(|t| {
-            let (bullish, _) = self.analyze_ticker(t);
-            bullish
-        }).unwrap_or(true);
-
-        match (signal, strength) {
-            (Some(Signal::Buy), Some(strength)) => {
-                // 同时满足MACD信号、深度分析和行情分析
-                strength > 0.0001 && 
-                self.last_signal != Some(Signal::Buy) &&
-                depth_confirms &&
-                ticker_confirms
-            }
-            _ => false
+    fn on_signal(&mut self, decision: &Decision, price: f64, timestamp: i64) {
+        let signal = match decision.action {
+            Action::EnterLong => Some(Signal::Buy),
+            Action::EnterShort => Some(Signal::Sell),
+            Action::Exit | Action::Hold => None,
+        };
+        if signal.is_some() {
+            self.last_signal = signal;
         }
+        self.signal_history.record(decision, price, timestamp);
     }
 
-    fn should_sell(&self, price: f64, depth: Option<&MarketDepth>, ticker: Option<&MarketTicker>) -> bool {
+    fn evaluate(&self, ctx: &StrategyContext) -> Decision {
+        let price = ctx.price;
+        let current = match self.macd_history.last() {
+            Some(current) if self.macd_history.len() >= 3 => current,
+            _ => return Decision::hold().with_reason(format!(
+                "MACD 数据不足 ({} 个价格, 至少需要 {} 个)",
+                self.price_count,
+                self.macd.warm_up() + 2
+            )),
+        };
+
         let signal = self.check_momentum();
         let strength = self.momentum_strength();
-        
-        // 检查深度数据
-        let depth_confirms = depth.map(|d| {
-            let (_, strong_sell) = self.analyze_depth(d, price);
-            strong_sell
-        }).unwrap_or(true);
-        
-        // 检查24小时行情
-        let ticker_confirms = ticker.map(|t| {
-            let (_, bearish) = self.analyze_ticker(t);
-            bearish
-        }).unwrap_or(true);
-
-        match (signal, strength) {
-            (Some(Signal::Sell), Some(strength)) => {
-                // 同时满足MACD信号、深度分析和行情分析
-                strength > 0.0001 && 
-                self.last_signal != Some(Signal::Sell) &&
-                depth_confirms &&
-                ticker_confirms
+        let mut reasons = vec![format!(
+            "MACD {:.6} / 信号线 {:.6} / 柱 {:.6}",
+            current.macd, current.signal, current.histogram
+        )];
+
+        let (is_long, change_percent) = match (&signal, self.check_momentum_trend()) {
+            (Some(Signal::Buy), Some((_, change))) => (true, change),
+            (Some(Signal::Sell), Some((_, change))) => (false, change),
+            _ => return Decision::hold().with_reasons(reasons).with_reason("MACD 动量未加速或未接近交叉"),
+        };
+        reasons.push(format!(
+            "MACD 柱{}加速, 变化 {:.2}%",
+            if is_long { "向上" } else { "向下" },
+            change_percent
+        ));
+
+        if strength.unwrap_or_default() <= 0.0001 {
+            return Decision::hold().with_reasons(reasons).with_reason("动量强度不足");
+        }
+        let direction = if is_long { Signal::Buy } else { Signal::Sell };
+        if self.last_signal.as_ref() == Some(&direction) {
+            return Decision::hold().with_reasons(reasons).with_reason("与上次信号方向相同, 等待柱状图翻转");
+        }
+
+        // 过滤器确认 (缺少数据时视为确认, 但只计一半分数)
+        let outcome = self.filters.evaluate(ctx, is_long);
+
+        // 大周期趋势确认
+        let trend_confirms = match &self.trend_filter {
+            Some(filter) => match (filter.ema.value(), filter.last_close) {
+                (Some(ema), Some(close)) => {
+                    let confirms = if is_long { close > ema } else { close < ema };
+                    reasons.push(format!(
+                        "{} 趋势: 收盘 {:.4} {} EMA {:.4}",
+                        filter.interval,
+                        close,
+                        if close > ema { ">" } else { "<=" },
+                        ema
+                    ));
+                    confirms
+                }
+                _ => {
+                    reasons.push(format!("{} 趋势数据不足", filter.interval));
+                    false
+                }
+            },
+            None => true,
+        };
+
+        debug!(?signal, ?strength, rejected = ?outcome.rejected, trend_confirms, "MACD 信号检查");
+
+        // 持仓方向与信号的关系
+        let holding_long = ctx.position.map(|p| p.side == OrderSide::Buy);
+        let against_position = holding_long.is_some_and(|long| long != is_long);
+        if holding_long == Some(is_long) {
+            return Decision::hold()
+                .with_reasons(reasons)
+                .with_reason("已有同向持仓")
+                .with_verdicts(outcome.verdicts);
+        }
+
+        if !outcome.confirmed() || !trend_confirms {
+            let mut rejected = outcome.rejected.clone();
+            if !trend_confirms {
+                rejected.push("大周期趋势".to_string());
             }
-            _ => false
+            let missing = format!("{}未确认", rejected.join("、"));
+            // 反向信号即使未被确认也足以平掉现有持仓
+            if against_position {
+                return Decision::exit(0.4 * (change_percent / 20.0).min(1.0))
+                    .with_reasons(reasons)
+                    .with_reason(missing)
+                    .with_reason("MACD 动量与持仓方向相反, 平仓")
+                    .with_verdicts(outcome.verdicts);
+            }
+            return Decision::hold()
+                .with_reasons(reasons)
+                .with_reason(missing)
+                .with_verdicts(outcome.verdicts);
+        }
+        if against_position {
+            reasons.push("MACD 动量与持仓方向相反, 反手".to_string());
         }
+
+        let macd_score = (change_percent / 20.0).min(1.0);
+        let confidence = 0.4 * macd_score + 0.6 * outcome.score;
+        let (decision, stop_loss, take_profit) = if is_long {
+            (
+                Decision::enter_long(confidence),
+                price * (1.0 - self.stop_loss_pct / 100.0),
+                price * (1.0 + self.take_profit_pct / 100.0),
+            )
+        } else {
+            (
+                Decision::enter_short(confidence),
+                price * (1.0 + self.stop_loss_pct / 100.0),
+                price * (1.0 - self.take_profit_pct / 100.0),
+            )
+        };
+
+        decision
+            .with_stop_loss(stop_loss)
+            .with_take_profit(take_profit)
+            .with_reasons(reasons)
+            .with_verdicts(outcome.verdicts)
     }
 } 
\ No newline at end of file
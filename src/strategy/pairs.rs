@@ -0,0 +1,287 @@
+use crate::strategy::{Action, Decision, StrategyContext, TradingStrategy};
+use crate::types::{CurrencyConfig, Interval, Kline, Position};
+use std::collections::{BTreeMap, VecDeque};
+
+// 价差统计: 对数价格回归 ln(主) = alpha + beta * ln(对冲)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpreadStats {
+    pub hedge_ratio: f64,  // beta
+    pub spread: f64,       // 最新价差 ln(主) - beta * ln(对冲)
+    pub mean: f64,
+    pub std_dev: f64,
+    pub z_score: f64,
+}
+
+// 配对交易 (统计套利): 主交易对与对冲腿 (CurrencyConfig::legs 的第一个) 按开盘时间对齐收盘价,
+// 在回看窗口内估计对冲比例和价差 z 分数;
+// z 高于 entry_z 时做空主交易对、做多对冲腿, 低于 -entry_z 时反向, 两腿同时开平。
+// |z| 回落到 exit_z 以内、超过 stop_z 或持仓超过 max_bars 根K线时两腿同时平仓;
+// 只剩一条腿有持仓时立即平掉该腿。
+pub struct PairsStrategy {
+    lookback: usize,
+    entry_z: f64,
+    exit_z: f64,
+    stop_z: f64,
+    max_bars: usize,       // 最长持仓K线数 (0 为不限)
+    stop_pct: f64,         // 每条腿交易所止损幅度 (%), 防止单腿失控
+    target_pct: f64,       // 每条腿交易所止盈幅度 (%)
+    interval: Interval,
+    currency: Option<CurrencyConfig>,
+    leg: Option<CurrencyConfig>,
+    pending: BTreeMap<i64, (Option<f64>, Option<f64>)>,  // 开盘时间 -> (主, 对冲) 收盘价, 等待另一条腿
+    window: VecDeque<(f64, f64)>,                       // 已对齐的 (ln 主, ln 对冲)
+    bars: usize,                                        // 已对齐的K线总数
+    entered_at: Option<usize>,                          // 开仓时的 bars
+}
+
+impl PairsStrategy {
+    pub fn new(lookback: usize, entry_z: f64, exit_z: f64, stop_z: f64) -> Self {
+        Self {
+            lookback,
+            entry_z,
+            exit_z,
+            stop_z,
+            max_bars: 0,
+            stop_pct: 10.0,
+            target_pct: 20.0,
+            interval: Interval::FiveMinutes,
+            currency: None,
+            leg: None,
+            pending: BTreeMap::new(),
+            window: VecDeque::with_capacity(lookback),
+            bars: 0,
+            entered_at: None,
+        }
+    }
+
+    pub fn with_max_bars(mut self, max_bars: usize) -> Self {
+        self.max_bars = max_bars;
+        self
+    }
+
+    // 每条腿附带的交易所止损/止盈幅度 (%)
+    pub fn with_risk(mut self, stop_pct: f64, target_pct: f64) -> Self {
+        self.stop_pct = stop_pct;
+        self.target_pct = target_pct;
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Interval) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    // 对冲腿交易对 (未绑定币种配置时为 None)
+    pub fn leg_symbol(&self) -> Option<&str> {
+        self.leg.as_ref().map(|leg| leg.symbol.as_str())
+    }
+
+    // 回看窗口内的价差统计, 数据不足或价差无波动时为 None
+    pub fn stats(&self) -> Option<SpreadStats> {
+        if self.window.len() < self.lookback || self.lookback < 2 {
+            return None;
+        }
+        let n = self.window.len() as f64;
+        let mean_y = self.window.iter().map(|(y, _)| y).sum::<f64>() / n;
+        let mean_x = self.window.iter().map(|(_, x)| x).sum::<f64>() / n;
+        let (cov, var_x) = self.window.iter().fold((0.0, 0.0), |(cov, var), (y, x)| {
+            (cov + (y - mean_y) * (x - mean_x), var + (x - mean_x).powi(2))
+        });
+        if var_x <= 0.0 {
+            return None;
+        }
+        let hedge_ratio = cov / var_x;
+        let spreads: Vec<f64> = self.window.iter().map(|(y, x)| y - hedge_ratio * x).collect();
+        let mean = spreads.iter().sum::<f64>() / n;
+        let std_dev = (spreads.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n).sqrt();
+        if std_dev <= f64::EPSILON {
+            return None;
+        }
+        let spread = spreads[spreads.len() - 1];
+        Some(SpreadStats { hedge_ratio, spread, mean, std_dev, z_score: (spread - mean) / std_dev })
+    }
+
+    // 记录一条腿的收盘价, 两条腿都到齐后加入回看窗口
+    fn record(&mut self, open_time: i64, primary: Option<f64>, hedge: Option<f64>) {
+        let entry = self.pending.entry(open_time).or_insert((None, None));
+        entry.0 = primary.or(entry.0);
+        entry.1 = hedge.or(entry.1);
+        let (y, x) = match self.pending[&open_time] {
+            (Some(y), Some(x)) if y > 0.0 && x > 0.0 => (y, x),
+            _ => {
+                // 一条腿长期缺数据时只保留最近 lookback 根等待配对
+                while self.pending.len() > self.lookback {
+                    self.pending.pop_first();
+                }
+                return;
+            }
+        };
+        // 更早的未配对K线不会再配对
+        self.pending = self.pending.split_off(&(open_time + 1));
+        if self.window.len() == self.lookback {
+            self.window.pop_front();
+        }
+        self.window.push_back((y.ln(), x.ln()));
+        self.bars += 1;
+    }
+
+    // 开仓两条腿: 主交易对数量按置信度计算, 对冲腿按对冲比例换算为等效名义价值
+    fn enter(&self, primary_long: bool, price: f64, leg_price: f64, stats: &SpreadStats, confidence: f64) -> Decision {
+        let (currency, leg) = match (&self.currency, &self.leg) {
+            (Some(currency), Some(leg)) => (currency, leg),
+            _ => return Decision::hold().with_reason("未配置对冲腿"),
+        };
+        let quantity = currency.order_quantity(confidence);
+        let leg_quantity = leg.floor_qty(stats.hedge_ratio * quantity * price / leg_price).max(leg.min_qty);
+        let reason = format!(
+            "价差 z={:.2} ({}), 对冲比例 {:.3}",
+            stats.z_score,
+            if primary_long { "偏低, 做多主交易对" } else { "偏高, 做空主交易对" },
+            stats.hedge_ratio
+        );
+        let primary = self.leg_decision(primary_long, price, confidence).with_quantity(quantity);
+        let hedge = self.leg_decision(!primary_long, leg_price, confidence).with_quantity(leg_quantity);
+        primary.with_reason(reason).with_leg(&leg.symbol, hedge)
+    }
+
+    fn leg_decision(&self, long: bool, price: f64, confidence: f64) -> Decision {
+        let (stop, target) = (self.stop_pct / 100.0, self.target_pct / 100.0);
+        if long {
+            Decision::enter_long(confidence)
+                .with_stop_loss(price * (1.0 - stop))
+                .with_take_profit(price * (1.0 + target))
+        } else {
+            Decision::enter_short(confidence)
+                .with_stop_loss(price * (1.0 + stop))
+                .with_take_profit(price * (1.0 - target))
+        }
+    }
+
+    // 持仓中的平仓条件
+    fn exit_reason(&self, stats: &SpreadStats) -> Option<String> {
+        let z = stats.z_score.abs();
+        if z <= self.exit_z {
+            return Some(format!("价差回归 z={:.2}", stats.z_score));
+        }
+        if z >= self.stop_z {
+            return Some(format!("价差扩大止损 z={:.2}", stats.z_score));
+        }
+        let held = self.entered_at.map(|at| self.bars.saturating_sub(at));
+        match held {
+            Some(held) if self.max_bars > 0 && held >= self.max_bars => Some(format!("持仓 {} 根K线超时", held)),
+            _ => None,
+        }
+    }
+}
+
+impl TradingStrategy for PairsStrategy {
+    fn name(&self) -> &str {
+        "pairs"
+    }
+
+    fn bind_currency(&mut self, config: &CurrencyConfig) {
+        self.currency = Some(config.clone());
+        self.leg = config.legs.first().cloned();
+    }
+
+    fn required_legs(&self) -> usize {
+        1
+    }
+
+    // 收盘价通过 add_candle 按开盘时间与对冲腿对齐
+    fn add_price(&mut self, _price: f64) {}
+
+    fn intervals(&self) -> Vec<Interval> {
+        vec![self.interval]
+    }
+
+    fn lookback(&self, interval: Interval) -> usize {
+        if interval == self.interval { self.lookback } else { 0 }
+    }
+
+    fn is_ready(&self) -> bool {
+        self.window.len() >= self.lookback
+    }
+
+    fn add_candle(&mut self, interval: Interval, kline: &Kline) {
+        if interval == self.interval {
+            self.record(kline.open_time, Some(kline.close), None);
+        }
+    }
+
+    fn add_leg_candle(&mut self, symbol: &str, interval: Interval, kline: &Kline) {
+        if interval == self.interval && self.leg_symbol() == Some(symbol) {
+            self.record(kline.open_time, None, Some(kline.close));
+        }
+    }
+
+    fn evaluate(&self, ctx: &StrategyContext) -> Decision {
+        let leg_symbol = match self.leg_symbol() {
+            Some(symbol) => symbol,
+            None => return Decision::hold().with_reason("未配置对冲腿"),
+        };
+        let leg = match ctx.leg(leg_symbol) {
+            Some(leg) => leg,
+            None => return Decision::hold().with_reason(format!("缺少对冲腿 {} 的行情", leg_symbol)),
+        };
+        let exit_both = |reason: String| Decision::exit(1.0).with_reason(reason).with_leg(leg_symbol, Decision::exit(1.0));
+
+        // 单腿持仓 (另一条腿开仓失败或被交易所止损) 时立即平掉剩余的腿
+        let held: (Option<&Position>, Option<&Position>) = (ctx.position, leg.position.as_ref());
+        match held {
+            (Some(_), None) => return Decision::exit(1.0).with_reason(format!("对冲腿 {} 无持仓, 平掉主交易对", leg_symbol)),
+            (None, Some(_)) => {
+                return Decision::hold()
+                    .with_reason(format!("主交易对无持仓, 平掉对冲腿 {}", leg_symbol))
+                    .with_leg(leg_symbol, Decision::exit(1.0));
+            }
+            _ => {}
+        }
+
+        let stats = match self.stats() {
+            Some(stats) => stats,
+            None => return Decision::hold().with_reason("价差数据不足"),
+        };
+        if held.0.is_some() {
+            return match self.exit_reason(&stats) {
+                Some(reason) => exit_both(reason),
+                None => Decision::hold().with_reason(format!("持仓中, 价差 z={:.2}", stats.z_score)),
+            };
+        }
+
+        if stats.hedge_ratio <= 0.0 {
+            return Decision::hold().with_reason(format!("对冲比例 {:.3} 非正, 不开仓", stats.hedge_ratio));
+        }
+        let confidence = ((stats.z_score.abs() - self.entry_z) / (self.stop_z - self.entry_z)).clamp(0.0, 1.0);
+        if stats.z_score >= self.entry_z && stats.z_score < self.stop_z {
+            self.enter(false, ctx.price, leg.price, &stats, confidence)
+        } else if stats.z_score <= -self.entry_z && stats.z_score > -self.stop_z {
+            self.enter(true, ctx.price, leg.price, &stats, confidence)
+        } else {
+            Decision::hold().with_reason(format!("价差 z={:.2} 未达到开仓阈值 {:.2}", stats.z_score, self.entry_z))
+        }
+    }
+
+    fn on_signal(&mut self, decision: &Decision, _price: f64, _timestamp: i64) {
+        match decision.action {
+            Action::EnterLong | Action::EnterShort => self.entered_at = Some(self.bars),
+            Action::Exit => self.entered_at = None,
+            Action::Hold => {}
+        }
+    }
+
+    fn describe(&self) -> Vec<String> {
+        let leg = self.leg_symbol().unwrap_or("-");
+        let mut lines = vec![format!("对冲腿: {}, 回看 {}/{} 根K线", leg, self.window.len(), self.lookback)];
+        if let Some(stats) = self.stats() {
+            lines.push(format!(
+                "对冲比例 {:.4}, 价差 {:.6} (均值 {:.6}, 标准差 {:.6}), z={:.2}",
+                stats.hedge_ratio, stats.spread, stats.mean, stats.std_dev, stats.z_score
+            ));
+        }
+        if let Some(at) = self.entered_at {
+            lines.push(format!("持仓 {} 根K线", self.bars.saturating_sub(at)));
+        }
+        lines
+    }
+}
@@ -0,0 +1,588 @@
+use crate::strategy::{Action, Decision, DeferredDecision, StrategyContext, TradingStrategy};
+use crate::trading::candles::sort_for_replay;
+use crate::types::{CurrencyConfig, Interval, Kline, OrderSide};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+// 外部进程策略插件, 通过 stdin/stdout 逐行交换 JSON 消息:
+//
+//   -> {"type":"hello","name":"my_plugin","params":{...}}
+//   <- {"type":"hello","intervals":["5m","1h"],"lookback":{"5m":50}}     (两个字段均可省略)
+//   -> {"type":"bind","symbol":"BTC-USDT","min_qty":0.001,"market":"swap"}
+//   -> {"type":"candle","interval":"5m","kline":{...}}                     (不需要回复)
+//   -> {"type":"evaluate","id":1,"symbol":...,"price":...,"timestamp":...,"depth":...,"ticker":...,"position":...,"regime":...}
+//   <- {"type":"decision","id":1,"action":"enter_long","confidence":0.8,"stop_loss":95.0,"reasons":["..."]}
+//   -> {"type":"signal","action":"enter_long","price":...,"timestamp":...}  (决策已执行, 不需要回复)
+//
+// action 可为 enter_long / enter_short / exit / hold, 可选字段: stop_loss, take_profit, quantity, close_fraction。
+// 插件的 stderr 和 stdout 中的非 JSON 行按日志输出。超时或进程退出时本轮观望,
+// 之后按 restart_delay 重启进程并重新发送 hello、bind 和缓存的K线 (每个周期最多 lookback 根)。
+// 进程读写、重启和K线重放都在每个插件独占的 I/O 线程中进行, 策略方法只投递请求。
+
+// 插件启动命令: 程序 + 参数 (按空白分隔, 不支持引号)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginCommand {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl std::str::FromStr for PluginCommand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace().map(str::to_string);
+        let program = parts.next().ok_or_else(|| "插件命令不能为空".to_string())?;
+        Ok(Self { program, args: parts.collect() })
+    }
+}
+
+impl std::fmt::Display for PluginCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.program)?;
+        for arg in &self.args {
+            write!(f, " {}", arg)?;
+        }
+        Ok(())
+    }
+}
+
+// 插件握手回复
+#[derive(Debug, Default, Deserialize)]
+struct Hello {
+    #[serde(default)]
+    intervals: Vec<Interval>,
+    #[serde(default)]
+    lookback: BTreeMap<String, usize>,
+}
+
+// 插件返回的决策
+#[derive(Debug, Deserialize)]
+struct PluginDecision {
+    action: String,
+    #[serde(default)]
+    confidence: f64,
+    stop_loss: Option<f64>,
+    take_profit: Option<f64>,
+    quantity: Option<f64>,
+    close_fraction: Option<f64>,
+    #[serde(default)]
+    reasons: Vec<String>,
+}
+
+impl PluginDecision {
+    fn into_decision(self) -> Result<Decision, String> {
+        let mut decision = match self.action.as_str() {
+            "enter_long" => Decision::enter_long(self.confidence),
+            "enter_short" => Decision::enter_short(self.confidence),
+            "exit" => match self.close_fraction {
+                Some(fraction) => Decision::reduce(fraction, self.confidence),
+                None => Decision::exit(self.confidence),
+            },
+            "hold" => Decision::hold(),
+            other => return Err(format!("未知的动作: {}", other)),
+        };
+        decision.stop_loss = self.stop_loss;
+        decision.take_profit = self.take_profit;
+        decision.quantity = self.quantity.filter(|quantity| *quantity > 0.0);
+        Ok(decision.with_reasons(self.reasons))
+    }
+}
+
+// 运行中的插件进程
+struct PluginProcess {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,  // stdout 的每一行, 进程退出后断开
+}
+
+impl PluginProcess {
+    fn spawn(name: &str, command: &PluginCommand) -> Result<Self, String> {
+        let mut child = Command::new(&command.program)
+            .args(&command.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("启动插件 {} 失败: {}", command, e))?;
+        let stdin = child.stdin.take().ok_or("无法获取插件 stdin")?;
+        let stdout = child.stdout.take().ok_or("无法获取插件 stdout")?;
+        let stderr = child.stderr.take().ok_or("无法获取插件 stderr")?;
+
+        let (sender, lines) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        let plugin = name.to_string();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                info!(plugin = %plugin, "插件输出: {}", line);
+            }
+        });
+        info!(plugin = name, pid = child.id(), command = %command, "插件进程已启动");
+        Ok(Self { child, stdin, lines })
+    }
+
+    fn send(&mut self, message: &Value) -> Result<(), String> {
+        writeln!(self.stdin, "{}", message)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|e| format!("写入插件失败: {}", e))
+    }
+
+    // 等待指定类型 (及请求编号) 的回复, 其他输出按日志处理
+    fn receive(&mut self, name: &str, kind: &str, id: Option<u64>, timeout: Duration) -> Result<Value, String> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let line = match self.lines.recv_timeout(remaining) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => return Err(format!("等待 {} 超时 ({} ms)", kind, timeout.as_millis())),
+                Err(RecvTimeoutError::Disconnected) => {
+                    let status = self.child.wait().map(|s| s.to_string()).unwrap_or_default();
+                    return Err(format!("插件进程已退出 {}", status));
+                }
+            };
+            let message: Value = match serde_json::from_str(&line) {
+                Ok(message) => message,
+                Err(_) => {
+                    if !line.trim().is_empty() {
+                        info!(plugin = name, "插件输出: {}", line);
+                    }
+                    continue;
+                }
+            };
+            let matches = message["type"] == kind && id.is_none_or(|id| message["id"] == id);
+            if matches {
+                return Ok(message);
+            }
+            debug!(plugin = name, %message, "忽略插件消息");
+        }
+    }
+}
+
+impl Drop for PluginProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+// 启动插件进程所需的参数 (I/O 线程重启进程时复用)
+#[derive(Clone)]
+struct Launcher {
+    name: String,
+    command: PluginCommand,
+    params: BTreeMap<String, f64>,
+    startup_timeout: Duration,
+}
+
+impl Launcher {
+    // 启动进程并握手
+    fn start(&self) -> Result<(PluginProcess, Hello), String> {
+        let mut process = PluginProcess::spawn(&self.name, &self.command)?;
+        process.send(&json!({ "type": "hello", "name": self.name, "params": self.params }))?;
+        let reply = process.receive(&self.name, "hello", None, self.startup_timeout)
+            .map_err(|e| format!("插件 {} 握手失败: {}", self.name, e))?;
+        let hello = serde_json::from_value(reply).map_err(|e| format!("插件 {} 的 hello 格式错误: {}", self.name, e))?;
+        Ok((process, hello))
+    }
+}
+
+// 策略与 I/O 线程共享的状态
+struct PluginState {
+    running: bool,
+    restarts: usize,
+    last_error: Option<String>,
+    restart_delay: Duration,
+}
+
+// 投递给 I/O 线程的请求
+enum Request {
+    Bind(Value),
+    Candle(Interval, Kline),
+    Signal(Value),
+    Evaluate {
+        id: u64,
+        message: Value,
+        deadline: Instant,  // 调用方等待的截止时间, 过期的请求不再发送
+        reply: Sender<Result<Value, String>>,
+    },
+}
+
+// I/O 线程: 独占插件进程, 按顺序处理请求; 策略被丢弃 (请求通道关闭) 时结束进程
+struct PluginWorker {
+    launcher: Launcher,
+    lookback: BTreeMap<Interval, usize>,
+    state: Arc<Mutex<PluginState>>,
+    process: Option<PluginProcess>,
+    bind: Option<Value>,
+    history: BTreeMap<Interval, VecDeque<Kline>>,  // 重启后重新发送的K线
+    last_failure: Option<Instant>,
+}
+
+impl PluginWorker {
+    fn run(mut self, requests: Receiver<Request>) {
+        for request in requests {
+            match request {
+                Request::Bind(bind) => {
+                    self.bind = Some(bind.clone());
+                    self.notify(&bind);
+                }
+                Request::Candle(interval, kline) => {
+                    let capacity = self.lookback.get(&interval).copied().unwrap_or(0);
+                    let history = self.history.entry(interval).or_default();
+                    history.push_back(kline.clone());
+                    while history.len() > capacity {
+                        history.pop_front();
+                    }
+                    // 进程重启时会重放包含本根在内的缓存K线
+                    if self.process.is_some() {
+                        self.notify(&json!({ "type": "candle", "interval": interval, "kline": kline }));
+                    } else {
+                        let _ = self.ensure_running();
+                    }
+                }
+                Request::Signal(signal) => self.notify(&signal),
+                Request::Evaluate { id, message, deadline, reply } => {
+                    if Instant::now() >= deadline {
+                        debug!(plugin = %self.launcher.name, id, "评估请求已过期, 不再发送");
+                        continue;
+                    }
+                    let name = self.launcher.name.clone();
+                    let result = self.ensure_running().and_then(|process| {
+                        process.send(&message)?;
+                        process.receive(&name, "decision", Some(id), deadline.saturating_duration_since(Instant::now()))
+                    });
+                    if let Err(e) = &result {
+                        if self.process.is_some() {
+                            self.fail(e.clone());
+                        }
+                    }
+                    let _ = reply.send(result);
+                }
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, PluginState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    // 确保进程在运行, 需要时重启并重放缓存的K线
+    fn ensure_running(&mut self) -> Result<&mut PluginProcess, String> {
+        if self.process.is_none() {
+            if let Some(failed_at) = self.last_failure {
+                let state = self.lock();
+                if failed_at.elapsed() < state.restart_delay {
+                    return Err(format!("插件不可用, 等待重启: {}", state.last_error.clone().unwrap_or_default()));
+                }
+            }
+            let restarted = self.launcher.start().and_then(|(mut process, _)| {
+                if let Some(bind) = &self.bind {
+                    process.send(bind)?;
+                }
+                let mut replay: Vec<(Interval, Kline)> = self.history.iter()
+                    .flat_map(|(interval, klines)| klines.iter().map(move |kline| (*interval, kline.clone())))
+                    .collect();
+                sort_for_replay(&mut replay);
+                for (interval, kline) in &replay {
+                    process.send(&json!({ "type": "candle", "interval": interval, "kline": kline }))?;
+                }
+                Ok(process)
+            });
+            match restarted {
+                Ok(process) => {
+                    let mut state = self.lock();
+                    state.running = true;
+                    state.restarts += 1;
+                    warn!(plugin = %self.launcher.name, restarts = state.restarts, "插件进程已重启");
+                    drop(state);
+                    self.process = Some(process);
+                }
+                Err(e) => {
+                    self.last_failure = Some(Instant::now());
+                    self.lock().last_error = Some(e.clone());
+                    return Err(e);
+                }
+            }
+        }
+        self.process.as_mut().ok_or_else(|| "插件未运行".to_string())
+    }
+
+    // 通信失败: 结束进程, 之后按 restart_delay 重启
+    fn fail(&mut self, error: String) {
+        warn!(plugin = %self.launcher.name, error = %error, "插件通信失败");
+        self.process = None;
+        self.last_failure = Some(Instant::now());
+        let mut state = self.lock();
+        state.running = false;
+        state.last_error = Some(error);
+    }
+
+    // 发送不需要回复的消息
+    fn notify(&mut self, message: &Value) {
+        let sent = self.ensure_running().and_then(|process| process.send(message));
+        if let Err(e) = sent {
+            if self.process.is_some() {
+                self.fail(e);
+            }
+        }
+    }
+}
+
+// 已投递、等待 I/O 线程回复的评估请求
+struct PendingDecision {
+    name: String,
+    reply: Receiver<Result<Value, String>>,
+    deadline: Instant,
+    timeout: Duration,
+}
+
+impl PendingDecision {
+    // 最多等到截止时间, 失败时观望
+    fn wait(self) -> Decision {
+        let reply = match self.reply.recv_timeout(self.deadline.saturating_duration_since(Instant::now())) {
+            Ok(reply) => reply,
+            Err(RecvTimeoutError::Timeout) => Err(format!("等待 decision 超时 ({} ms)", self.timeout.as_millis())),
+            Err(RecvTimeoutError::Disconnected) => Err("插件 I/O 线程已退出".to_string()),
+        };
+        let decision = reply.and_then(|reply| {
+            serde_json::from_value::<PluginDecision>(reply)
+                .map_err(|e| format!("决策格式错误: {}", e))
+                .and_then(PluginDecision::into_decision)
+        });
+        match decision {
+            Ok(decision) => decision,
+            Err(e) => Decision::hold().with_reason(format!("插件 {} 无可用决策: {}", self.name, e)),
+        }
+    }
+}
+
+// 外部进程策略: 每个实例 (每个交易对) 启动一个插件进程和一个 I/O 线程。
+// evaluate 同步等待插件回复 (最长 timeout); 管理器通过 evaluate_deferred 在策略锁之外等待。
+pub struct PluginStrategy {
+    name: String,
+    command: PluginCommand,
+    intervals: Vec<Interval>,
+    lookback: BTreeMap<Interval, usize>,
+    timeout: Duration,
+    bars: usize,  // 已输入的主周期K线数
+    next_id: AtomicU64,
+    requests: Sender<Request>,
+    state: Arc<Mutex<PluginState>>,
+}
+
+impl PluginStrategy {
+    // 启动插件并完成握手, 插件无法启动或未按时回复 hello 时返回错误
+    pub fn spawn(name: &str, command: PluginCommand, params: BTreeMap<String, f64>) -> Result<Self, String> {
+        let launcher = Launcher {
+            name: name.to_string(),
+            command: command.clone(),
+            params,
+            startup_timeout: Duration::from_secs(10),
+        };
+        let (process, hello) = launcher.start()?;
+        let intervals = if hello.intervals.is_empty() { vec![Interval::FiveMinutes] } else { hello.intervals };
+        let mut lookback = BTreeMap::new();
+        for (interval, bars) in hello.lookback {
+            let interval = interval.parse::<Interval>().map_err(|e| format!("插件 {} 的 lookback: {}", name, e))?;
+            lookback.insert(interval, bars);
+        }
+
+        let state = Arc::new(Mutex::new(PluginState {
+            running: true,
+            restarts: 0,
+            last_error: None,
+            restart_delay: Duration::from_secs(5),
+        }));
+        let worker = PluginWorker {
+            launcher,
+            lookback: lookback.clone(),
+            state: state.clone(),
+            process: Some(process),
+            bind: None,
+            history: BTreeMap::new(),
+            last_failure: None,
+        };
+        let (requests, receiver) = mpsc::channel();
+        std::thread::spawn(move || worker.run(receiver));
+        Ok(Self {
+            name: name.to_string(),
+            command,
+            intervals,
+            lookback,
+            timeout: Duration::from_millis(1000),
+            bars: 0,
+            next_id: AtomicU64::new(1),
+            requests,
+            state,
+        })
+    }
+
+    // 评估请求的超时时间 (默认 1 秒)
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    // 插件崩溃或超时后, 至少间隔多久再重启 (默认 5 秒)
+    pub fn with_restart_delay(self, restart_delay: Duration) -> Self {
+        self.lock().restart_delay = restart_delay;
+        self
+    }
+
+    // 插件进程已重启的次数
+    pub fn restarts(&self) -> usize {
+        self.lock().restarts
+    }
+
+    // 最近一次通信失败的原因
+    pub fn last_error(&self) -> Option<String> {
+        self.lock().last_error.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, PluginState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn primary(&self) -> Interval {
+        self.intervals.first().copied().unwrap_or(Interval::FiveMinutes)
+    }
+
+    // I/O 线程只在策略被丢弃后退出, 投递失败时忽略
+    fn post(&self, request: Request) {
+        if self.requests.send(request).is_err() {
+            warn!(plugin = %self.name, "插件 I/O 线程已退出, 丢弃请求");
+        }
+    }
+
+    // 把评估请求投递给 I/O 线程, 不等待回复
+    fn submit(&self, ctx: &StrategyContext) -> PendingDecision {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let message = json!({
+            "type": "evaluate",
+            "id": id,
+            "symbol": ctx.symbol,
+            "price": ctx.price,
+            "timestamp": ctx.timestamp,
+            "depth": ctx.depth.map(|depth| json!({ "asks": depth.asks, "bids": depth.bids })),
+            "ticker": ctx.ticker.map(|ticker| json!({
+                "price_change_percent": ticker.price_change_percent,
+                "high_price": ticker.high_price,
+                "low_price": ticker.low_price,
+                "last_price": ticker.last_price,
+                "volume": ticker.volume,
+                "bid_price": ticker.bid_price,
+                "ask_price": ticker.ask_price,
+            })),
+            "position": ctx.position.map(|position| json!({
+                "side": if position.side == OrderSide::Buy { "long" } else { "short" },
+                "quantity": position.quantity,
+                "entry_price": position.entry_price,
+                "unrealized_pnl": position.unrealized_pnl,
+            })),
+            "regime": ctx.regime.map(|reading| json!({
+                "regime": reading.regime.as_str(),
+                "adx": reading.adx,
+                "atr_percentile": reading.atr_percentile,
+                "spread_pct": reading.spread_pct,
+                "depth_notional": reading.depth_notional,
+            })),
+        });
+        let deadline = Instant::now() + self.timeout;
+        let (reply, receiver) = mpsc::channel();
+        self.post(Request::Evaluate { id, message, deadline, reply });
+        PendingDecision { name: self.name.clone(), reply: receiver, deadline, timeout: self.timeout }
+    }
+}
+
+impl TradingStrategy for PluginStrategy {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn bind_currency(&mut self, config: &CurrencyConfig) {
+        self.post(Request::Bind(json!({
+            "type": "bind",
+            "symbol": config.symbol,
+            "min_qty": config.min_qty,
+            "market": config.market,
+        })));
+    }
+
+    fn add_price(&mut self, price: f64) {
+        let kline = Kline { open_time: 0, open: price, high: price, low: price, close: price, volume: 0.0, close_time: 0 };
+        self.add_candle(self.primary(), &kline);
+    }
+
+    fn intervals(&self) -> Vec<Interval> {
+        self.intervals.clone()
+    }
+
+    fn lookback(&self, interval: Interval) -> usize {
+        self.lookback.get(&interval).copied().unwrap_or(0)
+    }
+
+    // 评估请求总是附带市场状态
+    fn uses_regime(&self) -> bool {
+        true
+    }
+
+    fn is_ready(&self) -> bool {
+        self.bars >= self.lookback(self.primary())
+    }
+
+    fn add_candle(&mut self, interval: Interval, kline: &Kline) {
+        if interval == self.primary() {
+            self.bars += 1;
+        }
+        self.post(Request::Candle(interval, kline.clone()));
+    }
+
+    fn evaluate(&self, ctx: &StrategyContext) -> Decision {
+        self.submit(ctx).wait()
+    }
+
+    fn evaluate_deferred(&self, ctx: &StrategyContext) -> Option<DeferredDecision> {
+        let pending = self.submit(ctx);
+        Some(DeferredDecision::new(move || pending.wait()))
+    }
+
+    fn on_signal(&mut self, decision: &Decision, price: f64, timestamp: i64) {
+        let action = match decision.action {
+            Action::EnterLong => "enter_long",
+            Action::EnterShort => "enter_short",
+            Action::Exit => "exit",
+            Action::Hold => return,
+        };
+        self.post(Request::Signal(json!({ "type": "signal", "action": action, "price": price, "timestamp": timestamp })));
+    }
+
+    fn describe(&self) -> Vec<String> {
+        let state = self.lock();
+        let mut lines = vec![format!(
+            "插件 {} ({}), {}",
+            self.name,
+            self.command,
+            if state.running { "运行中" } else { "未运行" }
+        )];
+        if state.restarts > 0 {
+            lines.push(format!("已重启 {} 次", state.restarts));
+        }
+        if let Some(error) = &state.last_error {
+            lines.push(format!("最近错误: {}", error));
+        }
+        lines
+    }
+}
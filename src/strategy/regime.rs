@@ -0,0 +1,221 @@
+use crate::indicators::{Bar, Indicator, RingBuffer, ADX, ATR};
+use crate::strategy::filters::{DepthFilter, TickerFilter};
+use crate::strategy::{MarketDepth, MarketTicker};
+use crate::types::{Kline, Regime};
+use std::fmt;
+
+// 市场状态识别, 按以下顺序判断:
+//   流动性不足: 买卖价差超过 max_spread_pct, 或当前价附近的挂单金额低于 min_depth_notional
+//   高波动: ATR 占收盘价的比例在最近 window 根K线中的分位数不低于 high_vol_percentile
+//   趋势: ADX 不低于 trend_adx
+//   震荡: 其他情况
+// ADX 与 ATR 按主周期收盘K线更新, 价差与深度使用每轮获取的实时行情。
+
+// 计算 ATR 分位数至少需要的样本数
+const MIN_PERCENTILE_SAMPLES: usize = 20;
+
+// 一次识别的结果与依据
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegimeReading {
+    pub regime: Regime,
+    pub adx: Option<f64>,
+    pub atr_percentile: Option<f64>,  // 0 - 100
+    pub spread_pct: Option<f64>,
+    pub depth_notional: Option<f64>,  // 当前价附近的买卖挂单金额
+}
+
+impl RegimeReading {
+    pub fn is(&self, regime: Regime) -> bool {
+        self.regime == regime
+    }
+}
+
+impl fmt::Display for RegimeReading {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut details = Vec::new();
+        if let Some(adx) = self.adx {
+            details.push(format!("ADX {:.1}", adx));
+        }
+        if let Some(percentile) = self.atr_percentile {
+            details.push(format!("ATR 分位 {:.0}%", percentile));
+        }
+        if let Some(spread) = self.spread_pct {
+            details.push(format!("价差 {:.3}%", spread));
+        }
+        if let Some(notional) = self.depth_notional {
+            details.push(format!("挂单 {:.0}", notional));
+        }
+        if details.is_empty() {
+            write!(f, "{}", self.regime)
+        } else {
+            write!(f, "{} ({})", self.regime, details.join(", "))
+        }
+    }
+}
+
+// 单个交易对的市场状态识别器
+#[derive(Debug, Clone)]
+pub struct RegimeDetector {
+    pub trend_adx: f64,
+    pub high_vol_percentile: f64,
+    pub max_spread_pct: f64,
+    pub min_depth_notional: f64,  // 0 表示不检查深度
+    pub depth_band_pct: f64,
+    adx: ADX,
+    atr: ATR,
+    atr_history: RingBuffer<f64>,  // ATR / 收盘价 (%)
+    reading: Option<RegimeReading>,
+}
+
+impl Default for RegimeDetector {
+    fn default() -> Self {
+        Self {
+            trend_adx: 25.0,
+            high_vol_percentile: 80.0,
+            max_spread_pct: TickerFilter::default().max_spread_pct,
+            min_depth_notional: 0.0,
+            depth_band_pct: DepthFilter::default().band_pct,
+            adx: ADX::new(14),
+            atr: ATR::new(14),
+            atr_history: RingBuffer::new(100),
+            reading: None,
+        }
+    }
+}
+
+impl RegimeDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_periods(mut self, adx_period: usize, atr_period: usize) -> Self {
+        self.adx = ADX::new(adx_period);
+        self.atr = ATR::new(atr_period);
+        self
+    }
+
+    // 计算 ATR 分位数的K线数量
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.atr_history = RingBuffer::new(window.max(1));
+        self
+    }
+
+    pub fn with_trend_adx(mut self, trend_adx: f64) -> Self {
+        self.trend_adx = trend_adx;
+        self
+    }
+
+    pub fn with_high_vol_percentile(mut self, high_vol_percentile: f64) -> Self {
+        self.high_vol_percentile = high_vol_percentile;
+        self
+    }
+
+    pub fn with_max_spread_pct(mut self, max_spread_pct: f64) -> Self {
+        self.max_spread_pct = max_spread_pct;
+        self
+    }
+
+    pub fn with_min_depth(mut self, min_depth_notional: f64, depth_band_pct: f64) -> Self {
+        self.min_depth_notional = min_depth_notional;
+        self.depth_band_pct = depth_band_pct;
+        self
+    }
+
+    // 输入一根已收盘的K线
+    pub fn update(&mut self, kline: &Kline) {
+        let bar = Bar::from(kline);
+        self.adx.update(bar);
+        if let Some(atr) = self.atr.update(bar) {
+            if kline.close > 0.0 {
+                self.atr_history.push(atr / kline.close * 100.0);
+            }
+        }
+    }
+
+    fn min_samples(&self) -> usize {
+        MIN_PERCENTILE_SAMPLES.min(self.atr_history.capacity())
+    }
+
+    // 识别趋势与波动所需的K线数量
+    pub fn lookback(&self) -> usize {
+        self.adx.warm_up().max(self.atr.warm_up() + self.min_samples() - 1)
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.adx.is_ready() && self.atr_history.len() >= self.min_samples()
+    }
+
+    pub fn reset(&mut self) {
+        self.adx.reset();
+        self.atr.reset();
+        self.atr_history = RingBuffer::new(self.atr_history.capacity());
+        self.reading = None;
+    }
+
+    // 最新 ATR 在历史中的分位数 (相同的值计一半)
+    pub fn atr_percentile(&self) -> Option<f64> {
+        if self.atr_history.len() < self.min_samples() {
+            return None;
+        }
+        let current = self.atr_history.last()?;
+        let (below, equal) = self.atr_history.iter().fold((0.0, 0.0), |(below, equal), value| {
+            if value < current {
+                (below + 1.0, equal)
+            } else if value == current {
+                (below, equal + 1.0)
+            } else {
+                (below, equal)
+            }
+        });
+        Some((below + equal / 2.0) / self.atr_history.len() as f64 * 100.0)
+    }
+
+    // 按当前行情识别市场状态, 趋势与波动数据不足且流动性正常时返回 None
+    pub fn classify(&self, price: f64, depth: Option<&MarketDepth>, ticker: Option<&MarketTicker>) -> Option<RegimeReading> {
+        // 优先使用行情中的买一卖一, 没有行情时使用深度
+        let spread_pct = ticker
+            .filter(|ticker| ticker.bid_price > 0.0 && ticker.ask_price > 0.0)
+            .map(|ticker| (ticker.ask_price - ticker.bid_price) / ticker.bid_price * 100.0)
+            .or_else(|| {
+                let depth = depth?;
+                let best_bid = depth.bids.iter().map(|(price, _)| *price).fold(f64::NAN, f64::max);
+                let best_ask = depth.asks.iter().map(|(price, _)| *price).fold(f64::NAN, f64::min);
+                (best_bid > 0.0 && best_ask > 0.0).then(|| (best_ask - best_bid) / best_bid * 100.0)
+            });
+        let band = self.depth_band_pct / 100.0;
+        let depth_notional = depth.map(|depth| {
+            let bids = depth.bids.iter().filter(|(level, _)| *level > price * (1.0 - band));
+            let asks = depth.asks.iter().filter(|(level, _)| *level < price * (1.0 + band));
+            bids.chain(asks).map(|(level, quantity)| level * quantity).sum::<f64>()
+        });
+        let adx = self.adx.value().map(|output| output.adx);
+        let atr_percentile = self.atr_percentile();
+
+        let illiquid = spread_pct.is_some_and(|spread| spread > self.max_spread_pct)
+            || (self.min_depth_notional > 0.0
+                && depth_notional.is_some_and(|notional| notional < self.min_depth_notional));
+        let regime = if illiquid {
+            Regime::Illiquid
+        } else if !self.is_ready() {
+            return None;
+        } else if atr_percentile.is_some_and(|percentile| percentile >= self.high_vol_percentile) {
+            Regime::HighVolatility
+        } else if adx.is_some_and(|adx| adx >= self.trend_adx) {
+            Regime::Trending
+        } else {
+            Regime::Ranging
+        };
+        Some(RegimeReading { regime, adx, atr_percentile, spread_pct, depth_notional })
+    }
+
+    // 识别并记录本轮的市场状态
+    pub fn observe(&mut self, price: f64, depth: Option<&MarketDepth>, ticker: Option<&MarketTicker>) -> Option<RegimeReading> {
+        self.reading = self.classify(price, depth, ticker);
+        self.reading.clone()
+    }
+
+    // 最近一次识别的结果
+    pub fn reading(&self) -> Option<&RegimeReading> {
+        self.reading.as_ref()
+    }
+}
@@ -0,0 +1,427 @@
+use crate::strategy::filters::{DepthFilter, FilterSet, FILTER_PARAMS};
+use crate::strategy::{
+    parse_rules, CombineMode, CompositeStrategy, DCAStrategy, DonchianStrategy, GridStrategy, MACDStrategy, MeanReversionStrategy,
+    PairsStrategy, PluginCommand, PluginStrategy, RuleDefinition, RuleStrategy, TradingStrategy, MAX_PERIOD,
+};
+use crate::types::{Interval, StrategyConfig};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::Path;
+use std::time::Duration;
+
+pub type BoxedStrategy = Box<dyn TradingStrategy + Send + Sync>;
+
+// 根据参数构造策略的工厂函数
+pub type StrategyFactory = Box<dyn Fn(&StrategyConfig) -> Result<BoxedStrategy, Box<dyn Error>> + Send + Sync>;
+
+// 组合策略的工厂函数: 参数 + 已构造的子策略
+pub type CompositeFactory =
+    Box<dyn Fn(&StrategyConfig, Vec<BoxedStrategy>) -> Result<BoxedStrategy, Box<dyn Error>> + Send + Sync>;
+
+// 策略注册表: 按名称构造策略
+pub struct StrategyRegistry {
+    factories: BTreeMap<String, StrategyFactory>,
+    composites: BTreeMap<String, CompositeFactory>,
+}
+
+impl StrategyRegistry {
+    // 空注册表
+    pub fn new() -> Self {
+        Self { factories: BTreeMap::new(), composites: BTreeMap::new() }
+    }
+
+    // 包含内置策略的注册表
+    pub fn with_builtin() -> Self {
+        let mut registry = Self::new();
+        registry.register("macd", build_macd);
+        registry.register("grid", build_grid);
+        registry.register("dca", build_dca);
+        registry.register("pairs", build_pairs);
+        registry.register("donchian", build_donchian);
+        registry.register("mean_reversion", build_mean_reversion);
+        registry.register_composite("unanimous", |config, children| build_composite(config, CombineMode::Unanimous, children));
+        registry.register_composite("majority", |config, children| build_composite(config, CombineMode::Majority, children));
+        registry.register_composite("weighted", |config, children| {
+            build_composite(config, CombineMode::Weighted(config.param("threshold", 0.5)), children)
+        });
+        registry.register_composite("filtered", |config, children| build_composite(config, CombineMode::Filter, children));
+        registry
+    }
+
+    // 注册策略 (同名时替换)
+    pub fn register<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(&StrategyConfig) -> Result<BoxedStrategy, Box<dyn Error>> + Send + Sync + 'static,
+    {
+        self.factories.insert(name.trim().to_ascii_lowercase(), Box::new(factory));
+    }
+
+    // 注册组合策略 (子策略由注册表按 StrategyConfig::children 构造后传入)
+    pub fn register_composite<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(&StrategyConfig, Vec<BoxedStrategy>) -> Result<BoxedStrategy, Box<dyn Error>> + Send + Sync + 'static,
+    {
+        self.composites.insert(name.trim().to_ascii_lowercase(), Box::new(factory));
+    }
+
+    // 注册规则文本中定义的全部策略, 返回策略名称 (不允许与已注册的策略重名)
+    pub fn register_rules(&mut self, source: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        let definitions = parse_rules(source)?;
+        if let Some(taken) = definitions.iter().find(|definition| self.contains(&definition.name)) {
+            return Err(format!("规则策略 {} 与已注册的策略重名", taken.name).into());
+        }
+        let names = definitions.iter().map(|definition| definition.name.clone()).collect();
+        for definition in definitions {
+            let name = definition.name.clone();
+            self.register(&name, move |config| build_rules(config, &definition));
+        }
+        Ok(names)
+    }
+
+    // 从文件加载规则策略
+    pub fn load_rules(&mut self, path: impl AsRef<Path>) -> Result<Vec<String>, Box<dyn Error>> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| format!("读取规则文件 {} 失败: {}", path.display(), e))?;
+        self.register_rules(&source)
+            .map_err(|e| format!("{}: {}", path.display(), e).into())
+    }
+
+    // 注册外部进程策略插件, 每次构造策略时启动一个插件进程 (不允许与已注册的策略重名)
+    pub fn register_plugin(&mut self, name: &str, command: PluginCommand) -> Result<(), Box<dyn Error>> {
+        if self.contains(name) {
+            return Err(format!("策略插件 {} 与已注册的策略重名", name.trim()).into());
+        }
+        let plugin = name.trim().to_ascii_lowercase();
+        self.register(name, move |config| build_plugin(config, &plugin, &command));
+        Ok(())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        let name = name.trim().to_ascii_lowercase();
+        self.factories.contains_key(&name) || self.composites.contains_key(&name)
+    }
+
+    // 已注册的策略名称 (含组合策略, 按字母排序)
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.factories.keys().chain(self.composites.keys()).map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    pub fn build(&self, config: &StrategyConfig) -> Result<BoxedStrategy, Box<dyn Error>> {
+        let name = config.name.trim().to_ascii_lowercase();
+        if let Some(factory) = self.composites.get(&name) {
+            let children = config.children.iter()
+                .map(|child| self.build(child).map_err(|e| format!("组合策略 {} 的子策略 {}: {}", config.name, child.name, e)))
+                .collect::<Result<Vec<_>, _>>()?;
+            return factory(config, children);
+        }
+        let factory = self.factories.get(&name)
+            .ok_or_else(|| format!("未知的策略: {} (可用: {})", config.name, self.names().join(", ")))?;
+        if !config.children.is_empty() {
+            return Err(format!("策略 {} 不是组合策略, 不支持子策略", config.name).into());
+        }
+        factory(config)
+    }
+}
+
+impl Default for StrategyRegistry {
+    fn default() -> Self {
+        Self::with_builtin()
+    }
+}
+
+// 周期参数必须是不超过 MAX_PERIOD 的正整数
+pub fn period_param(config: &StrategyConfig, key: &str, default: usize) -> Result<usize, Box<dyn Error>> {
+    let value = config.param(key, default as f64);
+    if value < 1.0 || value.fract() != 0.0 {
+        return Err(format!("策略 {} 的参数 {} 必须是正整数: {}", config.name, key, value).into());
+    }
+    if value > MAX_PERIOD as f64 {
+        return Err(format!("策略 {} 的参数 {} 不能超过 {}: {}", config.name, key, MAX_PERIOD, value).into());
+    }
+    Ok(value as usize)
+}
+
+// K线周期参数以分钟数表示 (例如 60 为 1 小时)
+pub fn interval_param(config: &StrategyConfig, key: &str, default: Interval) -> Result<Interval, Box<dyn Error>> {
+    let minutes = config.param(key, (default.duration_ms() / 60_000) as f64);
+    if minutes.fract() != 0.0 {
+        return Err(format!("策略 {} 的参数 {} 必须是整数分钟: {}", config.name, key, minutes).into());
+    }
+    Interval::from_minutes(minutes as i64)
+        .ok_or_else(|| format!("策略 {} 的参数 {} 不是支持的K线周期: {} 分钟", config.name, key, minutes).into())
+}
+
+// 拒绝未知参数, 避免拼写错误被静默忽略
+pub fn check_params(config: &StrategyConfig, known: &[&str]) -> Result<(), Box<dyn Error>> {
+    match config.params.keys().find(|key| !known.contains(&key.as_str())) {
+        Some(key) => Err(format!("策略 {} 不支持参数 {} (支持: {})", config.name, key, known.join(", ")).into()),
+        None => Ok(()),
+    }
+}
+
+// 参数: fast (默认12), slow (默认26), signal (默认9), stop_pct (默认5), target_pct (默认10)
+fn build_macd(config: &StrategyConfig) -> Result<BoxedStrategy, Box<dyn Error>> {
+    let known: Vec<&str> = ["fast", "slow", "signal", "stop_pct", "target_pct", "interval", "trend_interval", "trend_ema"]
+        .into_iter()
+        .chain(FILTER_PARAMS.iter().copied())
+        .collect();
+    check_params(config, &known)?;
+    let fast = period_param(config, "fast", 12)?;
+    let slow = period_param(config, "slow", 26)?;
+    let signal = period_param(config, "signal", 9)?;
+    if fast >= slow {
+        return Err(format!("MACD 快线周期 ({}) 必须小于慢线周期 ({})", fast, slow).into());
+    }
+    let stop_pct = config.param("stop_pct", 5.0);
+    let target_pct = config.param("target_pct", 10.0);
+    if stop_pct <= 0.0 || target_pct <= 0.0 {
+        return Err("MACD 止损/止盈幅度必须大于 0".into());
+    }
+    let mut strategy = MACDStrategy::new(fast, slow, signal)
+        .with_risk(stop_pct, target_pct)
+        .with_filters(FilterSet::from_config(config)?)
+        .with_interval(interval_param(config, "interval", Interval::FiveMinutes)?);
+    // 趋势过滤: trend_interval 为大周期分钟数, trend_ema 为 EMA 周期
+    if config.params.contains_key("trend_interval") || config.params.contains_key("trend_ema") {
+        let trend_interval = interval_param(config, "trend_interval", Interval::OneHour)?;
+        strategy = strategy.with_trend_filter(trend_interval, period_param(config, "trend_ema", 50)?);
+    }
+    Ok(Box::new(strategy))
+}
+
+// 参数: lower, upper (必填), levels (网格线数量, 默认10), qty (每格数量, 默认0 即最小下单量),
+// stop_pct (超出区间止损幅度, 默认5), interval (默认5分钟)
+fn build_grid(config: &StrategyConfig) -> Result<BoxedStrategy, Box<dyn Error>> {
+    check_params(config, &["lower", "upper", "levels", "qty", "stop_pct", "interval"])?;
+    let (lower, upper) = match (config.params.get("lower"), config.params.get("upper")) {
+        (Some(&lower), Some(&upper)) => (lower, upper),
+        _ => return Err("网格策略必须指定价格区间 lower 和 upper".into()),
+    };
+    if lower <= 0.0 || upper <= lower {
+        return Err(format!("网格价格区间无效: {} - {}", lower, upper).into());
+    }
+    let levels = period_param(config, "levels", 10)?;
+    if levels < 2 {
+        return Err("网格线数量 levels 至少为 2".into());
+    }
+    let quantity = config.param("qty", 0.0);
+    let stop_pct = config.param("stop_pct", 5.0);
+    if quantity < 0.0 || stop_pct < 0.0 {
+        return Err("网格参数 qty 和 stop_pct 不能为负".into());
+    }
+    Ok(Box::new(
+        GridStrategy::new(lower, upper, levels)
+            .with_quantity(quantity)
+            .with_stop_pct(stop_pct)
+            .with_interval(interval_param(config, "interval", Interval::FiveMinutes)?),
+    ))
+}
+
+// 参数: base_qty / safety_qty (默认0 即最小下单量), safety_orders (默认5), deviation_pct (默认1),
+// step_scale (默认1), volume_scale (默认1.5), take_profit_pct (默认1), interval (默认5分钟)
+fn build_dca(config: &StrategyConfig) -> Result<BoxedStrategy, Box<dyn Error>> {
+    check_params(config, &[
+        "base_qty", "safety_qty", "safety_orders", "deviation_pct", "step_scale", "volume_scale", "take_profit_pct", "interval",
+    ])?;
+    let safety_orders = config.param("safety_orders", 5.0);
+    if safety_orders < 0.0 || safety_orders.fract() != 0.0 || safety_orders > MAX_PERIOD as f64 {
+        return Err(format!("定投参数 safety_orders 必须是不超过 {} 的非负整数: {}", MAX_PERIOD, safety_orders).into());
+    }
+    let safety_orders = safety_orders as usize;
+    let base_qty = config.param("base_qty", 0.0);
+    let safety_qty = config.param("safety_qty", 0.0);
+    if base_qty < 0.0 || safety_qty < 0.0 {
+        return Err("定投参数 base_qty 和 safety_qty 不能为负".into());
+    }
+    let deviation_pct = config.param("deviation_pct", 1.0);
+    let step_scale = config.param("step_scale", 1.0);
+    let volume_scale = config.param("volume_scale", 1.5);
+    let take_profit_pct = config.param("take_profit_pct", 1.0);
+    if deviation_pct <= 0.0 || step_scale <= 0.0 || volume_scale <= 0.0 || take_profit_pct <= 0.0 {
+        return Err("定投参数 deviation_pct、step_scale、volume_scale 和 take_profit_pct 必须大于 0".into());
+    }
+    // 最后一张安全单的累计偏离必须小于 100%
+    let total_deviation: f64 = (0..safety_orders).map(|i| deviation_pct * step_scale.powi(i as i32)).sum();
+    if total_deviation >= 100.0 {
+        return Err(format!("定投安全单累计偏离 {:.2}% 超过 100%", total_deviation).into());
+    }
+    Ok(Box::new(
+        DCAStrategy::new(safety_orders, deviation_pct, take_profit_pct)
+            .with_quantities(base_qty, safety_qty)
+            .with_scaling(step_scale, volume_scale)
+            .with_interval(interval_param(config, "interval", Interval::FiveMinutes)?),
+    ))
+}
+
+// 对冲腿由币种配置的 legs 指定。参数: lookback (默认100), entry_z (默认2), exit_z (默认0.5), stop_z (默认4),
+// max_bars (最长持仓K线数, 默认0 即不限), stop_pct (默认10), target_pct (默认20), interval (默认5分钟)
+fn build_pairs(config: &StrategyConfig) -> Result<BoxedStrategy, Box<dyn Error>> {
+    check_params(config, &["lookback", "entry_z", "exit_z", "stop_z", "max_bars", "stop_pct", "target_pct", "interval"])?;
+    let lookback = period_param(config, "lookback", 100)?;
+    if lookback < 10 {
+        return Err(format!("配对交易回看窗口 lookback 至少为 10: {}", lookback).into());
+    }
+    let entry_z = config.param("entry_z", 2.0);
+    let exit_z = config.param("exit_z", 0.5);
+    let stop_z = config.param("stop_z", 4.0);
+    if !(0.0 <= exit_z && exit_z < entry_z && entry_z < stop_z) {
+        return Err(format!("配对交易阈值必须满足 0 <= exit_z ({}) < entry_z ({}) < stop_z ({})", exit_z, entry_z, stop_z).into());
+    }
+    let max_bars = config.param("max_bars", 0.0);
+    if max_bars < 0.0 || max_bars.fract() != 0.0 {
+        return Err(format!("配对交易参数 max_bars 必须是非负整数: {}", max_bars).into());
+    }
+    let stop_pct = config.param("stop_pct", 10.0);
+    let target_pct = config.param("target_pct", 20.0);
+    if stop_pct <= 0.0 || target_pct <= 0.0 {
+        return Err("配对交易止损/止盈幅度必须大于 0".into());
+    }
+    Ok(Box::new(
+        PairsStrategy::new(lookback, entry_z, exit_z, stop_z)
+            .with_max_bars(max_bars as usize)
+            .with_risk(stop_pct, target_pct)
+            .with_interval(interval_param(config, "interval", Interval::FiveMinutes)?),
+    ))
+}
+
+// 参数: entry (入场通道周期, 默认20), exit (离场通道周期, 默认10), atr (ATR 周期, 默认14),
+// stop_atr (止损 ATR 倍数, 默认2), target_atr (止盈 ATR 倍数, 默认6),
+// risk (每笔风险金额, 默认0 即按波动率缩放), target_vol_pct (目标波动率 %, 默认1), interval (默认5分钟)
+fn build_donchian(config: &StrategyConfig) -> Result<BoxedStrategy, Box<dyn Error>> {
+    check_params(config, &["entry", "exit", "atr", "stop_atr", "target_atr", "risk", "target_vol_pct", "interval"])?;
+    let entry = period_param(config, "entry", 20)?;
+    let exit = period_param(config, "exit", 10)?;
+    if exit > entry {
+        return Err(format!("唐奇安离场通道周期 ({}) 不能大于入场通道周期 ({})", exit, entry).into());
+    }
+    let atr = period_param(config, "atr", 14)?;
+    let stop_atr = config.param("stop_atr", 2.0);
+    let target_atr = config.param("target_atr", 6.0);
+    if stop_atr <= 0.0 || target_atr <= 0.0 {
+        return Err("唐奇安止损/止盈 ATR 倍数必须大于 0".into());
+    }
+    let risk = config.param("risk", 0.0);
+    let target_vol_pct = config.param("target_vol_pct", 1.0);
+    if risk < 0.0 || target_vol_pct <= 0.0 {
+        return Err("唐奇安参数 risk 不能为负, target_vol_pct 必须大于 0".into());
+    }
+    Ok(Box::new(
+        DonchianStrategy::new(entry, exit, atr)
+            .with_atr_risk(stop_atr, target_atr)
+            .with_sizing(risk, target_vol_pct)
+            .with_interval(interval_param(config, "interval", Interval::FiveMinutes)?),
+    ))
+}
+
+// 参数: period (布林带周期, 默认20), std_dev (标准差倍数, 默认2), rsi (RSI 周期, 默认14),
+// oversold / overbought (默认30 / 70), max_bars (时间止损K线数, 默认0 即不限), stop_pct (默认2),
+// depth_filter (1 启用深度确认, 默认0), depth_band_pct, depth_ratio, interval (默认5分钟)
+fn build_mean_reversion(config: &StrategyConfig) -> Result<BoxedStrategy, Box<dyn Error>> {
+    check_params(config, &[
+        "period", "std_dev", "rsi", "oversold", "overbought", "max_bars", "stop_pct",
+        "depth_filter", "depth_band_pct", "depth_ratio", "interval",
+    ])?;
+    let period = period_param(config, "period", 20)?;
+    let std_dev = config.param("std_dev", 2.0);
+    if std_dev <= 0.0 {
+        return Err(format!("布林带标准差倍数必须大于 0: {}", std_dev).into());
+    }
+    let oversold = config.param("oversold", 30.0);
+    let overbought = config.param("overbought", 70.0);
+    if !(0.0 < oversold && oversold < overbought && overbought < 100.0) {
+        return Err(format!("RSI 阈值必须满足 0 < oversold ({}) < overbought ({}) < 100", oversold, overbought).into());
+    }
+    let max_bars = config.param("max_bars", 0.0);
+    if max_bars < 0.0 || max_bars.fract() != 0.0 {
+        return Err(format!("均值回归参数 max_bars 必须是非负整数: {}", max_bars).into());
+    }
+    let stop_pct = config.param("stop_pct", 2.0);
+    if stop_pct <= 0.0 {
+        return Err("均值回归止损幅度必须大于 0".into());
+    }
+    let mut filters = FilterSet::new();
+    if config.param("depth_filter", 0.0) != 0.0 {
+        let defaults = DepthFilter::default();
+        let band_pct = config.param("depth_band_pct", defaults.band_pct);
+        let ratio = config.param("depth_ratio", defaults.pressure_ratio);
+        if band_pct <= 0.0 || ratio <= 0.0 {
+            return Err("深度过滤参数 depth_band_pct 和 depth_ratio 必须大于 0".into());
+        }
+        filters = filters.with_filter(DepthFilter::new().with_band_pct(band_pct).with_pressure_ratio(ratio));
+    }
+    Ok(Box::new(
+        MeanReversionStrategy::new(period, std_dev, period_param(config, "rsi", 14)?)
+            .with_rsi_levels(oversold, overbought)
+            .with_max_bars(max_bars as usize)
+            .with_stop_pct(stop_pct)
+            .with_filters(filters)
+            .with_interval(interval_param(config, "interval", Interval::FiveMinutes)?),
+    ))
+}
+
+// 规则策略参数 (覆盖规则文件中的设置): stop_pct, target_pct, confidence
+fn build_rules(config: &StrategyConfig, definition: &RuleDefinition) -> Result<BoxedStrategy, Box<dyn Error>> {
+    check_params(config, &["stop_pct", "target_pct", "confidence"])?;
+    let stop_pct = config.param("stop_pct", definition.stop_pct);
+    let target_pct = config.param("target_pct", definition.target_pct);
+    if stop_pct <= 0.0 || target_pct <= 0.0 {
+        return Err(format!("规则策略 {} 的止损/止盈幅度必须大于 0", definition.name).into());
+    }
+    let confidence = config.param("confidence", definition.confidence);
+    if !(0.0..=1.0).contains(&confidence) {
+        return Err(format!("规则策略 {} 的置信度必须在 [0, 1] 内: {}", definition.name, confidence).into());
+    }
+    Ok(Box::new(
+        RuleStrategy::new(definition.clone())
+            .with_risk(stop_pct, target_pct)
+            .with_confidence(confidence),
+    ))
+}
+
+// 插件参数: timeout_ms (评估超时, 默认1000), restart_secs (崩溃后重启间隔, 默认5), 其余参数原样传给插件
+fn build_plugin(config: &StrategyConfig, name: &str, command: &PluginCommand) -> Result<BoxedStrategy, Box<dyn Error>> {
+    let timeout_ms = config.param("timeout_ms", 1000.0);
+    let restart_secs = config.param("restart_secs", 5.0);
+    if timeout_ms <= 0.0 || restart_secs < 0.0 {
+        return Err(format!("插件 {} 的 timeout_ms 必须大于 0, restart_secs 不能为负", name).into());
+    }
+    let params = config.params.iter()
+        .filter(|(key, _)| !matches!(key.as_str(), "timeout_ms" | "restart_secs"))
+        .map(|(key, value)| (key.clone(), *value))
+        .collect();
+    Ok(Box::new(
+        PluginStrategy::spawn(name, command.clone(), params)?
+            .with_timeout(Duration::from_millis(timeout_ms as u64))
+            .with_restart_delay(Duration::from_secs_f64(restart_secs)),
+    ))
+}
+
+// 组合策略参数: threshold (加权模式的得分阈值, 默认0.5), w1, w2, ... (各子策略权重, 默认1)
+fn build_composite(config: &StrategyConfig, mode: CombineMode, children: Vec<BoxedStrategy>) -> Result<BoxedStrategy, Box<dyn Error>> {
+    let weight_keys: Vec<String> = (1..=children.len()).map(|i| format!("w{}", i)).collect();
+    let known: Vec<&str> = match mode {
+        CombineMode::Weighted(_) => std::iter::once("threshold").chain(weight_keys.iter().map(String::as_str)).collect(),
+        _ => Vec::new(),
+    };
+    check_params(config, &known)?;
+    match mode {
+        CombineMode::Filter if children.len() != 2 => {
+            return Err(format!("过滤组合需要恰好 2 个子策略 (过滤, 信号), 当前 {} 个", children.len()).into());
+        }
+        _ if children.len() < 2 => {
+            return Err(format!("组合策略 {} 至少需要 2 个子策略, 当前 {} 个", config.name, children.len()).into());
+        }
+        CombineMode::Weighted(threshold) if !(threshold > 0.0 && threshold <= 1.0) => {
+            return Err(format!("加权组合的阈值必须在 (0, 1] 内: {}", threshold).into());
+        }
+        _ => {}
+    }
+    let weights: Vec<f64> = weight_keys.iter().map(|key| config.param(key, 1.0)).collect();
+    if weights.iter().any(|weight| *weight < 0.0) || weights.iter().all(|weight| *weight == 0.0) {
+        return Err(format!("组合策略权重不能为负且不能全为 0: {:?}", weights).into());
+    }
+    Ok(Box::new(CompositeStrategy::new(mode, children).with_weights(weights)))
+}
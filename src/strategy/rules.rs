@@ -0,0 +1,1166 @@
+use crate::indicators::{
+    ADXOutput, Bar, BollingerBands, BollingerOutput, Indicator, MACDIndicator, ATR, ADX, EMA, RSI, SMA,
+};
+use crate::strategy::filters::DepthFilter;
+use crate::strategy::{Action, Decision, SignalLog, StrategyContext, TradingStrategy, MAX_PERIOD};
+use crate::types::{Interval, Kline, OrderSide, Regime, MACD};
+use std::fmt;
+
+// 规则文件格式 (每行一条指令, # 之后为注释):
+//
+//   strategy macd_rsi                 # 开始定义一个策略, 名称用于策略配置
+//   interval 5m                       # K线周期 (默认 5m)
+//   stop_pct 2                        # 止损幅度 % (默认 5)
+//   target_pct 4                      # 止盈幅度 % (默认 10)
+//   confidence 0.6                    # 开仓/平仓置信度 (默认 0.5)
+//   enter_long when macd.cross_up and rsi(14) < 40 and depth.imbalance > 1.2
+//   enter_short when macd.cross_down and rsi(14) > 60
+//   exit when position.long and rsi(14) > 70
+//
+// 表达式支持 and / or / not、比较 (< <= > >= == !=)、四则运算和括号。可用的数据:
+//   price / close
+//   depth.imbalance / bid_volume / ask_volume (当前价 ±1% 内), depth.spread_pct
+//   ticker.change_pct / high / low / last / volume / bid / ask / spread_pct / volatility_pct / range_pct
+//   position.open / long / short (条件), position.pnl / entry
+//   regime.trending / ranging / high_volatility / illiquid (条件), regime.adx / atr_percentile
+//   rsi(n), ema(n), sma(n), atr(n), adx(n) [.plus_di / .minus_di]
+//   macd[(fast,slow,signal)].line / signal / histogram / cross_up / cross_down
+//   bb[(period,std_dev)].upper / middle / lower / percent_b / bandwidth
+// 缺少数据 (无深度、指标预热中) 时规则不成立。
+
+// 规则文件的解析错误, 指出出错的行列
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleError {
+    pub line: usize,    // 从 1 开始
+    pub column: usize,  // 从 1 开始 (按字符计)
+    pub message: String,
+    pub source_line: String,
+}
+
+impl RuleError {
+    fn new(line: usize, column: usize, message: impl Into<String>, source_line: &str) -> Self {
+        Self { line, column, message: message.into(), source_line: source_line.to_string() }
+    }
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "规则第 {} 行第 {} 列: {}", self.line, self.column, self.message)?;
+        if !self.source_line.is_empty() {
+            write!(f, "\n  {}\n  {}^", self.source_line, " ".repeat(self.column.saturating_sub(1)))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+// 规则引用的指标 (同一策略中相同参数的指标只计算一次)
+#[derive(Debug, Clone, PartialEq)]
+pub enum IndicatorSpec {
+    Rsi(usize),
+    Ema(usize),
+    Sma(usize),
+    Atr(usize),
+    Adx(usize),
+    Macd(usize, usize, usize),
+    Bollinger(usize, f64),
+}
+
+impl IndicatorSpec {
+    fn build(&self) -> Series {
+        match *self {
+            IndicatorSpec::Rsi(period) => Series::Rsi(RSI::new(period)),
+            IndicatorSpec::Ema(period) => Series::Ema(EMA::new(period)),
+            IndicatorSpec::Sma(period) => Series::Sma(SMA::new(period)),
+            IndicatorSpec::Atr(period) => Series::Atr(ATR::new(period)),
+            IndicatorSpec::Adx(period) => Series::Adx(ADX::new(period)),
+            IndicatorSpec::Macd(fast, slow, signal) => Series::Macd(MACDIndicator::new(fast, slow, signal)),
+            IndicatorSpec::Bollinger(period, std_dev) => Series::Bollinger(BollingerBands::new(period, std_dev)),
+        }
+    }
+}
+
+impl fmt::Display for IndicatorSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IndicatorSpec::Rsi(period) => write!(f, "rsi({})", period),
+            IndicatorSpec::Ema(period) => write!(f, "ema({})", period),
+            IndicatorSpec::Sma(period) => write!(f, "sma({})", period),
+            IndicatorSpec::Atr(period) => write!(f, "atr({})", period),
+            IndicatorSpec::Adx(period) => write!(f, "adx({})", period),
+            IndicatorSpec::Macd(fast, slow, signal) => write!(f, "macd({},{},{})", fast, slow, signal),
+            IndicatorSpec::Bollinger(period, std_dev) => write!(f, "bb({},{})", period, std_dev),
+        }
+    }
+}
+
+// 行情与持仓字段
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MarketField {
+    Price,
+    DepthImbalance,
+    DepthBidVolume,
+    DepthAskVolume,
+    DepthSpreadPct,
+    TickerChangePct,
+    TickerHigh,
+    TickerLow,
+    TickerLast,
+    TickerVolume,
+    TickerBid,
+    TickerAsk,
+    TickerSpreadPct,
+    TickerVolatilityPct,
+    TickerRangePct,
+    PositionOpen,
+    PositionLong,
+    PositionShort,
+    PositionPnl,
+    PositionEntry,
+    RegimeIs(Regime),
+    RegimeAdx,
+    RegimeAtrPercentile,
+}
+
+// 指标输出的字段
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IndicatorField {
+    Value,
+    PlusDi,
+    MinusDi,
+    MacdLine,
+    MacdSignal,
+    MacdHistogram,
+    CrossUp,
+    CrossDown,
+    Upper,
+    Middle,
+    Lower,
+    PercentB,
+    Bandwidth,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Type {
+    Number,
+    Bool,
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Number => write!(f, "数值"),
+            Type::Bool => write!(f, "条件"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+// 解析后的表达式 (已完成类型检查)
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f64),
+    Bool(bool),
+    Market(MarketField),
+    Indicator(usize, IndicatorField),  // 指标槽位, 字段
+    Neg(Box<Expr>),
+    Not(Box<Expr>),
+    Arith(ArithOp, Box<Expr>, Box<Expr>),
+    Compare(CompareOp, Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    // 是否引用了市场状态字段
+    fn reads_regime(&self) -> bool {
+        match self {
+            Expr::Market(field) => matches!(field, MarketField::RegimeIs(_) | MarketField::RegimeAdx | MarketField::RegimeAtrPercentile),
+            Expr::Neg(inner) | Expr::Not(inner) => inner.reads_regime(),
+            Expr::Arith(_, left, right) | Expr::Compare(_, left, right) | Expr::And(left, right) | Expr::Or(left, right) => {
+                left.reads_regime() || right.reads_regime()
+            }
+            Expr::Number(_) | Expr::Bool(_) | Expr::Indicator(..) => false,
+        }
+    }
+}
+
+// 一条规则: 条件成立时执行动作
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub action: Action,
+    pub text: String,   // 条件原文, 用于决策依据
+    pub line: usize,
+    condition: Expr,
+}
+
+// 从规则文件解析出的策略定义
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleDefinition {
+    pub name: String,
+    pub interval: Interval,
+    pub stop_pct: f64,
+    pub target_pct: f64,
+    pub confidence: f64,
+    pub rules: Vec<Rule>,
+    pub indicators: Vec<IndicatorSpec>,
+}
+
+impl RuleDefinition {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            interval: Interval::FiveMinutes,
+            stop_pct: 5.0,
+            target_pct: 10.0,
+            confidence: 0.5,
+            rules: Vec::new(),
+            indicators: Vec::new(),
+        }
+    }
+}
+
+// 解析规则文件, 返回其中定义的全部策略
+pub fn parse_rules(source: &str) -> Result<Vec<RuleDefinition>, RuleError> {
+    let mut definitions: Vec<RuleDefinition> = Vec::new();
+    let mut last_line = (0, "");
+    for (index, raw) in source.lines().enumerate() {
+        let line = index + 1;
+        let text = raw.split('#').next().unwrap_or_default().trim_end();
+        let indent = text.chars().take_while(|c| c.is_whitespace()).count();
+        let text = text.trim_start();
+        if text.is_empty() {
+            continue;
+        }
+        last_line = (line, raw);
+        let (keyword, rest) = match text.split_once(char::is_whitespace) {
+            Some((keyword, rest)) => (keyword, rest.trim_start()),
+            None => (text, ""),
+        };
+        // rest 在原始行中的起始列
+        let rest_column = indent + text.chars().count() - rest.chars().count() + 1;
+        let error = |column: usize, message: String| RuleError::new(line, column, message, raw);
+
+        if keyword == "strategy" {
+            let name = rest.trim();
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+                return Err(error(rest_column, format!("策略名称只能包含小写字母、数字和下划线: {:?}", name)));
+            }
+            if definitions.iter().any(|d| d.name == name) {
+                return Err(error(rest_column, format!("策略 {} 重复定义", name)));
+            }
+            if let Some(previous) = definitions.last() {
+                if previous.rules.is_empty() {
+                    return Err(error(indent + 1, format!("策略 {} 没有任何规则", previous.name)));
+                }
+            }
+            definitions.push(RuleDefinition::new(name));
+            continue;
+        }
+
+        let definition = definitions.last_mut()
+            .ok_or_else(|| error(indent + 1, format!("{} 必须写在 strategy <名称> 之后", keyword)))?;
+        match keyword {
+            "interval" => {
+                definition.interval = rest.trim().parse().map_err(|e: String| error(rest_column, e))?;
+            }
+            "stop_pct" | "target_pct" | "confidence" => {
+                let value: f64 = rest.trim().parse()
+                    .map_err(|_| error(rest_column, format!("{} 必须是数字: {:?}", keyword, rest.trim())))?;
+                let valid = match keyword {
+                    "confidence" => (0.0..=1.0).contains(&value),
+                    _ => value > 0.0,
+                };
+                if !valid {
+                    let range = if keyword == "confidence" { "必须在 [0, 1] 内" } else { "必须大于 0" };
+                    return Err(error(rest_column, format!("{} {}: {}", keyword, range, value)));
+                }
+                match keyword {
+                    "stop_pct" => definition.stop_pct = value,
+                    "target_pct" => definition.target_pct = value,
+                    _ => definition.confidence = value,
+                }
+            }
+            "enter_long" | "enter_short" | "exit" => {
+                let action = match keyword {
+                    "enter_long" => Action::EnterLong,
+                    "enter_short" => Action::EnterShort,
+                    _ => Action::Exit,
+                };
+                let condition = match rest.strip_prefix("when") {
+                    Some(condition) if condition.is_empty() || condition.starts_with(char::is_whitespace) => condition,
+                    _ => return Err(error(rest_column, format!("{} 之后应为 when <条件>", keyword))),
+                };
+                let offset = rest_column - 1 + "when".len();
+                let tokens = tokenize(condition, offset).map_err(|(column, message)| error(column, message))?;
+                let start = tokens[0].column;
+                let mut parser = Parser { tokens, pos: 0, indicators: &mut definition.indicators };
+                let (expr, ty) = parser.expression().map_err(|(column, message)| error(column, message))?;
+                let token = parser.peek();
+                if token.kind != Tok::End {
+                    return Err(error(token.column, format!("多余的内容: {}", token.kind)));
+                }
+                if ty != Type::Bool {
+                    return Err(error(start, "规则条件必须是条件表达式 (例如 rsi(14) < 30), 而不是数值".to_string()));
+                }
+                definition.rules.push(Rule { action, text: condition.trim().to_string(), line, condition: expr });
+            }
+            other => {
+                return Err(error(indent + 1, format!(
+                    "未知指令: {} (可用: strategy, interval, stop_pct, target_pct, confidence, enter_long, enter_short, exit)",
+                    other
+                )));
+            }
+        }
+    }
+
+    match definitions.last() {
+        None => Err(RuleError::new(last_line.0.max(1), 1, "规则文件中没有定义策略 (strategy <名称>)", last_line.1)),
+        Some(last) if last.rules.is_empty() => {
+            Err(RuleError::new(last_line.0, 1, format!("策略 {} 没有任何规则", last.name), last_line.1))
+        }
+        Some(_) => Ok(definitions),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Number(f64),
+    Ident(String),
+    Dot,
+    Comma,
+    LParen,
+    RParen,
+    Op(&'static str),
+    End,
+}
+
+impl fmt::Display for Tok {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tok::Number(value) => write!(f, "{}", value),
+            Tok::Ident(name) => write!(f, "{}", name),
+            Tok::Dot => write!(f, "."),
+            Tok::Comma => write!(f, ","),
+            Tok::LParen => write!(f, "("),
+            Tok::RParen => write!(f, ")"),
+            Tok::Op(op) => write!(f, "{}", op),
+            Tok::End => write!(f, "行尾"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: Tok,
+    column: usize,
+}
+
+type ParseError = (usize, String);
+
+// 拆分条件表达式, offset 为表达式之前的字符数
+fn tokenize(text: &str, offset: usize) -> Result<Vec<Token>, ParseError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let column = offset + i + 1;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        let kind = if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit)) {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let literal: String = chars[start..i].iter().collect();
+            Tok::Number(literal.parse().map_err(|_| (column, format!("无效的数字: {}", literal)))?)
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            Tok::Ident(chars[start..i].iter().collect::<String>().to_ascii_lowercase())
+        } else {
+            let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+            let (kind, len) = match (two.as_str(), c) {
+                ("<=", _) => (Tok::Op("<="), 2),
+                (">=", _) => (Tok::Op(">="), 2),
+                ("==", _) => (Tok::Op("=="), 2),
+                ("!=", _) => (Tok::Op("!="), 2),
+                ("&&", _) => (Tok::Ident("and".to_string()), 2),
+                ("||", _) => (Tok::Ident("or".to_string()), 2),
+                (_, '<') => (Tok::Op("<"), 1),
+                (_, '>') => (Tok::Op(">"), 1),
+                (_, '+') => (Tok::Op("+"), 1),
+                (_, '-') => (Tok::Op("-"), 1),
+                (_, '*') => (Tok::Op("*"), 1),
+                (_, '/') => (Tok::Op("/"), 1),
+                (_, '!') => (Tok::Ident("not".to_string()), 1),
+                (_, '.') => (Tok::Dot, 1),
+                (_, ',') => (Tok::Comma, 1),
+                (_, '(') => (Tok::LParen, 1),
+                (_, ')') => (Tok::RParen, 1),
+                (_, '=') => return Err((column, "比较相等请使用 ==".to_string())),
+                _ => return Err((column, format!("无法识别的字符: {:?}", c))),
+            };
+            i += len;
+            kind
+        };
+        tokens.push(Token { kind, column });
+    }
+    tokens.push(Token { kind: Tok::End, column: offset + chars.len() + 1 });
+    Ok(tokens)
+}
+
+// 递归下降解析: or < and < not < 比较 < 加减 < 乘除 < 负号 < 基本项
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    indicators: &'a mut Vec<IndicatorSpec>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos.min(self.tokens.len() - 1)]
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.peek().clone();
+        if self.pos < self.tokens.len() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(&self.peek().kind, Tok::Ident(name) if name == keyword)
+    }
+
+    fn expect_type(column: usize, ty: Type, expected: Type, context: &str) -> Result<(), ParseError> {
+        if ty == expected {
+            Ok(())
+        } else {
+            Err((column, format!("{} 需要{}, 这里是{}", context, expected, ty)))
+        }
+    }
+
+    fn expression(&mut self) -> Result<(Expr, Type), ParseError> {
+        let column = self.peek().column;
+        let (mut left, ty) = self.and()?;
+        while self.is_keyword("or") {
+            self.next();
+            Self::expect_type(column, ty, Type::Bool, "or 左侧")?;
+            let right_column = self.peek().column;
+            let (right, right_ty) = self.and()?;
+            Self::expect_type(right_column, right_ty, Type::Bool, "or 右侧")?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+        Ok((left, ty))
+    }
+
+    fn and(&mut self) -> Result<(Expr, Type), ParseError> {
+        let column = self.peek().column;
+        let (mut left, ty) = self.not()?;
+        while self.is_keyword("and") {
+            self.next();
+            Self::expect_type(column, ty, Type::Bool, "and 左侧")?;
+            let right_column = self.peek().column;
+            let (right, right_ty) = self.not()?;
+            Self::expect_type(right_column, right_ty, Type::Bool, "and 右侧")?;
+            left = Expr::And(Box::new(left), Box::new(right));
+        }
+        Ok((left, ty))
+    }
+
+    fn not(&mut self) -> Result<(Expr, Type), ParseError> {
+        if self.is_keyword("not") {
+            self.next();
+            let column = self.peek().column;
+            let (expr, ty) = self.not()?;
+            Self::expect_type(column, ty, Type::Bool, "not")?;
+            return Ok((Expr::Not(Box::new(expr)), Type::Bool));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<(Expr, Type), ParseError> {
+        let column = self.peek().column;
+        let (left, ty) = self.additive()?;
+        let op = match self.peek().kind {
+            Tok::Op("<") => CompareOp::Lt,
+            Tok::Op("<=") => CompareOp::Le,
+            Tok::Op(">") => CompareOp::Gt,
+            Tok::Op(">=") => CompareOp::Ge,
+            Tok::Op("==") => CompareOp::Eq,
+            Tok::Op("!=") => CompareOp::Ne,
+            _ => return Ok((left, ty)),
+        };
+        let op_column = self.next().column;
+        Self::expect_type(column, ty, Type::Number, "比较")?;
+        let right_column = self.peek().column;
+        let (right, right_ty) = self.additive()?;
+        Self::expect_type(right_column, right_ty, Type::Number, "比较")?;
+        if matches!(self.peek().kind, Tok::Op("<" | "<=" | ">" | ">=" | "==" | "!=")) {
+            return Err((self.peek().column, format!("不支持连续比较, 请用 and 连接 (第 {} 列的比较之后)", op_column)));
+        }
+        Ok((Expr::Compare(op, Box::new(left), Box::new(right)), Type::Bool))
+    }
+
+    fn additive(&mut self) -> Result<(Expr, Type), ParseError> {
+        let column = self.peek().column;
+        let (mut left, ty) = self.term()?;
+        loop {
+            let op = match self.peek().kind {
+                Tok::Op("+") => ArithOp::Add,
+                Tok::Op("-") => ArithOp::Sub,
+                _ => return Ok((left, ty)),
+            };
+            self.next();
+            Self::expect_type(column, ty, Type::Number, "加减")?;
+            let right_column = self.peek().column;
+            let (right, right_ty) = self.term()?;
+            Self::expect_type(right_column, right_ty, Type::Number, "加减")?;
+            left = Expr::Arith(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn term(&mut self) -> Result<(Expr, Type), ParseError> {
+        let column = self.peek().column;
+        let (mut left, ty) = self.unary()?;
+        loop {
+            let op = match self.peek().kind {
+                Tok::Op("*") => ArithOp::Mul,
+                Tok::Op("/") => ArithOp::Div,
+                _ => return Ok((left, ty)),
+            };
+            self.next();
+            Self::expect_type(column, ty, Type::Number, "乘除")?;
+            let right_column = self.peek().column;
+            let (right, right_ty) = self.unary()?;
+            Self::expect_type(right_column, right_ty, Type::Number, "乘除")?;
+            left = Expr::Arith(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn unary(&mut self) -> Result<(Expr, Type), ParseError> {
+        if self.peek().kind == Tok::Op("-") {
+            self.next();
+            let column = self.peek().column;
+            let (expr, ty) = self.unary()?;
+            Self::expect_type(column, ty, Type::Number, "负号")?;
+            return Ok((Expr::Neg(Box::new(expr)), Type::Number));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<(Expr, Type), ParseError> {
+        let token = self.next();
+        match token.kind {
+            Tok::Number(value) => Ok((Expr::Number(value), Type::Number)),
+            Tok::LParen => {
+                let result = self.expression()?;
+                let close = self.next();
+                if close.kind != Tok::RParen {
+                    return Err((close.column, format!("缺少右括号, 遇到 {}", close.kind)));
+                }
+                Ok(result)
+            }
+            Tok::Ident(name) => self.reference(&name, token.column),
+            other => Err((token.column, format!("应为数值、字段或指标, 遇到 {}", other))),
+        }
+    }
+
+    // 数据引用: 名称 [(参数, ...)] [.字段]
+    fn reference(&mut self, name: &str, column: usize) -> Result<(Expr, Type), ParseError> {
+        match name {
+            "true" => return Ok((Expr::Bool(true), Type::Bool)),
+            "false" => return Ok((Expr::Bool(false), Type::Bool)),
+            "and" | "or" => return Err((column, format!("{} 之前缺少条件", name))),
+            _ => {}
+        }
+        let mut args = Vec::new();
+        if self.peek().kind == Tok::LParen {
+            self.next();
+            loop {
+                let token = self.next();
+                match token.kind {
+                    Tok::Number(value) => args.push((value, token.column)),
+                    Tok::RParen if args.is_empty() => break,
+                    other => return Err((token.column, format!("{} 的参数必须是数字, 遇到 {}", name, other))),
+                }
+                let token = self.next();
+                match token.kind {
+                    Tok::Comma => continue,
+                    Tok::RParen => break,
+                    other => return Err((token.column, format!("{} 的参数之间应为逗号或右括号, 遇到 {}", name, other))),
+                }
+            }
+        }
+        let field = if self.peek().kind == Tok::Dot {
+            self.next();
+            let token = self.next();
+            match token.kind {
+                Tok::Ident(field) => Some((field, token.column)),
+                other => return Err((token.column, format!("{} 之后应为字段名, 遇到 {}", name, other))),
+            }
+        } else {
+            None
+        };
+        let field_name = field.as_ref().map(|(field, _)| field.as_str());
+        let field_column = field.as_ref().map_or(column, |(_, column)| *column);
+
+        // 行情与持仓字段
+        let market = match (name, field_name) {
+            ("price" | "close", None) => Some((MarketField::Price, Type::Number)),
+            ("depth", Some(field)) => Some(match field {
+                "imbalance" => (MarketField::DepthImbalance, Type::Number),
+                "bid_volume" => (MarketField::DepthBidVolume, Type::Number),
+                "ask_volume" => (MarketField::DepthAskVolume, Type::Number),
+                "spread_pct" => (MarketField::DepthSpreadPct, Type::Number),
+                other => return Err((field_column, format!(
+                    "未知的深度字段: {} (可用: imbalance, bid_volume, ask_volume, spread_pct)", other
+                ))),
+            }),
+            ("ticker", Some(field)) => Some(match field {
+                "change_pct" => (MarketField::TickerChangePct, Type::Number),
+                "high" => (MarketField::TickerHigh, Type::Number),
+                "low" => (MarketField::TickerLow, Type::Number),
+                "last" => (MarketField::TickerLast, Type::Number),
+                "volume" => (MarketField::TickerVolume, Type::Number),
+                "bid" => (MarketField::TickerBid, Type::Number),
+                "ask" => (MarketField::TickerAsk, Type::Number),
+                "spread_pct" => (MarketField::TickerSpreadPct, Type::Number),
+                "volatility_pct" => (MarketField::TickerVolatilityPct, Type::Number),
+                "range_pct" => (MarketField::TickerRangePct, Type::Number),
+                other => return Err((field_column, format!(
+                    "未知的行情字段: {} (可用: change_pct, high, low, last, volume, bid, ask, spread_pct, volatility_pct, range_pct)",
+                    other
+                ))),
+            }),
+            ("position", Some(field)) => Some(match field {
+                "open" => (MarketField::PositionOpen, Type::Bool),
+                "long" => (MarketField::PositionLong, Type::Bool),
+                "short" => (MarketField::PositionShort, Type::Bool),
+                "pnl" => (MarketField::PositionPnl, Type::Number),
+                "entry" => (MarketField::PositionEntry, Type::Number),
+                other => return Err((field_column, format!(
+                    "未知的持仓字段: {} (可用: open, long, short, pnl, entry)", other
+                ))),
+            }),
+            ("regime", Some(field)) => Some(match field {
+                "adx" => (MarketField::RegimeAdx, Type::Number),
+                "atr_percentile" => (MarketField::RegimeAtrPercentile, Type::Number),
+                other => match Regime::ALL.into_iter().find(|regime| regime.as_str() == other) {
+                    Some(regime) => (MarketField::RegimeIs(regime), Type::Bool),
+                    None => return Err((field_column, format!(
+                        "未知的市场状态字段: {} (可用: trending, ranging, high_volatility, illiquid, adx, atr_percentile)",
+                        other
+                    ))),
+                },
+            }),
+            ("depth" | "ticker" | "position" | "regime", None) => {
+                return Err((column, format!("{} 需要指定字段, 例如 {}.{}", name, name, match name {
+                    "depth" => "imbalance",
+                    "ticker" => "change_pct",
+                    "regime" => "trending",
+                    _ => "long",
+                })));
+            }
+            ("price" | "close", Some(_)) => return Err((field_column, format!("{} 没有字段", name))),
+            _ => None,
+        };
+        if let Some((field, ty)) = market {
+            if !args.is_empty() {
+                return Err((column, format!("{} 不接受参数", name)));
+            }
+            return Ok((Expr::Market(field), ty));
+        }
+
+        // 指标
+        let period = |index: usize, default: Option<usize>| -> Result<usize, ParseError> {
+            match args.get(index) {
+                Some(&(value, column)) if value > MAX_PERIOD as f64 => {
+                    Err((column, format!("{} 的周期不能超过 {}: {}", name, MAX_PERIOD, value)))
+                }
+                Some(&(value, _)) if value >= 1.0 && value.fract() == 0.0 => Ok(value as usize),
+                Some(&(value, column)) => Err((column, format!("{} 的周期必须是正整数: {}", name, value))),
+                None => default.ok_or((column, format!("{} 需要指定周期, 例如 {}(20)", name, name))),
+            }
+        };
+        let max_args = |count: usize| -> Result<(), ParseError> {
+            match args.get(count) {
+                Some(&(_, column)) => Err((column, format!("{} 最多接受 {} 个参数", name, count))),
+                None => Ok(()),
+            }
+        };
+        let spec = match name {
+            "rsi" => { max_args(1)?; IndicatorSpec::Rsi(period(0, Some(14))?) }
+            "ema" => { max_args(1)?; IndicatorSpec::Ema(period(0, None)?) }
+            "sma" => { max_args(1)?; IndicatorSpec::Sma(period(0, None)?) }
+            "atr" => { max_args(1)?; IndicatorSpec::Atr(period(0, Some(14))?) }
+            "adx" => { max_args(1)?; IndicatorSpec::Adx(period(0, Some(14))?) }
+            "macd" => {
+                max_args(3)?;
+                let (fast, slow, signal) = (period(0, Some(12))?, period(1, Some(26))?, period(2, Some(9))?);
+                if fast >= slow {
+                    return Err((column, format!("MACD 快线周期 ({}) 必须小于慢线周期 ({})", fast, slow)));
+                }
+                IndicatorSpec::Macd(fast, slow, signal)
+            }
+            "bb" | "bollinger" => {
+                max_args(2)?;
+                let std_dev = match args.get(1) {
+                    Some(&(value, _)) if value > 0.0 => value,
+                    Some(&(value, column)) => return Err((column, format!("布林带标准差倍数必须大于 0: {}", value))),
+                    None => 2.0,
+                };
+                IndicatorSpec::Bollinger(period(0, Some(20))?, std_dev)
+            }
+            other => {
+                return Err((column, format!(
+                    "未知的数据: {} (可用: price, depth.*, ticker.*, position.*, rsi, ema, sma, atr, adx, macd, bb)", other
+                )));
+            }
+        };
+
+        let (field, ty) = match (&spec, field_name) {
+            (IndicatorSpec::Macd(..), Some(field)) => match field {
+                "line" | "value" => (IndicatorField::MacdLine, Type::Number),
+                "signal" => (IndicatorField::MacdSignal, Type::Number),
+                "histogram" | "hist" => (IndicatorField::MacdHistogram, Type::Number),
+                "cross_up" => (IndicatorField::CrossUp, Type::Bool),
+                "cross_down" => (IndicatorField::CrossDown, Type::Bool),
+                other => return Err((field_column, format!(
+                    "未知的 MACD 字段: {} (可用: line, signal, histogram, cross_up, cross_down)", other
+                ))),
+            },
+            (IndicatorSpec::Macd(..), None) => {
+                return Err((column, "macd 需要指定字段, 例如 macd.cross_up 或 macd.histogram".to_string()));
+            }
+            (IndicatorSpec::Bollinger(..), Some(field)) => match field {
+                "upper" => (IndicatorField::Upper, Type::Number),
+                "middle" => (IndicatorField::Middle, Type::Number),
+                "lower" => (IndicatorField::Lower, Type::Number),
+                "percent_b" => (IndicatorField::PercentB, Type::Number),
+                "bandwidth" => (IndicatorField::Bandwidth, Type::Number),
+                other => return Err((field_column, format!(
+                    "未知的布林带字段: {} (可用: upper, middle, lower, percent_b, bandwidth)", other
+                ))),
+            },
+            (IndicatorSpec::Bollinger(..), None) => {
+                return Err((column, format!("{} 需要指定字段, 例如 {}.lower", name, name)));
+            }
+            (IndicatorSpec::Adx(_), Some("plus_di")) => (IndicatorField::PlusDi, Type::Number),
+            (IndicatorSpec::Adx(_), Some("minus_di")) => (IndicatorField::MinusDi, Type::Number),
+            (_, Some(field)) => return Err((field_column, format!("{} 没有字段 {}", spec, field))),
+            (_, None) => (IndicatorField::Value, Type::Number),
+        };
+
+        let slot = match self.indicators.iter().position(|existing| *existing == spec) {
+            Some(slot) => slot,
+            None => {
+                self.indicators.push(spec);
+                self.indicators.len() - 1
+            }
+        };
+        Ok((Expr::Indicator(slot, field), ty))
+    }
+}
+
+// 指标实例
+#[derive(Debug, Clone)]
+enum Series {
+    Rsi(RSI),
+    Ema(EMA),
+    Sma(SMA),
+    Atr(ATR),
+    Adx(ADX),
+    Macd(MACDIndicator),
+    Bollinger(BollingerBands),
+}
+
+// 指标的最新输出
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Reading {
+    Value(f64),
+    Adx(ADXOutput),
+    Macd(MACD),
+    Bands(BollingerOutput),
+}
+
+impl fmt::Display for Reading {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reading::Value(value) => write!(f, "{:.4}", value),
+            Reading::Adx(adx) => write!(f, "{:.1} (+DI {:.1} / -DI {:.1})", adx.adx, adx.plus_di, adx.minus_di),
+            Reading::Macd(macd) => write!(f, "{:.6} / 信号线 {:.6} / 柱 {:.6}", macd.macd, macd.signal, macd.histogram),
+            Reading::Bands(bands) => write!(f, "{:.4} / {:.4} / {:.4}", bands.lower, bands.middle, bands.upper),
+        }
+    }
+}
+
+impl Series {
+    fn update(&mut self, bar: Bar) -> Option<Reading> {
+        match self {
+            Series::Rsi(rsi) => rsi.update(bar.close).map(Reading::Value),
+            Series::Ema(ema) => ema.update(bar.close).map(Reading::Value),
+            Series::Sma(sma) => sma.update(bar.close).map(Reading::Value),
+            Series::Atr(atr) => atr.update(bar).map(Reading::Value),
+            Series::Adx(adx) => adx.update(bar).map(Reading::Adx),
+            Series::Macd(macd) => macd.update(bar.close).map(Reading::Macd),
+            Series::Bollinger(bands) => bands.update(bar.close).map(Reading::Bands),
+        }
+    }
+
+    fn warm_up(&self) -> usize {
+        match self {
+            Series::Rsi(rsi) => rsi.warm_up(),
+            Series::Ema(ema) => ema.warm_up(),
+            Series::Sma(sma) => sma.warm_up(),
+            Series::Atr(atr) => atr.warm_up(),
+            Series::Adx(adx) => adx.warm_up(),
+            // 判断交叉需要前一个值
+            Series::Macd(macd) => macd.warm_up() + 1,
+            Series::Bollinger(bands) => bands.warm_up(),
+        }
+    }
+}
+
+struct Slot {
+    spec: IndicatorSpec,
+    series: Series,
+    current: Option<Reading>,
+    previous: Option<Reading>,
+}
+
+// 规则策略: 按规则文件中的条件开仓/平仓。
+// 持仓时先检查 exit 规则, 再检查与持仓反向的开仓规则 (由管理器反手); 同向开仓规则被忽略。
+pub struct RuleStrategy {
+    definition: RuleDefinition,
+    slots: Vec<Slot>,
+    last_close: Option<f64>,
+    bars: usize,
+    signal_history: SignalLog,
+}
+
+impl RuleStrategy {
+    pub fn new(definition: RuleDefinition) -> Self {
+        let slots = definition.indicators.iter()
+            .map(|spec| Slot { spec: spec.clone(), series: spec.build(), current: None, previous: None })
+            .collect();
+        Self {
+            definition,
+            slots,
+            last_close: None,
+            bars: 0,
+            signal_history: SignalLog::default(),
+        }
+    }
+
+    // 覆盖规则文件中的止损/止盈幅度 (%)
+    pub fn with_risk(mut self, stop_pct: f64, target_pct: f64) -> Self {
+        self.definition.stop_pct = stop_pct;
+        self.definition.target_pct = target_pct;
+        self
+    }
+
+    pub fn with_confidence(mut self, confidence: f64) -> Self {
+        self.definition.confidence = confidence;
+        self
+    }
+
+    pub fn definition(&self) -> &RuleDefinition {
+        &self.definition
+    }
+
+    pub fn signal_history(&self) -> &SignalLog {
+        &self.signal_history
+    }
+
+    fn add_bar(&mut self, bar: Bar) {
+        for slot in &mut self.slots {
+            if let Some(reading) = slot.series.update(bar) {
+                slot.previous = slot.current.replace(reading);
+            }
+        }
+        self.last_close = Some(bar.close);
+        self.bars += 1;
+    }
+
+    fn reading(&self, slot: usize) -> Result<Reading, String> {
+        let slot = &self.slots[slot];
+        slot.current.ok_or_else(|| format!("{} 预热中", slot.spec))
+    }
+
+    fn number(&self, expr: &Expr, ctx: &StrategyContext) -> Result<f64, String> {
+        let value = match expr {
+            Expr::Number(value) => *value,
+            Expr::Market(field) => market_number(*field, ctx)?,
+            Expr::Indicator(slot, field) => match (self.reading(*slot)?, field) {
+                (Reading::Value(value), _) => value,
+                (Reading::Adx(adx), IndicatorField::PlusDi) => adx.plus_di,
+                (Reading::Adx(adx), IndicatorField::MinusDi) => adx.minus_di,
+                (Reading::Adx(adx), _) => adx.adx,
+                (Reading::Macd(macd), IndicatorField::MacdSignal) => macd.signal,
+                (Reading::Macd(macd), IndicatorField::MacdHistogram) => macd.histogram,
+                (Reading::Macd(macd), _) => macd.macd,
+                (Reading::Bands(bands), IndicatorField::Upper) => bands.upper,
+                (Reading::Bands(bands), IndicatorField::Lower) => bands.lower,
+                (Reading::Bands(bands), IndicatorField::PercentB) => bands.percent_b(self.last_close.unwrap_or(ctx.price)),
+                (Reading::Bands(bands), IndicatorField::Bandwidth) => bands.bandwidth(),
+                (Reading::Bands(bands), _) => bands.middle,
+            },
+            Expr::Neg(expr) => -self.number(expr, ctx)?,
+            Expr::Arith(op, left, right) => {
+                let (left, right) = (self.number(left, ctx)?, self.number(right, ctx)?);
+                match op {
+                    ArithOp::Add => left + right,
+                    ArithOp::Sub => left - right,
+                    ArithOp::Mul => left * right,
+                    ArithOp::Div if right == 0.0 => return Err("除数为 0".to_string()),
+                    ArithOp::Div => left / right,
+                }
+            }
+            _ => unreachable!("条件表达式已在解析时排除"),
+        };
+        if value.is_finite() {
+            Ok(value)
+        } else {
+            Err("计算结果无效".to_string())
+        }
+    }
+
+    // 条件求值, 缺少数据时返回缺少的内容
+    fn truth(&self, expr: &Expr, ctx: &StrategyContext) -> Result<bool, String> {
+        match expr {
+            Expr::Bool(value) => Ok(*value),
+            Expr::Market(MarketField::PositionOpen) => Ok(ctx.position.is_some()),
+            Expr::Market(MarketField::PositionLong) => Ok(ctx.position.is_some_and(|p| p.side == OrderSide::Buy)),
+            Expr::Market(MarketField::PositionShort) => Ok(ctx.position.is_some_and(|p| p.side == OrderSide::Sell)),
+            Expr::Market(MarketField::RegimeIs(regime)) => match ctx.regime {
+                Some(reading) => Ok(reading.is(*regime)),
+                None => Err("市场状态未知".to_string()),
+            },
+            Expr::Indicator(slot, field) => {
+                let previous = self.slots[*slot].previous;
+                match (self.reading(*slot)?, previous) {
+                    (Reading::Macd(current), Some(Reading::Macd(previous))) => Ok(match field {
+                        IndicatorField::CrossUp => previous.histogram <= 0.0 && current.histogram > 0.0,
+                        _ => previous.histogram >= 0.0 && current.histogram < 0.0,
+                    }),
+                    _ => Err(format!("{} 预热中", self.slots[*slot].spec)),
+                }
+            }
+            Expr::Not(expr) => self.truth(expr, ctx).map(|value| !value),
+            Expr::Compare(op, left, right) => {
+                let (left, right) = (self.number(left, ctx)?, self.number(right, ctx)?);
+                Ok(match op {
+                    CompareOp::Lt => left < right,
+                    CompareOp::Le => left <= right,
+                    CompareOp::Gt => left > right,
+                    CompareOp::Ge => left >= right,
+                    CompareOp::Eq => left == right,
+                    CompareOp::Ne => left != right,
+                })
+            }
+            // 一侧缺少数据时, 另一侧已能决定结果则不算缺少
+            Expr::And(left, right) => match self.truth(left, ctx) {
+                Ok(false) => Ok(false),
+                Ok(true) => self.truth(right, ctx),
+                Err(missing) => match self.truth(right, ctx) {
+                    Ok(false) => Ok(false),
+                    _ => Err(missing),
+                },
+            },
+            Expr::Or(left, right) => match self.truth(left, ctx) {
+                Ok(true) => Ok(true),
+                Ok(false) => self.truth(right, ctx),
+                Err(missing) => match self.truth(right, ctx) {
+                    Ok(true) => Ok(true),
+                    _ => Err(missing),
+                },
+            },
+            _ => unreachable!("数值表达式已在解析时排除"),
+        }
+    }
+}
+
+fn market_number(field: MarketField, ctx: &StrategyContext) -> Result<f64, String> {
+    let depth = || ctx.depth.ok_or_else(|| "无深度数据".to_string());
+    let ticker = || ctx.ticker.ok_or_else(|| "无24小时行情".to_string());
+    let position = || ctx.position.ok_or_else(|| "无持仓".to_string());
+    let regime = || ctx.regime.ok_or_else(|| "市场状态未知".to_string());
+    // 与深度过滤一致, 只统计当前价附近的挂单
+    let band = DepthFilter::default().band_pct / 100.0;
+    let bid_volume = |depth: &crate::strategy::MarketDepth| -> f64 {
+        depth.bids.iter().filter(|(price, _)| *price > ctx.price * (1.0 - band)).map(|(_, qty)| qty).sum()
+    };
+    let ask_volume = |depth: &crate::strategy::MarketDepth| -> f64 {
+        depth.asks.iter().filter(|(price, _)| *price < ctx.price * (1.0 + band)).map(|(_, qty)| qty).sum()
+    };
+    Ok(match field {
+        MarketField::Price => ctx.price,
+        MarketField::DepthImbalance => {
+            let depth = depth()?;
+            let asks = ask_volume(depth);
+            if asks <= 0.0 {
+                return Err("深度卖单为空".to_string());
+            }
+            bid_volume(depth) / asks
+        }
+        MarketField::DepthBidVolume => bid_volume(depth()?),
+        MarketField::DepthAskVolume => ask_volume(depth()?),
+        MarketField::DepthSpreadPct => {
+            let depth = depth()?;
+            let best_bid = depth.bids.iter().map(|(price, _)| *price).fold(f64::NAN, f64::max);
+            let best_ask = depth.asks.iter().map(|(price, _)| *price).fold(f64::NAN, f64::min);
+            if !(best_bid > 0.0 && best_ask > 0.0) {
+                return Err("深度缺少买一或卖一".to_string());
+            }
+            (best_ask - best_bid) / best_bid * 100.0
+        }
+        MarketField::TickerChangePct => ticker()?.price_change_percent,
+        MarketField::TickerHigh => ticker()?.high_price,
+        MarketField::TickerLow => ticker()?.low_price,
+        MarketField::TickerLast => ticker()?.last_price,
+        MarketField::TickerVolume => ticker()?.volume,
+        MarketField::TickerBid => ticker()?.bid_price,
+        MarketField::TickerAsk => ticker()?.ask_price,
+        MarketField::TickerSpreadPct => {
+            let ticker = ticker()?;
+            (ticker.ask_price - ticker.bid_price) / ticker.bid_price * 100.0
+        }
+        MarketField::TickerVolatilityPct => {
+            let ticker = ticker()?;
+            (ticker.high_price - ticker.low_price) / ticker.low_price * 100.0
+        }
+        MarketField::TickerRangePct => {
+            let ticker = ticker()?;
+            (ticker.last_price - ticker.low_price) / (ticker.high_price - ticker.low_price) * 100.0
+        }
+        MarketField::PositionPnl => position()?.unrealized_pnl,
+        MarketField::PositionEntry => position()?.entry_price,
+        MarketField::RegimeAdx => regime()?.adx.ok_or_else(|| "ADX 预热中".to_string())?,
+        MarketField::RegimeAtrPercentile => regime()?.atr_percentile.ok_or_else(|| "ATR 分位数预热中".to_string())?,
+        MarketField::PositionOpen | MarketField::PositionLong | MarketField::PositionShort | MarketField::RegimeIs(_) => {
+            unreachable!("条件字段不是数值")
+        }
+    })
+}
+
+impl TradingStrategy for RuleStrategy {
+    fn name(&self) -> &str {
+        &self.definition.name
+    }
+
+    fn add_price(&mut self, price: f64) {
+        self.add_bar(Bar::new(price, price, price, 0.0));
+    }
+
+    fn intervals(&self) -> Vec<Interval> {
+        vec![self.definition.interval]
+    }
+
+    fn lookback(&self, interval: Interval) -> usize {
+        if interval == self.definition.interval {
+            self.slots.iter().map(|slot| slot.series.warm_up()).max().unwrap_or(0)
+        } else {
+            0
+        }
+    }
+
+    fn uses_regime(&self) -> bool {
+        self.definition.rules.iter().any(|rule| rule.condition.reads_regime())
+    }
+
+    fn is_ready(&self) -> bool {
+        self.bars >= self.lookback(self.definition.interval)
+    }
+
+    fn add_candle(&mut self, interval: Interval, kline: &Kline) {
+        if interval == self.definition.interval {
+            self.add_bar(Bar::from(kline));
+        }
+    }
+
+    fn evaluate(&self, ctx: &StrategyContext) -> Decision {
+        let definition = &self.definition;
+        let holding_long = ctx.position.map(|position| position.side == OrderSide::Buy);
+        let mut missing = Vec::new();
+
+        // 持仓时先检查平仓规则, 再检查开仓规则
+        let exits = definition.rules.iter().filter(|rule| rule.action == Action::Exit && holding_long.is_some());
+        let entries = definition.rules.iter().filter(|rule| match rule.action {
+            Action::EnterLong => holding_long != Some(true),
+            Action::EnterShort => holding_long != Some(false),
+            _ => false,
+        });
+        for rule in exits.chain(entries) {
+            match self.truth(&rule.condition, ctx) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(reason) => {
+                    missing.push(format!("第 {} 行 {}: {}", rule.line, rule.action, reason));
+                    continue;
+                }
+            }
+            let reason = format!("规则成立 (第 {} 行, {}): {}", rule.line, rule.action, rule.text);
+            let price = ctx.price;
+            let (stop, target) = (definition.stop_pct / 100.0, definition.target_pct / 100.0);
+            return match rule.action {
+                Action::EnterLong => Decision::enter_long(definition.confidence)
+                    .with_stop_loss(price * (1.0 - stop))
+                    .with_take_profit(price * (1.0 + target)),
+                Action::EnterShort => Decision::enter_short(definition.confidence)
+                    .with_stop_loss(price * (1.0 + stop))
+                    .with_take_profit(price * (1.0 - target)),
+                _ => Decision::exit(definition.confidence),
+            }
+            .with_reason(reason);
+        }
+
+        let mut decision = Decision::hold().with_reason(format!("规则策略 {}: 没有成立的规则", definition.name));
+        for reason in missing {
+            decision = decision.with_reason(format!("缺少数据 ({})", reason));
+        }
+        decision
+    }
+
+    fn on_signal(&mut self, decision: &Decision, price: f64, timestamp: i64) {
+        self.signal_history.record(decision, price, timestamp);
+    }
+
+    fn describe(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self.slots.iter()
+            .map(|slot| match slot.current {
+                Some(reading) => format!("{}: {}", slot.spec, reading),
+                None => format!("{}: 预热中", slot.spec),
+            })
+            .collect();
+        lines.push(format!(
+            "{} 条规则, 止损 {}% / 止盈 {}%",
+            self.definition.rules.len(), self.definition.stop_pct, self.definition.target_pct
+        ));
+        lines
+    }
+}
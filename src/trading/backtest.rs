@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+use crate::strategy::{Action, Decision, StrategyContext, TradingStrategy};
+use crate::types::{CurrencyConfig, Interval, Kline, OrderSide, Position};
+use super::protective_prices;
+
+// 平仓原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitReason {
+    Signal,      // 策略平仓或反手
+    StopLoss,
+    TakeProfit,
+    EndOfData,   // 回放结束时按最后收盘价平仓
+}
+
+// 一笔已平仓的交易 (部分平仓单独记一笔)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trade {
+    pub side: OrderSide,
+    pub quantity: f64,
+    pub entry_price: f64,
+    pub exit_price: f64,
+    pub entry_time: i64,  // 开仓K线的收盘时间
+    pub exit_time: i64,
+    pub reason: ExitReason,
+}
+
+impl Trade {
+    pub fn pnl(&self) -> f64 {
+        let diff = self.exit_price - self.entry_price;
+        match self.side {
+            OrderSide::Buy => diff * self.quantity,
+            OrderSide::Sell => -diff * self.quantity,
+        }
+    }
+}
+
+// 回测结果
+#[derive(Debug, Clone, Default)]
+pub struct BacktestReport {
+    pub trades: Vec<Trade>,
+    pub signals: usize,  // 回报给策略的决策数量
+    pub bars: usize,     // 回放的K线数量
+}
+
+impl BacktestReport {
+    pub fn trade_count(&self) -> usize {
+        self.trades.len()
+    }
+
+    // 已实现盈亏 (计价币)
+    pub fn pnl(&self) -> f64 {
+        self.trades.iter().map(Trade::pnl).sum()
+    }
+
+    pub fn wins(&self) -> usize {
+        self.trades.iter().filter(|t| t.pnl() > 0.0).count()
+    }
+
+    // 盈利交易占比, 没有交易时为 0
+    pub fn win_rate(&self) -> f64 {
+        if self.trades.is_empty() {
+            return 0.0;
+        }
+        self.wins() as f64 / self.trades.len() as f64
+    }
+
+    // 按平仓顺序累计盈亏的最大回撤
+    pub fn max_drawdown(&self) -> f64 {
+        let (mut equity, mut peak, mut drawdown) = (0.0f64, 0.0f64, 0.0f64);
+        for trade in &self.trades {
+            equity += trade.pnl();
+            peak = peak.max(equity);
+            drawdown = drawdown.max(peak - equity);
+        }
+        drawdown
+    }
+}
+
+// 回测中的持仓及其止盈止损价
+struct OpenPosition {
+    position: Position,
+    take_profit: f64,
+    stop_loss: f64,
+    entry_time: i64,
+}
+
+impl OpenPosition {
+    // 本根K线触及的保护价 (同时触及时按止损计), 跳空越过止损时按开盘价成交
+    fn triggered(&self, kline: &Kline) -> Option<(f64, ExitReason)> {
+        match self.position.side {
+            OrderSide::Buy if kline.low <= self.stop_loss => Some((self.stop_loss.min(kline.open), ExitReason::StopLoss)),
+            OrderSide::Sell if kline.high >= self.stop_loss => Some((self.stop_loss.max(kline.open), ExitReason::StopLoss)),
+            OrderSide::Buy if kline.high >= self.take_profit => Some((self.take_profit, ExitReason::TakeProfit)),
+            OrderSide::Sell if kline.low <= self.take_profit => Some((self.take_profit, ExitReason::TakeProfit)),
+            _ => None,
+        }
+    }
+
+    fn close(&self, quantity: f64, price: f64, time: i64, reason: ExitReason) -> Trade {
+        Trade {
+            side: self.position.side.clone(),
+            quantity,
+            entry_price: self.position.entry_price,
+            exit_price: price,
+            entry_time: self.entry_time,
+            exit_time: time,
+            reason,
+        }
+    }
+}
+
+// 不连接交易所, 按K线回放策略并模拟成交
+//
+// 与交易管理器的合约下单规则一致: 同向信号不加仓, 反向信号先平仓再反手,
+// 开仓时使用策略给出或默认的止盈止损。信号按当根收盘价成交,
+// 止盈止损在之后的K线最高/最低价触及时成交。只回放策略的主周期。
+pub struct Backtest {
+    config: CurrencyConfig,
+}
+
+impl Backtest {
+    // config 提供交易对、下单数量、精度与杠杆
+    pub fn new(config: CurrencyConfig) -> Self {
+        Self { config }
+    }
+
+    // 回放按时间升序的主周期K线, 结束时按最后收盘价平掉剩余持仓
+    pub fn run(&self, strategy: &mut dyn TradingStrategy, klines: &[Kline]) -> BacktestReport {
+        strategy.bind_currency(&self.config);
+        let interval = strategy.intervals().first().copied().unwrap_or(Interval::FiveMinutes);
+        let mut report = BacktestReport::default();
+        let mut open: Option<OpenPosition> = None;
+
+        for (i, kline) in klines.iter().enumerate() {
+            // 先按本根K线的价格区间检查之前设置的止盈止损
+            if let Some((price, reason)) = open.as_ref().and_then(|held| held.triggered(kline)) {
+                if let Some(held) = open.take() {
+                    report.trades.push(held.close(held.position.quantity, price, kline.close_time, reason));
+                }
+            }
+            if let Some(held) = open.as_mut() {
+                let diff = kline.close - held.position.entry_price;
+                held.position.unrealized_pnl = match held.position.side {
+                    OrderSide::Buy => diff * held.position.quantity,
+                    OrderSide::Sell => -diff * held.position.quantity,
+                };
+            }
+
+            strategy.add_candle(interval, kline);
+            report.bars += 1;
+            if !strategy.is_ready() {
+                continue;
+            }
+
+            let candles = BTreeMap::from([(interval, &klines[..=i])]);
+            let ctx = StrategyContext::new(&self.config.symbol, kline.close)
+                .with_timestamp(kline.close_time)
+                .with_position(open.as_ref().map(|held| &held.position))
+                .with_candles(Some(&candles));
+            let decision = strategy.evaluate(&ctx);
+            if decision.action == Action::Hold {
+                continue;
+            }
+            self.execute(&decision, kline, &mut open, &mut report.trades);
+            strategy.on_signal(&decision, kline.close, kline.close_time);
+            report.signals += 1;
+        }
+
+        if let (Some(held), Some(last)) = (open, klines.last()) {
+            report.trades.push(held.close(held.position.quantity, last.close, last.close_time, ExitReason::EndOfData));
+        }
+        report
+    }
+
+    fn execute(&self, decision: &Decision, kline: &Kline, open: &mut Option<OpenPosition>, trades: &mut Vec<Trade>) {
+        let (price, time) = (kline.close, kline.close_time);
+        let side = match decision.action {
+            Action::Hold => return,
+            Action::Exit => {
+                if let Some(mut held) = open.take() {
+                    // 部分平仓按数量精度向下取整
+                    let quantity = if decision.close_fraction >= 1.0 {
+                        held.position.quantity
+                    } else {
+                        self.config.floor_qty(held.position.quantity * decision.close_fraction.max(0.0))
+                    };
+                    if quantity > 0.0 {
+                        trades.push(held.close(quantity, price, time, ExitReason::Signal));
+                        held.position.quantity -= quantity;
+                    }
+                    if held.position.quantity > 1e-12 {
+                        *open = Some(held);
+                    }
+                }
+                return;
+            }
+            Action::EnterLong => OrderSide::Buy,
+            Action::EnterShort => OrderSide::Sell,
+        };
+
+        // 已有同向持仓时不加仓; 反向信号先平掉现有持仓再反手
+        if let Some(held) = open.as_ref() {
+            if held.position.side == side {
+                return;
+            }
+            trades.push(held.close(held.position.quantity, price, time, ExitReason::Signal));
+        }
+        let quantity = match decision.quantity {
+            Some(quantity) => self.config.floor_qty(quantity).max(self.config.min_qty),
+            None => self.config.order_quantity(decision.confidence),
+        };
+        let (take_profit, stop_loss) = protective_prices(&side, price, decision);
+        *open = Some(OpenPosition {
+            position: Position {
+                symbol: self.config.symbol.clone(),
+                side,
+                quantity,
+                entry_price: price,
+                unrealized_pnl: 0.0,
+                leverage: self.config.leverage,
+            },
+            take_profit,
+            stop_loss,
+            entry_time: time,
+        });
+    }
+}
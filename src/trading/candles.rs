@@ -0,0 +1,218 @@
+use std::collections::{BTreeMap, VecDeque};
+use crate::types::{Interval, Kline};
+
+// 单个币种缓存的最大K线数量
+pub const MAX_CANDLES: usize = 500;
+
+// 单个币种的K线缓存
+// 只追加已收盘且比缓存中更新的K线 (按 open_time 判断), 正在形成的K线不入缓存
+#[derive(Debug, Clone)]
+pub struct CandleBuffer {
+    interval: Interval,
+    capacity: usize,
+    candles: VecDeque<Kline>,
+}
+
+impl CandleBuffer {
+    pub fn new(interval: Interval) -> Self {
+        Self::with_capacity(interval, MAX_CANDLES)
+    }
+
+    pub fn with_capacity(interval: Interval, capacity: usize) -> Self {
+        Self {
+            interval,
+            capacity: capacity.max(1),
+            candles: VecDeque::new(),
+        }
+    }
+
+    pub fn interval(&self) -> Interval {
+        self.interval
+    }
+
+    // 扩大容量 (不会缩小)
+    pub fn reserve(&mut self, capacity: usize) {
+        self.capacity = self.capacity.max(capacity);
+    }
+
+    // 合并新拉取的K线, 返回本次新增的已收盘K线 (按时间升序)
+    // now 为当前时间 (毫秒), 下一根K线已开盘的K线视为已收盘
+    pub fn ingest(&mut self, klines: &[Kline], now: i64) -> Vec<Kline> {
+        let mut closed: Vec<&Kline> = klines.iter()
+            .filter(|k| self.interval.next_open_time(k.open_time) <= now)
+            .collect();
+        closed.sort_by_key(|k| k.open_time);
+
+        let mut appended = Vec::new();
+        for kline in closed {
+            if self.last_open_time().is_some_and(|last| kline.open_time <= last) {
+                continue;
+            }
+            self.candles.push_back(kline.clone());
+            appended.push(kline.clone());
+        }
+
+        while self.candles.len() > self.capacity {
+            self.candles.pop_front();
+        }
+        // 保持连续存储, 供 as_slice 借出
+        self.candles.make_contiguous();
+        appended
+    }
+
+    // 合并历史K线 (可早于已缓存的K线), 按 open_time 去重后保留最新的 capacity 根
+    pub fn backfill(&mut self, klines: &[Kline], now: i64) {
+        let mut merged: BTreeMap<i64, Kline> = self.candles.drain(..)
+            .map(|k| (k.open_time, k))
+            .collect();
+        for kline in klines.iter().filter(|k| self.interval.next_open_time(k.open_time) <= now) {
+            merged.entry(kline.open_time).or_insert_with(|| kline.clone());
+        }
+        let skip = merged.len().saturating_sub(self.capacity);
+        self.candles = merged.into_values().skip(skip).collect();
+    }
+
+    pub fn last_open_time(&self) -> Option<i64> {
+        self.candles.back().map(|k| k.open_time)
+    }
+
+    pub fn last(&self) -> Option<&Kline> {
+        self.candles.back()
+    }
+
+    // 按时间升序的已缓存K线 (ingest/backfill 后存储总是连续的)
+    pub fn as_slice(&self) -> &[Kline] {
+        let (front, back) = self.candles.as_slices();
+        debug_assert!(back.is_empty());
+        front
+    }
+
+    pub fn candles(&self) -> Vec<Kline> {
+        self.candles.iter().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.candles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.candles.is_empty()
+    }
+}
+
+// 单个币种各周期的K线缓存, 第一个周期为触发策略评估的主周期
+#[derive(Debug, Clone)]
+pub struct CandleSet {
+    primary: Interval,
+    buffers: BTreeMap<Interval, CandleBuffer>,
+}
+
+impl CandleSet {
+    pub fn new(intervals: &[Interval]) -> Self {
+        let primary = intervals.first().copied().unwrap_or(Interval::FiveMinutes);
+        let mut buffers = BTreeMap::new();
+        buffers.insert(primary, CandleBuffer::new(primary));
+        for &interval in intervals {
+            buffers.entry(interval).or_insert_with(|| CandleBuffer::new(interval));
+        }
+        Self { primary, buffers }
+    }
+
+    pub fn primary(&self) -> Interval {
+        self.primary
+    }
+
+    // 所有周期, 主周期在前
+    pub fn intervals(&self) -> Vec<Interval> {
+        let mut intervals = vec![self.primary];
+        intervals.extend(self.buffers.keys().filter(|&&i| i != self.primary));
+        intervals
+    }
+
+    pub fn buffer(&self, interval: Interval) -> Option<&CandleBuffer> {
+        self.buffers.get(&interval)
+    }
+
+    // 已缓存的K线数量
+    pub fn len(&self, interval: Interval) -> usize {
+        self.buffers.get(&interval).map_or(0, CandleBuffer::len)
+    }
+
+    // 缓存的最新K线与新拉取的最早K线之间缺少的K线数量 (停机超过一次拉取的范围时出现)
+    pub fn gap(&self, interval: Interval, klines: &[Kline]) -> usize {
+        let last = match self.buffers.get(&interval).and_then(CandleBuffer::last_open_time) {
+            Some(last) => last,
+            None => return 0,
+        };
+        let expected = interval.next_open_time(last);
+        match klines.iter().map(|k| k.open_time).min() {
+            Some(first) if first > expected => ((first - expected) / interval.duration_ms()).max(1) as usize,
+            _ => 0,
+        }
+    }
+
+    // 保证指定周期至少能缓存 capacity 根K线
+    pub fn reserve(&mut self, interval: Interval, capacity: usize) {
+        self.buffers.entry(interval)
+            .or_insert_with(|| CandleBuffer::new(interval))
+            .reserve(capacity);
+    }
+
+    // 回填指定周期的历史K线
+    pub fn backfill(&mut self, interval: Interval, klines: &[Kline], now: i64) {
+        self.buffers.entry(interval)
+            .or_insert_with(|| CandleBuffer::new(interval))
+            .backfill(klines, now);
+    }
+
+    // 沿用旧缓存中相同周期的K线 (重新添加币种时避免重复拉取)
+    pub fn merge_from(&mut self, cached: CandleSet) {
+        for (interval, buffer) in cached.buffers {
+            if let Some(current) = self.buffers.get_mut(&interval) {
+                let capacity = current.capacity;
+                *current = buffer;
+                current.reserve(capacity);
+            }
+        }
+    }
+
+    // 所有已缓存K线, 按回放顺序排列
+    pub fn replay(&self) -> Vec<(Interval, Kline)> {
+        let mut candles: Vec<(Interval, Kline)> = self.buffers.iter()
+            .flat_map(|(&interval, buffer)| buffer.candles.iter().map(move |k| (interval, k.clone())))
+            .collect();
+        sort_for_replay(&mut candles);
+        candles
+    }
+
+    // 合并指定周期新拉取的K线, 返回新增的已收盘K线
+    pub fn ingest(&mut self, interval: Interval, klines: &[Kline], now: i64) -> Vec<Kline> {
+        self.buffers.entry(interval)
+            .or_insert_with(|| CandleBuffer::new(interval))
+            .ingest(klines, now)
+    }
+
+    // 按主周期最新收盘时间对齐的各周期K线, 不包含之后才收盘的K线
+    pub fn aligned(&self) -> BTreeMap<Interval, &[Kline]> {
+        let cutoff = self.buffers.get(&self.primary)
+            .and_then(CandleBuffer::last)
+            .map(|k| self.primary.close_time(k.open_time))
+            .unwrap_or(i64::MIN);
+
+        self.buffers.iter()
+            .map(|(&interval, buffer)| {
+                // 缓存按时间升序, 截掉对齐时间之后收盘的部分即可
+                let candles = buffer.as_slice();
+                let end = candles.partition_point(|k| interval.close_time(k.open_time) <= cutoff);
+                (interval, &candles[..end])
+            })
+            .collect()
+    }
+}
+
+// 按收盘时间排序, 同时收盘时大周期在前, 保证主周期触发时大周期数据已更新
+pub fn sort_for_replay(candles: &mut [(Interval, Kline)]) {
+    candles.sort_by_key(|(interval, k)| {
+        (interval.close_time(k.open_time), std::cmp::Reverse(interval.duration_ms()))
+    });
+}
@@ -1,59 +1,255 @@
+pub mod backtest;
+pub mod candles;
+
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use crate::types::{CurrencyConfig, CurrencyStatus, TradingStatus, Position};
-use crate::types::{OrderRequest, OrderType, OrderSide};
-use crate::strategy::{MACDStrategy, TradingStrategy, MarketDepth, MarketTicker};
-use crate::exchange::bingx::BingXClient;
+use crate::types::{CurrencyConfig, CurrencyStatus, TradingStatus, Position, WarmUpStatus};
+use crate::types::{Interval, Kline, MarketType, OrderSide, Venue};
+use crate::strategy::{Action, BoxedStrategy, Decision, DeferredDecision, LegState, OrderIntent, StrategyContext, StrategyRegistry, MarketDepth, MarketTicker, TradingStrategy};
+use crate::strategy::{RegimeDetector, RegimeReading};
+use crate::exchange::{Exchange, ExchangeOrder, LimitOrder, OrderStatus, OrderUpdate};
 use chrono::{Duration, Utc};
-use serde_json;
+use tracing::{debug, error, info, warn};
+use candles::{sort_for_replay, CandleSet};
+
+// 每个周期每次拉取的K线数量
+const CANDLE_FETCH_LIMIT: u32 = 24;
+// 回填历史K线时单次请求的最大数量
+const MAX_BACKFILL_LIMIT: usize = 1000;
+
+// 策略挂出的限价单
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestingOrder {
+    pub order_id: String,
+    pub intent: OrderIntent,  // 已按币种精度调整
+}
 
 pub struct TradingManager {
-    client: Arc<BingXClient>,
+    exchanges: HashMap<Venue, Arc<dyn Exchange>>,
     currencies: Arc<RwLock<HashMap<String, CurrencyStatus>>>,
-    strategies: Arc<RwLock<HashMap<String, MACDStrategy>>>,
+    strategies: Arc<RwLock<HashMap<String, BoxedStrategy>>>,
+    candles: Arc<RwLock<HashMap<String, CandleSet>>>,
+    resting: Arc<RwLock<HashMap<String, Vec<RestingOrder>>>>,
+    regimes: Arc<RwLock<HashMap<String, RegimeDetector>>>,
+    regime_detector: RegimeDetector,  // 新增币种时复制的识别器模板
+    registry: StrategyRegistry,
 }
 
 impl TradingManager {
-    pub fn new(client: BingXClient) -> Self {
+    // client 为默认交易所 (通常是 BingX) 的客户端
+    pub fn new(client: impl Exchange + 'static) -> Self {
+        let mut exchanges: HashMap<Venue, Arc<dyn Exchange>> = HashMap::new();
+        exchanges.insert(client.venue(), Arc::new(client));
+
         Self {
-            client: Arc::new(client),
+            exchanges,
             currencies: Arc::new(RwLock::new(HashMap::new())),
             strategies: Arc::new(RwLock::new(HashMap::new())),
+            candles: Arc::new(RwLock::new(HashMap::new())),
+            resting: Arc::new(RwLock::new(HashMap::new())),
+            regimes: Arc::new(RwLock::new(HashMap::new())),
+            regime_detector: RegimeDetector::default(),
+            registry: StrategyRegistry::with_builtin(),
         }
     }
 
-    // 获取客户端引用
-    pub fn get_client(&self) -> &BingXClient {
-        &self.client
+    // 注册其他交易所 (同一交易所重复注册时替换)
+    pub fn with_exchange(mut self, exchange: impl Exchange + 'static) -> Self {
+        self.exchanges.insert(exchange.venue(), Arc::new(exchange));
+        self
+    }
+
+    // 替换策略注册表 (用于注册自定义策略)
+    pub fn with_strategy_registry(mut self, registry: StrategyRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    pub fn strategy_registry(&self) -> &StrategyRegistry {
+        &self.registry
+    }
+
+    // 替换市场状态识别的参数 (对之后添加的币种生效)
+    pub fn with_regime_detector(mut self, detector: RegimeDetector) -> Self {
+        self.regime_detector = detector;
+        self
+    }
+
+    // 币种配置所属交易所的客户端
+    fn exchange(&self, venue: Venue) -> Result<&dyn Exchange, Box<dyn std::error::Error + Send + Sync>> {
+        self.exchanges.get(&venue)
+            .map(|exchange| exchange.as_ref())
+            .ok_or_else(|| format!("未配置交易所: {:?}", venue).into())
+    }
+
+    // 交易所的当前时间 (毫秒), 回放会话时为录制时间, 用于判断K线收盘与冷却期
+    fn now(&self, venue: Venue) -> i64 {
+        self.exchanges.get(&venue)
+            .map_or_else(|| Utc::now().timestamp_millis(), |exchange| exchange.now_millis())
+    }
+
+    // 回放会话中尚未使用的录制条数 (没有交易所在回放时为 None)
+    fn replay_remaining(&self) -> Option<usize> {
+        self.exchanges.values()
+            .filter_map(|exchange| exchange.replay_remaining())
+            .reduce(|total, remaining| total + remaining)
     }
 
-    // 添加新的交易币种
-    pub async fn add_currency(&self, config: CurrencyConfig) {
+    // 添加新的交易币种 (按配置从注册表构造策略, 策略名或参数无效时返回错误)
+    pub async fn add_currency(&self, config: CurrencyConfig) -> Result<(), Box<dyn std::error::Error>> {
+        let mut strategy = self.registry.build(&config.strategy)?;
+        strategy.bind_currency(&config);
+        if config.legs.len() < strategy.required_legs() {
+            return Err(format!(
+                "策略 {} 需要 {} 个附加交易对, 当前配置了 {} 个",
+                config.strategy, strategy.required_legs(), config.legs.len()
+            ).into());
+        }
+        // 限价单只接入永续合约, 挂单策略无法在现货上交易
+        if config.market == MarketType::Spot && strategy.places_resting_orders() {
+            return Err(format!("策略 {} 需要挂限价单, 现货暂不支持限价挂单", config.strategy).into());
+        }
+        // 附加交易对的持仓由主交易对的策略管理, 不能同时作为其他币种交易
+        for leg in &config.legs {
+            let taken = self.currencies.read().await.get(&leg.symbol)
+                .is_some_and(|status| status.owner.as_deref() != Some(config.symbol.as_str()));
+            if leg.symbol == config.symbol || taken {
+                return Err(format!("附加交易对 {} 已在交易中", leg.symbol).into());
+            }
+        }
+        info!(symbol = %config.symbol, strategy = %config.strategy, "添加交易币种");
+
+        // 回填历史K线并回放给策略, 预热完成前不评估实时信号
+        let mut leg_sets = Vec::with_capacity(config.legs.len());
+        for leg in &config.legs {
+            let leg_set = self.backfill(leg, strategy.as_ref(), 0).await;
+            for (interval, kline) in leg_set.replay() {
+                strategy.add_leg_candle(&leg.symbol, interval, &kline);
+            }
+            leg_sets.push((leg.clone(), leg_set));
+        }
+        let mut detector = self.regime_detector.clone();
+        detector.reset();
+        // 按市场状态限制开仓或策略读取市场状态时, 同时回填识别所需的K线
+        let regime_lookback = if config.regimes.is_empty() && !strategy.uses_regime() { 0 } else { detector.lookback() };
+        let candle_set = self.backfill(&config, strategy.as_ref(), regime_lookback).await;
+        let primary = candle_set.primary();
+        for (interval, kline) in candle_set.replay() {
+            strategy.add_candle(interval, &kline);
+            if interval == primary {
+                detector.update(&kline);
+            }
+        }
+        let warm_up = WarmUpStatus {
+            required: strategy.lookback(primary),
+            loaded: candle_set.len(primary),
+            ready: strategy.is_ready(),
+        };
+        info!(
+            symbol = %config.symbol,
+            required = warm_up.required,
+            loaded = warm_up.loaded,
+            ready = warm_up.ready,
+            "策略预热"
+        );
+
         let mut currencies = self.currencies.write().await;
         let mut strategies = self.strategies.write().await;
-        
-        let status = CurrencyStatus {
+        let mut candles = self.candles.write().await;
+        let mut regimes = self.regimes.write().await;
+
+        let status = |config: &CurrencyConfig, owner: Option<&str>, warm_up: WarmUpStatus| CurrencyStatus {
             config: config.clone(),
             status: TradingStatus::Active,
             last_update: Utc::now().timestamp_millis(),
             current_position: None,
+            last_trade_at: None,
+            warm_up,
+            owner: owner.map(str::to_string),
         };
-        
-        currencies.insert(config.symbol.clone(), status);
-        strategies.insert(
-            config.symbol.clone(),
-            MACDStrategy::new(12, 26, 9)
-        );
+        for (leg, leg_set) in leg_sets {
+            let leg_warm_up = WarmUpStatus {
+                required: strategy.lookback(primary),
+                loaded: leg_set.len(primary),
+                ready: warm_up.ready,
+            };
+            let leg_status = status(&leg, Some(&config.symbol), leg_warm_up);
+            currencies.entry(leg.symbol.clone()).or_insert(leg_status);
+            candles.insert(leg.symbol.clone(), leg_set);
+        }
+
+        currencies.insert(config.symbol.clone(), status(&config, None, warm_up));
+        strategies.insert(config.symbol.clone(), strategy);
+        candles.insert(config.symbol.clone(), candle_set);
+        regimes.insert(config.symbol.clone(), detector);
+        Ok(())
     }
 
-    // 移除交易币种
+    // 按策略声明的回看数量准备K线 (优先使用已缓存的K线, 不足时从交易所拉取)
+    // regime_lookback: 市场状态识别需要的主周期K线数量
+    async fn backfill(&self, config: &CurrencyConfig, strategy: &(dyn TradingStrategy + Send + Sync), regime_lookback: usize) -> CandleSet {
+        let mut set = CandleSet::new(&strategy.intervals());
+        if let Some(cached) = self.candles.read().await.get(&config.symbol).cloned() {
+            set.merge_from(cached);
+        }
+
+        let primary = set.primary();
+        for interval in set.intervals() {
+            let required = if interval == primary {
+                strategy.lookback(interval).max(regime_lookback)
+            } else {
+                strategy.lookback(interval)
+            };
+            if required == 0 {
+                continue;
+            }
+            set.reserve(interval, required);
+            if set.len(interval) >= required {
+                debug!(symbol = %config.symbol, %interval, required, "使用缓存K线预热");
+                continue;
+            }
+            // 多取一根, 最新一根可能尚未收盘
+            let limit = (required + 1).min(MAX_BACKFILL_LIMIT) as u32;
+            if let Some(klines) = self.fetch_klines(config, interval, limit).await {
+                set.backfill(interval, &klines, self.now(config.venue));
+            }
+        }
+        set
+    }
+
+    // 移除交易币种 (先撤销策略挂出的限价单)
     pub async fn remove_currency(&self, symbol: &str) {
+        let config = self.get_currency_status(symbol).await.map(|status| status.config);
+        let resting = self.resting.write().await.remove(symbol).unwrap_or_default();
+        if let Some(config) = config.filter(|_| !resting.is_empty()) {
+            match self.exchange(config.venue) {
+                Ok(exchange) => {
+                    for order in &resting {
+                        if let Err(e) = exchange.cancel_order(symbol, config.market, &order.order_id).await {
+                            warn!(symbol, order_id = %order.order_id, error = %e, "撤销限价单失败");
+                        }
+                    }
+                }
+                Err(e) => warn!(symbol, error = %e, "无法撤销限价单"),
+            }
+        }
+
         let mut currencies = self.currencies.write().await;
         let mut strategies = self.strategies.write().await;
-        
-        currencies.remove(symbol);
+        let mut candles = self.candles.write().await;
+
+        // 同时移除该币种策略的附加交易对
+        let legs: Vec<String> = currencies.iter()
+            .filter(|(_, status)| status.owner.as_deref() == Some(symbol))
+            .map(|(leg, _)| leg.clone())
+            .collect();
+        for leg in legs.iter().map(String::as_str).chain([symbol]) {
+            currencies.remove(leg);
+            candles.remove(leg);
+        }
         strategies.remove(symbol);
+        self.regimes.write().await.remove(symbol);
     }
 
     // 获取币种状态
@@ -70,6 +266,46 @@ impl TradingManager {
         }
     }
 
+    // 获取币种主周期已缓存的收盘K线
+    pub async fn candles(&self, symbol: &str) -> Option<Vec<Kline>> {
+        let candles = self.candles.read().await;
+        let set = candles.get(symbol)?;
+        set.buffer(set.primary()).map(|buffer| buffer.candles())
+    }
+
+    // 获取币种指定周期已缓存的收盘K线
+    pub async fn candles_for(&self, symbol: &str, interval: Interval) -> Option<Vec<Kline>> {
+        self.candles.read().await.get(symbol)?.buffer(interval).map(|buffer| buffer.candles())
+    }
+
+    // 策略当前挂出的限价单
+    pub async fn resting_orders(&self, symbol: &str) -> Vec<RestingOrder> {
+        self.resting.read().await.get(symbol).cloned().unwrap_or_default()
+    }
+
+    // 最近一轮识别的市场状态
+    pub async fn regime(&self, symbol: &str) -> Option<RegimeReading> {
+        self.regimes.read().await.get(symbol)?.reading().cloned()
+    }
+
+    // 策略内部状态 (供状态面板显示)
+    pub async fn strategy_state(&self, symbol: &str) -> Vec<String> {
+        self.strategies.read().await
+            .get(symbol)
+            .map(|strategy| strategy.describe())
+            .unwrap_or_default()
+    }
+
+    // 更新策略预热进度
+    async fn update_warm_up(&self, symbol: &str, warm_up: WarmUpStatus) {
+        if let Some(currency) = self.currencies.write().await.get_mut(symbol) {
+            if !currency.warm_up.ready && warm_up.ready {
+                info!(symbol, loaded = warm_up.loaded, "策略预热完成");
+            }
+            currency.warm_up = warm_up;
+        }
+    }
+
     // 获取所有币种状态
     pub async fn get_all_status(&self) -> Vec<(String, CurrencyStatus)> {
         let currencies = self.currencies.read().await;
@@ -78,8 +314,8 @@ impl TradingManager {
             .collect()
     }
 
-    // 下单功能
-    pub async fn place_order(&self, symbol: &str, side: OrderSide, price: f64) -> Result<(), Box<dyn std::error::Error>> {
+    // 读取活跃币种配置 (立即释放读锁, 避免下单成功后更新持仓时死锁)
+    async fn active_config(&self, symbol: &str) -> Result<CurrencyConfig, Box<dyn std::error::Error + Send + Sync>> {
         let currencies = self.currencies.read().await;
         let currency = currencies.get(symbol)
             .ok_or_else(|| format!("未找到币种配置: {}", symbol))?;
@@ -87,193 +323,868 @@ impl TradingManager {
         if currency.status != TradingStatus::Active {
             return Err(format!("币种 {} 当前不可交易", symbol).into());
         }
+        Ok(currency.config.clone())
+    }
+
+    // 下单功能 (最小数量, 默认止盈止损)
+    pub async fn place_order(&self, symbol: &str, side: OrderSide, price: f64) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let decision = match side {
+            OrderSide::Buy => Decision::enter_long(0.0),
+            OrderSide::Sell => Decision::enter_short(0.0),
+        };
+        self.execute_decision(symbol, price, &decision).await
+    }
+
+    // 执行策略决策: 按置信度确定数量, 优先使用策略建议的止盈止损
+    pub async fn execute_decision(&self, symbol: &str, price: f64, decision: &Decision) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let config = self.active_config(symbol).await?;
+        let exchange = self.exchange(config.venue)?;
 
-        // 计算止盈止损价格
-        let (take_profit_price, stop_loss_price) = match side {
-            OrderSide::Buy => (
-                price * 1.10,  // 买入时，止盈价格为入场价格+10%
-                price * 0.95,  // 买入时，止损价格为入场价格-5%
-            ),
-            OrderSide::Sell => (
-                price * 0.90,  // 卖出时，止盈价格为入场价格-10%
-                price * 1.05,  // 卖出时，止损价格为入场价格+5%
-            ),
+        let side = match decision.action {
+            Action::Hold => return Ok(()),
+            Action::Exit => return self.close_position(exchange, &config, price, decision.close_fraction).await,
+            Action::EnterLong => OrderSide::Buy,
+            Action::EnterShort => OrderSide::Sell,
+        };
+        // 策略指定数量时按数量精度取整, 否则按置信度计算
+        let quantity = match decision.quantity {
+            Some(quantity) => config.floor_qty(quantity).max(config.min_qty),
+            None => config.order_quantity(decision.confidence),
         };
 
-        // 构造止盈止损JSON
-        let take_profit = serde_json::json!({
-            "type": "TAKE_PROFIT_MARKET",
-            "stopPrice": take_profit_price,
-            "workingType": "MARK_PRICE",
-            "closePosition": true
-        }).to_string();
-
-        let stop_loss = serde_json::json!({
-            "type": "STOP_MARKET",
-            "stopPrice": stop_loss_price,
-            "workingType": "MARK_PRICE",
-            "closePosition": true
-        }).to_string();
+        // 现货没有杠杆和止盈止损, 单独处理
+        if config.market == MarketType::Spot {
+            return self.place_spot_order(exchange, &config, side, price, quantity).await;
+        }
+
+        // 已有同向持仓时不加仓; 反向信号先平掉现有持仓再反手
+        let held_side = self.get_currency_status(symbol).await
+            .and_then(|status| status.current_position)
+            .map(|position| position.side);
+        match held_side {
+            Some(held) if held == side => {
+                debug!(symbol, side = ?side, "已有同向持仓, 忽略开仓信号");
+                return Ok(());
+            }
+            Some(_) => {
+                info!(symbol, side = ?side, "信号与持仓方向相反, 先平仓");
+                self.close_position(exchange, &config, price, 1.0).await?;
+            }
+            None => {}
+        }
+
+        let (take_profit_price, stop_loss_price) = protective_prices(&side, price, decision);
 
         // 开仓订单
-        let order = OrderRequest {
+        let order = ExchangeOrder {
             symbol: symbol.to_string(),
-            order_type: OrderType::Market,
+            market: config.market,
             side: side.clone(),
-            quantity: currency.config.min_qty,  // 使用配置中的最小数量
-            timestamp: Utc::now().timestamp_millis(),
-            stop_price: None,
-            working_type: None,
-            take_profit: Some(take_profit),
-            stop_loss: Some(stop_loss),
+            quantity,
+            take_profit: Some(take_profit_price),
+            stop_loss: Some(stop_loss_price),
+            reduce_only: false,
+        };
+
+        let fill = match exchange.place_order(order).await {
+            Ok(fill) => fill,
+            Err(e) => {
+                error!(symbol, error = %e, "开仓错误");
+                return Err(e);
+            }
+        };
+        let entry_price = if fill.price > 0.0 { fill.price } else { price };
+        info!(
+            venue = ?config.venue,
+            order_id = %fill.order_id,
+            symbol = %fill.symbol,
+            side = ?fill.side,
+            quantity = fill.quantity,
+            confidence = decision.confidence,
+            entry_price,
+            take_profit = take_profit_price,
+            stop_loss = stop_loss_price,
+            max_loss_pct = (stop_loss_price - entry_price).abs() / entry_price * 100.0,
+            target_profit_pct = (take_profit_price - entry_price).abs() / entry_price * 100.0,
+            "开仓成功"
+        );
+
+        // 更新币种状态
+        if let Some(currency) = self.currencies.write().await.get_mut(symbol) {
+            currency.current_position = Some(Position {
+                symbol: symbol.to_string(),
+                side: side.clone(),
+                quantity: fill.quantity,
+                entry_price,
+                unrealized_pnl: 0.0,
+                leverage: currency.config.leverage,
+            });
+            currency.last_trade_at = Some(self.now(config.venue));
+        }
+
+        Ok(())
+    }
+
+    // 按比例平掉当前持仓 (现货卖出持有的数量, 合约下只减仓的反向单)
+    async fn close_position(
+        &self,
+        exchange: &dyn Exchange,
+        config: &CurrencyConfig,
+        price: f64,
+        fraction: f64,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let symbol = config.symbol.as_str();
+        let position = match self.get_currency_status(symbol).await.and_then(|s| s.current_position) {
+            Some(position) => position,
+            None => {
+                debug!(symbol, "无持仓, 忽略平仓");
+                return Ok(());
+            }
+        };
+        let close_side = match position.side {
+            OrderSide::Buy => OrderSide::Sell,
+            OrderSide::Sell => OrderSide::Buy,
+        };
+
+        // 部分平仓按数量精度向下取整
+        let quantity = if fraction >= 1.0 {
+            position.quantity
+        } else {
+            let factor = 10f64.powi(config.qty_precision as i32);
+            (position.quantity * fraction.max(0.0) * factor).floor() / factor
+        };
+        if quantity <= 0.0 {
+            return Err(format!("{} 平仓数量过小 (比例 {})", symbol, fraction).into());
+        }
+
+        if config.market == MarketType::Spot {
+            return self.place_spot_order(exchange, config, close_side, price, quantity).await;
+        }
+
+        let order = ExchangeOrder {
+            symbol: symbol.to_string(),
+            market: config.market,
+            side: close_side,
+            quantity,
+            take_profit: None,
+            stop_loss: None,
+            reduce_only: true,
+        };
+
+        let fill = match exchange.place_order(order).await {
+            Ok(fill) => fill,
+            Err(e) => {
+                error!(symbol, error = %e, "平仓错误");
+                return Err(e);
+            }
+        };
+        let exit_price = if fill.price > 0.0 { fill.price } else { price };
+        let pnl = match position.side {
+            OrderSide::Buy => (exit_price - position.entry_price) * fill.quantity,
+            OrderSide::Sell => (position.entry_price - exit_price) * fill.quantity,
+        };
+        let remaining = position.quantity - fill.quantity;
+        info!(
+            order_id = %fill.order_id,
+            symbol,
+            quantity = fill.quantity,
+            remaining,
+            entry_price = position.entry_price,
+            exit_price,
+            pnl,
+            "平仓成功"
+        );
+        if let Some(currency) = self.currencies.write().await.get_mut(symbol) {
+            currency.current_position = match currency.current_position.take() {
+                Some(mut held) if remaining > 1e-12 => {
+                    held.quantity = remaining;
+                    Some(held)
+                }
+                _ => None,
+            };
+            currency.last_update = self.now(config.venue);
+            currency.last_trade_at = Some(currency.last_update);
+        }
+
+        Ok(())
+    }
+
+    // 现货下单: 买入累积持仓, 卖出只卖已持有的基础币
+    async fn place_spot_order(
+        &self,
+        exchange: &dyn Exchange,
+        config: &CurrencyConfig,
+        side: OrderSide,
+        price: f64,
+        quantity: f64,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let symbol = config.symbol.as_str();
+        let quantity = match side {
+            OrderSide::Buy => quantity,
+            OrderSide::Sell => {
+                let free = exchange.get_balance(&config.base_currency, MarketType::Spot).await?;
+                let factor = 10f64.powi(config.qty_precision as i32);
+                (quantity.min(free) * factor).floor() / factor
+            }
+        };
+        if quantity <= 0.0 {
+            return Err(format!("{} 现货无可卖余额", config.base_currency).into());
+        }
+
+        let order = ExchangeOrder {
+            symbol: symbol.to_string(),
+            market: MarketType::Spot,
+            side: side.clone(),
+            quantity,
+            take_profit: None,
+            stop_loss: None,
+            reduce_only: false,
+        };
+
+        let fill = match exchange.place_order(order).await {
+            Ok(fill) => fill,
+            Err(e) => {
+                error!(symbol, error = %e, "现货下单错误");
+                return Err(e);
+            }
+        };
+        let filled = fill.quantity;
+        let fill_price = if fill.price > 0.0 { fill.price } else { price };
+        info!(
+            order_id = %fill.order_id,
+            symbol,
+            side = ?fill.side,
+            quantity = filled,
+            price = fill_price,
+            "现货成交"
+        );
+
+        // 更新现货持仓 (买入按均价累积, 卖出减少)
+        if let Some(currency) = self.currencies.write().await.get_mut(symbol) {
+            let held = currency.current_position.take();
+            currency.current_position = match (side, held) {
+                (OrderSide::Buy, Some(mut position)) => {
+                    let total = position.quantity + filled;
+                    position.entry_price = (position.entry_price * position.quantity
+                        + fill_price * filled) / total;
+                    position.quantity = total;
+                    Some(position)
+                }
+                (OrderSide::Buy, None) => Some(Position {
+                    symbol: symbol.to_string(),
+                    side: OrderSide::Buy,
+                    quantity: filled,
+                    entry_price: fill_price,
+                    unrealized_pnl: 0.0,
+                    leverage: 1,
+                }),
+                (OrderSide::Sell, Some(mut position)) => {
+                    position.quantity -= filled;
+                    (position.quantity > 0.0).then_some(position)
+                }
+                (OrderSide::Sell, None) => None,
+            };
+            currency.last_update = self.now(config.venue);
+            currency.last_trade_at = Some(currency.last_update);
+        }
+
+        Ok(())
+    }
+
+    // 查询策略已挂出的限价单, 成交的回报给策略并更新持仓
+    async fn poll_resting_orders(&self, config: &CurrencyConfig) {
+        let symbol = config.symbol.as_str();
+        let tracked = self.resting.read().await.get(symbol).cloned().unwrap_or_default();
+        if tracked.is_empty() {
+            return;
+        }
+        let exchange = match self.exchange(config.venue) {
+            Ok(exchange) => exchange,
+            Err(e) => {
+                warn!(symbol, error = %e, "无法查询限价单");
+                return;
+            }
         };
 
-        match self.client.place_order(order).await {
-            Ok(response) => {
-                if response.code == 0 {
-                    if let Some(data) = response.data {
-                        println!("\n开仓成功:");
-                        println!("订单ID: {}", data.order.order_id);
-                        println!("交易对: {}", data.order.symbol);
-                        println!("方向: {}", data.order.side);
-                        println!("数量: {}", data.order.quantity);
-                        println!("入场价格: {}", price);
-                        println!("止盈价格: {:.2}", take_profit_price);
-                        println!("止损价格: {:.2}", stop_loss_price);
-
-                        println!("\n风险管理:");
-                        println!("最大止损: {:.2}%", 5.0);
-                        println!("预期盈利: {:.2}%", 10.0);
-                        println!("盈亏比: 1:2");
-
-                        // 更新币种状态
-                        if let Some(currency) = self.currencies.write().await.get_mut(symbol) {
-                            currency.current_position = Some(Position {
-                                symbol: symbol.to_string(),
-                                side: side.clone(),
-                                quantity: data.order.quantity,
-                                entry_price: price,
-                                unrealized_pnl: 0.0,
-                                leverage: currency.config.leverage,
-                            });
+        let mut open = Vec::with_capacity(tracked.len());
+        for order in tracked {
+            match exchange.get_order(symbol, config.market, &order.order_id).await {
+                Ok(update) if update.status == OrderStatus::Filled => {
+                    self.apply_limit_fill(config, &order.intent, &update).await;
+                }
+                // 部分成交后被撤销或过期的, 已成交部分照常计入持仓
+                Ok(update) if !update.status.is_open() => {
+                    info!(
+                        symbol,
+                        order_id = %order.order_id,
+                        status = ?update.status,
+                        filled = update.filled_quantity,
+                        "限价单已失效"
+                    );
+                    if update.filled_quantity > 0.0 {
+                        self.apply_limit_fill(config, &order.intent, &update).await;
+                    }
+                }
+                Ok(_) => open.push(order),
+                Err(e) => {
+                    warn!(symbol, order_id = %order.order_id, error = %e, "查询限价单失败");
+                    open.push(order);
+                }
+            }
+        }
+        self.store_resting_orders(symbol, open).await;
+    }
+
+    // 按策略期望调整限价挂单: 撤销不再需要的, 挂出缺少的
+    async fn reconcile_resting_orders(&self, config: &CurrencyConfig) {
+        let symbol = config.symbol.as_str();
+        let open = self.resting.read().await.get(symbol).cloned().unwrap_or_default();
+        let wanted = match self.strategies.read().await.get(symbol) {
+            Some(strategy) => strategy.resting_orders(),
+            None => return,
+        };
+        if open.is_empty() && wanted.is_empty() {
+            return;
+        }
+        let exchange = match self.exchange(config.venue) {
+            Ok(exchange) => exchange,
+            Err(e) => {
+                warn!(symbol, error = %e, "无法同步限价单");
+                return;
+            }
+        };
+        let desired: Vec<OrderIntent> = wanted.into_iter()
+            .filter_map(|intent| normalize_intent(config, intent))
+            .collect();
+
+        // 撤销不再需要的挂单 (撤单失败的保留, 下轮查询时确认状态)
+        let mut kept = Vec::with_capacity(desired.len());
+        for order in open {
+            if desired.contains(&order.intent) {
+                kept.push(order);
+                continue;
+            }
+            match exchange.cancel_order(symbol, config.market, &order.order_id).await {
+                Ok(()) => {
+                    info!(symbol, order_id = %order.order_id, key = %order.intent.key, "撤销限价单");
+                    // 撤单前已部分成交的数量计入持仓
+                    match exchange.get_order(symbol, config.market, &order.order_id).await {
+                        Ok(update) if update.filled_quantity > 0.0 => {
+                            self.apply_limit_fill(config, &order.intent, &update).await;
                         }
+                        Ok(_) => {}
+                        Err(e) => warn!(symbol, order_id = %order.order_id, error = %e, "查询已撤销限价单失败"),
                     }
-                } else {
-                    println!("开仓��败: {}", response.msg);
+                }
+                Err(e) => {
+                    warn!(symbol, order_id = %order.order_id, error = %e, "撤销限价单失败");
+                    kept.push(order);
                 }
             }
-            Err(e) => println!("开仓错误: {}", e),
         }
 
-        Ok(())
+        // 挂出缺少的限价单
+        for intent in desired {
+            if kept.iter().any(|order| order.intent.key == intent.key) {
+                continue;
+            }
+            let order = LimitOrder {
+                symbol: symbol.to_string(),
+                market: config.market,
+                side: intent.side.clone(),
+                price: intent.price,
+                quantity: intent.quantity,
+                reduce_only: intent.reduce_only,
+            };
+            match exchange.place_limit_order(order).await {
+                Ok(update) => {
+                    info!(
+                        symbol,
+                        order_id = %update.order_id,
+                        key = %intent.key,
+                        side = ?intent.side,
+                        price = intent.price,
+                        quantity = intent.quantity,
+                        "挂出限价单"
+                    );
+                    kept.push(RestingOrder { order_id: update.order_id, intent });
+                }
+                Err(e) => warn!(symbol, key = %intent.key, price = intent.price, error = %e, "挂限价单失败"),
+            }
+        }
+        self.store_resting_orders(symbol, kept).await;
     }
 
-    // 监控所有币种
+    async fn store_resting_orders(&self, symbol: &str, orders: Vec<RestingOrder>) {
+        let mut resting = self.resting.write().await;
+        if orders.is_empty() {
+            resting.remove(symbol);
+        } else {
+            resting.insert(symbol.to_string(), orders);
+        }
+    }
+
+    // 限价单成交: 通知策略并更新持仓
+    async fn apply_limit_fill(&self, config: &CurrencyConfig, intent: &OrderIntent, update: &OrderUpdate) {
+        let symbol = config.symbol.as_str();
+        let quantity = if update.filled_quantity > 0.0 { update.filled_quantity } else { intent.quantity };
+        let price = update.fill_price();
+        info!(
+            symbol,
+            order_id = %update.order_id,
+            key = %intent.key,
+            side = ?update.side,
+            price,
+            quantity,
+            "限价单成交"
+        );
+
+        if let Some(strategy) = self.strategies.write().await.get_mut(symbol) {
+            strategy.on_order_filled(intent, update);
+        }
+        if let Some(currency) = self.currencies.write().await.get_mut(symbol) {
+            let held = currency.current_position.take();
+            currency.current_position = match held {
+                // 平仓单或反向成交减少持仓
+                Some(mut position) if intent.reduce_only || position.side != intent.side => {
+                    position.quantity -= quantity;
+                    (position.quantity > 1e-12).then_some(position)
+                }
+                Some(mut position) => {
+                    let total = position.quantity + quantity;
+                    position.entry_price = (position.entry_price * position.quantity + price * quantity) / total;
+                    position.quantity = total;
+                    Some(position)
+                }
+                None if intent.reduce_only => None,
+                None => Some(Position {
+                    symbol: symbol.to_string(),
+                    side: intent.side.clone(),
+                    quantity,
+                    entry_price: price,
+                    unrealized_pnl: 0.0,
+                    leverage: currency.config.leverage,
+                }),
+            };
+            currency.last_update = self.now(config.venue);
+            currency.last_trade_at = Some(currency.last_update);
+        }
+    }
+
+    // 成交后的剩余冷却时间 (毫秒), 不在冷却期时为 None
+    pub async fn cooldown_remaining(&self, symbol: &str) -> Option<i64> {
+        let currencies = self.currencies.read().await;
+        let currency = currencies.get(symbol)?;
+        let cooldown_ms = currency.config.cooldown_secs as i64 * 1000;
+        let elapsed = self.now(currency.config.venue) - currency.last_trade_at?;
+        (elapsed < cooldown_ms).then_some(cooldown_ms - elapsed)
+    }
+
+    // 获取深度数据
+    async fn fetch_depth(&self, config: &CurrencyConfig) -> Option<MarketDepth> {
+        let symbol = config.symbol.as_str();
+        let result = match self.exchange(config.venue) {
+            Ok(exchange) => exchange.get_market_depth(symbol, config.market, Some(20)).await,
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(depth) => Some(depth),
+            Err(e) => {
+                warn!(symbol, error = %e, "获取深度数据失败");
+                None
+            }
+        }
+    }
+
+    // 获取24小时行情
+    async fn fetch_ticker(&self, config: &CurrencyConfig) -> Option<MarketTicker> {
+        let symbol = config.symbol.as_str();
+        let result = match self.exchange(config.venue) {
+            Ok(exchange) => exchange.get_market_ticker(symbol, config.market).await,
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(ticker) => Some(ticker),
+            Err(e) => {
+                warn!(symbol, error = %e, "获取24小时行情失败");
+                None
+            }
+        }
+    }
+
+    // 获取K线数据 (按时间升序返回)
+    async fn fetch_klines(&self, config: &CurrencyConfig, interval: Interval, limit: u32) -> Option<Vec<Kline>> {
+        let symbol = config.symbol.as_str();
+        let end = Utc::now();
+        let start = end - Duration::milliseconds(interval.duration_ms() * limit as i64);
+        let result = match self.exchange(config.venue) {
+            Ok(exchange) => exchange.get_klines(symbol, config.market, interval, Some(start), Some(end), Some(limit)).await,
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(mut klines) => {
+                klines.sort_by_key(|k| k.open_time);
+                Some(klines)
+            }
+            Err(e) => {
+                warn!(symbol, error = %e, "获取K线数据失败");
+                None
+            }
+        }
+    }
+
+    // 拉取各周期K线, 任一周期失败时本轮跳过该币种 (已缓存的K线下轮去重)
+    async fn fetch_candle_series(&self, config: &CurrencyConfig, intervals: &[Interval]) -> Option<Vec<(Interval, Vec<Kline>)>> {
+        let mut series = Vec::with_capacity(intervals.len());
+        for &interval in intervals {
+            let mut klines = self.fetch_klines(config, interval, CANDLE_FETCH_LIMIT).await?;
+            // 停机超过一次拉取的范围时缓存与新K线之间有缺口, 按缺口大小重新回填
+            let gap = self.candles.read().await.get(&config.symbol).map_or(0, |set| set.gap(interval, &klines));
+            if gap > 0 {
+                info!(symbol = %config.symbol, %interval, gap, "K线缓存有缺口, 重新回填");
+                let limit = (gap + CANDLE_FETCH_LIMIT as usize).min(MAX_BACKFILL_LIMIT) as u32;
+                klines = self.fetch_klines(config, interval, limit).await?;
+            }
+            series.push((interval, klines));
+        }
+        Some(series)
+    }
+
+    // 监控所有币种 (回放会话时不等待, 录制的响应用完或不再匹配时结束)
     pub async fn monitor_all(&self) {
+        let mut replay_remaining = self.replay_remaining();
         loop {
+            for (venue, exchange) in &self.exchanges {
+                if let Err(e) = exchange.sync_time_if_stale().await {
+                    warn!(?venue, error = %e, "同步服务器时间失败");
+                }
+            }
+            self.monitor_once().await;
+
+            if let Some(before) = replay_remaining {
+                let remaining = self.replay_remaining().unwrap_or_default();
+                if remaining == 0 || remaining == before {
+                    info!(remaining, "会话回放结束");
+                    return;
+                }
+                replay_remaining = Some(remaining);
+                continue;
+            }
+
+            // 等待一定时间后再次检查
+            tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+        }
+    }
+
+    // 对所有活跃币种执行一轮检查
+    pub async fn monitor_once(&self) {
+        // 先取出活跃币种列表, 不在网络请求期间持有锁
+        let configs: Vec<CurrencyConfig> = {
             let currencies = self.currencies.read().await;
-            let mut strategies = self.strategies.write().await;
-            
-            for (symbol, currency) in currencies.iter() {
-                if currency.status != TradingStatus::Active {
-                    continue;
+            // 附加交易对由所属主交易对的策略一并处理
+            currencies.values()
+                .filter(|currency| currency.status == TradingStatus::Active && currency.owner.is_none())
+                .map(|currency| currency.config.clone())
+                .collect()
+        };
+
+        // 先回报限价单成交, 策略评估时持仓已是最新
+        for config in &configs {
+            self.poll_resting_orders(config).await;
+        }
+
+        for config in &configs {
+            let symbol = config.symbol.as_str();
+
+            // 获取市场数据
+            let depth = self.fetch_depth(config).await;
+            let ticker = self.fetch_ticker(config).await;
+
+            // 获取策略所需各周期的K线数据 (主周期在前)
+            let intervals = match self.candles.read().await.get(symbol) {
+                Some(set) => set.intervals(),
+                None => continue,
+            };
+            let series = match self.fetch_candle_series(config, &intervals).await {
+                Some(series) => series,
+                None => continue,
+            };
+
+            // 主周期最新K线 (可能尚未收盘) 的收盘价作为当前价格
+            let latest_close = match series.first().and_then(|(_, klines)| klines.last()) {
+                Some(latest_kline) => latest_kline.close,
+                None => continue,
+            };
+
+            // 附加交易对使用与主交易对相同的周期, 任一失败时本轮跳过
+            let mut leg_series = Vec::with_capacity(config.legs.len());
+            for leg in &config.legs {
+                match self.fetch_candle_series(leg, &intervals).await {
+                    Some(series) => leg_series.push((leg.symbol.clone(), series)),
+                    None => break,
                 }
-                
-                // 获取市场数据
-                let depth = match self.client.get_depth(&symbol, Some(20)).await {
-                    Ok(depth_data) => {
-                        let asks: Vec<(f64, f64)> = depth_data.asks.iter()
-                            .filter_map(|ask| {
-                                let price = ask[0].parse().ok()?;
-                                let quantity = ask[1].parse().ok()?;
-                                Some((price, quantity))
-                            })
-                            .collect();
-                        let bids: Vec<(f64, f64)> = depth_data.bids.iter()
-                            .filter_map(|bid| {
-                                let price = bid[0].parse().ok()?;
-                                let quantity = bid[1].parse().ok()?;
-                                Some((price, quantity))
-                            })
-                            .collect();
-                        Some(MarketDepth { asks, bids })
-                    }
-                    Err(e) => {
-                        println!("{} - 获取深度数据失败: {}", symbol, e);
-                        None
+            }
+            if leg_series.len() < config.legs.len() {
+                continue;
+            }
+
+            // 只取新收盘的K线, 每根K线只推送给策略一次
+            let (new_candles, leg_candles, primary_closed, loaded) = {
+                let mut candles = self.candles.write().await;
+                let now = self.now(config.venue);
+                let mut leg_candles: Vec<(String, Vec<(Interval, Kline)>)> = Vec::with_capacity(leg_series.len());
+                for (leg, series) in &leg_series {
+                    let leg_set = match candles.get_mut(leg) {
+                        Some(set) => set,
+                        None => continue,
+                    };
+                    let mut new_leg_candles: Vec<(Interval, Kline)> = series.iter()
+                        .flat_map(|(interval, klines)| {
+                            leg_set.ingest(*interval, klines, now).into_iter().map(move |k| (*interval, k))
+                        })
+                        .collect();
+                    sort_for_replay(&mut new_leg_candles);
+                    leg_candles.push((leg.clone(), new_leg_candles));
+                }
+                let set = match candles.get_mut(symbol) {
+                    Some(set) => set,
+                    None => continue,
+                };
+                let mut new_candles: Vec<(Interval, Kline)> = series.iter()
+                    .flat_map(|(interval, klines)| {
+                        set.ingest(*interval, klines, now).into_iter().map(move |k| (*interval, k))
+                    })
+                    .collect();
+                sort_for_replay(&mut new_candles);
+                let primary_closed = new_candles.iter().any(|(interval, _)| *interval == set.primary());
+                (new_candles, leg_candles, primary_closed, set.len(set.primary()))
+            };
+            let primary = series[0].0;
+            if new_candles.is_empty() && leg_candles.iter().all(|(_, candles)| candles.is_empty()) {
+                debug!(symbol, "没有新收盘的K线, 跳过策略评估");
+                continue;
+            }
+
+            // 按新收盘的主周期K线和实时行情识别市场状态
+            let regime = match self.regimes.write().await.get_mut(symbol) {
+                Some(detector) => {
+                    for (_, kline) in new_candles.iter().filter(|(interval, _)| *interval == primary) {
+                        detector.update(kline);
                     }
+                    detector.observe(latest_close, depth.as_ref(), ticker.as_ref())
+                }
+                None => None,
+            };
+
+            // 当前持仓 (供策略判断是否平仓)
+            let position = self.get_currency_status(symbol).await.and_then(|s| s.current_position);
+            let mut legs = Vec::with_capacity(leg_series.len());
+            for (leg, series) in &leg_series {
+                if let Some(kline) = series.first().and_then(|(_, klines)| klines.last()) {
+                    legs.push(LegState {
+                        symbol: leg.clone(),
+                        price: kline.close,
+                        position: self.get_currency_status(leg).await.and_then(|s| s.current_position),
+                    });
+                }
+            }
+
+            // 获取策略决策 (下单前释放策略锁, 评估期间借用K线缓存而不复制)
+            let (warm_up, decision) = {
+                let mut strategies = self.strategies.write().await;
+                let candles = self.candles.read().await;
+                let (strategy, set) = match (strategies.get_mut(symbol), candles.get(symbol)) {
+                    (Some(strategy), Some(set)) => (strategy, set),
+                    _ => continue,
                 };
 
-                // 获取24小时行情
-                let ticker = match self.client.get_ticker(Some(symbol)).await {
-                    Ok(tickers) if !tickers.is_empty() => {
-                        let t = &tickers[0];
-                        Some(MarketTicker {
-                            price_change_percent: t.price_change_percent.parse().unwrap_or_default(),
-                            high_price: t.high_price.parse().unwrap_or_default(),
-                            low_price: t.low_price.parse().unwrap_or_default(),
-                            last_price: t.last_price.parse().unwrap_or_default(),
-                            volume: t.volume.parse().unwrap_or_default(),
-                            bid_price: t.bid_price.parse().unwrap_or_default(),
-                            ask_price: t.ask_price.parse().unwrap_or_default(),
-                        })
+                // 更新策略数据 (附加交易对在前, 主周期收盘时对冲腿的同一根K线已就绪)
+                for (leg, candles) in &leg_candles {
+                    for (interval, kline) in candles {
+                        strategy.add_leg_candle(leg, *interval, kline);
                     }
-                    Ok(_) => None,
+                }
+                for (interval, kline) in &new_candles {
+                    strategy.add_candle(*interval, kline);
+                }
+                let warm_up = WarmUpStatus {
+                    required: strategy.lookback(primary),
+                    loaded,
+                    ready: strategy.is_ready(),
+                };
+                if !primary_closed || !warm_up.ready {
+                    (warm_up, None)
+                } else {
+                    debug!(
+                        symbol,
+                        price = latest_close,
+                        change_pct = ticker.as_ref().map(|t| t.price_change_percent),
+                        regime = %regime.as_ref().map_or("未知".to_string(), |r| r.to_string()),
+                        "市场状况更新"
+                    );
+
+                    let aligned = set.aligned();
+                    let ctx = StrategyContext::new(symbol, latest_close)
+                        .with_timestamp(self.now(config.venue))
+                        .with_depth(depth.as_ref())
+                        .with_ticker(ticker.as_ref())
+                        .with_position(position.as_ref())
+                        .with_candles(Some(&aligned))
+                        .with_legs(&legs)
+                        .with_regime(regime.as_ref());
+                    // 需要等待外部进程的策略只在锁内投递请求
+                    let evaluation = match strategy.evaluate_deferred(&ctx) {
+                        Some(deferred) => Evaluation::Deferred(deferred),
+                        None => Evaluation::Ready(strategy.evaluate(&ctx)),
+                    };
+                    (warm_up, Some(evaluation))
+                }
+            };
+
+            // 预热完成前不评估实时信号
+            let ready = warm_up.ready;
+            for leg in &config.legs {
+                self.update_warm_up(&leg.symbol, warm_up.clone()).await;
+            }
+            self.update_warm_up(symbol, warm_up).await;
+            let decision = match decision {
+                Some(Evaluation::Ready(decision)) => decision,
+                // 释放策略锁后在阻塞线程池中等待, 不占用异步工作线程
+                Some(Evaluation::Deferred(deferred)) => match tokio::task::spawn_blocking(move || deferred.wait()).await {
+                    Ok(decision) => decision,
                     Err(e) => {
-                        println!("{} - 获取24小时行情失败: {}", symbol, e);
-                        None
+                        error!(symbol, error = %e, "等待策略决策失败");
+                        continue;
                     }
+                },
+                None if !ready => {
+                    debug!(symbol, loaded, "策略预热中, 跳过策略评估");
+                    continue;
+                }
+                None => {
+                    debug!(symbol, "主周期没有新收盘的K线, 跳过策略评估");
+                    continue;
+                }
+            };
+
+            let verdicts: Vec<String> = decision.verdicts.iter().map(|v| v.to_string()).collect();
+            if !decision.is_actionable() {
+                debug!(symbol, price = latest_close, reasons = ?decision.reasons, filters = ?verdicts, "策略决策: 观望");
+                continue;
+            }
+
+            // 冷却期内只允许平仓
+            if decision.is_entry() {
+                if let Some(remaining_ms) = self.cooldown_remaining(symbol).await {
+                    info!(symbol, action = %decision.action, remaining_ms, "冷却中, 忽略开仓信号");
+                    continue;
+                }
+            }
+
+            // 当前市场状态不在币种允许的范围内时只允许平仓, 反手信号降级为平仓
+            let decision = if decision.is_entry() && !config.allows_regime(regime.as_ref().map(|r| r.regime)) {
+                let regime = regime.as_ref().map_or("未知".to_string(), |r| r.to_string());
+                let reverses = match (&position, decision.action) {
+                    (Some(held), Action::EnterLong) => held.side == OrderSide::Sell,
+                    (Some(held), Action::EnterShort) => held.side == OrderSide::Buy,
+                    _ => false,
                 };
+                if !reverses {
+                    info!(symbol, action = %decision.action, regime = %regime, "市场状态不允许开仓, 忽略开仓信号");
+                    continue;
+                }
+                info!(symbol, action = %decision.action, regime = %regime, "市场状态不允许开仓, 反手信号只平仓");
+                // 附加交易对的开仓同样降级为平仓, 避免主交易对平仓后留下对冲腿
+                let exit = Decision::exit(decision.confidence)
+                    .with_reasons(decision.reasons)
+                    .with_verdicts(decision.verdicts)
+                    .with_reason(format!("市场状态 {} 不允许反手开仓", regime));
+                decision.legs.into_iter().fold(exit, |exit, (leg_symbol, leg)| {
+                    let leg = if leg.is_entry() {
+                        Decision::exit(leg.confidence).with_reasons(leg.reasons).with_verdicts(leg.verdicts)
+                    } else {
+                        leg
+                    };
+                    exit.with_leg(&leg_symbol, leg)
+                })
+            } else {
+                decision
+            };
 
-                // 获取K线数据
-                if let Ok(mut klines) = self.client.get_klines(
-                    symbol,
-                    crate::types::Interval::FiveMinutes,
-                    Some(Utc::now() - Duration::hours(2)),
-                    Some(Utc::now()),
-                    Some(24),
-                ).await {
-                    klines.reverse();
-                    
-                    if let Some(strategy) = strategies.get_mut(symbol) {
-                        // 更新策略数据
-                        for kline in &klines {
-                            strategy.add_price(kline.close);
-                        }
-                        
-                        // 获取最新K线数据
-                        if let Some(latest_kline) = klines.last() {
-                            println!("\n{} - 市场状况更新", symbol);
-                            println!("最新价格: {:.2}", latest_kline.close);
-                            
-                            if let Some(t) = &ticker {
-                                println!("24小时变动: {:.2}%", t.price_change_percent);
-                                println!("��内波动率: {:.2}%", 
-                                    (t.high_price - t.low_price) / t.low_price * 100.0);
-                                let position = (t.last_price - t.low_price) / 
-                                    (t.high_price - t.low_price) * 100.0;
-                                println!("价格位置: 日内区间的 {:.1}%", position);
-                            }
-                            
-                            // 检查交易信号
-                            if strategy.should_buy(latest_kline.close, depth.as_ref(), ticker.as_ref()) {
-                                println!("\n>>> {} - 发现买入信号!", symbol);
-                                if let Err(e) = self.place_order(symbol, OrderSide::Buy, latest_kline.close).await {
-                                    println!("下单失败: {}", e);
-                                }
-                            } else if strategy.should_sell(latest_kline.close, depth.as_ref(), ticker.as_ref()) {
-                                println!("\n<<< {} - 发现卖出信号!", symbol);
-                                if let Err(e) = self.place_order(symbol, OrderSide::Sell, latest_kline.close).await {
-                                    println!("下单失败: {}", e);
-                                }
-                            }
-                        }
+            info!(
+                symbol,
+                price = latest_close,
+                action = %decision.action,
+                confidence = decision.confidence,
+                stop_loss = ?decision.stop_loss,
+                take_profit = ?decision.take_profit,
+                reasons = ?decision.reasons,
+                filters = ?verdicts,
+                "策略决策"
+            );
+            // 主交易对下单失败时不执行附加交易对, 也不记录信号
+            if let Err(e) = self.execute_decision(symbol, latest_close, &decision).await {
+                error!(symbol, error = %e, "执行决策失败");
+                continue;
+            }
+            // 多交易对策略: 主交易对之后依次执行附加交易对的决策
+            for (leg, leg_decision) in &decision.legs {
+                let leg_price = match legs.iter().find(|state| &state.symbol == leg) {
+                    Some(state) => state.price,
+                    None => {
+                        warn!(symbol, leg = %leg, "缺少附加交易对行情, 跳过执行");
+                        continue;
                     }
+                };
+                info!(symbol, leg = %leg, action = %leg_decision.action, quantity = ?leg_decision.quantity, "附加交易对决策");
+                if let Err(e) = self.execute_decision(leg, leg_price, leg_decision).await {
+                    error!(symbol, leg = %leg, error = %e, "执行附加交易对决策失败");
                 }
             }
-            
-            // 等待一定时间后再次检查
-            tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+            // 通知策略信号已执行 (用于信号复位判断和记录)
+            if let Some(strategy) = self.strategies.write().await.get_mut(symbol) {
+                strategy.on_signal(&decision, latest_close, self.now(config.venue));
+            }
+        }
+
+        // 按策略最新状态撤销/挂出限价单
+        for config in &configs {
+            self.reconcile_resting_orders(config).await;
         }
     }
+}
+
+// 策略评估结果: 已得到的决策, 或需要在策略锁之外等待的决策
+enum Evaluation {
+    Ready(Decision),
+    Deferred(DeferredDecision),
+}
+
+// 开仓的 (止盈价, 止损价), 策略未给出时使用默认止盈止损
+pub(crate) fn protective_prices(side: &OrderSide, price: f64, decision: &Decision) -> (f64, f64) {
+    match side {
+        OrderSide::Buy => (
+            decision.take_profit.unwrap_or(price * 1.10),  // 买入时，止盈价格默认为入场价格+10%
+            decision.stop_loss.unwrap_or(price * 0.95),    // 买入时，止损价格默认为入场价格-5%
+        ),
+        OrderSide::Sell => (
+            decision.take_profit.unwrap_or(price * 0.90),  // 卖出时，止盈价格默认为入场价格-10%
+            decision.stop_loss.unwrap_or(price * 1.05),    // 卖出时，止损价格默认为入场价格+5%
+        ),
+    }
+}
+
+// 按币种精度调整挂单价格与数量, 数量为 0 时使用最小下单量
+fn normalize_intent(config: &CurrencyConfig, mut intent: OrderIntent) -> Option<OrderIntent> {
+    intent.price = config.round_price(intent.price);
+    let quantity = if intent.quantity > 0.0 { intent.quantity } else { config.min_qty };
+    intent.quantity = config.floor_qty(quantity);
+    if intent.price <= 0.0 || intent.quantity <= 0.0 {
+        warn!(symbol = %config.symbol, key = %intent.key, "挂单价格或数量按精度取整后为 0, 忽略");
+        return None;
+    }
+    Some(intent)
 }
\ No newline at end of file
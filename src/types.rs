@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Kline {
     pub open_time: i64,
     pub open: f64,
@@ -25,20 +26,154 @@ pub struct Trade {
     pub time: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Interval {
     #[serde(rename = "1m")]
     OneMinute,
+    #[serde(rename = "3m")]
+    ThreeMinutes,
     #[serde(rename = "5m")]
     FiveMinutes,
     #[serde(rename = "15m")]
     FifteenMinutes,
+    #[serde(rename = "30m")]
+    ThirtyMinutes,
     #[serde(rename = "1h")]
     OneHour,
+    #[serde(rename = "2h")]
+    TwoHours,
     #[serde(rename = "4h")]
     FourHours,
+    #[serde(rename = "6h")]
+    SixHours,
+    #[serde(rename = "12h")]
+    TwelveHours,
     #[serde(rename = "1d")]
     OneDay,
+    #[serde(rename = "1w")]
+    OneWeek,
+    #[serde(rename = "1M")]
+    OneMonth,
+}
+
+const MINUTE_MS: i64 = 60_000;
+const DAY_MS: i64 = 24 * 60 * MINUTE_MS;
+// 1970-01-01 是周四, 周K线从周一 00:00 (UTC) 开始
+const WEEK_OFFSET_MS: i64 = 4 * DAY_MS;
+
+impl Interval {
+    pub const ALL: [Interval; 13] = [
+        Interval::OneMinute,
+        Interval::ThreeMinutes,
+        Interval::FiveMinutes,
+        Interval::FifteenMinutes,
+        Interval::ThirtyMinutes,
+        Interval::OneHour,
+        Interval::TwoHours,
+        Interval::FourHours,
+        Interval::SixHours,
+        Interval::TwelveHours,
+        Interval::OneDay,
+        Interval::OneWeek,
+        Interval::OneMonth,
+    ];
+
+    // 交易所接口使用的周期参数
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Interval::OneMinute => "1m",
+            Interval::ThreeMinutes => "3m",
+            Interval::FiveMinutes => "5m",
+            Interval::FifteenMinutes => "15m",
+            Interval::ThirtyMinutes => "30m",
+            Interval::OneHour => "1h",
+            Interval::TwoHours => "2h",
+            Interval::FourHours => "4h",
+            Interval::SixHours => "6h",
+            Interval::TwelveHours => "12h",
+            Interval::OneDay => "1d",
+            Interval::OneWeek => "1w",
+            Interval::OneMonth => "1M",
+        }
+    }
+
+    // K线周期长度 (毫秒), 月线按 30 天估算, 精确边界用 next_open_time
+    pub fn duration_ms(&self) -> i64 {
+        match self {
+            Interval::OneMinute => MINUTE_MS,
+            Interval::ThreeMinutes => 3 * MINUTE_MS,
+            Interval::FiveMinutes => 5 * MINUTE_MS,
+            Interval::FifteenMinutes => 15 * MINUTE_MS,
+            Interval::ThirtyMinutes => 30 * MINUTE_MS,
+            Interval::OneHour => 60 * MINUTE_MS,
+            Interval::TwoHours => 2 * 60 * MINUTE_MS,
+            Interval::FourHours => 4 * 60 * MINUTE_MS,
+            Interval::SixHours => 6 * 60 * MINUTE_MS,
+            Interval::TwelveHours => 12 * 60 * MINUTE_MS,
+            Interval::OneDay => DAY_MS,
+            Interval::OneWeek => 7 * DAY_MS,
+            Interval::OneMonth => 30 * DAY_MS,
+        }
+    }
+
+    // 按分钟数查找周期 (用于策略参数, 月线为 43200)
+    pub fn from_minutes(minutes: i64) -> Option<Interval> {
+        Interval::ALL.into_iter().find(|i| i.duration_ms() == minutes * MINUTE_MS)
+    }
+
+    // 时间戳所在K线的开盘时间 (UTC)
+    pub fn align(&self, timestamp: i64) -> i64 {
+        match self {
+            Interval::OneWeek => timestamp - (timestamp - WEEK_OFFSET_MS).rem_euclid(self.duration_ms()),
+            Interval::OneMonth => {
+                let date = month_start(timestamp);
+                date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp_millis()
+            }
+            _ => timestamp - timestamp.rem_euclid(self.duration_ms()),
+        }
+    }
+
+    // 下一根K线的开盘时间
+    pub fn next_open_time(&self, open_time: i64) -> i64 {
+        match self {
+            Interval::OneMonth => {
+                let start = month_start(open_time);
+                let next = start.checked_add_months(chrono::Months::new(1)).unwrap_or(start);
+                next.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp_millis()
+            }
+            _ => open_time + self.duration_ms(),
+        }
+    }
+
+    // K线收盘时间 (下一根开盘前 1 毫秒)
+    pub fn close_time(&self, open_time: i64) -> i64 {
+        self.next_open_time(open_time) - 1
+    }
+}
+
+// 时间戳所在月份的第一天 (UTC)
+fn month_start(timestamp: i64) -> chrono::NaiveDate {
+    use chrono::Datelike;
+    let date = chrono::DateTime::from_timestamp_millis(timestamp)
+        .unwrap_or_default()
+        .date_naive();
+    date.with_day(1).unwrap_or(date)
+}
+
+impl std::str::FromStr for Interval {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Interval::ALL.into_iter()
+            .find(|i| i.as_str() == s.trim())
+            .ok_or_else(|| format!("未知K线周期: {} (可选: 1m/3m/5m/15m/30m/1h/2h/4h/6h/12h/1d/1w/1M)", s))
+    }
+}
+
+impl std::fmt::Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -53,7 +188,7 @@ pub enum OrderType {
     TakeProfitMarket,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, PartialEq)]
 pub enum OrderSide {
     #[serde(rename = "BUY")]
     Buy,
@@ -61,7 +196,7 @@ pub enum OrderSide {
     Sell,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
 pub enum PositionSide {
     #[serde(rename = "BOTH")]
     Both,
@@ -80,6 +215,8 @@ pub struct OrderRequest {
     pub quantity: f64,
     pub timestamp: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<f64>,                   // 限价单价格
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub stop_price: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub working_type: Option<String>,
@@ -87,6 +224,10 @@ pub struct OrderRequest {
     pub take_profit: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stop_loss: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position_side: Option<PositionSide>,  // 双向持仓模式下的持仓方向 (默认 LONG)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reduce_only: Option<bool>,            // 单向持仓模式下只减仓
 }
 
 #[derive(Debug, Deserialize)]
@@ -129,13 +270,234 @@ pub struct OrderData {
     pub stop_guaranteed: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct MACD {
     pub macd: f64,
     pub signal: f64,
     pub histogram: f64,
 }
 
+// 交易市场类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MarketType {
+    #[default]
+    #[serde(rename = "swap")]
+    Swap,   // 永续合约
+    #[serde(rename = "spot")]
+    Spot,   // 现货
+}
+
+impl std::str::FromStr for MarketType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "swap" | "perp" | "合约" => Ok(MarketType::Swap),
+            "spot" | "现货" => Ok(MarketType::Spot),
+            other => Err(format!("未知的市场类型: {}", other)),
+        }
+    }
+}
+
+// 交易所
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Venue {
+    #[default]
+    #[serde(rename = "bingx")]
+    BingX,
+    #[serde(rename = "binance")]
+    Binance,   // 币安 U本位合约
+}
+
+impl std::str::FromStr for Venue {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "bingx" => Ok(Venue::BingX),
+            "binance" => Ok(Venue::Binance),
+            other => Err(format!("未知的交易所: {}", other)),
+        }
+    }
+}
+
+// 市场状态 (用于按状态启用或暂停策略开仓)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Regime {
+    #[serde(rename = "trending")]
+    Trending,        // 趋势
+    #[serde(rename = "ranging")]
+    Ranging,         // 震荡
+    #[serde(rename = "high_volatility")]
+    HighVolatility,  // 高波动
+    #[serde(rename = "illiquid")]
+    Illiquid,        // 流动性不足
+}
+
+impl Regime {
+    pub const ALL: [Regime; 4] = [Regime::Trending, Regime::Ranging, Regime::HighVolatility, Regime::Illiquid];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Regime::Trending => "trending",
+            Regime::Ranging => "ranging",
+            Regime::HighVolatility => "high_volatility",
+            Regime::Illiquid => "illiquid",
+        }
+    }
+}
+
+impl std::str::FromStr for Regime {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "trending" | "趋势" => Ok(Regime::Trending),
+            "ranging" | "震荡" => Ok(Regime::Ranging),
+            "high_volatility" | "高波动" => Ok(Regime::HighVolatility),
+            "illiquid" | "流动性不足" => Ok(Regime::Illiquid),
+            other => Err(format!("未知的市场状态: {} (可选: trending/ranging/high_volatility/illiquid)", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for Regime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Regime::Trending => "趋势",
+            Regime::Ranging => "震荡",
+            Regime::HighVolatility => "高波动",
+            Regime::Illiquid => "流动性不足",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+// 策略选择: 名称 + 数值参数 (+ 组合策略的子策略)
+// 文本格式: "macd" 或 "macd:fast=12;slow=26;signal=9",
+// 组合策略在参数后用括号列出子策略, 以 | 分隔: "weighted:threshold=0.5(macd|donchian:entry=30)"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategyConfig {
+    pub name: String,
+    #[serde(default)]
+    pub params: BTreeMap<String, f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<StrategyConfig>,
+}
+
+impl StrategyConfig {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.trim().to_ascii_lowercase(),
+            params: BTreeMap::new(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn with_param(mut self, key: &str, value: f64) -> Self {
+        self.params.insert(key.to_string(), value);
+        self
+    }
+
+    // 追加子策略 (组合策略)
+    pub fn with_child(mut self, child: StrategyConfig) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    // 读取参数, 未设置时使用默认值
+    pub fn param(&self, key: &str, default: f64) -> f64 {
+        self.params.get(key).copied().unwrap_or(default)
+    }
+}
+
+impl Default for StrategyConfig {
+    fn default() -> Self {
+        Self::new("macd")
+    }
+}
+
+impl std::str::FromStr for StrategyConfig {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (s, children) = match s.trim().split_once('(') {
+            Some((head, rest)) => {
+                let inner = rest.strip_suffix(')')
+                    .ok_or_else(|| format!("子策略列表缺少右括号: {}", s.trim()))?;
+                (head, split_children(inner)?)
+            }
+            None => (s, Vec::new()),
+        };
+        let (name, params) = match s.split_once(':') {
+            Some((name, params)) => (name, params),
+            None => (s, ""),
+        };
+        if name.trim().is_empty() {
+            return Err("策略名称不能为空".to_string());
+        }
+
+        let mut config = StrategyConfig::new(name);
+        for pair in params.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=')
+                .ok_or_else(|| format!("策略参数格式错误: {} (应为 key=value)", pair))?;
+            let value: f64 = value.trim().parse()
+                .map_err(|_| format!("策略参数 {} 不是数字: {}", key.trim(), value.trim()))?;
+            config.params.insert(key.trim().to_string(), value);
+        }
+        config.children = children.iter()
+            .map(|child| child.parse())
+            .collect::<Result<_, _>>()?;
+        Ok(config)
+    }
+}
+
+// 按最外层的 | 拆分子策略 (子策略自身也可以是带括号的组合策略)
+fn split_children(inner: &str) -> Result<Vec<&str>, String> {
+    let mut children = Vec::new();
+    let (mut depth, mut start) = (0usize, 0);
+    for (i, c) in inner.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.checked_sub(1).ok_or_else(|| format!("子策略列表括号不匹配: {}", inner))?,
+            '|' if depth == 0 => {
+                children.push(&inner[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        return Err(format!("子策略列表括号不匹配: {}", inner));
+    }
+    children.push(&inner[start..]);
+    if children.iter().any(|child| child.trim().is_empty()) {
+        return Err(format!("子策略不能为空: ({})", inner));
+    }
+    Ok(children)
+}
+
+impl std::fmt::Display for StrategyConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.params.is_empty() {
+            let params = self.params.iter()
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect::<Vec<String>>()
+                .join(";");
+            write!(f, ":{}", params)?;
+        }
+        if !self.children.is_empty() {
+            let children = self.children.iter()
+                .map(|child| child.to_string())
+                .collect::<Vec<String>>()
+                .join("|");
+            write!(f, "({})", children)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CurrencyConfig {
     pub symbol: String,
@@ -146,9 +508,17 @@ pub struct CurrencyConfig {
     pub qty_precision: u32,       // 数量精度
     pub min_notional: f64,       // 最小名义价值
     pub leverage: u32,           // 杠杆倍数
+    pub market: MarketType,      // 现货或永续合约
+    pub venue: Venue,            // 交易所
+    pub strategy: StrategyConfig, // 使用的交易策略
+    pub max_size_multiplier: f64, // 置信度为 1 时的下单数量倍数 (相对 min_qty)
+    pub cooldown_secs: u64,       // 任意成交后暂停开仓的秒数
+    pub legs: Vec<CurrencyConfig>, // 多交易对策略同时交易的其他交易对 (例如配对交易的对冲腿)
+    pub regimes: Vec<Regime>,     // 允许开仓的市场状态 (为空时不限制)
 }
 
 impl CurrencyConfig {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         symbol: &str,
         base_currency: &str,
@@ -168,7 +538,83 @@ impl CurrencyConfig {
             qty_precision,
             min_notional,
             leverage,
+            market: MarketType::Swap,
+            venue: Venue::BingX,
+            strategy: StrategyConfig::default(),
+            max_size_multiplier: 1.0,
+            cooldown_secs: 0,
+            legs: Vec::new(),
+            regimes: Vec::new(),
+        }
+    }
+
+    // 指定交易市场 (默认为永续合约)
+    pub fn with_market(mut self, market: MarketType) -> Self {
+        self.market = market;
+        if market == MarketType::Spot {
+            self.leverage = 1;  // 现货不使用杠杆
         }
+        self
+    }
+
+    // 指定交易所 (默认为 BingX)
+    pub fn with_venue(mut self, venue: Venue) -> Self {
+        self.venue = venue;
+        self
+    }
+
+    // 按策略置信度放大下单数量: 数量 = min_qty * (1 + 置信度 * (倍数 - 1))
+    pub fn with_max_size_multiplier(mut self, multiplier: f64) -> Self {
+        self.max_size_multiplier = multiplier.max(1.0);
+        self
+    }
+
+    // 按置信度计算下单数量 (按数量精度向下取整, 不低于 min_qty)
+    pub fn order_quantity(&self, confidence: f64) -> f64 {
+        let scale = 1.0 + confidence.clamp(0.0, 1.0) * (self.max_size_multiplier - 1.0);
+        let factor = 10f64.powi(self.qty_precision as i32);
+        ((self.min_qty * scale * factor).floor() / factor).max(self.min_qty)
+    }
+
+    // 按价格精度四舍五入
+    pub fn round_price(&self, price: f64) -> f64 {
+        let factor = 10f64.powi(self.price_precision as i32);
+        (price * factor).round() / factor
+    }
+
+    // 按数量精度向下取整 (容忍浮点误差, 例如 0.3 不会被取成 0.2)
+    pub fn floor_qty(&self, quantity: f64) -> f64 {
+        let factor = 10f64.powi(self.qty_precision as i32);
+        (quantity * factor + 1e-9).floor() / factor
+    }
+
+    // 成交后的冷却时间 (秒), 期间忽略新的开仓信号 (平仓不受影响)
+    pub fn with_cooldown(mut self, cooldown_secs: u64) -> Self {
+        self.cooldown_secs = cooldown_secs;
+        self
+    }
+
+    // 指定交易策略 (默认为 MACD 12/26/9)
+    pub fn with_strategy(mut self, strategy: StrategyConfig) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    // 添加多交易对策略的附加交易对 (行情与持仓由主交易对的策略管理)
+    pub fn with_leg(mut self, leg: CurrencyConfig) -> Self {
+        self.legs.push(leg);
+        self
+    }
+
+    // 只在指定的市场状态下开仓 (平仓不受影响)
+    pub fn with_regimes(mut self, regimes: Vec<Regime>) -> Self {
+        self.regimes = regimes;
+        self
+    }
+
+    // 当前市场状态是否允许开仓 (设置了限制时, 状态未知也不开仓)
+    pub fn allows_regime(&self, regime: Option<Regime>) -> bool {
+        self.regimes.is_empty() || regime.is_some_and(|regime| self.regimes.contains(&regime))
     }
 }
 
@@ -187,6 +633,17 @@ pub struct CurrencyStatus {
     pub status: TradingStatus,
     pub last_update: i64,
     pub current_position: Option<Position>,
+    pub last_trade_at: Option<i64>,   // 最近一次成交时间 (毫秒)
+    pub warm_up: WarmUpStatus,
+    pub owner: Option<String>,        // 作为附加交易对时所属的主交易对
+}
+
+// 策略预热进度
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WarmUpStatus {
+    pub required: usize,  // 主周期需要的历史K线数量
+    pub loaded: usize,    // 已输入策略的主周期K线数量
+    pub ready: bool,      // 策略数据是否足以发出信号
 }
 
 // 持仓信息
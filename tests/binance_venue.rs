@@ -0,0 +1,141 @@
+mod common;
+
+use common::{binance_client, binance_server, bingx_server, manager_for, API_KEY};
+use chrono::Utc;
+use crypto_trading_bot::exchange::binance::BinanceFuturesClient;
+use crypto_trading_bot::exchange::ApiError;
+use crypto_trading_bot::exchange::mock::{MockBinanceServer, MockFault, BINANCE_CODE_INVALID_SYMBOL};
+use crypto_trading_bot::exchange::{normalize_symbol, to_venue_symbol, Exchange, ExchangeOrder};
+use crypto_trading_bot::strategy::{MarketDepth, MarketTicker};
+use crypto_trading_bot::types::{CurrencyConfig, Interval, Kline, MarketType, OrderSide, Venue};
+
+async fn start_binance() -> MockBinanceServer {
+    let server = binance_server().await;
+    let start = Utc::now().timestamp_millis() - 5 * 300_000;
+    server.set_klines("BTCUSDT", (0..5).map(|i| Kline {
+        open_time: start + i * 300_000,
+        open: 60_000.0,
+        high: 60_100.0,
+        low: 59_900.0,
+        close: 60_000.0 + i as f64,
+        volume: 2.0,
+        close_time: start + (i + 1) * 300_000 - 1,
+    }).collect());
+    server.set_depth("BTCUSDT", MarketDepth {
+        asks: vec![(60_004.5, 1.5)],
+        bids: vec![(60_003.5, 2.5)],
+    });
+    server.set_ticker("BTCUSDT", MarketTicker {
+        price_change_percent: 1.5,
+        high_price: 61_000.0,
+        low_price: 59_000.0,
+        last_price: 60_004.0,
+        volume: 1200.0,
+        bid_price: 60_003.5,
+        ask_price: 60_004.5,
+    });
+    server
+}
+
+#[test]
+fn normalizes_symbols_between_venues() {
+    assert_eq!(normalize_symbol("BTCUSDT"), "BTC-USDT");
+    assert_eq!(normalize_symbol("btc_usdt"), "BTC-USDT");
+    assert_eq!(normalize_symbol("ETH/BTC"), "ETH-BTC");
+    assert_eq!(normalize_symbol("1000PEPE-USDT"), "1000PEPE-USDT");
+    assert_eq!(to_venue_symbol(Venue::Binance, "BTC-USDT"), "BTCUSDT");
+    assert_eq!(to_venue_symbol(Venue::BingX, "BTCUSDT"), "BTC-USDT");
+}
+
+#[tokio::test]
+async fn binance_adapter_maps_market_data() {
+    let server = start_binance().await;
+    let client = binance_client(&server);
+
+    let klines = client.get_klines("BTC-USDT", MarketType::Swap, Interval::FiveMinutes, None, None, Some(2)).await.unwrap();
+    assert_eq!(klines.iter().map(|k| k.close).collect::<Vec<_>>(), vec![60_003.0, 60_004.0]);
+
+    let depth = client.get_market_depth("BTC-USDT", MarketType::Swap, Some(20)).await.unwrap();
+    assert_eq!(depth.bids, vec![(60_003.5, 2.5)]);
+
+    let ticker = client.get_market_ticker("BTC-USDT", MarketType::Swap).await.unwrap();
+    assert_eq!(ticker.price_change_percent, 1.5);
+    assert_eq!(ticker.ask_price, 60_004.5);
+    assert_eq!(client.get_price("BTC-USDT", MarketType::Swap).await.unwrap(), 60_004.0);
+
+    assert!(server.requests().iter().all(|r| r.params.get("symbol").map(String::as_str) == Some("BTCUSDT")));
+    assert!(client.get_klines("BTC-USDT", MarketType::Spot, Interval::FiveMinutes, None, None, None).await.is_err());
+}
+
+#[tokio::test]
+async fn binance_order_places_protective_orders() {
+    let server = start_binance().await;
+    server.set_balance("USDT", 250.0);
+    let client = binance_client(&server);
+
+    let fill = client.place_order(ExchangeOrder {
+        symbol: "BTC-USDT".to_string(),
+        market: MarketType::Swap,
+        side: OrderSide::Buy,
+        quantity: 0.002,
+        take_profit: Some(66_000.0),
+        stop_loss: Some(57_000.0),
+        reduce_only: false,
+    }).await.unwrap();
+    assert_eq!(fill.symbol, "BTC-USDT");
+    assert_eq!(fill.quantity, 0.002);
+    assert_eq!(fill.price, 60_004.0);
+
+    let orders = server.orders();
+    let types: Vec<&str> = orders.iter().map(|o| o.order_type.as_str()).collect();
+    assert_eq!(types, vec!["MARKET", "TAKE_PROFIT_MARKET", "STOP_MARKET"]);
+    assert!(orders[1..].iter().all(|o| o.side == "SELL"));
+    assert_eq!(orders[2].price, 57_000.0);
+    assert!(server.requests().iter().all(|r| r.api_key.as_deref() == Some(API_KEY)));
+
+    assert_eq!(client.get_balance("USDT", MarketType::Swap).await.unwrap(), 250.0);
+}
+
+#[tokio::test]
+async fn binance_errors_surface_as_api_errors() {
+    let server = start_binance().await;
+    let client = binance_client(&server);
+
+    let err = client.get_price("DOGE-USDT", MarketType::Swap).await.unwrap_err();
+    assert_eq!(err.downcast_ref::<ApiError>().unwrap().code, BINANCE_CODE_INVALID_SYMBOL);
+
+    server.inject_fault("/fapi/v1/depth", MockFault::ApiError { code: -1003, msg: "Too many requests".to_string() });
+    let err = client.get_market_depth("BTC-USDT", MarketType::Swap, None).await.unwrap_err();
+    assert_eq!(err.downcast_ref::<ApiError>().unwrap().code, -1003);
+
+    let wrong_secret = BinanceFuturesClient::with_base_url(API_KEY.to_string(), "wrong".to_string(), &server.url());
+    assert!(wrong_secret.get_balance("USDT", MarketType::Swap).await.is_err());
+}
+
+#[tokio::test]
+async fn manager_routes_currencies_by_venue() {
+    let binance = start_binance().await;
+    let bingx = bingx_server().await;
+    bingx.set_price("ETH-USDT", 3000.0);
+
+    let manager = manager_for(&bingx)
+        .with_exchange(binance_client(&binance));
+    manager.add_currency(CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.002, 1, 3, 5.0, 20).with_venue(Venue::Binance)).await.unwrap();
+    manager.add_currency(CurrencyConfig::new("ETH-USDT", "ETH", "USDT", 0.01, 2, 3, 5.0, 20)).await.unwrap();
+
+    manager.place_order("BTC-USDT", OrderSide::Sell, 60_000.0).await.unwrap();
+    manager.place_order("ETH-USDT", OrderSide::Buy, 3000.0).await.unwrap();
+
+    assert_eq!(binance.orders()[0].symbol, "BTCUSDT");
+    assert_eq!(bingx.orders().len(), 1);
+    assert_eq!(bingx.orders()[0].symbol, "ETH-USDT");
+
+    let position = manager.get_currency_status("BTC-USDT").await.unwrap().current_position.unwrap();
+    assert_eq!(position.side, OrderSide::Sell);
+    assert_eq!(position.entry_price, 60_004.0);
+
+    // 添加币种时回填一次, 监控时再拉取一次
+    manager.monitor_once().await;
+    assert_eq!(binance.request_count("/fapi/v1/klines"), 2);
+    assert_eq!(bingx.request_count("/openApi/swap/v3/quote/klines"), 2);
+}
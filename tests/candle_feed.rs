@@ -0,0 +1,109 @@
+mod common;
+
+use common::{bar, bingx_server, manager_for, FIVE_MINUTES};
+use chrono::Utc;
+use crypto_trading_bot::strategy::{Decision, StrategyContext, StrategyRegistry, TradingStrategy};
+use crypto_trading_bot::trading::candles::CandleBuffer;
+use crypto_trading_bot::types::{CurrencyConfig, Interval, Kline, StrategyConfig};
+use std::sync::{Arc, Mutex};
+
+// 记录收到的全部价格, 从不下单
+struct Recorder {
+    prices: Arc<Mutex<Vec<f64>>>,
+    lookback: usize,
+}
+
+impl TradingStrategy for Recorder {
+    fn name(&self) -> &str {
+        "recorder"
+    }
+
+    fn add_price(&mut self, price: f64) {
+        self.prices.lock().unwrap().push(price);
+    }
+
+    fn lookback(&self, _interval: Interval) -> usize {
+        self.lookback
+    }
+
+    fn evaluate(&self, _ctx: &StrategyContext) -> Decision {
+        Decision::hold()
+    }
+}
+
+#[test]
+fn buffer_appends_only_new_closed_candles() {
+    let mut buffer = CandleBuffer::with_capacity(Interval::FiveMinutes, 3);
+    let now = 10 * FIVE_MINUTES + 1_000;
+
+    // 最后一根K线尚未收盘, 不入缓存
+    let klines: Vec<Kline> = (7..11).map(|i| bar(i * FIVE_MINUTES, i as f64)).collect();
+    let appended = buffer.ingest(&klines, now);
+    assert_eq!(appended.iter().map(|k| k.close).collect::<Vec<_>>(), vec![7.0, 8.0, 9.0]);
+
+    // 重复拉取同样的数据不会重复追加
+    assert!(buffer.ingest(&klines, now).is_empty());
+
+    // 乱序输入按 open_time 排序, 超出容量丢弃最旧的K线
+    let klines = vec![bar(11 * FIVE_MINUTES, 11.0), bar(10 * FIVE_MINUTES, 10.0), bar(9 * FIVE_MINUTES, 9.5)];
+    let appended = buffer.ingest(&klines, 12 * FIVE_MINUTES);
+    assert_eq!(appended.iter().map(|k| k.close).collect::<Vec<_>>(), vec![10.0, 11.0]);
+    assert_eq!(buffer.len(), 3);
+    assert_eq!(buffer.candles().iter().map(|k| k.close).collect::<Vec<_>>(), vec![9.0, 10.0, 11.0]);
+    assert_eq!(buffer.last_open_time(), Some(11 * FIVE_MINUTES));
+}
+
+#[tokio::test]
+async fn manager_feeds_each_closed_candle_once() {
+    let server = bingx_server().await;
+    let now = Utc::now().timestamp_millis();
+    let start = now - 3 * FIVE_MINUTES - FIVE_MINUTES / 2;
+    let mut klines: Vec<Kline> = (0..4).map(|i| bar(start + i * FIVE_MINUTES, 100.0 + i as f64)).collect();
+    server.set_klines("BTC-USDT", klines.clone());
+
+    let prices = Arc::new(Mutex::new(Vec::new()));
+    let recorded = prices.clone();
+    let mut registry = StrategyRegistry::with_builtin();
+    registry.register("recorder", move |_| Ok(Box::new(Recorder { prices: recorded.clone(), lookback: 0 })));
+    let manager = manager_for(&server).with_strategy_registry(registry);
+    manager.add_currency(CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20)
+        .with_strategy(StrategyConfig::new("recorder"))).await.unwrap();
+
+    // 第 4 根K线仍在形成中
+    manager.monitor_once().await;
+    manager.monitor_once().await;
+    assert_eq!(*prices.lock().unwrap(), vec![100.0, 101.0, 102.0]);
+
+    // 正在形成的K线价格变化不会推送给策略
+    klines[3].close = 104.0;
+    server.set_klines("BTC-USDT", klines.clone());
+    manager.monitor_once().await;
+    assert_eq!(prices.lock().unwrap().len(), 3);
+    assert_eq!(manager.candles("BTC-USDT").await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn manager_refills_gap_after_downtime() {
+    let server = bingx_server().await;
+    let now = Utc::now().timestamp_millis();
+    let start = now - 60 * FIVE_MINUTES - FIVE_MINUTES / 2;
+    let klines: Vec<Kline> = (0..61).map(|i| bar(start + i * FIVE_MINUTES, 100.0 + i as f64)).collect();
+    server.set_klines("BTC-USDT", klines[..4].to_vec());
+
+    let prices = Arc::new(Mutex::new(Vec::new()));
+    let recorded = prices.clone();
+    let mut registry = StrategyRegistry::with_builtin();
+    registry.register("recorder", move |_| Ok(Box::new(Recorder { prices: recorded.clone(), lookback: 60 })));
+    let manager = manager_for(&server).with_strategy_registry(registry);
+    // 回填时交易所只有停机前的K线
+    manager.add_currency(CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20)
+        .with_strategy(StrategyConfig::new("recorder"))).await.unwrap();
+    assert_eq!(prices.lock().unwrap().len(), 4);
+
+    // 停机期间错过的K线超过一次拉取的数量, 重新回填后按顺序全部推送给策略
+    server.set_klines("BTC-USDT", klines.clone());
+    manager.monitor_once().await;
+    let expected: Vec<f64> = (0..60).map(|i| 100.0 + i as f64).collect();
+    assert_eq!(*prices.lock().unwrap(), expected);
+    assert_eq!(server.request_count("/openApi/swap/v3/quote/klines"), 3);
+}
@@ -0,0 +1,68 @@
+// 集成测试共用的凭证、模拟交易所与K线/持仓构造函数 (各测试文件只用到其中一部分)
+#![allow(dead_code)]
+
+use crypto_trading_bot::exchange::binance::BinanceFuturesClient;
+use crypto_trading_bot::exchange::bingx::BingXClient;
+use crypto_trading_bot::exchange::mock::{MockBinanceServer, MockBingXServer};
+use crypto_trading_bot::trading::TradingManager;
+use crypto_trading_bot::types::{Interval, Kline, OrderSide, Position};
+
+pub const API_KEY: &str = "test-key";
+pub const API_SECRET: &str = "test-secret";
+pub const FIVE_MINUTES: i64 = 300_000;
+
+pub async fn bingx_server() -> MockBingXServer {
+    MockBingXServer::start(API_KEY, API_SECRET).await.unwrap()
+}
+
+pub async fn binance_server() -> MockBinanceServer {
+    MockBinanceServer::start(API_KEY, API_SECRET).await.unwrap()
+}
+
+pub fn bingx_client(server: &MockBingXServer) -> BingXClient {
+    BingXClient::with_base_url(API_KEY.to_string(), API_SECRET.to_string(), &server.url())
+}
+
+pub fn binance_client(server: &MockBinanceServer) -> BinanceFuturesClient {
+    BinanceFuturesClient::with_base_url(API_KEY.to_string(), API_SECRET.to_string(), &server.url())
+}
+
+// 连接模拟 BingX 的交易管理器 (使用内置策略)
+pub fn manager_for(server: &MockBingXServer) -> TradingManager {
+    TradingManager::new(bingx_client(server))
+}
+
+// 5 分钟K线, 最高/最低价为收盘价 ±1
+pub fn bar(open_time: i64, close: f64) -> Kline {
+    interval_bar(Interval::FiveMinutes, open_time, close, 2.0)
+}
+
+// 5 分钟K线, 最高价与最低价相差 range
+pub fn bar_with_range(open_time: i64, close: f64, range: f64) -> Kline {
+    interval_bar(Interval::FiveMinutes, open_time, close, range)
+}
+
+// 指定周期的K线, 收盘时间按周期计算
+pub fn interval_bar(interval: Interval, open_time: i64, close: f64, range: f64) -> Kline {
+    Kline {
+        open_time,
+        open: close,
+        high: close + range / 2.0,
+        low: close - range / 2.0,
+        close,
+        volume: 1.0,
+        close_time: interval.close_time(open_time),
+    }
+}
+
+// 100 开仓的 0.01 张 20 倍持仓
+pub fn position(symbol: &str, side: OrderSide) -> Position {
+    Position {
+        symbol: symbol.to_string(),
+        side,
+        quantity: 0.01,
+        entry_price: 100.0,
+        unrealized_pnl: 0.0,
+        leverage: 20,
+    }
+}
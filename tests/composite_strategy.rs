@@ -0,0 +1,236 @@
+mod common;
+
+use common::{bar_with_range, bingx_server, manager_for};
+use chrono::Utc;
+use crypto_trading_bot::strategy::{
+    Action, BoxedStrategy, CombineMode, CompositeStrategy, Decision, StrategyContext, StrategyRegistry, TradingStrategy,
+};
+use crypto_trading_bot::types::{CurrencyConfig, Interval, Kline, OrderSide, Position, StrategyConfig};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+// 固定给出同一决策的子策略
+struct Fixed(Decision);
+
+impl TradingStrategy for Fixed {
+    fn name(&self) -> &str {
+        "fixed"
+    }
+
+    fn add_price(&mut self, _price: f64) {}
+
+    fn evaluate(&self, _ctx: &StrategyContext) -> Decision {
+        self.0.clone()
+    }
+}
+
+// 收盘价高于 100 时看多, 低于 100 时看空
+struct Trend(f64);
+
+impl TradingStrategy for Trend {
+    fn name(&self) -> &str {
+        "trend"
+    }
+
+    fn add_price(&mut self, price: f64) {
+        self.0 = price;
+    }
+
+    fn evaluate(&self, _ctx: &StrategyContext) -> Decision {
+        if self.0 > 100.0 {
+            Decision::enter_long(1.0)
+        } else if self.0 < 100.0 {
+            Decision::enter_short(1.0)
+        } else {
+            Decision::hold()
+        }
+    }
+}
+
+// 转发给内部策略, 并统计收到的成交回报
+struct Notified {
+    inner: BoxedStrategy,
+    signals: Arc<AtomicUsize>,
+}
+
+impl TradingStrategy for Notified {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn add_price(&mut self, price: f64) {
+        self.inner.add_price(price);
+    }
+
+    fn add_candle(&mut self, interval: Interval, kline: &Kline) {
+        self.inner.add_candle(interval, kline);
+    }
+
+    fn evaluate(&self, ctx: &StrategyContext) -> Decision {
+        self.inner.evaluate(ctx)
+    }
+
+    fn on_signal(&mut self, _decision: &Decision, _price: f64, _timestamp: i64) {
+        self.signals.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn notified(inner: BoxedStrategy) -> (BoxedStrategy, Arc<AtomicUsize>) {
+    let signals = Arc::new(AtomicUsize::new(0));
+    (Box::new(Notified { inner, signals: signals.clone() }), signals)
+}
+
+fn fixed(decision: Decision) -> BoxedStrategy {
+    Box::new(Fixed(decision))
+}
+
+fn long(confidence: f64, stop_loss: f64) -> Decision {
+    Decision::enter_long(confidence).with_stop_loss(stop_loss).with_take_profit(stop_loss + 20.0)
+}
+
+#[test]
+fn parses_nested_child_strategies() {
+    let config: StrategyConfig = "weighted:threshold=0.6;w1=2(macd:fast=8;slow=21|majority(donchian|mean_reversion))"
+        .parse()
+        .unwrap();
+    assert_eq!(config.param("w1", 0.0), 2.0);
+    assert_eq!(config.children.len(), 2);
+    assert_eq!(config.children[0].param("slow", 0.0), 21.0);
+    assert_eq!(config.children[1].children[1].name, "mean_reversion");
+    assert_eq!(config.to_string().parse::<StrategyConfig>().unwrap(), config);
+
+    assert!("majority(macd|donchian".parse::<StrategyConfig>().is_err());
+    assert!("majority(macd||donchian)".parse::<StrategyConfig>().is_err());
+    assert!("majority(macd|donchian))".parse::<StrategyConfig>().is_err());
+}
+
+#[test]
+fn registry_builds_composites_and_reports_child_errors() {
+    let registry = StrategyRegistry::with_builtin();
+    let strategy = registry.build(&"majority(macd|donchian|mean_reversion)".parse().unwrap()).unwrap();
+    assert_eq!(strategy.name(), "majority");
+    assert!(strategy.intervals().contains(&Interval::FiveMinutes));
+    assert_eq!(strategy.lookback(Interval::FiveMinutes), 36);
+
+    let err = registry.build(&"unanimous(macd|donchian:entry=5;exit=10)".parse().unwrap()).err().unwrap();
+    assert!(err.to_string().contains("子策略 donchian"), "{}", err);
+    assert!(registry.build(&"majority(macd)".parse().unwrap()).is_err());
+    assert!(registry.build(&"filtered(macd|donchian|mean_reversion)".parse().unwrap()).is_err());
+    assert!(registry.build(&"weighted:threshold=2(macd|donchian)".parse().unwrap()).is_err());
+    assert!(registry.build(&"weighted:w3=1(macd|donchian)".parse().unwrap()).is_err());
+    assert!(registry.build(&"macd(donchian)".parse().unwrap()).is_err());
+}
+
+#[test]
+fn votes_combine_by_mode() {
+    let ctx = StrategyContext::new("BTC-USDT", 100.0);
+    let children = || vec![fixed(long(0.8, 95.0)), fixed(long(0.4, 97.0)), fixed(Decision::hold())];
+
+    assert_eq!(CompositeStrategy::new(CombineMode::Unanimous, children()).evaluate(&ctx).action, Action::Hold);
+
+    // 多数同意: 止损取更紧的, 止盈取更近的, 置信度为同意者的平均
+    let decision = CompositeStrategy::new(CombineMode::Majority, children()).evaluate(&ctx);
+    assert_eq!(decision.action, Action::EnterLong);
+    assert_eq!((decision.stop_loss, decision.take_profit), (Some(97.0), Some(115.0)));
+    assert!((decision.confidence - 0.6).abs() < 1e-9);
+    assert!(decision.reasons[0].starts_with("[fixed] 开多"));
+
+    // 加权: (2 × 0.8 + 0.4) / 4 = 0.5
+    let weighted = |threshold| CompositeStrategy::new(CombineMode::Weighted(threshold), children()).with_weights(vec![2.0, 1.0, 1.0]);
+    assert_eq!(weighted(0.5).evaluate(&ctx).action, Action::EnterLong);
+    assert_eq!(weighted(0.6).evaluate(&ctx).action, Action::Hold);
+
+    // 持仓时反向开仓算作离场票
+    let position = Position {
+        symbol: "BTC-USDT".to_string(),
+        side: OrderSide::Buy,
+        quantity: 0.01,
+        entry_price: 100.0,
+        unrealized_pnl: 0.0,
+        leverage: 20,
+    };
+    let exiting = CompositeStrategy::new(CombineMode::Majority, vec![
+        fixed(Decision::exit(1.0)),
+        fixed(Decision::enter_short(0.5)),
+        fixed(Decision::hold()),
+    ]);
+    let decision = exiting.evaluate(&ctx.with_position(Some(&position)));
+    assert_eq!(decision.action, Action::Exit);
+    assert_eq!(decision.stop_loss, None);
+}
+
+#[test]
+fn filter_child_gates_signal_direction() {
+    let mut filtered = CompositeStrategy::new(CombineMode::Filter, vec![Box::new(Trend(100.0)), fixed(long(1.0, 95.0))]);
+    let ctx = StrategyContext::new("BTC-USDT", 100.0);
+    assert_eq!(filtered.evaluate(&ctx).action, Action::Hold);
+
+    filtered.add_candle(Interval::FiveMinutes, &bar_with_range(0, 90.0, 0.0));
+    assert_eq!(filtered.bias(), Some(false));
+    let decision = filtered.evaluate(&ctx);
+    assert_eq!(decision.action, Action::Hold);
+    assert!(decision.reasons.iter().any(|r| r.contains("只做空")));
+
+    // 无方向的K线保持最近的方向, 过滤子策略转多后放行
+    filtered.add_candle(Interval::FiveMinutes, &bar_with_range(0, 100.0, 0.0));
+    assert_eq!(filtered.bias(), Some(false));
+    filtered.add_candle(Interval::FiveMinutes, &bar_with_range(0, 105.0, 0.0));
+    let decision = filtered.evaluate(&ctx);
+    assert_eq!(decision.action, Action::EnterLong);
+    assert_eq!(decision.stop_loss, Some(95.0));
+    assert!(filtered.describe().contains(&"过滤方向: 多".to_string()));
+}
+
+#[test]
+fn signals_reach_only_children_that_voted_for_them() {
+    let (first, first_signals) = notified(fixed(long(0.8, 95.0)));
+    let (second, second_signals) = notified(fixed(long(0.6, 96.0)));
+    let (dissent, dissent_signals) = notified(fixed(Decision::enter_short(1.0)));
+    let mut majority = CompositeStrategy::new(CombineMode::Majority, vec![first, second, dissent]);
+    let decision = majority.evaluate(&StrategyContext::new("BTC-USDT", 100.0));
+    assert_eq!(decision.action, Action::EnterLong);
+    majority.on_signal(&decision, 100.0, 1_000);
+    assert_eq!(first_signals.load(Ordering::SeqCst), 1);
+    assert_eq!(second_signals.load(Ordering::SeqCst), 1);
+    assert_eq!(dissent_signals.load(Ordering::SeqCst), 0);
+
+    // 过滤模式只回报给信号子策略
+    let (filter, filter_signals) = notified(Box::new(Trend(100.0)));
+    let (signal, signal_signals) = notified(fixed(long(1.0, 95.0)));
+    let mut filtered = CompositeStrategy::new(CombineMode::Filter, vec![filter, signal]);
+    filtered.add_candle(Interval::FiveMinutes, &bar_with_range(0, 105.0, 0.0));
+    let decision = filtered.evaluate(&StrategyContext::new("BTC-USDT", 105.0));
+    assert_eq!(decision.action, Action::EnterLong);
+    filtered.on_signal(&decision, 105.0, 1_000);
+    assert_eq!(filter_signals.load(Ordering::SeqCst), 0);
+    assert_eq!(signal_signals.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn manager_runs_composite_from_config_text() {
+    let server = bingx_server().await;
+    let now = Utc::now().timestamp_millis();
+    server.set_klines("BTC-USDT", vec![Kline {
+        open_time: now - 300_000,
+        open: 105.0,
+        high: 106.0,
+        low: 104.0,
+        close: 105.0,
+        volume: 1.0,
+        close_time: now - 1,
+    }]);
+
+    let mut registry = StrategyRegistry::with_builtin();
+    registry.register("trend", |_| Ok(Box::new(Trend(100.0))));
+    registry.register("buy", |_| Ok(Box::new(Fixed(long(1.0, 100.0)))));
+    let manager = manager_for(&server).with_strategy_registry(registry);
+    let strategy: StrategyConfig = "filtered(trend|buy)".parse().unwrap();
+    let config = CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20).with_strategy(strategy);
+    manager.add_currency(config).await.unwrap();
+
+    manager.monitor_once().await;
+    let order = server.orders().into_iter().next().unwrap();
+    assert_eq!((order.side.as_str(), order.stop_loss.as_deref().is_some()), ("BUY", true));
+    let state = manager.strategy_state("BTC-USDT").await;
+    assert_eq!(state[0], "组合方式: 过滤");
+}
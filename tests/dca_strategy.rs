@@ -0,0 +1,157 @@
+mod common;
+
+use common::{bar_with_range, bingx_server, manager_for, FIVE_MINUTES};
+use chrono::Utc;
+use crypto_trading_bot::exchange::{OrderStatus, OrderUpdate};
+use crypto_trading_bot::strategy::{DCAStrategy, OrderIntent, StrategyRegistry, TradingStrategy};
+use crypto_trading_bot::types::{CurrencyConfig, OrderSide, StrategyConfig};
+
+fn currency() -> CurrencyConfig {
+    CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20)
+}
+
+fn fill(intent: &OrderIntent, price: f64) -> OrderUpdate {
+    OrderUpdate {
+        order_id: "1".to_string(),
+        symbol: "BTC-USDT".to_string(),
+        side: intent.side.clone(),
+        price,
+        quantity: intent.quantity,
+        filled_quantity: intent.quantity,
+        avg_price: price,
+        status: OrderStatus::Filled,
+    }
+}
+
+fn order(orders: &[OrderIntent], key: &str) -> OrderIntent {
+    orders.iter().find(|o| o.key == key).cloned().unwrap()
+}
+
+#[test]
+fn ladder_scales_deviation_and_volume_with_precision() {
+    let mut dca = DCAStrategy::new(3, 2.0, 1.0).with_scaling(2.0, 1.5);
+    dca.bind_currency(&currency());
+    dca.add_price(100.04);
+
+    // 底仓按价格精度挂在最新收盘价, 数量为最小下单量
+    let base = order(&dca.resting_orders(), "c1-base");
+    assert_eq!((&base.side, base.price, base.quantity), (&OrderSide::Buy, 100.0, 0.01));
+    assert!(dca.ladder().is_empty());
+
+    dca.on_order_filled(&base, &fill(&base, 100.0));
+    let ladder = dca.ladder();
+    assert_eq!(ladder.iter().map(|o| o.price).collect::<Vec<_>>(), vec![98.0, 94.0, 86.0]);
+    // 0.01 × 1.5² = 0.0225 按数量精度向下取整
+    assert_eq!(ladder.iter().map(|o| o.quantity).collect::<Vec<_>>(), vec![0.01, 0.015, 0.022]);
+    assert_eq!(dca.take_profit_price(), Some(101.0));
+}
+
+#[test]
+fn take_profit_follows_average_entry_and_starts_next_cycle() {
+    let mut dca = DCAStrategy::new(2, 2.0, 1.0);
+    dca.bind_currency(&currency());
+    dca.add_price(100.0);
+    let base = order(&dca.resting_orders(), "c1-base");
+    dca.on_order_filled(&base, &fill(&base, 100.0));
+
+    let safety = order(&dca.resting_orders(), "c1-so1");
+    dca.on_order_filled(&safety, &fill(&safety, 98.0));
+    assert_eq!(dca.average_price(), Some(99.0));
+    let take_profit = order(&dca.resting_orders(), "c1-tp");
+    assert_eq!((take_profit.price, take_profit.quantity, take_profit.reduce_only), (100.0, 0.02, true));
+    assert!(dca.resting_orders().iter().all(|o| o.key != "c1-so1"));
+
+    dca.on_order_filled(&take_profit, &fill(&take_profit, 100.0));
+    assert_eq!(dca.completed_cycles(), 1);
+    assert!((dca.realized_profit() - 0.02).abs() < 1e-9);
+    assert_eq!(dca.average_price(), None);
+
+    // 上一轮迟到的成交被忽略
+    let stale = OrderIntent::buy("c1-so2", 96.0, 0.015);
+    dca.on_order_filled(&stale, &fill(&stale, 96.0));
+    assert_eq!(dca.position_quantity(), 0.0);
+    assert_eq!(dca.resting_orders()[0].key, "c2-base");
+}
+
+#[test]
+fn partial_take_profit_keeps_cycle_and_reprices_remainder() {
+    let mut dca = DCAStrategy::new(2, 2.0, 1.0);
+    dca.bind_currency(&currency());
+    dca.add_price(100.0);
+    let base = order(&dca.resting_orders(), "c1-base");
+    dca.on_order_filled(&base, &fill(&base, 100.0));
+    let safety = order(&dca.resting_orders(), "c1-so1");
+    dca.on_order_filled(&safety, &fill(&safety, 98.0));
+
+    // 止盈单撤单重挂前只成交了一半
+    let take_profit = order(&dca.resting_orders(), "c1-tp");
+    let mut partial = fill(&take_profit, 100.0);
+    partial.filled_quantity = 0.01;
+    partial.status = OrderStatus::Canceled;
+    dca.on_order_filled(&take_profit, &partial);
+    assert_eq!(dca.completed_cycles(), 0);
+    assert!((dca.position_quantity() - 0.01).abs() < 1e-9);
+    assert!((dca.realized_profit() - 0.01).abs() < 1e-9);
+    assert_eq!(dca.average_price(), Some(99.0));
+
+    // 第二张安全单成交后按剩余持仓重新计算均价与止盈单
+    let safety = order(&dca.resting_orders(), "c1-so2");
+    dca.on_order_filled(&safety, &fill(&safety, 96.0));
+    assert!((dca.position_quantity() - 0.02).abs() < 1e-9);
+    assert!((dca.average_price().unwrap() - 97.5).abs() < 1e-9);
+    let take_profit = order(&dca.resting_orders(), "c1-tp");
+    assert_eq!((take_profit.price, take_profit.quantity), (98.5, 0.02));
+
+    dca.on_order_filled(&take_profit, &fill(&take_profit, 98.5));
+    assert_eq!(dca.completed_cycles(), 1);
+    assert!((dca.realized_profit() - 0.03).abs() < 1e-9);
+    assert_eq!(dca.resting_orders()[0].key, "c2-base");
+}
+
+#[test]
+fn registry_validates_dca_params() {
+    let registry = StrategyRegistry::with_builtin();
+    assert!(registry.build(&"dca".parse().unwrap()).is_ok());
+    assert!(registry.build(&"dca:safety_orders=1.5".parse().unwrap()).is_err());
+    assert!(registry.build(&"dca:take_profit_pct=0".parse().unwrap()).is_err());
+    assert!(registry.build(&"dca:safety_orders=5;deviation_pct=10;step_scale=2".parse().unwrap()).is_err());
+}
+
+#[tokio::test]
+async fn manager_runs_dca_cycle_and_reports_ladder() {
+    let server = bingx_server().await;
+    let start = Utc::now().timestamp_millis() - 12 * FIVE_MINUTES;
+    server.set_klines("BTC-USDT", (0..3).map(|i| bar_with_range(start + i * FIVE_MINUTES, 100.0, 0.0)).collect());
+    let manager = manager_for(&server);
+    let strategy: StrategyConfig = "dca:safety_orders=2;deviation_pct=2;take_profit_pct=1".parse().unwrap();
+    manager.add_currency(currency().with_strategy(strategy)).await.unwrap();
+
+    // 底仓买单挂在最新价, 立即成交; 下一轮挂出安全单和止盈单
+    manager.monitor_once().await;
+    manager.monitor_once().await;
+    let position = manager.get_currency_status("BTC-USDT").await.unwrap().current_position.unwrap();
+    assert_eq!((position.quantity, position.entry_price), (0.01, 100.0));
+    let mut prices: Vec<f64> = server.open_orders().iter().map(|o| o.price).collect();
+    prices.sort_by(f64::total_cmp);
+    assert_eq!(prices, vec![96.0, 98.0, 101.0]);
+
+    // 安全单成交后按新均价重挂止盈单
+    server.set_price("BTC-USDT", 97.5);
+    manager.monitor_once().await;
+    let position = manager.get_currency_status("BTC-USDT").await.unwrap().current_position.unwrap();
+    assert_eq!((position.quantity, position.entry_price), (0.02, 99.0));
+    let take_profit = server.open_orders().into_iter().find(|o| o.side == "SELL").unwrap();
+    assert_eq!((take_profit.price, take_profit.quantity), (100.0, 0.02));
+    let state = manager.strategy_state("BTC-USDT").await;
+    assert!(state.contains(&"安全单 1: 0.01 @ 98 (已成交)".to_string()));
+    assert!(state.contains(&"安全单 2: 0.015 @ 96 (挂单中)".to_string()));
+
+    // 止盈成交: 平仓, 撤销剩余安全单, 开始下一轮
+    server.set_price("BTC-USDT", 100.5);
+    manager.monitor_once().await;
+    assert!(manager.get_currency_status("BTC-USDT").await.unwrap().current_position.is_none());
+    let open = server.open_orders();
+    assert_eq!(open.len(), 1);
+    assert_eq!((open[0].side.as_str(), open[0].price), ("BUY", 100.0));
+    assert!(manager.strategy_state("BTC-USDT").await[0].contains("已完成 1 轮"));
+}
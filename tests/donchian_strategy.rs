@@ -0,0 +1,177 @@
+mod common;
+
+use common::{bar_with_range, bingx_server, manager_for, position, FIVE_MINUTES};
+use chrono::Utc;
+use crypto_trading_bot::strategy::{Action, DonchianStrategy, MACDStrategy, StrategyContext, StrategyRegistry, TradingStrategy};
+use crypto_trading_bot::strategy::filters::FilterSet;
+use crypto_trading_bot::trading::backtest::{Backtest, ExitReason};
+use crypto_trading_bot::types::{CurrencyConfig, Interval, Kline, OrderSide, Position, StrategyConfig};
+
+fn currency() -> CurrencyConfig {
+    CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 2, 3, 5.0, 20).with_max_size_multiplier(10.0)
+}
+
+// 在 [99, 101] 区间内横盘的K线
+fn ranging(strategy: &mut DonchianStrategy, bars: usize) {
+    for i in 0..bars {
+        strategy.add_candle(Interval::FiveMinutes, &bar_with_range(i as i64 * FIVE_MINUTES, 100.0, 2.0));
+    }
+}
+
+#[test]
+fn breakout_sets_atr_stops_and_scales_size_with_volatility() {
+    let mut donchian = DonchianStrategy::new(5, 3, 3);
+    donchian.bind_currency(&currency());
+    ranging(&mut donchian, 5);
+    assert!(!donchian.is_ready());
+    ranging(&mut donchian, 6);
+    assert!(donchian.is_ready());
+    let ctx = StrategyContext::new("BTC-USDT", 100.0);
+    assert_eq!(donchian.evaluate(&ctx).action, Action::Hold);
+
+    donchian.add_candle(Interval::FiveMinutes, &bar_with_range(100 * FIVE_MINUTES, 102.0, 2.0));
+    let channel = donchian.channel(5).unwrap();
+    assert_eq!((channel.lower, channel.upper), (99.0, 101.0));
+    let atr = donchian.atr().unwrap();
+    let decision = donchian.evaluate(&ctx);
+    assert_eq!(decision.action, Action::EnterLong);
+    assert!((decision.stop_loss.unwrap() - (102.0 - 2.0 * atr)).abs() < 1e-9);
+    assert!((decision.take_profit.unwrap() - (102.0 + 6.0 * atr)).abs() < 1e-9);
+    // ATR 约为价格的 2%, 超过 1% 的目标波动率, 仓位减半左右
+    let full = currency().order_quantity(decision.confidence);
+    let scaled = decision.quantity.unwrap();
+    assert!(scaled < full && scaled >= 0.01, "{} / {}", scaled, full);
+
+    // 波动率翻倍时仓位更小
+    let mut volatile = DonchianStrategy::new(5, 3, 3);
+    volatile.bind_currency(&currency());
+    for i in 0..11 {
+        volatile.add_candle(Interval::FiveMinutes, &bar_with_range(i * FIVE_MINUTES, 100.0, 4.0));
+    }
+    volatile.add_candle(Interval::FiveMinutes, &bar_with_range(11 * FIVE_MINUTES, 104.0, 4.0));
+    let decision = volatile.evaluate(&ctx);
+    assert_eq!(decision.action, Action::EnterLong);
+    assert!(decision.quantity.unwrap() < scaled);
+}
+
+#[test]
+fn fixed_risk_sizing_divides_by_stop_distance() {
+    let mut donchian = DonchianStrategy::new(5, 3, 3).with_sizing(10.0, 1.0);
+    donchian.bind_currency(&currency());
+    ranging(&mut donchian, 11);
+    donchian.add_candle(Interval::FiveMinutes, &bar_with_range(11 * FIVE_MINUTES, 97.0, 2.0));
+    let atr = donchian.atr().unwrap();
+
+    let decision = donchian.evaluate(&StrategyContext::new("BTC-USDT", 97.0));
+    assert_eq!(decision.action, Action::EnterShort);
+    assert!(decision.stop_loss.unwrap() > 97.0);
+    let expected = (10.0 / (2.0 * atr) * 1000.0).floor() / 1000.0;
+    assert_eq!(decision.quantity, Some(expected));
+}
+
+#[test]
+fn exits_on_opposite_channel_and_replays_deterministically() {
+    // 先横盘, 再上涨 10 根, 最后回落
+    let closes: Vec<f64> = std::iter::repeat_n(100.0, 11)
+        .chain((1..=10).map(|i| 100.0 + 1.5 * i as f64))
+        .chain((1..=6).map(|i| 115.0 - 2.0 * i as f64))
+        .collect();
+    let run = || {
+        let mut donchian = DonchianStrategy::new(5, 3, 3);
+        let mut holding: Option<Position> = None;
+        let mut trades = Vec::new();
+        for (i, &close) in closes.iter().enumerate() {
+            donchian.add_candle(Interval::FiveMinutes, &bar_with_range(i as i64 * FIVE_MINUTES, close, 2.0));
+            if !donchian.is_ready() {
+                continue;
+            }
+            let decision = donchian.evaluate(&StrategyContext::new("BTC-USDT", close).with_position(holding.as_ref()));
+            match decision.action {
+                Action::EnterLong => holding = Some(position("BTC-USDT", OrderSide::Buy)),
+                Action::EnterShort => holding = Some(position("BTC-USDT", OrderSide::Sell)),
+                Action::Exit => holding = None,
+                Action::Hold => continue,
+            }
+            trades.push((i, decision.action));
+        }
+        trades
+    };
+
+    let trades = run();
+    assert_eq!(trades[0], (11, Action::EnterLong));
+    let (exit_at, exit) = trades[1];
+    assert_eq!(exit, Action::Exit);
+    assert!(exit_at > 20);
+    // 只依赖K线数据, 重复回放结果一致
+    assert_eq!(run(), trades);
+}
+
+#[test]
+fn backtest_compares_breakouts_with_macd_on_trends() {
+    // 横盘后上涨 40 根 (每 4 根回调一次), 再横盘后下跌 40 根
+    let zigzag = |i: usize| if i % 4 == 3 { -1.0 } else { 2.0 };
+    let mut closes = vec![100.0; 20];
+    for i in 0..40 {
+        closes.push(closes.last().unwrap() + zigzag(i));
+    }
+    closes.extend(std::iter::repeat_n(*closes.last().unwrap(), 20));
+    for i in 0..40 {
+        closes.push(closes.last().unwrap() - zigzag(i));
+    }
+    let klines: Vec<Kline> = closes.iter().enumerate()
+        .map(|(i, &close)| bar_with_range(i as i64 * FIVE_MINUTES, close, 2.0))
+        .collect();
+
+    let backtest = Backtest::new(currency());
+    let mut donchian = DonchianStrategy::new(10, 5, 5);
+    let breakouts = backtest.run(&mut donchian, &klines);
+    let mut macd = MACDStrategy::new(12, 26, 9).with_filters(FilterSet::new());
+    let momentum = backtest.run(&mut macd, &klines);
+    // 两个方向的趋势都被突破捕获, 盈利高于 MACD
+    assert!(breakouts.pnl() > 0.0 && momentum.pnl() > 0.0);
+    assert!(breakouts.pnl() > momentum.pnl(), "{:?} / {:?}", breakouts, momentum);
+    assert!(breakouts.trade_count() > momentum.trade_count());
+    assert!(breakouts.trades.iter().any(|t| t.side == OrderSide::Buy && t.reason == ExitReason::TakeProfit));
+    assert!(breakouts.trades.iter().any(|t| t.side == OrderSide::Sell && t.reason == ExitReason::TakeProfit));
+    assert_eq!(breakouts.bars, klines.len());
+
+    // 重复回放结果一致
+    let replay = backtest.run(&mut DonchianStrategy::new(10, 5, 5), &klines);
+    assert_eq!(replay.trades, breakouts.trades);
+}
+
+#[test]
+fn registry_validates_donchian_params() {
+    let registry = StrategyRegistry::with_builtin();
+    assert!(registry.build(&"donchian".parse().unwrap()).is_ok());
+    assert!(registry.build(&"donchian:entry=10;exit=20".parse().unwrap()).is_err());
+    assert!(registry.build(&"donchian:stop_atr=0".parse().unwrap()).is_err());
+    assert!(registry.build(&"donchian:risk=-1".parse().unwrap()).is_err());
+    assert!(registry.build(&"donchian:channel=20".parse().unwrap()).is_err());
+}
+
+#[tokio::test]
+async fn manager_opens_breakout_with_strategy_size() {
+    let server = bingx_server().await;
+    let current = Interval::FiveMinutes.align(Utc::now().timestamp_millis());
+    let mut klines: Vec<Kline> = (0..20).map(|i| bar_with_range(current - (21 - i) * FIVE_MINUTES, 100.0, 2.0)).collect();
+    server.set_klines("BTC-USDT", klines.clone());
+    server.set_price("BTC-USDT", 100.0);
+    let manager = manager_for(&server);
+    let strategy: StrategyConfig = "donchian:entry=10;exit=5;atr=5;risk=1".parse().unwrap();
+    manager.add_currency(currency().with_strategy(strategy)).await.unwrap();
+    manager.monitor_once().await;
+    assert!(server.orders().is_empty());
+
+    // 最新收盘K线突破通道: 按风险金额 / 止损距离开多
+    klines.push(bar_with_range(current - FIVE_MINUTES, 103.0, 2.0));
+    server.set_klines("BTC-USDT", klines);
+    server.set_price("BTC-USDT", 103.0);
+    manager.monitor_once().await;
+    let order = server.orders().into_iter().next().unwrap();
+    assert_eq!((order.side.as_str(), order.position_side.as_str()), ("BUY", "LONG"));
+    assert!(order.quantity > 0.01);
+    let position = manager.get_currency_status("BTC-USDT").await.unwrap().current_position.unwrap();
+    assert_eq!(position.quantity, order.quantity);
+    assert!(manager.strategy_state("BTC-USDT").await.iter().any(|line| line.starts_with("入场通道 (10)")));
+}
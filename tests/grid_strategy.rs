@@ -0,0 +1,259 @@
+mod common;
+
+use common::{bar_with_range, binance_client, binance_server, bingx_client, bingx_server, manager_for, FIVE_MINUTES};
+use chrono::Utc;
+use crypto_trading_bot::exchange::mock::MockBingXServer;
+use crypto_trading_bot::exchange::{Exchange, LimitOrder, OrderStatus, OrderUpdate};
+use crypto_trading_bot::strategy::{
+    Action, Decision, GridStrategy, OrderIntent, StrategyContext, StrategyRegistry, TradingStrategy,
+};
+use crypto_trading_bot::types::{CurrencyConfig, MarketType, OrderSide, Position, StrategyConfig};
+use std::sync::{Arc, Mutex};
+
+fn fill(intent: &OrderIntent, price: f64) -> OrderUpdate {
+    OrderUpdate {
+        order_id: "1".to_string(),
+        symbol: "BTC-USDT".to_string(),
+        side: intent.side.clone(),
+        price,
+        quantity: intent.quantity,
+        filled_quantity: intent.quantity,
+        avg_price: price,
+        status: OrderStatus::Filled,
+    }
+}
+
+fn limit(symbol: &str, side: OrderSide, price: f64) -> LimitOrder {
+    LimitOrder {
+        symbol: symbol.to_string(),
+        market: MarketType::Swap,
+        side,
+        price,
+        quantity: 0.01,
+        reduce_only: false,
+    }
+}
+
+// 挂出测试指定的限价单, 从不发出信号
+struct Resting {
+    wanted: Arc<Mutex<Vec<OrderIntent>>>,
+}
+
+impl TradingStrategy for Resting {
+    fn name(&self) -> &str {
+        "resting"
+    }
+
+    fn add_price(&mut self, _price: f64) {}
+
+    fn evaluate(&self, _ctx: &StrategyContext) -> Decision {
+        Decision::hold()
+    }
+
+    fn resting_orders(&self) -> Vec<OrderIntent> {
+        self.wanted.lock().unwrap().clone()
+    }
+}
+
+fn grid_config() -> CurrencyConfig {
+    let strategy = StrategyConfig::new("grid")
+        .with_param("lower", 90.0)
+        .with_param("upper", 110.0)
+        .with_param("levels", 5.0);
+    CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20).with_strategy(strategy)
+}
+
+// K线均已收盘 (后续追加的K线同样位于过去)
+async fn start_server() -> (MockBingXServer, i64) {
+    let server = bingx_server().await;
+    let start = Utc::now().timestamp_millis() - 12 * FIVE_MINUTES;
+    server.set_klines("BTC-USDT", [100.0, 100.0, 101.0, 101.0].iter()
+        .enumerate()
+        .map(|(i, &close)| bar_with_range(start + i as i64 * FIVE_MINUTES, close, 0.0))
+        .collect());
+    (server, start)
+}
+
+#[test]
+fn grid_reposts_opposite_order_and_tracks_profit() {
+    let mut grid = GridStrategy::new(90.0, 110.0, 5).with_quantity(0.5);
+    assert_eq!(grid.lines(), &[90.0, 95.0, 100.0, 105.0, 110.0]);
+    assert!(grid.resting_orders().is_empty());
+
+    // 只在当前价下方挂买单
+    grid.add_price(101.0);
+    let orders = grid.resting_orders();
+    assert_eq!(orders.iter().map(|o| o.price).collect::<Vec<_>>(), vec![90.0, 95.0, 100.0]);
+    assert!(orders.iter().all(|o| o.side == OrderSide::Buy && o.quantity == 0.5));
+
+    // 买单成交后在上一条网格线挂卖单
+    let buy = orders[2].clone();
+    grid.on_order_filled(&buy, &fill(&buy, 100.0));
+    let sell = grid.resting_orders().into_iter().find(|o| o.side == OrderSide::Sell).unwrap();
+    assert_eq!(sell.price, 105.0);
+    assert!(sell.reduce_only);
+    assert_eq!(grid.filled_cells(), 1);
+
+    // 卖单成交后记录利润并重新挂回买单
+    grid.on_order_filled(&sell, &fill(&sell, 105.0));
+    assert!((grid.grid_profit() - 2.5).abs() < 1e-9);
+    assert_eq!(grid.round_trips(), 1);
+    assert_eq!(grid.resting_orders().len(), 3);
+    assert!(grid.describe().iter().any(|line| line.contains("2.5000 (1 次买卖)")));
+}
+
+#[test]
+fn grid_stops_when_price_leaves_range() {
+    let mut grid = GridStrategy::new(90.0, 110.0, 5).with_stop_pct(5.0);
+    grid.add_price(86.0);
+    assert!(grid.stop_reason().is_none());
+
+    grid.add_price(85.0);
+    assert!(grid.stop_reason().unwrap().contains("跌破"));
+    assert!(grid.resting_orders().is_empty());
+
+    // 回到区间内也不再恢复挂单
+    grid.add_price(100.0);
+    assert!(grid.resting_orders().is_empty());
+
+    let position = Position {
+        symbol: "BTC-USDT".to_string(),
+        side: OrderSide::Buy,
+        quantity: 0.01,
+        entry_price: 95.0,
+        unrealized_pnl: 0.0,
+        leverage: 20,
+    };
+    let ctx = StrategyContext::new("BTC-USDT", 100.0);
+    assert_eq!(grid.evaluate(&ctx).action, Action::Hold);
+    assert_eq!(grid.evaluate(&ctx.with_position(Some(&position))).action, Action::Exit);
+}
+
+#[test]
+fn registry_validates_grid_params() {
+    let registry = StrategyRegistry::with_builtin();
+    assert!(registry.build(&grid_config().strategy).is_ok());
+    assert!(registry.build(&StrategyConfig::new("grid")).is_err());
+    let inverted = StrategyConfig::new("grid").with_param("lower", 110.0).with_param("upper", 90.0);
+    assert!(registry.build(&inverted).is_err());
+    let single = grid_config().strategy.with_param("levels", 1.0);
+    assert!(registry.build(&single).is_err());
+}
+
+#[tokio::test]
+async fn limit_orders_fill_and_cancel_on_both_venues() {
+    let (bingx, _) = start_server().await;
+    bingx.set_price("BTC-USDT", 100.0);
+    let client = bingx_client(&bingx);
+
+    let resting = client.place_limit_order(limit("BTC-USDT", OrderSide::Buy, 95.0)).await.unwrap();
+    assert_eq!(resting.status, OrderStatus::New);
+    assert_eq!(bingx.open_orders()[0].position_side, "LONG");
+    bingx.set_price("BTC-USDT", 94.5);
+    let update = client.get_order("BTC-USDT", MarketType::Swap, &resting.order_id).await.unwrap();
+    assert_eq!(update.status, OrderStatus::Filled);
+    assert_eq!((update.filled_quantity, update.fill_price()), (0.01, 95.0));
+
+    let resting = client.place_limit_order(limit("BTC-USDT", OrderSide::Sell, 99.0)).await.unwrap();
+    Exchange::cancel_order(&client, "BTC-USDT", MarketType::Swap, &resting.order_id).await.unwrap();
+    let update = client.get_order("BTC-USDT", MarketType::Swap, &resting.order_id).await.unwrap();
+    assert_eq!(update.status, OrderStatus::Canceled);
+    assert!(bingx.open_orders().is_empty());
+
+    let binance = binance_server().await;
+    binance.set_price("BTCUSDT", 60_000.0);
+    let client = binance_client(&binance);
+    let resting = client.place_limit_order(limit("BTC-USDT", OrderSide::Buy, 59_000.0)).await.unwrap();
+    assert_eq!((resting.status, resting.price), (OrderStatus::New, 59_000.0));
+    binance.set_price("BTCUSDT", 58_900.0);
+    let update = client.get_order("BTC-USDT", MarketType::Swap, &resting.order_id).await.unwrap();
+    assert_eq!(update.status, OrderStatus::Filled);
+    let resting = client.place_limit_order(limit("BTC-USDT", OrderSide::Buy, 58_000.0)).await.unwrap();
+    client.cancel_order("BTC-USDT", MarketType::Swap, &resting.order_id).await.unwrap();
+    assert!(binance.open_orders().is_empty());
+}
+
+#[tokio::test]
+async fn manager_runs_grid_round_trip() {
+    let (server, _) = start_server().await;
+    let manager = manager_for(&server);
+    manager.add_currency(grid_config()).await.unwrap();
+
+    manager.monitor_once().await;
+    let mut prices: Vec<f64> = server.open_orders().iter().map(|o| o.price).collect();
+    prices.sort_by(f64::total_cmp);
+    assert_eq!(prices, vec![90.0, 95.0, 100.0]);
+    assert_eq!(manager.resting_orders("BTC-USDT").await.len(), 3);
+
+    // 买单成交: 持仓增加, 在上一条网格线挂只减仓卖单
+    server.set_price("BTC-USDT", 99.0);
+    manager.monitor_once().await;
+    let position = manager.get_currency_status("BTC-USDT").await.unwrap().current_position.unwrap();
+    assert_eq!((position.quantity, position.entry_price), (0.01, 100.0));
+    let sell = server.open_orders().into_iter().find(|o| o.side == "SELL").unwrap();
+    assert_eq!((sell.price, sell.position_side.as_str(), sell.reduce_only), (105.0, "LONG", true));
+
+    // 卖单成交: 平掉持仓并记录网格利润, 买单重新挂回
+    server.set_price("BTC-USDT", 105.5);
+    manager.monitor_once().await;
+    assert!(manager.get_currency_status("BTC-USDT").await.unwrap().current_position.is_none());
+    assert!(manager.strategy_state("BTC-USDT").await.iter().any(|line| line.contains("0.0500 (1 次买卖)")));
+    assert_eq!(server.open_orders().iter().filter(|o| o.side == "BUY").count(), 3);
+}
+
+#[tokio::test]
+async fn manager_grid_safety_stop_cancels_orders_and_exits() {
+    let (server, start) = start_server().await;
+    let manager = manager_for(&server);
+    manager.add_currency(grid_config()).await.unwrap();
+    manager.monitor_once().await;
+
+    // 价格跌穿整个网格: 买单全部成交后收盘价触发止损
+    server.set_price("BTC-USDT", 80.0);
+    server.push_kline("BTC-USDT", bar_with_range(start + 5 * FIVE_MINUTES, 80.0, 0.0));
+    manager.monitor_once().await;
+
+    assert!(server.open_orders().is_empty());
+    assert!(manager.resting_orders("BTC-USDT").await.is_empty());
+    assert!(manager.get_currency_status("BTC-USDT").await.unwrap().current_position.is_none());
+    let exit = server.orders().into_iter().find(|o| o.order_type == "MARKET").unwrap();
+    assert_eq!((exit.side.as_str(), exit.quantity), ("SELL", 0.03));
+    assert!(manager.strategy_state("BTC-USDT").await.iter().any(|line| line.contains("已停止")));
+}
+
+#[tokio::test]
+async fn manager_keeps_partial_fills_of_cancelled_orders() {
+    let (server, _) = start_server().await;
+    server.set_price("BTC-USDT", 101.0);
+    let wanted = Arc::new(Mutex::new(vec![
+        OrderIntent::buy("a", 95.0, 0.01),
+        OrderIntent::buy("b", 90.0, 0.01),
+    ]));
+    let intents = wanted.clone();
+    let mut registry = StrategyRegistry::with_builtin();
+    registry.register("resting", move |_| Ok(Box::new(Resting { wanted: intents.clone() })));
+    let manager = manager_for(&server).with_strategy_registry(registry);
+    manager.add_currency(CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.001, 1, 3, 5.0, 20)
+        .with_strategy(StrategyConfig::new("resting"))).await.unwrap();
+    manager.monitor_once().await;
+    let order_id = |price: f64| server.open_orders().iter().find(|o| o.price == price).unwrap().order_id;
+    let (first, second) = (order_id(95.0), order_id(90.0));
+
+    // 部分成交后被交易所取消: 已成交部分计入持仓
+    server.partially_fill(first, 0.004);
+    server.expire_order(first);
+    wanted.lock().unwrap().remove(0);
+    manager.monitor_once().await;
+    let position = manager.get_currency_status("BTC-USDT").await.unwrap().current_position.unwrap();
+    assert_eq!((position.quantity, position.entry_price), (0.004, 95.0));
+
+    // 部分成交后策略不再需要而被撤销: 已成交部分同样计入持仓
+    server.partially_fill(second, 0.006);
+    wanted.lock().unwrap().clear();
+    manager.monitor_once().await;
+    assert!(server.open_orders().is_empty());
+    assert!(manager.resting_orders("BTC-USDT").await.is_empty());
+    let position = manager.get_currency_status("BTC-USDT").await.unwrap().current_position.unwrap();
+    assert!((position.quantity - 0.01).abs() < 1e-9);
+    assert!((position.entry_price - 92.0).abs() < 1e-9);
+}
@@ -0,0 +1,183 @@
+use crypto_trading_bot::indicators::{
+    Bar, BollingerBands, Indicator, MACDIndicator, RingBuffer, Stochastic, SuperTrend,
+    ADX, ATR, EMA, OBV, RSI, SMA, VWAP, WMA,
+};
+
+fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+    assert!((actual - expected).abs() <= tolerance, "期望 {}, 实际 {}", expected, actual);
+}
+
+fn feed<I: Indicator<Input = f64>>(indicator: &mut I, prices: &[f64]) -> Vec<Option<I::Output>> {
+    prices.iter().map(|&price| indicator.update(price)).collect()
+}
+
+#[test]
+fn ring_buffer_keeps_latest_values_in_order() {
+    let mut buffer = RingBuffer::new(3);
+    assert_eq!(buffer.push(1), None);
+    buffer.push(2);
+    buffer.push(3);
+    assert_eq!(buffer.push(4), Some(1));
+    assert_eq!(buffer.iter().collect::<Vec<_>>(), vec![2, 3, 4]);
+    assert_eq!((buffer.first(), buffer.last()), (Some(2), Some(4)));
+    buffer.clear();
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn moving_averages_match_reference_values() {
+    let prices: Vec<f64> = (1..=10).map(f64::from).collect();
+
+    let mut sma = SMA::new(3);
+    let values = feed(&mut sma, &prices);
+    assert_eq!(values[1], None);
+    assert_eq!(values[2], Some(2.0));
+    assert_eq!(sma.value(), Some(9.0));
+
+    // 以 SMA 作为初始值, 线性序列的 EMA 固定滞后一个周期
+    let mut ema = EMA::new(3);
+    let values = feed(&mut ema, &prices);
+    assert_eq!(values[2], Some(2.0));
+    assert_eq!(ema.value(), Some(9.0));
+
+    let mut wma = WMA::new(3);
+    feed(&mut wma, &prices);
+    assert_close(wma.value().unwrap(), (8.0 + 2.0 * 9.0 + 3.0 * 10.0) / 6.0, 1e-9);
+
+    assert_eq!(sma.warm_up(), 3);
+    sma.reset();
+    assert!(!sma.is_ready());
+}
+
+#[test]
+fn macd_is_built_from_streaming_emas() {
+    let prices: Vec<f64> = (0..60).map(|i| 100.0 + (i as f64 * 0.3).sin() * 5.0).collect();
+    let mut macd = MACDIndicator::new(12, 26, 9);
+    assert_eq!(macd.warm_up(), 34);
+
+    let values = feed(&mut macd, &prices);
+    assert!(values[32].is_none());
+    assert!(values[33].is_some());
+
+    let (mut fast, mut slow, mut signal) = (EMA::new(12), EMA::new(26), EMA::new(9));
+    let mut expected = None;
+    for &price in &prices {
+        if let (Some(f), Some(s)) = (fast.update(price), slow.update(price)) {
+            expected = signal.update(f - s).map(|sig| (f - s, sig));
+        }
+    }
+    let (line, sig) = expected.unwrap();
+    let value = macd.value().unwrap();
+    assert_close(value.macd, line, 1e-12);
+    assert_close(value.signal, sig, 1e-12);
+    assert_close(value.histogram, line - sig, 1e-12);
+}
+
+#[test]
+fn rsi_matches_wilder_reference() {
+    // StockCharts RSI(14) 示例数据 (期望值为未经四舍五入的 Wilder 平滑结果)
+    let prices = [
+        44.34, 44.09, 44.15, 43.61, 44.33, 44.83, 45.10, 45.42, 45.84, 46.08,
+        45.89, 46.03, 45.61, 46.28, 46.28, 46.00, 46.03, 46.41, 46.22, 45.64,
+    ];
+    let mut rsi = RSI::new(14);
+    assert_eq!(rsi.warm_up(), 15);
+    let values = feed(&mut rsi, &prices);
+    assert!(values[13].is_none());
+
+    let expected = [70.46, 66.25, 66.48, 69.35, 66.29, 57.92];
+    for (value, expected) in values[14..].iter().zip(expected) {
+        assert_close(value.unwrap(), expected, 0.01);
+    }
+}
+
+#[test]
+fn bollinger_bands_use_population_deviation_without_drift() {
+    let mut bands = BollingerBands::new(5, 2.0);
+    let value = feed(&mut bands, &[1.0, 2.0, 3.0, 4.0, 5.0])[4].unwrap();
+    assert_close(value.middle, 3.0, 1e-12);
+    assert_close(value.upper, 3.0 + 2.0 * 2f64.sqrt(), 1e-12);
+    assert_close(value.percent_b(3.0), 0.5, 1e-12);
+
+    // 长序列上滑动更新与直接计算一致
+    let prices: Vec<f64> = (0..5000).map(|i| 60_000.0 + (i as f64 * 0.7).sin() * 300.0).collect();
+    let mut bands = BollingerBands::new(20, 2.0);
+    feed(&mut bands, &prices);
+    let window = &prices[prices.len() - 20..];
+    let mean = window.iter().sum::<f64>() / 20.0;
+    let std_dev = (window.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / 20.0).sqrt();
+    let value = bands.value().unwrap();
+    assert_close(value.middle, mean, 1e-6);
+    assert_close(value.upper - value.middle, 2.0 * std_dev, 1e-6);
+}
+
+#[test]
+fn atr_stochastic_and_adx_follow_bar_data() {
+    // 每根K线上涨 1, 振幅 1: 真实波幅恒为 1, 只有向上的方向变动
+    let bars: Vec<Bar> = (0..30).map(|i| {
+        let low = i as f64;
+        Bar::new(low + 1.0, low, low + 1.0, 10.0)
+    }).collect();
+
+    let mut atr = ATR::new(14);
+    let mut adx = ADX::new(14);
+    let mut stochastic = Stochastic::new(5, 3);
+    for (i, bar) in bars.iter().enumerate() {
+        let atr_value = atr.update(*bar);
+        assert_eq!(atr_value.is_some(), i + 1 >= atr.warm_up());
+        let adx_value = adx.update(*bar);
+        assert_eq!(adx_value.is_some(), i + 1 >= adx.warm_up());
+        stochastic.update(*bar);
+    }
+    assert_close(atr.value().unwrap(), 1.0, 1e-12);
+    let adx_value = adx.value().unwrap();
+    assert_close(adx_value.adx, 100.0, 1e-9);
+    assert_close(adx_value.plus_di, 100.0, 1e-9);
+    assert_eq!(adx_value.minus_di, 0.0);
+    // 收盘价处于窗口最高点
+    let value = stochastic.value().unwrap();
+    assert_close(value.k, 100.0, 1e-12);
+    assert_close(value.d, 100.0, 1e-12);
+
+    let mut stochastic = Stochastic::new(3, 1);
+    stochastic.update(Bar::new(10.0, 8.0, 9.0, 1.0));
+    stochastic.update(Bar::new(12.0, 9.0, 11.0, 1.0));
+    let value = stochastic.update(Bar::new(11.0, 7.0, 8.0, 1.0)).unwrap();
+    assert_close(value.k, (8.0 - 7.0) / (12.0 - 7.0) * 100.0, 1e-12);
+}
+
+#[test]
+fn volume_indicators_accumulate_volume() {
+    let bars = [
+        Bar::new(11.0, 9.0, 10.0, 100.0),
+        Bar::new(12.0, 10.0, 11.0, 200.0),
+        Bar::new(11.0, 9.0, 10.0, 50.0),
+        Bar::new(11.0, 9.0, 10.0, 70.0),
+    ];
+
+    let mut obv = OBV::new();
+    let values: Vec<f64> = bars.iter().map(|b| obv.update(*b).unwrap()).collect();
+    assert_eq!(values, vec![0.0, 200.0, 150.0, 150.0]);
+
+    let mut vwap = VWAP::new(2);
+    assert!(vwap.update(bars[0]).is_none());
+    assert_close(vwap.update(bars[1]).unwrap(), (10.0 * 100.0 + 11.0 * 200.0) / 300.0, 1e-12);
+    assert_close(vwap.update(bars[2]).unwrap(), (11.0 * 200.0 + 10.0 * 50.0) / 250.0, 1e-12);
+}
+
+#[test]
+fn supertrend_flips_when_price_breaks_the_band() {
+    let mut supertrend = SuperTrend::new(3, 2.0);
+    let mut value = None;
+    for i in 0..10 {
+        let close = 100.0 + i as f64;
+        value = supertrend.update(Bar::new(close + 0.5, close - 0.5, close, 1.0));
+    }
+    let up = value.unwrap();
+    assert!(up.uptrend);
+    assert!(up.value < 109.0);
+
+    let down = supertrend.update(Bar::new(100.0, 90.0, 90.0, 1.0)).unwrap();
+    assert!(!down.uptrend);
+    assert!(down.value > 90.0);
+}
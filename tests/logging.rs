@@ -0,0 +1,21 @@
+use crypto_trading_bot::logging::{redact_url, REDACTED};
+
+#[test]
+fn redacts_signature_and_keeps_other_params() {
+    let url = "https://open-api-vst.bingx.com/openApi/swap/v2/trade/order?symbol=BTC-USDT&timestamp=1&signature=abcdef0123";
+    let redacted = redact_url(url);
+
+    assert!(!redacted.contains("abcdef0123"));
+    assert!(redacted.contains("symbol=BTC-USDT"));
+    let signature = reqwest::Url::parse(&redacted).unwrap()
+        .query_pairs()
+        .find(|(k, _)| k == "signature")
+        .map(|(_, v)| v.into_owned());
+    assert_eq!(signature.as_deref(), Some(REDACTED));
+}
+
+#[test]
+fn leaves_unsigned_urls_untouched() {
+    let url = "https://open-api-vst.bingx.com/openApi/swap/v1/ticker/price?symbol=BTC-USDT";
+    assert_eq!(redact_url(url), url);
+}
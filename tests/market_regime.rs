@@ -0,0 +1,260 @@
+mod common;
+
+use common::{bar_with_range, bingx_server, manager_for};
+use chrono::Utc;
+use crypto_trading_bot::strategy::{
+    parse_rules, Action, Decision, MarketDepth, MarketTicker, RegimeDetector, RuleStrategy, StrategyContext,
+    StrategyRegistry, TradingStrategy,
+};
+use crypto_trading_bot::types::{CurrencyConfig, Kline, OrderSide, Regime, StrategyConfig};
+
+// 始终发出开多信号
+struct AlwaysBuy;
+
+impl TradingStrategy for AlwaysBuy {
+    fn name(&self) -> &str {
+        "always_buy"
+    }
+
+    fn add_price(&mut self, _price: f64) {}
+
+    fn evaluate(&self, _ctx: &StrategyContext) -> Decision {
+        Decision::enter_long(1.0)
+    }
+}
+
+// 开多主交易对并做空对冲腿
+struct LongPrimaryShortLeg;
+
+impl TradingStrategy for LongPrimaryShortLeg {
+    fn name(&self) -> &str {
+        "long_primary_short_leg"
+    }
+
+    fn required_legs(&self) -> usize {
+        1
+    }
+
+    fn add_price(&mut self, _price: f64) {}
+
+    fn evaluate(&self, _ctx: &StrategyContext) -> Decision {
+        Decision::enter_long(1.0).with_leg("ETH-USDT", Decision::enter_short(1.0))
+    }
+}
+
+// 在 100 与 101 之间来回波动 (真实波幅恒为 2)
+fn ranging_bars(count: usize, end_time: i64) -> Vec<Kline> {
+    (0..count)
+        .map(|i| {
+            let open_time = end_time - (count - i) as i64 * 300_000;
+            bar_with_range(open_time, if i % 2 == 0 { 100.0 } else { 101.0 }, 2.0)
+        })
+        .collect()
+}
+
+fn ticker(bid_price: f64, ask_price: f64) -> MarketTicker {
+    MarketTicker {
+        price_change_percent: 0.5,
+        high_price: 102.0,
+        low_price: 99.0,
+        last_price: 100.5,
+        volume: 1000.0,
+        bid_price,
+        ask_price,
+    }
+}
+
+#[test]
+fn classifies_trend_range_and_volatility() {
+    let narrow = ticker(100.0, 100.05);
+
+    let mut trending = RegimeDetector::new();
+    assert_eq!(trending.lookback(), 33);
+    for i in 0..40 {
+        trending.update(&bar_with_range(i * 300_000, 100.0 + i as f64, 1.0));
+    }
+    assert!(trending.is_ready());
+    let reading = trending.classify(140.0, None, Some(&narrow)).unwrap();
+    assert_eq!(reading.regime, Regime::Trending);
+    assert!(reading.adx.unwrap() > 25.0);
+
+    let mut detector = RegimeDetector::new();
+    for kline in ranging_bars(32, 0) {
+        detector.update(&kline);
+    }
+    assert!(!detector.is_ready());
+    assert!(detector.classify(100.0, None, Some(&narrow)).is_none());
+    for kline in ranging_bars(30, 0) {
+        detector.update(&kline);
+    }
+    let reading = detector.observe(100.0, None, Some(&narrow)).unwrap();
+    assert_eq!(reading.regime, Regime::Ranging, "{}", reading);
+    assert_eq!(detector.reading(), Some(&reading));
+
+    // 波幅突然放大, ATR 处于历史高位
+    for i in 0..3 {
+        detector.update(&bar_with_range(i * 300_000, 100.0, 10.0));
+    }
+    let reading = detector.classify(100.0, None, Some(&narrow)).unwrap();
+    assert_eq!(reading.regime, Regime::HighVolatility);
+    assert!(reading.atr_percentile.unwrap() >= 80.0);
+    assert!(reading.to_string().starts_with("高波动 (ADX "), "{}", reading);
+}
+
+#[test]
+fn detects_illiquid_markets_from_spread_and_depth() {
+    // 价差判断不需要等待指标预热
+    let detector = RegimeDetector::new();
+    let reading = detector.classify(100.0, None, Some(&ticker(100.0, 100.5))).unwrap();
+    assert_eq!(reading.regime, Regime::Illiquid);
+    assert!((reading.spread_pct.unwrap() - 0.5).abs() < 1e-9);
+
+    // 没有行情时使用深度的买一卖一
+    let depth = MarketDepth {
+        asks: vec![(100.02, 10.0), (103.0, 500.0)],
+        bids: vec![(99.98, 10.0)],
+    };
+    let mut detector = RegimeDetector::new().with_min_depth(5_000.0, 1.0);
+    for kline in ranging_bars(60, 0) {
+        detector.update(&kline);
+    }
+    let reading = detector.classify(100.0, Some(&depth), None).unwrap();
+    assert_eq!(reading.regime, Regime::Illiquid);
+    assert!((reading.depth_notional.unwrap() - 2_000.0).abs() < 1e-6);
+    let deep = MarketDepth {
+        asks: vec![(100.02, 30.0)],
+        bids: vec![(99.98, 30.0)],
+    };
+    assert_eq!(detector.classify(100.0, Some(&deep), None).unwrap().regime, Regime::Ranging);
+}
+
+#[test]
+fn parses_regimes_and_checks_allowed_list() {
+    assert_eq!("high_volatility".parse::<Regime>().unwrap(), Regime::HighVolatility);
+    assert_eq!(" 震荡 ".parse::<Regime>().unwrap(), Regime::Ranging);
+    assert!("calm".parse::<Regime>().unwrap_err().contains("未知的市场状态"));
+    assert_eq!(Regime::Illiquid.to_string(), "流动性不足");
+
+    let config = CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20);
+    assert!(config.allows_regime(None));
+    let config = config.with_regimes(vec![Regime::Trending]);
+    assert!(config.allows_regime(Some(Regime::Trending)));
+    assert!(!config.allows_regime(Some(Regime::Ranging)));
+    assert!(!config.allows_regime(None));
+}
+
+#[test]
+fn rules_can_reference_regime() {
+    let definitions = parse_rules("strategy trend\nenter_long when regime.trending and regime.adx > 30").unwrap();
+    let strategy = RuleStrategy::new(definitions[0].clone());
+    assert!(strategy.uses_regime());
+    let plain = parse_rules("strategy plain\nenter_long when not (price > 100 or rsi(14) < 30)").unwrap();
+    assert!(!RuleStrategy::new(plain[0].clone()).uses_regime());
+    let ctx = StrategyContext::new("BTC-USDT", 140.0);
+    let decision = strategy.evaluate(&ctx);
+    assert_eq!(decision.action, Action::Hold);
+    assert!(decision.reasons.iter().any(|r| r.contains("市场状态未知")), "{:?}", decision.reasons);
+
+    let mut detector = RegimeDetector::new();
+    for i in 0..40 {
+        detector.update(&bar_with_range(i * 300_000, 100.0 + i as f64, 1.0));
+    }
+    let reading = detector.classify(140.0, None, None).unwrap();
+    assert_eq!(strategy.evaluate(&ctx.with_regime(Some(&reading))).action, Action::EnterLong);
+
+    let err = parse_rules("strategy a\nenter_long when regime.calm").unwrap_err();
+    assert!(err.message.contains("未知的市场状态字段"), "{}", err);
+    assert!(parse_rules("strategy a\nenter_long when regime > 1").unwrap_err().message.contains("regime.trending"));
+}
+
+#[tokio::test]
+async fn manager_only_enters_in_allowed_regimes() {
+    let server = bingx_server().await;
+    let now = Utc::now().timestamp_millis();
+    for symbol in ["BTC-USDT", "ETH-USDT"] {
+        server.set_klines(symbol, ranging_bars(60, now - 300_000));
+    }
+    // BTC 价差过大, ETH 流动性正常
+    server.set_ticker("BTC-USDT", ticker(100.0, 100.5));
+    server.set_ticker("ETH-USDT", ticker(100.0, 100.05));
+
+    let mut registry = StrategyRegistry::with_builtin();
+    registry.register("always_buy", |_| Ok(Box::new(AlwaysBuy)));
+    let manager = manager_for(&server).with_strategy_registry(registry);
+    for (symbol, base) in [("BTC-USDT", "BTC"), ("ETH-USDT", "ETH")] {
+        manager.add_currency(CurrencyConfig::new(symbol, base, "USDT", 0.01, 1, 3, 5.0, 20)
+            .with_strategy(StrategyConfig::new("always_buy"))
+            .with_regimes(vec![Regime::Ranging, Regime::Trending])).await.unwrap();
+    }
+    // BTC 持有空仓: 不允许开仓的市场状态下反手信号只平仓
+    manager.place_order("BTC-USDT", OrderSide::Sell, 100.0).await.unwrap();
+
+    // 回填的K线用于预热, 新收盘一根K线后才评估
+    for symbol in ["BTC-USDT", "ETH-USDT"] {
+        server.push_kline(symbol, bar_with_range(now - 300_000, 100.0, 2.0));
+    }
+    manager.monitor_once().await;
+    let orders: Vec<(String, String)> = server.orders().into_iter().map(|order| (order.symbol, order.side)).collect();
+    assert_eq!(orders.len(), 3, "{:?}", orders);
+    assert!(orders.contains(&("ETH-USDT".to_string(), "BUY".to_string())));
+    assert_eq!(orders.iter().filter(|(symbol, _)| symbol == "BTC-USDT").count(), 2);
+    assert!(manager.get_currency_status("BTC-USDT").await.unwrap().current_position.is_none());
+    assert_eq!(manager.regime("BTC-USDT").await.unwrap().regime, Regime::Illiquid);
+    assert_eq!(manager.regime("ETH-USDT").await.unwrap().regime, Regime::Ranging);
+
+    manager.remove_currency("BTC-USDT").await;
+    assert!(manager.regime("BTC-USDT").await.is_none());
+}
+
+#[tokio::test]
+async fn manager_backfills_regime_for_strategies_that_read_it() {
+    let server = bingx_server().await;
+    let now = Utc::now().timestamp_millis();
+    server.set_klines("ETH-USDT", ranging_bars(60, now - 300_000));
+    server.set_ticker("ETH-USDT", ticker(100.0, 100.05));
+
+    // 币种未限制市场状态, 但规则读取 regime.ranging
+    let mut registry = StrategyRegistry::with_builtin();
+    registry.register_rules("strategy range_buy\nenter_long when regime.ranging").unwrap();
+    let manager = manager_for(&server).with_strategy_registry(registry);
+    manager.add_currency(CurrencyConfig::new("ETH-USDT", "ETH", "USDT", 0.01, 1, 3, 5.0, 20)
+        .with_strategy(StrategyConfig::new("range_buy"))).await.unwrap();
+
+    server.push_kline("ETH-USDT", bar_with_range(now - 300_000, 100.0, 2.0));
+    manager.monitor_once().await;
+    assert_eq!(manager.regime("ETH-USDT").await.unwrap().regime, Regime::Ranging);
+    assert_eq!(server.orders().len(), 1);
+}
+
+#[tokio::test]
+async fn manager_downgrades_reversing_legs_in_disallowed_regimes() {
+    let server = bingx_server().await;
+    let now = Utc::now().timestamp_millis();
+    for symbol in ["BTC-USDT", "ETH-USDT"] {
+        server.set_klines(symbol, ranging_bars(60, now - 300_000));
+    }
+    server.set_ticker("BTC-USDT", ticker(100.0, 100.5));
+
+    let mut registry = StrategyRegistry::with_builtin();
+    registry.register("long_primary_short_leg", |_| Ok(Box::new(LongPrimaryShortLeg)));
+    let manager = manager_for(&server).with_strategy_registry(registry);
+    manager.add_currency(CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20)
+        .with_strategy(StrategyConfig::new("long_primary_short_leg"))
+        .with_leg(CurrencyConfig::new("ETH-USDT", "ETH", "USDT", 0.01, 1, 3, 5.0, 20))
+        .with_regimes(vec![Regime::Ranging, Regime::Trending])).await.unwrap();
+    // 持有空主交易对、多对冲腿的配对仓位, 反手信号在流动性不足时两条腿都只平仓
+    manager.place_order("BTC-USDT", OrderSide::Sell, 100.0).await.unwrap();
+    manager.place_order("ETH-USDT", OrderSide::Buy, 100.0).await.unwrap();
+
+    for symbol in ["BTC-USDT", "ETH-USDT"] {
+        server.push_kline(symbol, bar_with_range(now - 300_000, 100.0, 2.0));
+    }
+    manager.monitor_once().await;
+    let orders: Vec<(String, String)> = server.orders().into_iter().map(|order| (order.symbol, order.side)).collect();
+    assert_eq!(orders.len(), 4, "{:?}", orders);
+    assert!(orders.contains(&("ETH-USDT".to_string(), "SELL".to_string())));
+    assert_eq!(manager.regime("BTC-USDT").await.unwrap().regime, Regime::Illiquid);
+    for symbol in ["BTC-USDT", "ETH-USDT"] {
+        assert!(manager.get_currency_status(symbol).await.unwrap().current_position.is_none(), "{}", symbol);
+    }
+}
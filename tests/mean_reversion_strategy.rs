@@ -0,0 +1,129 @@
+mod common;
+
+use common::{bar, position, FIVE_MINUTES};
+use crypto_trading_bot::strategy::{
+    Action, MACDStrategy, MarketDepth, MeanReversionStrategy, StrategyContext, StrategyRegistry, TradingStrategy,
+};
+use crypto_trading_bot::strategy::filters::{DepthFilter, FilterSet};
+use crypto_trading_bot::trading::backtest::Backtest;
+use crypto_trading_bot::types::{CurrencyConfig, Kline, OrderSide};
+
+// 围绕 100 小幅震荡后急跌
+fn selloff() -> Vec<f64> {
+    (0..30)
+        .map(|i| if i % 2 == 0 { 100.2 } else { 99.8 })
+        .chain([99.0, 97.5, 96.0])
+        .collect()
+}
+
+fn strategy() -> MeanReversionStrategy {
+    MeanReversionStrategy::new(20, 2.0, 14)
+}
+
+fn feed(strategy: &mut dyn TradingStrategy, closes: &[f64]) {
+    for &close in closes {
+        strategy.add_price(close);
+    }
+}
+
+#[test]
+fn enters_on_band_touch_with_rsi_confirmation() {
+    let mut mean_reversion = strategy();
+    feed(&mut mean_reversion, &selloff()[..30]);
+    assert!(mean_reversion.is_ready());
+    let ctx = StrategyContext::new("BTC-USDT", 99.5);
+    assert_eq!(mean_reversion.evaluate(&ctx).action, Action::Hold);
+
+    feed(&mut mean_reversion, &selloff()[30..]);
+    let bands = mean_reversion.bands().unwrap();
+    assert!(96.0 < bands.lower && mean_reversion.rsi().unwrap() < 30.0);
+    let decision = mean_reversion.evaluate(&StrategyContext::new("BTC-USDT", 96.0));
+    assert_eq!(decision.action, Action::EnterLong);
+    assert_eq!(decision.take_profit, Some(bands.middle));
+    assert!((decision.stop_loss.unwrap() - 96.0 * 0.98).abs() < 1e-9);
+
+    // 触及下轨但 RSI 未超卖时不开仓
+    let mut strict = strategy().with_rsi_levels(10.0, 90.0);
+    feed(&mut strict, &selloff());
+    assert_eq!(strict.evaluate(&ctx).action, Action::Hold);
+}
+
+#[test]
+fn exits_at_mid_band_or_on_time_stop() {
+    let mut mean_reversion = strategy().with_max_bars(3);
+    feed(&mut mean_reversion, &selloff());
+    let entry = mean_reversion.evaluate(&StrategyContext::new("BTC-USDT", 96.0));
+    mean_reversion.on_signal(&entry, 96.0, 0);
+    let long = position("BTC-USDT", OrderSide::Buy);
+    let ctx = StrategyContext::new("BTC-USDT", 96.0).with_position(Some(&long));
+
+    // 未回到中轨, 持仓不足 3 根K线
+    feed(&mut mean_reversion, &[96.0, 96.2]);
+    assert_eq!(mean_reversion.evaluate(&ctx).action, Action::Hold);
+    feed(&mut mean_reversion, &[96.1]);
+    let decision = mean_reversion.evaluate(&ctx);
+    assert_eq!(decision.action, Action::Exit);
+    assert!(decision.reasons.iter().any(|r| r.contains("时间止损")));
+
+    // 回到中轨平仓
+    let mut mean_reversion = strategy();
+    feed(&mut mean_reversion, &selloff());
+    feed(&mut mean_reversion, &[99.0, 100.0]);
+    let decision = mean_reversion.evaluate(&ctx);
+    assert_eq!(decision.action, Action::Exit);
+    assert!(decision.reasons.iter().any(|r| r.contains("中轨")));
+}
+
+#[test]
+fn depth_imbalance_confirms_entry() {
+    let depth = |bids: f64, asks: f64| MarketDepth { bids: vec![(95.4, bids)], asks: vec![(95.6, asks)] };
+    let mut mean_reversion = strategy().with_filters(FilterSet::new().with_filter(DepthFilter::new()));
+    feed(&mut mean_reversion, &selloff());
+
+    let selling = depth(1.0, 5.0);
+    let decision = mean_reversion.evaluate(&StrategyContext::new("BTC-USDT", 96.0).with_depth(Some(&selling)));
+    assert_eq!(decision.action, Action::Hold);
+    assert!(decision.reasons.iter().any(|r| r.contains("深度未确认")));
+
+    let buying = depth(5.0, 1.0);
+    let decision = mean_reversion.evaluate(&StrategyContext::new("BTC-USDT", 96.0).with_depth(Some(&buying)));
+    assert_eq!(decision.action, Action::EnterLong);
+    assert_eq!(decision.verdicts.len(), 1);
+}
+
+#[test]
+fn registry_validates_mean_reversion_params() {
+    let registry = StrategyRegistry::with_builtin();
+    assert!(registry.build(&"mean_reversion".parse().unwrap()).is_ok());
+    assert!(registry.build(&"mean_reversion:depth_filter=1;depth_ratio=1.5".parse().unwrap()).is_ok());
+    assert!(registry.build(&"mean_reversion:oversold=70;overbought=30".parse().unwrap()).is_err());
+    assert!(registry.build(&"mean_reversion:std_dev=0".parse().unwrap()).is_err());
+    assert!(registry.build(&"mean_reversion:max_bars=-1".parse().unwrap()).is_err());
+}
+
+#[test]
+fn backtest_compares_with_macd_on_ranging_series() {
+    // 震荡行情: 反复急跌后回到 100
+    let closes: Vec<f64> = (0..4).flat_map(|_| {
+        let mut cycle = selloff();
+        cycle.extend([97.0, 98.5, 100.0, 100.5]);
+        cycle
+    }).collect();
+    let klines: Vec<Kline> = closes.iter().enumerate()
+        .map(|(i, &close)| bar(i as i64 * FIVE_MINUTES, close))
+        .collect();
+
+    let backtest = Backtest::new(CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 2, 3, 5.0, 20));
+    let mut mean_reversion = strategy();
+    let reverting = backtest.run(&mut mean_reversion, &klines);
+    let mut macd = MACDStrategy::new(12, 26, 9).with_filters(FilterSet::new());
+    let momentum = backtest.run(&mut macd, &klines);
+    // 每次急跌都在下轨买入并在中轨止盈; MACD 在震荡中追涨杀跌而亏损
+    assert!(reverting.trade_count() >= 3, "{:?}", reverting);
+    assert_eq!(reverting.win_rate(), 1.0);
+    assert!(reverting.pnl() > 0.0);
+    assert_eq!(reverting.signals, mean_reversion.signal_history().len());
+    assert!(momentum.trade_count() > 0);
+    assert!(momentum.pnl() < reverting.pnl(), "{:?}", momentum);
+    assert_eq!(momentum.signals, macd.signal_history().len());
+}
@@ -0,0 +1,215 @@
+mod common;
+
+use common::{bar, bingx_client, bingx_server, manager_for, API_KEY};
+use crypto_trading_bot::exchange::bingx::BingXClient;
+use crypto_trading_bot::exchange::ApiError;
+use crypto_trading_bot::exchange::mock::{MockBingXServer, MockFault, CODE_SYMBOL_NOT_FOUND};
+use crypto_trading_bot::strategy::{MarketDepth, MarketTicker};
+use crypto_trading_bot::types::{CurrencyConfig, Interval, OrderRequest, OrderSide, OrderType};
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const ORDER_PATH: &str = "/openApi/swap/v2/trade/order";
+
+fn market_order(symbol: &str, side: OrderSide) -> OrderRequest {
+    OrderRequest {
+        symbol: symbol.to_string(),
+        order_type: OrderType::Market,
+        side,
+        quantity: 0.01,
+        timestamp: Utc::now().timestamp_millis(),
+        price: None,
+        stop_price: None,
+        working_type: None,
+        take_profit: None,
+        stop_loss: None,
+        position_side: None,
+        reduce_only: None,
+    }
+}
+
+async fn start_server() -> MockBingXServer {
+    let server = bingx_server().await;
+    let start = Utc::now().timestamp_millis() - 5 * 300_000;
+    server.set_klines("BTC-USDT", (0..5).map(|i| bar(start + i * 300_000, 100.0 + i as f64)).collect());
+    server.set_depth("BTC-USDT", MarketDepth {
+        asks: vec![(104.5, 2.0), (105.0, 3.0)],
+        bids: vec![(103.5, 2.0), (103.0, 1.0)],
+    });
+    server.set_ticker("BTC-USDT", MarketTicker {
+        price_change_percent: 1.5,
+        high_price: 106.0,
+        low_price: 99.0,
+        last_price: 104.0,
+        volume: 1000.0,
+        bid_price: 103.5,
+        ask_price: 104.5,
+    });
+    server
+}
+
+#[tokio::test]
+async fn serves_market_data() {
+    let server = start_server().await;
+    let client = bingx_client(&server);
+
+    let klines = client.get_klines("BTC-USDT", Interval::FiveMinutes, None, None, Some(3)).await.unwrap();
+    let closes: Vec<f64> = klines.iter().map(|k| k.close).collect();
+    assert_eq!(closes, vec![104.0, 103.0, 102.0]);
+
+    let depth = client.get_depth("BTC-USDT", Some(1)).await.unwrap();
+    assert_eq!(depth.asks.len(), 1);
+    assert_eq!(depth.bids[0][0], "103.5");
+
+    let tickers = client.get_ticker(Some("BTC-USDT")).await.unwrap();
+    assert_eq!(tickers[0].last_price, "104");
+
+    assert_eq!(client.get_latest_price("BTC-USDT").await.unwrap(), 104.0);
+    assert!(client.get_latest_price("DOGE-USDT").await.is_err());
+}
+
+#[tokio::test]
+async fn latency_delays_responses_without_blocking_the_manager() {
+    let server = start_server().await;
+    let client = bingx_client(&server);
+    server.set_latency(Duration::from_millis(200));
+
+    // 每个连接各自等待, 并发请求不排队
+    let started = Instant::now();
+    let (price, depth) = tokio::join!(client.get_latest_price("BTC-USDT"), client.get_depth("BTC-USDT", Some(1)));
+    assert_eq!(price.unwrap(), 104.0);
+    assert_eq!(depth.unwrap().asks.len(), 1);
+    let elapsed = started.elapsed();
+    assert!(elapsed >= Duration::from_millis(200) && elapsed < Duration::from_millis(400), "{:?}", elapsed);
+
+    // 管理器等待交易所响应时不持有状态锁
+    let manager = manager_for(&server);
+    manager.add_currency(CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20)).await.unwrap();
+    let started = Instant::now();
+    let (_, probed_at) = tokio::join!(manager.monitor_once(), async {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(manager.get_currency_status("BTC-USDT").await.is_some());
+        started.elapsed()
+    });
+    assert!(probed_at < Duration::from_millis(200), "{:?}", probed_at);
+    assert!(started.elapsed() >= Duration::from_millis(400));
+
+    server.set_latency(Duration::ZERO);
+    let started = Instant::now();
+    client.get_latest_price("BTC-USDT").await.unwrap();
+    assert!(started.elapsed() < Duration::from_millis(200));
+}
+
+#[tokio::test]
+async fn rejects_bad_signature() {
+    let server = start_server().await;
+    let client = BingXClient::with_base_url(API_KEY.to_string(), "wrong".to_string(), &server.url());
+
+    let err = client.place_order(market_order("BTC-USDT", OrderSide::Buy)).await.unwrap_err();
+
+    assert!(err.to_string().contains("Signature verification failed"));
+    assert!(server.orders().is_empty());
+    assert_eq!(server.request_count(ORDER_PATH), 1);
+}
+
+#[tokio::test]
+async fn manager_places_signed_order_and_tracks_position() {
+    let server = start_server().await;
+    let manager = manager_for(&server);
+    manager.add_currency(CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20)).await.unwrap();
+
+    manager.place_order("BTC-USDT", OrderSide::Buy, 104.0).await.unwrap();
+
+    let orders = server.orders();
+    assert_eq!(orders.len(), 1);
+    assert_eq!(orders[0].side, "BUY");
+    assert_eq!(orders[0].quantity, 0.01);
+    assert!(orders[0].take_profit.as_deref().unwrap().contains("TAKE_PROFIT_MARKET"));
+
+    let position = manager.get_currency_status("BTC-USDT").await.unwrap().current_position.unwrap();
+    assert_eq!(position.quantity, 0.01);
+    assert_eq!(position.entry_price, 104.0);
+}
+
+#[tokio::test]
+async fn injected_faults_are_one_shot() {
+    let server = start_server().await;
+    let client = bingx_client(&server);
+    let price_path = "/openApi/swap/v1/ticker/price";
+
+    server.inject_fault(price_path, MockFault::ApiError { code: 100410, msg: "rate limited".to_string() });
+    server.inject_fault(price_path, MockFault::MalformedBody);
+
+    let err = client.get_latest_price("BTC-USDT").await.unwrap_err();
+    assert!(err.to_string().contains("rate limited"));
+    assert!(client.get_latest_price("BTC-USDT").await.is_err());
+    assert_eq!(client.get_latest_price("BTC-USDT").await.unwrap(), 104.0);
+}
+
+#[tokio::test]
+async fn monitor_once_queries_every_active_symbol() {
+    let server = start_server().await;
+    let manager = manager_for(&server);
+    manager.add_currency(CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20)).await.unwrap();
+    // 添加币种时回填历史K线
+    assert_eq!(server.request_count("/openApi/swap/v3/quote/klines"), 1);
+
+    manager.monitor_once().await;
+
+    assert_eq!(server.request_count("/openApi/swap/v2/quote/depth"), 1);
+    assert_eq!(server.request_count("/openApi/swap/v2/quote/ticker"), 1);
+    assert_eq!(server.request_count("/openApi/swap/v3/quote/klines"), 2);
+}
+
+#[tokio::test]
+async fn resyncs_clock_after_timestamp_rejection() {
+    let server = start_server().await;
+    server.set_clock_offset(60_000);
+    let client = bingx_client(&server).with_recv_window(2000);
+
+    let response = client.place_order(market_order("BTC-USDT", OrderSide::Buy)).await.unwrap();
+
+    assert!(response.order.order_id > 0);
+    assert_eq!(server.request_count(ORDER_PATH), 2);
+    assert_eq!(server.request_count("/openApi/swap/v2/server/time"), 1);
+    assert!((client.time_offset() - 60_000).abs() < 1000);
+    assert_eq!(server.requests().last().unwrap().params["recvWindow"], "2000");
+}
+
+#[tokio::test]
+async fn encodes_params_and_exposes_api_error_codes() {
+    let server = start_server().await;
+    let client = bingx_client(&server);
+
+    let err = client.get_latest_price("BTC-USDT&symbol=ETH-USDT").await.unwrap_err();
+
+    assert_eq!(err.downcast_ref::<ApiError>().unwrap().code, CODE_SYMBOL_NOT_FOUND);
+    let request = server.requests().pop().unwrap();
+    assert_eq!(request.params["symbol"], "BTC-USDT&symbol=ETH-USDT");
+    // 公共接口不签名, 也不附带时间戳
+    assert!(!request.params.contains_key("timestamp"));
+    assert!(!request.params.contains_key("signature"));
+}
+
+#[tokio::test]
+async fn monitor_all_runs_on_a_spawned_task() {
+    let server = start_server().await;
+    let manager = Arc::new(manager_for(&server));
+    manager.add_currency(CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20)).await.unwrap();
+
+    let task = tokio::spawn({
+        let manager = manager.clone();
+        async move { manager.monitor_all().await }
+    });
+    for _ in 0..200 {
+        if server.request_count("/openApi/swap/v2/quote/ticker") > 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    task.abort();
+
+    assert_eq!(server.request_count("/openApi/swap/v2/server/time"), 1);
+    assert_eq!(server.request_count("/openApi/swap/v2/quote/ticker"), 1);
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use common::{bingx_client, bingx_server, interval_bar, manager_for};
+use chrono::{TimeZone, Utc};
+use crypto_trading_bot::strategy::{Decision, StrategyContext, StrategyRegistry, TradingStrategy};
+use crypto_trading_bot::types::{CurrencyConfig, Interval, Kline, StrategyConfig};
+use std::sync::{Arc, Mutex};
+
+fn millis(y: i32, m: u32, d: u32, h: u32) -> i64 {
+    Utc.with_ymd_and_hms(y, m, d, h, 0, 0).unwrap().timestamp_millis()
+}
+
+#[derive(Default)]
+struct Seen {
+    candles: Vec<(Interval, i64)>,     // (周期, 收盘时间)
+    aligned_hours: Vec<usize>,         // 每次评估时可见的 1 小时K线数量
+}
+
+// 5 分钟触发, 1 小时过滤
+struct TwoTimeframes {
+    seen: Arc<Mutex<Seen>>,
+}
+
+impl TradingStrategy for TwoTimeframes {
+    fn name(&self) -> &str {
+        "two_timeframes"
+    }
+
+    fn add_price(&mut self, _price: f64) {}
+
+    fn intervals(&self) -> Vec<Interval> {
+        vec![Interval::FiveMinutes, Interval::OneHour]
+    }
+
+    fn add_candle(&mut self, interval: Interval, kline: &Kline) {
+        self.seen.lock().unwrap().candles.push((interval, kline.close_time));
+    }
+
+    fn evaluate(&self, ctx: &StrategyContext) -> Decision {
+        let hours = ctx.candles(Interval::OneHour);
+        let last_trigger = ctx.candles(Interval::FiveMinutes).last().unwrap().close_time;
+        assert!(hours.iter().all(|k| k.close_time <= last_trigger));
+        self.seen.lock().unwrap().aligned_hours.push(hours.len());
+        Decision::hold()
+    }
+}
+
+#[test]
+fn interval_arithmetic_covers_calendar_intervals() {
+    for interval in Interval::ALL {
+        assert_eq!(interval.to_string().parse::<Interval>().unwrap(), interval);
+    }
+    assert!("2d".parse::<Interval>().is_err());
+    assert_eq!(Interval::from_minutes(60), Some(Interval::OneHour));
+    assert_eq!(Interval::from_minutes(7), None);
+
+    // 月线按自然月计算, 含闰年二月
+    let feb = millis(2024, 2, 1, 0);
+    assert_eq!(Interval::OneMonth.next_open_time(feb), millis(2024, 3, 1, 0));
+    assert_eq!(Interval::OneMonth.close_time(millis(2024, 12, 1, 0)), millis(2025, 1, 1, 0) - 1);
+    assert_eq!(Interval::OneMonth.align(millis(2024, 2, 17, 13)), feb);
+
+    // 周线从周一开始
+    assert_eq!(Interval::OneWeek.align(millis(2024, 1, 3, 10)), millis(2024, 1, 1, 0));
+    assert_eq!(Interval::SixHours.align(millis(2024, 1, 3, 10)), millis(2024, 1, 3, 6));
+    assert_eq!(Interval::TwelveHours.close_time(millis(2024, 1, 3, 12)), millis(2024, 1, 4, 0) - 1);
+}
+
+#[tokio::test]
+async fn bingx_klines_get_close_time_from_interval() {
+    let server = bingx_server().await;
+    let open_time = millis(2024, 1, 3, 10);
+    server.set_interval_klines("BTC-USDT", Interval::TwoHours, vec![interval_bar(Interval::TwoHours, open_time, 100.0, 2.0)]);
+
+    let client = bingx_client(&server);
+    let klines = client.get_klines("BTC-USDT", Interval::TwoHours, None, None, None).await.unwrap();
+    assert_eq!(klines[0].open_time, open_time);
+    assert_eq!(klines[0].close_time, millis(2024, 1, 3, 12) - 1);
+    assert_eq!(server.requests()[0].params.get("interval").map(String::as_str), Some("2h"));
+}
+
+#[test]
+fn macd_declares_trend_filter_interval() {
+    let registry = StrategyRegistry::with_builtin();
+    let strategy = registry.build(&"macd:interval=15;trend_interval=240;trend_ema=20".parse().unwrap()).unwrap();
+    assert_eq!(strategy.intervals(), vec![Interval::FifteenMinutes, Interval::FourHours]);
+    assert_eq!(registry.build(&StrategyConfig::default()).unwrap().intervals(), vec![Interval::FiveMinutes]);
+    assert!(registry.build(&"macd:interval=7".parse().unwrap()).is_err());
+}
+
+#[tokio::test]
+async fn manager_feeds_aligned_series_for_each_interval() {
+    let server = bingx_server().await;
+    let now = Utc::now().timestamp_millis();
+    let hour = Interval::OneHour.align(now);
+    let five = Interval::FiveMinutes.align(now);
+    // 两根已收盘的 1 小时K线和三根已收盘的 5 分钟K线, 各带一根未收盘K线
+    server.set_interval_klines("BTC-USDT", Interval::OneHour, (0..3)
+        .map(|i| interval_bar(Interval::OneHour, hour - (2 - i) * 3_600_000, 100.0, 2.0))
+        .collect());
+    server.set_interval_klines("BTC-USDT", Interval::FiveMinutes, (0..4)
+        .map(|i| interval_bar(Interval::FiveMinutes, five - (3 - i) * 300_000, 100.0, 2.0))
+        .collect());
+
+    let seen = Arc::new(Mutex::new(Seen::default()));
+    let shared = seen.clone();
+    let mut registry = StrategyRegistry::with_builtin();
+    registry.register("two_timeframes", move |_| Ok(Box::new(TwoTimeframes { seen: shared.clone() })));
+    let manager = manager_for(&server).with_strategy_registry(registry);
+    manager.add_currency(CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20)
+        .with_strategy(StrategyConfig::new("two_timeframes"))).await.unwrap();
+
+    manager.monitor_once().await;
+    manager.monitor_once().await;
+
+    assert_eq!(manager.candles_for("BTC-USDT", Interval::OneHour).await.unwrap().len(), 2);
+    assert_eq!(server.request_count("/openApi/swap/v3/quote/klines"), 4);
+
+    let seen = seen.lock().unwrap();
+    let count = |interval| seen.candles.iter().filter(|(i, _)| *i == interval).count();
+    assert_eq!(count(Interval::OneHour), 2);
+    assert_eq!(count(Interval::FiveMinutes), 3);
+    assert!(seen.candles.windows(2).all(|w| w[0].1 <= w[1].1));
+    // 只有主周期收盘时评估一次
+    assert_eq!(seen.aligned_hours, vec![2]);
+}
@@ -0,0 +1,214 @@
+mod common;
+
+use common::{bar_with_range, bingx_server, manager_for, position, FIVE_MINUTES};
+use crypto_trading_bot::exchange::mock::MockFault;
+use chrono::Utc;
+use crypto_trading_bot::strategy::{Action, LegState, PairsStrategy, StrategyContext, StrategyRegistry, TradingStrategy};
+use crypto_trading_bot::types::{CurrencyConfig, Interval, OrderSide, StrategyConfig};
+
+const LOOKBACK: usize = 20;
+
+// 对冲腿围绕 100 波动, 主交易对约为其 2 倍, 价差带少量噪声
+fn prices(i: usize) -> (f64, f64) {
+    let leg = 100.0 + 5.0 * (i as f64 * 0.7).sin();
+    let noise = 0.001 * ((i % 3) as f64 - 1.0);
+    (2.0 * leg * (1.0 + noise), leg)
+}
+
+fn btc() -> CurrencyConfig {
+    CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 2, 3, 5.0, 20)
+}
+
+fn eth() -> CurrencyConfig {
+    CurrencyConfig::new("ETH-USDT", "ETH", "USDT", 0.01, 2, 3, 5.0, 20)
+}
+
+fn warmed_up() -> PairsStrategy {
+    let mut pairs = PairsStrategy::new(LOOKBACK, 2.0, 0.5, 6.0);
+    pairs.bind_currency(&btc().with_leg(eth()));
+    for i in 0..LOOKBACK {
+        let (primary, leg) = prices(i);
+        let open_time = i as i64 * FIVE_MINUTES;
+        pairs.add_leg_candle("ETH-USDT", Interval::FiveMinutes, &bar_with_range(open_time, leg, 0.0));
+        pairs.add_candle(Interval::FiveMinutes, &bar_with_range(open_time, primary, 0.0));
+    }
+    pairs
+}
+
+#[test]
+fn aligns_legs_and_estimates_hedge_ratio() {
+    let mut pairs = PairsStrategy::new(LOOKBACK, 2.0, 0.5, 6.0);
+    pairs.bind_currency(&btc().with_leg(eth()));
+    assert_eq!(pairs.leg_symbol(), Some("ETH-USDT"));
+
+    // 只有主交易对的K线不计入窗口
+    pairs.add_candle(Interval::FiveMinutes, &bar_with_range(0, 200.0, 0.0));
+    assert!(!pairs.is_ready());
+    assert!(pairs.describe()[0].contains("回看 0/20"));
+
+    let pairs = warmed_up();
+    assert!(pairs.is_ready());
+    let stats = pairs.stats().unwrap();
+    assert!((stats.hedge_ratio - 1.0).abs() < 0.05, "hedge ratio {}", stats.hedge_ratio);
+    assert!(stats.z_score.abs() < 2.0);
+}
+
+#[test]
+fn keeps_only_lookback_unpaired_bars() {
+    let mut pairs = PairsStrategy::new(LOOKBACK, 2.0, 0.5, 6.0);
+    pairs.bind_currency(&btc().with_leg(eth()));
+    let bars = 3 * LOOKBACK as i64;
+    for i in 0..bars {
+        pairs.add_candle(Interval::FiveMinutes, &bar_with_range(i * FIVE_MINUTES, 200.0, 0.0));
+    }
+
+    // 超出 lookback 的未配对K线已丢弃, 迟到的对冲腿不再配对
+    pairs.add_leg_candle("ETH-USDT", Interval::FiveMinutes, &bar_with_range(0, 100.0, 0.0));
+    assert!(pairs.describe()[0].contains("回看 0/20"));
+    pairs.add_leg_candle("ETH-USDT", Interval::FiveMinutes, &bar_with_range((bars - 1) * FIVE_MINUTES, 100.0, 0.0));
+    assert!(pairs.describe()[0].contains("回看 1/20"));
+}
+
+#[test]
+fn enters_offsetting_legs_and_exits_together() {
+    let mut pairs = warmed_up();
+    let (primary, leg) = prices(LOOKBACK);
+    let open_time = LOOKBACK as i64 * FIVE_MINUTES;
+    let primary = primary * 1.004;
+    pairs.add_leg_candle("ETH-USDT", Interval::FiveMinutes, &bar_with_range(open_time, leg, 0.0));
+    pairs.add_candle(Interval::FiveMinutes, &bar_with_range(open_time, primary, 0.0));
+    let z = pairs.stats().unwrap().z_score;
+    assert!(z > 2.0 && z < 6.0, "z {}", z);
+
+    // 价差偏高: 做空主交易对, 做多对冲腿, 对冲腿数量按对冲比例换算名义价值
+    let legs = [LegState { symbol: "ETH-USDT".to_string(), price: leg, position: None }];
+    let ctx = StrategyContext::new("BTC-USDT", primary).with_legs(&legs);
+    let decision = pairs.evaluate(&ctx);
+    assert_eq!(decision.action, Action::EnterShort);
+    assert!(decision.stop_loss.unwrap() > primary);
+    let (symbol, hedge) = &decision.legs[0];
+    assert_eq!((symbol.as_str(), &hedge.action), ("ETH-USDT", &Action::EnterLong));
+    let hedge_ratio = pairs.stats().unwrap().hedge_ratio;
+    let expected = hedge_ratio * decision.quantity.unwrap() * primary / leg;
+    assert!((hedge.quantity.unwrap() - expected).abs() < 0.001);
+
+    // 两腿持仓: 价差未回归时继续持有
+    pairs.on_signal(&decision, primary, 0);
+    let short = position("BTC-USDT", OrderSide::Sell);
+    let legs = [LegState { symbol: "ETH-USDT".to_string(), price: leg, position: Some(position("ETH-USDT", OrderSide::Buy)) }];
+    let ctx = StrategyContext::new("BTC-USDT", primary).with_position(Some(&short)).with_legs(&legs);
+    assert_eq!(pairs.evaluate(&ctx).action, Action::Hold);
+
+    // 价差回归 (最后一根K线不带噪声): 两腿同时平仓
+    for i in LOOKBACK + 1..=2 * LOOKBACK {
+        let (primary, leg) = prices(i);
+        let open_time = i as i64 * FIVE_MINUTES;
+        pairs.add_leg_candle("ETH-USDT", Interval::FiveMinutes, &bar_with_range(open_time, leg, 0.0));
+        pairs.add_candle(Interval::FiveMinutes, &bar_with_range(open_time, primary, 0.0));
+    }
+    let exit = pairs.evaluate(&ctx);
+    assert_eq!(exit.action, Action::Exit);
+    assert_eq!(exit.legs[0].1.action, Action::Exit);
+}
+
+#[test]
+fn closes_orphaned_leg() {
+    let pairs = warmed_up();
+    let (primary, leg) = prices(LOOKBACK - 1);
+
+    // 只剩对冲腿: 主交易对观望, 平掉对冲腿
+    let legs = [LegState { symbol: "ETH-USDT".to_string(), price: leg, position: Some(position("ETH-USDT", OrderSide::Buy)) }];
+    let decision = pairs.evaluate(&StrategyContext::new("BTC-USDT", primary).with_legs(&legs));
+    assert_eq!(decision.action, Action::Hold);
+    assert_eq!(decision.legs[0].1.action, Action::Exit);
+    assert!(decision.is_actionable());
+
+    // 只剩主交易对: 平掉主交易对
+    let short = position("BTC-USDT", OrderSide::Sell);
+    let legs = [LegState { symbol: "ETH-USDT".to_string(), price: leg, position: None }];
+    let ctx = StrategyContext::new("BTC-USDT", primary).with_position(Some(&short)).with_legs(&legs);
+    let decision = pairs.evaluate(&ctx);
+    assert_eq!(decision.action, Action::Exit);
+    assert!(decision.legs.is_empty());
+}
+
+#[test]
+fn registry_validates_pairs_params() {
+    let registry = StrategyRegistry::with_builtin();
+    assert!(registry.build(&"pairs".parse().unwrap()).is_ok());
+    assert!(registry.build(&"pairs:entry_z=1;exit_z=1".parse().unwrap()).is_err());
+    assert!(registry.build(&"pairs:entry_z=5;stop_z=4".parse().unwrap()).is_err());
+    assert!(registry.build(&"pairs:lookback=5".parse().unwrap()).is_err());
+    assert!(registry.build(&"pairs:max_bars=1.5".parse().unwrap()).is_err());
+}
+
+#[tokio::test]
+async fn manager_opens_both_legs_and_rejects_missing_leg() {
+    let server = bingx_server().await;
+    // 历史K线截止到上上根, 最近一根已收盘的K线稍后推送
+    let bars = 2 * LOOKBACK;
+    let start = Interval::FiveMinutes.align(Utc::now().timestamp_millis()) - (bars as i64 + 1) * FIVE_MINUTES;
+    let history: Vec<(f64, f64)> = (0..bars).map(prices).collect();
+    server.set_klines("BTC-USDT", history.iter().enumerate().map(|(i, (p, _))| bar_with_range(start + i as i64 * FIVE_MINUTES, *p, 0.0)).collect());
+    server.set_klines("ETH-USDT", history.iter().enumerate().map(|(i, (_, l))| bar_with_range(start + i as i64 * FIVE_MINUTES, *l, 0.0)).collect());
+
+    let manager = manager_for(&server);
+    let strategy: StrategyConfig = "pairs:lookback=20;entry_z=2;exit_z=0.5;stop_z=6".parse().unwrap();
+    assert!(manager.add_currency(btc().with_strategy(strategy.clone())).await.is_err());
+    manager.add_currency(btc().with_strategy(strategy.clone()).with_leg(eth())).await.unwrap();
+    let leg_status = manager.get_currency_status("ETH-USDT").await.unwrap();
+    assert_eq!(leg_status.owner.as_deref(), Some("BTC-USDT"));
+    assert!(leg_status.config.legs.is_empty());
+    // 对冲腿已被占用, 不能再单独交易
+    assert!(manager.add_currency(eth().with_strategy(strategy).with_leg(btc())).await.is_err());
+
+    // 主交易对相对对冲腿走高: 做空主交易对, 做多对冲腿
+    let (primary, leg) = prices(bars);
+    let open_time = start + bars as i64 * FIVE_MINUTES;
+    server.push_kline("ETH-USDT", bar_with_range(open_time, leg, 0.0));
+    server.push_kline("BTC-USDT", bar_with_range(open_time, primary * 1.004, 0.0));
+    server.set_price("ETH-USDT", leg);
+    server.set_price("BTC-USDT", primary * 1.004);
+    manager.monitor_once().await;
+
+    let orders = server.orders();
+    let short = orders.iter().find(|o| o.symbol == "BTC-USDT").unwrap();
+    let long = orders.iter().find(|o| o.symbol == "ETH-USDT").unwrap();
+    assert_eq!((short.side.as_str(), short.position_side.as_str()), ("SELL", "SHORT"));
+    assert_eq!((long.side.as_str(), long.position_side.as_str()), ("BUY", "LONG"));
+    assert!(long.quantity > short.quantity);
+    let btc_position = manager.get_currency_status("BTC-USDT").await.unwrap().current_position.unwrap();
+    let eth_position = manager.get_currency_status("ETH-USDT").await.unwrap().current_position.unwrap();
+    assert_eq!((btc_position.side, eth_position.side), (OrderSide::Sell, OrderSide::Buy));
+
+    // 移除主交易对时一并移除对冲腿
+    manager.remove_currency("BTC-USDT").await;
+    assert!(manager.get_currency_status("ETH-USDT").await.is_none());
+}
+
+#[tokio::test]
+async fn manager_skips_hedge_leg_when_primary_order_fails() {
+    let server = bingx_server().await;
+    let bars = 2 * LOOKBACK;
+    let start = Interval::FiveMinutes.align(Utc::now().timestamp_millis()) - (bars as i64 + 1) * FIVE_MINUTES;
+    let history: Vec<(f64, f64)> = (0..bars).map(prices).collect();
+    server.set_klines("BTC-USDT", history.iter().enumerate().map(|(i, (p, _))| bar_with_range(start + i as i64 * FIVE_MINUTES, *p, 0.0)).collect());
+    server.set_klines("ETH-USDT", history.iter().enumerate().map(|(i, (_, l))| bar_with_range(start + i as i64 * FIVE_MINUTES, *l, 0.0)).collect());
+    let manager = manager_for(&server);
+    let strategy: StrategyConfig = "pairs:lookback=20;entry_z=2;exit_z=0.5;stop_z=6".parse().unwrap();
+    manager.add_currency(btc().with_strategy(strategy).with_leg(eth())).await.unwrap();
+
+    // 主交易对下单被拒绝时不开对冲腿
+    let (primary, leg) = prices(bars);
+    let open_time = start + bars as i64 * FIVE_MINUTES;
+    server.push_kline("ETH-USDT", bar_with_range(open_time, leg, 0.0));
+    server.push_kline("BTC-USDT", bar_with_range(open_time, primary * 1.004, 0.0));
+    server.set_price("ETH-USDT", leg);
+    server.set_price("BTC-USDT", primary * 1.004);
+    server.inject_fault("/openApi/swap/v2/trade/order", MockFault::ApiError { code: 101204, msg: "Insufficient margin".to_string() });
+    manager.monitor_once().await;
+
+    assert!(server.orders().is_empty());
+    assert_eq!(server.request_count("/openApi/swap/v2/trade/order"), 1);
+    assert!(manager.get_currency_status("ETH-USDT").await.unwrap().current_position.is_none());
+}
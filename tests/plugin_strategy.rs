@@ -0,0 +1,210 @@
+mod common;
+
+use common::{bar_with_range, bingx_server, interval_bar, manager_for};
+use chrono::Utc;
+use crypto_trading_bot::strategy::{
+    Action, MarketDepth, PluginCommand, PluginStrategy, StrategyContext, StrategyRegistry, TradingStrategy,
+};
+use crypto_trading_bot::types::{CurrencyConfig, Interval, OrderSide, Position, StrategyConfig};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+use std::time::{Duration, Instant};
+
+// 插件进程使用本测试程序自身: 以 PLUGIN_MODE 环境变量运行 plugin_child
+const MODE_VAR: &str = "PLUGIN_MODE";
+
+fn command(mode: &str) -> PluginCommand {
+    let exe = std::env::current_exe().unwrap().display().to_string();
+    // 通过 env 设置插件模式, 避免修改测试进程自身的环境变量
+    let launcher = if cfg!(windows) {
+        format!("cmd /C set {}={}&& {} plugin_child --exact --nocapture --quiet", MODE_VAR, mode, exe)
+    } else {
+        format!("env {}={} {} plugin_child --exact --nocapture --quiet", MODE_VAR, mode, exe)
+    };
+    launcher.parse().unwrap()
+}
+
+// 简单插件: 收到 3 根K线后, 收盘价高于 100 看多; 持仓时看空则平仓
+#[test]
+fn plugin_child() {
+    let mode = match std::env::var(MODE_VAR) {
+        Ok(mode) => mode,
+        Err(_) => return,
+    };
+    let stdout = std::io::stdout();
+    let reply = |message: Value| {
+        let mut out = stdout.lock();
+        writeln!(out, "{}", message).unwrap();
+        out.flush().unwrap();
+    };
+    let (mut candles, mut last_close, mut symbol, mut evaluations) = (0, 0.0, String::new(), 0);
+    for line in std::io::stdin().lock().lines() {
+        let message: Value = serde_json::from_str(&line.unwrap()).unwrap();
+        match message["type"].as_str().unwrap() {
+            "hello" => {
+                eprintln!("hello from {}", message["name"]);
+                reply(json!({ "type": "hello", "intervals": ["15m"], "lookback": { "15m": 3 } }));
+            }
+            "bind" => symbol = message["symbol"].as_str().unwrap().to_string(),
+            "candle" => {
+                candles += 1;
+                last_close = message["kline"]["close"].as_f64().unwrap();
+            }
+            "evaluate" => {
+                evaluations += 1;
+                if mode == "crash" && evaluations == 2 {
+                    std::process::exit(1);
+                }
+                if mode == "slow" {
+                    std::thread::sleep(Duration::from_secs(5));
+                }
+                let holding = message["position"]["side"].as_str();
+                let mut decision = match (holding, candles >= 3, last_close > 100.0) {
+                    (Some("long"), _, false) => json!({ "action": "exit", "confidence": 1.0 }),
+                    (None, true, true) => json!({ "action": "enter_long", "confidence": 0.8, "stop_loss": 95.0 }),
+                    _ => json!({ "action": "hold" }),
+                };
+                decision["type"] = json!("decision");
+                decision["id"] = message["id"].clone();
+                decision["reasons"] = json!([format!("{} candles={} depth={}", symbol, candles, !message["depth"].is_null())]);
+                // 先输出一条编号不符的决策, 应被忽略
+                reply(json!({ "type": "decision", "id": 0, "action": "enter_short" }));
+                reply(decision);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn parses_plugin_command() {
+    let command: PluginCommand = " python3  strategies/momentum.py --fast 12 ".parse().unwrap();
+    assert_eq!(command.program, "python3");
+    assert_eq!(command.args, vec!["strategies/momentum.py", "--fast", "12"]);
+    assert_eq!(command.to_string(), "python3 strategies/momentum.py --fast 12");
+    assert!("  ".parse::<PluginCommand>().is_err());
+}
+
+#[test]
+fn exchanges_json_lines_with_plugin() {
+    let mut plugin = PluginStrategy::spawn("momentum", command("normal"), BTreeMap::new()).unwrap();
+    assert_eq!(plugin.intervals(), vec![Interval::FifteenMinutes]);
+    assert_eq!(plugin.lookback(Interval::FifteenMinutes), 3);
+    plugin.bind_currency(&CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20));
+
+    let ctx = StrategyContext::new("BTC-USDT", 105.0);
+    plugin.add_candle(Interval::FifteenMinutes, &bar_with_range(0, 101.0, 0.0));
+    assert!(!plugin.is_ready());
+    assert_eq!(plugin.evaluate(&ctx).action, Action::Hold);
+
+    plugin.add_candle(Interval::FifteenMinutes, &bar_with_range(0, 102.0, 0.0));
+    plugin.add_candle(Interval::FifteenMinutes, &bar_with_range(0, 105.0, 0.0));
+    assert!(plugin.is_ready());
+    let depth = MarketDepth { asks: vec![(105.5, 1.0)], bids: vec![(104.5, 1.0)] };
+    let decision = plugin.evaluate(&ctx.with_depth(Some(&depth)));
+    assert_eq!(decision.action, Action::EnterLong);
+    assert_eq!((decision.confidence, decision.stop_loss), (0.8, Some(95.0)));
+    assert_eq!(decision.reasons, vec!["BTC-USDT candles=3 depth=true"]);
+    plugin.on_signal(&decision, 105.0, 0);
+
+    let position = Position {
+        symbol: "BTC-USDT".to_string(),
+        side: OrderSide::Buy,
+        quantity: 0.01,
+        entry_price: 105.0,
+        unrealized_pnl: 0.0,
+        leverage: 20,
+    };
+    plugin.add_candle(Interval::FifteenMinutes, &bar_with_range(0, 99.0, 0.0));
+    assert_eq!(plugin.evaluate(&ctx.with_position(Some(&position))).action, Action::Exit);
+    assert_eq!(plugin.restarts(), 0);
+    assert!(plugin.describe()[0].contains("运行中"));
+}
+
+#[test]
+fn restarts_crashed_plugin_and_replays_candles() {
+    let mut plugin = PluginStrategy::spawn("crashy", command("crash"), BTreeMap::new())
+        .unwrap()
+        .with_restart_delay(Duration::ZERO);
+    plugin.bind_currency(&CurrencyConfig::new("ETH-USDT", "ETH", "USDT", 0.01, 1, 3, 5.0, 20));
+    for close in [101.0, 102.0, 103.0, 104.0] {
+        plugin.add_candle(Interval::FifteenMinutes, &bar_with_range(0, close, 0.0));
+    }
+    let ctx = StrategyContext::new("ETH-USDT", 104.0);
+    assert_eq!(plugin.evaluate(&ctx).action, Action::EnterLong);
+
+    // 第二次评估时插件退出: 本轮观望, 下一轮重启并重放最近 3 根K线
+    let decision = plugin.evaluate(&ctx);
+    assert_eq!(decision.action, Action::Hold);
+    assert!(decision.reasons[0].contains("插件进程已退出"), "{:?}", decision.reasons);
+    assert!(plugin.last_error().is_some());
+
+    let decision = plugin.evaluate(&ctx);
+    assert_eq!(decision.action, Action::EnterLong);
+    assert_eq!(decision.reasons, vec!["ETH-USDT candles=3 depth=false"]);
+    assert_eq!(plugin.restarts(), 1);
+}
+
+#[test]
+fn times_out_slow_plugin() {
+    let plugin = PluginStrategy::spawn("slow", command("slow"), BTreeMap::new())
+        .unwrap()
+        .with_timeout(Duration::from_millis(200));
+    let started = std::time::Instant::now();
+    let decision = plugin.evaluate(&StrategyContext::new("BTC-USDT", 100.0));
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert_eq!(decision.action, Action::Hold);
+    assert!(decision.reasons[0].contains("超时"), "{:?}", decision.reasons);
+    // 超时后进程被结束, 重启间隔内不再尝试
+    let decision = plugin.evaluate(&StrategyContext::new("BTC-USDT", 100.0));
+    assert!(decision.reasons[0].contains("等待重启"), "{:?}", decision.reasons);
+}
+
+#[test]
+fn registry_builds_plugin_strategies() {
+    let mut registry = StrategyRegistry::with_builtin();
+    registry.register_plugin("momentum", command("normal")).unwrap();
+    let config: StrategyConfig = "momentum:timeout_ms=500;fast=12".parse().unwrap();
+    let strategy = registry.build(&config).unwrap();
+    assert_eq!(strategy.name(), "momentum");
+    assert_eq!(strategy.intervals(), vec![Interval::FifteenMinutes]);
+    assert_eq!(strategy.evaluate(&StrategyContext::new("BTC-USDT", 100.0)).action, Action::Hold);
+    assert!(registry.build(&"momentum:timeout_ms=0".parse().unwrap()).is_err());
+
+    registry.register_plugin("missing", "/nonexistent/plugin".parse().unwrap()).unwrap();
+    let err = registry.build(&StrategyConfig::new("missing")).err().unwrap();
+    assert!(err.to_string().contains("启动插件"), "{}", err);
+
+    // 不允许覆盖内置策略或已注册的插件
+    assert!(registry.register_plugin("MACD", command("normal")).is_err());
+    assert!(registry.register_plugin("momentum", command("slow")).is_err());
+}
+
+#[tokio::test]
+async fn manager_waits_for_plugin_outside_strategy_lock() {
+    let server = bingx_server().await;
+    let quarter = 900_000;
+    let start = (Utc::now().timestamp_millis() / quarter - 5) * quarter;
+    let bar = |i: i64| interval_bar(Interval::FifteenMinutes, start + i * quarter, 101.0 + i as f64, 0.0);
+    server.set_klines("BTC-USDT", (0..4).map(bar).collect());
+
+    let mut registry = StrategyRegistry::with_builtin();
+    registry.register_plugin("slow", command("slow")).unwrap();
+    let manager = manager_for(&server).with_strategy_registry(registry);
+    manager.add_currency(CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20)
+        .with_strategy("slow:timeout_ms=1500".parse().unwrap())).await.unwrap();
+    server.push_kline("BTC-USDT", bar(4));
+
+    // 插件评估期间 (最长 1.5 秒) 其他任务仍可读取策略状态
+    let started = Instant::now();
+    let (_, (probed_at, state)) = tokio::join!(manager.monitor_once(), async {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let state = manager.strategy_state("BTC-USDT").await;
+        (started.elapsed(), state)
+    });
+    assert!(probed_at < Duration::from_millis(1000), "{:?}", probed_at);
+    assert!(state[0].contains("slow"), "{:?}", state);
+    assert!(started.elapsed() >= Duration::from_millis(1000));
+    assert!(server.orders().is_empty());
+}
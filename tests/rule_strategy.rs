@@ -0,0 +1,161 @@
+mod common;
+
+use common::position;
+use crypto_trading_bot::strategy::{
+    parse_rules, Action, MarketDepth, RuleStrategy, StrategyContext, StrategyRegistry, TradingStrategy,
+};
+use crypto_trading_bot::types::{Interval, OrderSide, Position, StrategyConfig};
+
+const RULES: &str = "
+# 超卖且买盘占优时开多
+strategy rsi_depth
+interval 15m
+stop_pct 2
+target_pct 4
+confidence 0.7
+enter_long when rsi(3) < 40 and depth.imbalance > 1.2
+enter_short when rsi(3) > 80 and not position.open
+exit when position.long and (rsi(3) > 60 or position.pnl >= 5)
+
+strategy macd_cross
+enter_long when macd(3, 6, 2).cross_up
+";
+
+fn strategy() -> RuleStrategy {
+    let definitions = parse_rules(RULES).unwrap();
+    RuleStrategy::new(definitions[0].clone())
+}
+
+#[test]
+fn parses_definitions_and_shares_indicators() {
+    let definitions = parse_rules(RULES).unwrap();
+    assert_eq!(definitions.len(), 2);
+    let rsi_depth = &definitions[0];
+    assert_eq!(rsi_depth.name, "rsi_depth");
+    assert_eq!(rsi_depth.interval, Interval::FifteenMinutes);
+    assert_eq!((rsi_depth.stop_pct, rsi_depth.target_pct, rsi_depth.confidence), (2.0, 4.0, 0.7));
+    assert_eq!(rsi_depth.rules.len(), 3);
+    assert_eq!(rsi_depth.rules[2].action, Action::Exit);
+    // 三条规则引用同一个 rsi(3)
+    assert_eq!(rsi_depth.indicators.len(), 1);
+    assert_eq!(definitions[1].indicators[0].to_string(), "macd(3,6,2)");
+
+    let strategy = strategy();
+    assert_eq!(strategy.intervals(), vec![Interval::FifteenMinutes]);
+    assert_eq!(strategy.lookback(Interval::FifteenMinutes), 4);
+}
+
+#[test]
+fn reports_parse_errors_with_line_and_column() {
+    let err = parse_rules("strategy a\nenter_long when rsx(14) < 40").unwrap_err();
+    assert_eq!((err.line, err.column), (2, 17));
+    assert!(err.message.contains("未知的数据: rsx"), "{}", err);
+    assert!(err.to_string().ends_with("\n  enter_long when rsx(14) < 40\n                  ^"), "{}", err);
+
+    let column = |source: &str| {
+        let err = parse_rules(source).unwrap_err();
+        (err.column, err.message)
+    };
+    assert_eq!(column("strategy a\nenter_long when rsi(14) < 40 and").0, 33);
+    assert_eq!(column("strategy a\nenter_long when rsi(14) + 40").0, 17);
+    assert!(column("strategy a\nenter_long when depth.imbalanse > 1").1.contains("未知的深度字段"));
+    assert!(column("strategy a\nenter_long when macd < 0").1.contains("macd 需要指定字段"));
+    assert!(column("strategy a\nenter_long when rsi(14) = 40").1.contains("=="));
+    assert!(column("strategy a\nenter_long when rsi(0) < 40").1.contains("正整数"));
+    // 过大的周期在参数位置报错, 不会分配巨大的缓冲区
+    let (at, message) = column("strategy a\nexit when sma(100000000000000000000) > 1");
+    assert_eq!(at, 15);
+    assert!(message.contains("不能超过 1000"), "{}", message);
+    assert_eq!(column("strategy a\nexit when macd(12, 2000).line > 0").0, 20);
+    assert!(column("strategy a\nenter_long when 1 < rsi(14) < 40").1.contains("连续比较"));
+    assert!(column("strategy a\nenter_long rsi(14) < 40").1.contains("when"));
+    assert!(column("enter_long when rsi(14) < 40").1.contains("strategy"));
+    assert!(column("strategy a\ninterval 7m").1.contains("未知K线周期"));
+    assert!(column("strategy a\nconfidence 2").1.contains("[0, 1]"));
+    assert!(column("strategy a\nstrategy b\nexit when true").1.contains("策略 a 没有任何规则"));
+    assert!(column("strategy a\nexit when true\nstrategy a").1.contains("重复定义"));
+    assert!(column("strategy a\nbuy when true").1.contains("未知指令"));
+}
+
+#[test]
+fn evaluates_rules_against_market_data() {
+    let mut strategy = strategy();
+    let ctx = StrategyContext::new("BTC-USDT", 100.0);
+    assert!(!strategy.is_ready());
+    for price in [110.0, 108.0, 105.0, 100.0] {
+        strategy.add_price(price);
+    }
+    assert!(strategy.is_ready());
+
+    // 没有深度数据时开多规则不成立
+    let decision = strategy.evaluate(&ctx);
+    assert_eq!(decision.action, Action::Hold);
+    assert!(decision.reasons.iter().any(|r| r.contains("无深度数据")), "{:?}", decision.reasons);
+
+    let depth = MarketDepth {
+        asks: vec![(100.5, 4.0)],
+        bids: vec![(99.5, 6.0)],
+    };
+    let decision = strategy.evaluate(&ctx.with_depth(Some(&depth)));
+    assert_eq!(decision.action, Action::EnterLong);
+    assert_eq!(decision.confidence, 0.7);
+    assert_eq!((decision.stop_loss, decision.take_profit), (Some(98.0), Some(104.0)));
+    assert!(decision.reasons[0].contains("第 8 行"), "{:?}", decision.reasons);
+
+    // 已持有多单时忽略开多规则, 盈利达到 5% 时平仓
+    let long = Position { unrealized_pnl: 1.0, ..position("BTC-USDT", OrderSide::Buy) };
+    let holding = ctx.with_depth(Some(&depth)).with_position(Some(&long));
+    assert_eq!(strategy.evaluate(&holding).action, Action::Hold);
+    let profitable = Position { unrealized_pnl: 6.0, ..position("BTC-USDT", OrderSide::Buy) };
+    assert_eq!(strategy.evaluate(&ctx.with_position(Some(&profitable))).action, Action::Exit);
+
+    // 急涨后 RSI 超买, 无持仓时开空
+    for price in [110.0, 120.0, 130.0] {
+        strategy.add_price(price);
+    }
+    assert_eq!(strategy.evaluate(&ctx).action, Action::EnterShort);
+    assert_eq!(strategy.evaluate(&ctx.with_position(Some(&long))).action, Action::Exit);
+    assert!(strategy.describe()[0].starts_with("rsi(3): "));
+}
+
+#[test]
+fn detects_macd_histogram_cross() {
+    let definitions = parse_rules(RULES).unwrap();
+    let mut strategy = RuleStrategy::new(definitions[1].clone());
+    let ctx = StrategyContext::new("BTC-USDT", 100.0);
+    let mut crossed = false;
+    for price in [100.0, 99.0, 98.0, 97.0, 96.0, 95.0, 94.0, 95.0, 97.0, 100.0] {
+        strategy.add_price(price);
+        if strategy.evaluate(&ctx).action == Action::EnterLong {
+            crossed = true;
+            break;
+        }
+    }
+    assert!(crossed);
+    // 交叉只在发生的那根K线成立
+    strategy.add_price(104.0);
+    assert_eq!(strategy.evaluate(&ctx).action, Action::Hold);
+}
+
+#[test]
+fn registry_loads_rule_file() {
+    let path = std::env::temp_dir().join(format!("rules-{}.txt", std::process::id()));
+    std::fs::write(&path, RULES).unwrap();
+    let mut registry = StrategyRegistry::with_builtin();
+    let names = registry.load_rules(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(names, vec!["rsi_depth", "macd_cross"]);
+    assert!(registry.contains("rsi_depth"));
+
+    let strategy = registry.build(&"rsi_depth:stop_pct=1".parse::<StrategyConfig>().unwrap()).unwrap();
+    assert_eq!(strategy.name(), "rsi_depth");
+    assert!(strategy.describe().last().unwrap().contains("止损 1%"));
+    assert!(registry.build(&"rsi_depth:fast=1".parse().unwrap()).is_err());
+
+    // 重复加载会与已注册的策略重名
+    let err = registry.register_rules(RULES).unwrap_err();
+    assert!(err.to_string().contains("重名"), "{}", err);
+    let err = StrategyRegistry::with_builtin().register_rules("strategy macd\nexit when true").unwrap_err();
+    assert!(err.to_string().contains("重名"), "{}", err);
+    assert!(StrategyRegistry::with_builtin().load_rules("/nonexistent/rules.txt").is_err());
+}
@@ -0,0 +1,206 @@
+mod common;
+
+use common::{binance_client, binance_server, bingx_client, bingx_server, API_KEY};
+use chrono::Utc;
+use crypto_trading_bot::exchange::binance::BinanceFuturesClient;
+use crypto_trading_bot::exchange::bingx::BingXClient;
+use crypto_trading_bot::exchange::mock::MockBingXServer;
+use crypto_trading_bot::logging::REDACTED;
+use crypto_trading_bot::strategy::{MarketDepth, MarketTicker};
+use crypto_trading_bot::trading::TradingManager;
+use crypto_trading_bot::types::{CurrencyConfig, Interval, Kline, MarketType, OrderSide, Venue};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn session_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("bingx-session-{}-{}.jsonl", name, std::process::id()))
+}
+
+async fn start_server() -> MockBingXServer {
+    let server = bingx_server().await;
+    let start = Utc::now().timestamp_millis() - 30 * 300_000;
+    server.set_klines("BTC-USDT", (0..30).map(|i| Kline {
+        open_time: start + i * 300_000,
+        open: 100.0,
+        high: 101.0 + i as f64,
+        low: 99.0,
+        close: 100.0 + i as f64,
+        volume: 5.0,
+        close_time: start + i * 300_000,
+    }).collect());
+    server.set_depth("BTC-USDT", MarketDepth {
+        asks: vec![(130.5, 1.0)],
+        bids: vec![(129.5, 1.0)],
+    });
+    server.set_ticker("BTC-USDT", MarketTicker {
+        price_change_percent: 0.5,
+        high_price: 131.0,
+        low_price: 99.0,
+        last_price: 129.0,
+        volume: 100.0,
+        bid_price: 129.5,
+        ask_price: 130.5,
+    });
+    server
+}
+
+#[tokio::test]
+async fn replays_recorded_session_offline() {
+    let path = session_path("replay");
+    let config = CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20);
+
+    let recorded_klines = {
+        let server = start_server().await;
+        let client = bingx_client(&server)
+            .record_to(&path)
+            .unwrap();
+        let klines = client.get_klines("BTC-USDT", Interval::FiveMinutes, None, None, Some(10)).await.unwrap();
+
+        let manager = TradingManager::new(client);
+        manager.add_currency(config.clone()).await.unwrap();
+        manager.monitor_once().await;
+        manager.place_order("BTC-USDT", OrderSide::Buy, 129.0).await.unwrap();
+        assert_eq!(server.orders().len(), 1);
+        klines
+    };
+
+    // 模拟服务器已关闭, 回放不应访问网络
+    let client = Arc::new(BingXClient::replay_from(&path).unwrap());
+    let klines = client.get_klines("BTC-USDT", Interval::FiveMinutes, None, None, Some(10)).await.unwrap();
+    let closes: Vec<f64> = klines.iter().map(|k| k.close).collect();
+    let recorded: Vec<f64> = recorded_klines.iter().map(|k| k.close).collect();
+    assert_eq!(closes, recorded);
+
+    let manager = TradingManager::new(client.clone());
+    manager.add_currency(config).await.unwrap();
+    manager.monitor_once().await;
+    manager.place_order("BTC-USDT", OrderSide::Buy, 129.0).await.unwrap();
+
+    let position = manager.get_currency_status("BTC-USDT").await.unwrap().current_position.unwrap();
+    assert_eq!(position.quantity, 0.01);
+    assert_eq!(client.replay_remaining(), Some(0));
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[tokio::test]
+async fn recorded_session_redacts_signature() {
+    let path = session_path("redact");
+    let server = start_server().await;
+    let client = bingx_client(&server)
+        .record_to(&path)
+        .unwrap();
+    let manager = TradingManager::new(client);
+    manager.add_currency(CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20)).await.unwrap();
+    manager.place_order("BTC-USDT", OrderSide::Sell, 129.0).await.unwrap();
+
+    let signature = server.requests().last().unwrap().params["signature"].clone();
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(contents.contains(REDACTED));
+    assert!(!contents.contains(&signature));
+    assert!(!contents.contains(API_KEY));
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[tokio::test]
+async fn replay_fails_for_unrecorded_request() {
+    let path = session_path("missing");
+    std::fs::write(&path, "").unwrap();
+
+    let client = BingXClient::replay_from(&path).unwrap();
+    let err = client.get_latest_price("BTC-USDT").await.unwrap_err();
+    assert!(err.to_string().contains("/openApi/swap/v1/ticker/price"));
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[tokio::test]
+async fn replay_uses_recorded_time_and_does_not_wait() {
+    use crypto_trading_bot::exchange::Exchange;
+
+    let path = session_path("clock");
+    let server = start_server().await;
+    // 最新一根K线在录制结束后才收盘
+    let open_time = Utc::now().timestamp_millis() + 1_500 - 300_000;
+    server.push_kline("BTC-USDT", Kline {
+        open_time,
+        open: 129.0,
+        high: 130.0,
+        low: 128.0,
+        close: 129.0,
+        volume: 1.0,
+        close_time: open_time + 299_999,
+    });
+    let config = CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20);
+
+    let recorded = {
+        let client = bingx_client(&server)
+            .record_to(&path)
+            .unwrap();
+        let manager = TradingManager::new(client);
+        manager.add_currency(config.clone()).await.unwrap();
+        manager.monitor_once().await;
+        let candles = manager.candles("BTC-USDT").await.unwrap();
+        candles.iter().map(|k| k.open_time).collect::<Vec<i64>>()
+    };
+    assert!(recorded.iter().all(|&recorded_open| recorded_open < open_time));
+
+    // 等到该K线按本地时间已收盘, 回放仍按录制时间判断
+    let wait = open_time + 300_000 + 50 - Utc::now().timestamp_millis();
+    tokio::time::sleep(Duration::from_millis(wait.max(0) as u64)).await;
+    let client = Arc::new(BingXClient::replay_from(&path).unwrap());
+    let manager = TradingManager::new(client.clone());
+    manager.add_currency(config).await.unwrap();
+    tokio::time::timeout(Duration::from_secs(5), manager.monitor_all()).await.unwrap();
+
+    let candles = manager.candles("BTC-USDT").await.unwrap();
+    assert_eq!(candles.iter().map(|k| k.open_time).collect::<Vec<i64>>(), recorded);
+    assert_eq!(client.replay_remaining(), Some(0));
+    assert!(client.now_millis() < open_time + 300_000);
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[tokio::test]
+async fn binance_client_records_and_replays_sessions() {
+    use crypto_trading_bot::exchange::Exchange;
+
+    let path = session_path("binance");
+    let server = binance_server().await;
+    let start = Utc::now().timestamp_millis() - 5 * 300_000;
+    server.set_klines("BTCUSDT", (0..5).map(|i| Kline {
+        open_time: start + i * 300_000,
+        open: 60_000.0,
+        high: 60_100.0,
+        low: 59_900.0,
+        close: 60_000.0 + i as f64,
+        volume: 2.0,
+        close_time: start + (i + 1) * 300_000 - 1,
+    }).collect());
+    server.set_price("BTCUSDT", 60_004.0);
+
+    let recorded = {
+        let client = binance_client(&server)
+            .record_to(&path)
+            .unwrap();
+        let klines = client.get_klines("BTC-USDT", MarketType::Swap, Interval::FiveMinutes, None, None, Some(5)).await.unwrap();
+        let err = client.get_price("DOGE-USDT", MarketType::Swap).await.unwrap_err();
+        (klines.iter().map(|k| k.close).collect::<Vec<f64>>(), err.to_string())
+    };
+
+    let client = BinanceFuturesClient::replay_from(&path).unwrap();
+    assert_eq!(client.venue(), Venue::Binance);
+    let klines = client.get_klines("BTC-USDT", MarketType::Swap, Interval::FiveMinutes, None, None, Some(5)).await.unwrap();
+    assert_eq!(klines.iter().map(|k| k.close).collect::<Vec<f64>>(), recorded.0);
+    // 录制的错误响应按原状态码回放
+    let err = client.get_price("DOGE-USDT", MarketType::Swap).await.unwrap_err();
+    assert_eq!(err.to_string(), recorded.1);
+    assert_eq!(client.replay_remaining(), Some(0));
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(!contents.contains(API_KEY));
+
+    std::fs::remove_file(&path).ok();
+}
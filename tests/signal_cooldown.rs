@@ -0,0 +1,211 @@
+mod common;
+
+use common::{bingx_server, manager_for};
+use chrono::Utc;
+use crypto_trading_bot::exchange::mock::MockFault;
+use crypto_trading_bot::strategy::{
+    Action, Decision, MACDStrategy, SignalLog, SignalRecord, StrategyContext, StrategyRegistry, TradingStrategy,
+};
+use crypto_trading_bot::types::{CurrencyConfig, Kline, OrderSide, StrategyConfig};
+use std::sync::{Arc, Mutex};
+
+// 始终发出开多信号
+struct AlwaysBuy;
+
+impl TradingStrategy for AlwaysBuy {
+    fn name(&self) -> &str {
+        "always_buy"
+    }
+
+    fn add_price(&mut self, _price: f64) {}
+
+    fn evaluate(&self, _ctx: &StrategyContext) -> Decision {
+        Decision::enter_long(1.0)
+    }
+}
+
+// 始终发出开多信号, 并记录管理器回报的已执行信号
+struct RecordingBuy {
+    signals: Arc<Mutex<Vec<SignalRecord>>>,
+}
+
+impl TradingStrategy for RecordingBuy {
+    fn name(&self) -> &str {
+        "recording_buy"
+    }
+
+    fn add_price(&mut self, _price: f64) {}
+
+    fn evaluate(&self, _ctx: &StrategyContext) -> Decision {
+        Decision::enter_long(1.0)
+    }
+
+    fn on_signal(&mut self, decision: &Decision, price: f64, timestamp: i64) {
+        self.signals.lock().unwrap().push(SignalRecord {
+            timestamp,
+            action: decision.action,
+            price,
+            confidence: decision.confidence,
+        });
+    }
+}
+
+#[test]
+fn macd_waits_for_histogram_reset_after_signal() {
+    let mut strategy = MACDStrategy::new(12, 26, 9);
+    let mut price = 100.0;
+    for i in 0..43 {
+        price = if i < 40 { 100.0 } else { 100.0 + 0.05 * ((i - 40) as f64).powi(2) };
+        strategy.add_price(price);
+    }
+
+    let decision = strategy.evaluate(&StrategyContext::new("BTC-USDT", price));
+    assert_eq!(decision.action, Action::EnterLong);
+    strategy.on_signal(&decision, price, 1_000);
+
+    let history = strategy.signal_history();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].timestamp, 1_000);
+    assert_eq!(history[0].action, Action::EnterLong);
+    assert!(strategy.awaiting_reset());
+
+    // 继续上涨时同向信号被抑制
+    price += 1.0;
+    strategy.add_price(price);
+    let decision = strategy.evaluate(&StrategyContext::new("BTC-USDT", price));
+    assert_eq!(decision.action, Action::Hold);
+    assert!(decision.reasons.iter().any(|r| r.contains("柱状图翻转")));
+
+    // 价格回落使柱状图翻转后解除抑制
+    for _ in 0..10 {
+        price -= 2.0;
+        strategy.add_price(price);
+    }
+    assert!(!strategy.awaiting_reset());
+}
+
+#[test]
+fn macd_rearms_only_after_histogram_flips_against_signal() {
+    let mut strategy = MACDStrategy::new(12, 26, 9);
+    let mut price = 100.0;
+    for _ in 0..40 {
+        strategy.add_price(price);
+    }
+    // 下跌使柱状图为负时发出开多信号 (例如接近金叉时)
+    for _ in 0..5 {
+        price -= 1.0;
+        strategy.add_price(price);
+    }
+    strategy.on_signal(&Decision::enter_long(0.8), price, 1_000);
+    assert!(strategy.awaiting_reset());
+
+    // 柱状图仍为负或转正 (信号方向) 都不能复位
+    price -= 0.5;
+    strategy.add_price(price);
+    assert!(strategy.awaiting_reset());
+    for _ in 0..10 {
+        price += 2.0;
+        strategy.add_price(price);
+    }
+    assert!(strategy.awaiting_reset());
+
+    // 柱状图由正翻转为负后才复位
+    for _ in 0..10 {
+        price -= 3.0;
+        strategy.add_price(price);
+    }
+    assert!(!strategy.awaiting_reset());
+}
+
+#[test]
+fn signal_log_keeps_latest_records() {
+    let mut log = SignalLog::new(3);
+    log.record(&Decision::hold(), 100.0, 0);
+    assert!(log.is_empty());
+
+    for i in 1..=5 {
+        log.record(&Decision::enter_long(0.5), 100.0 + i as f64, i);
+    }
+    assert_eq!(log.len(), 3);
+    assert_eq!(log.iter().map(|r| r.timestamp).collect::<Vec<_>>(), vec![3, 4, 5]);
+    assert_eq!(log[0].price, 103.0);
+    assert_eq!(log.last().unwrap().timestamp, 5);
+}
+
+#[tokio::test]
+async fn manager_skips_entries_during_cooldown() {
+    let server = bingx_server().await;
+    let now = Utc::now().timestamp_millis();
+    let bar = |open_time: i64| Kline {
+        open_time,
+        open: 100.0,
+        high: 101.0,
+        low: 99.0,
+        close: 100.0,
+        volume: 1.0,
+        close_time: open_time + 299_999,
+    };
+    server.set_klines("BTC-USDT", vec![bar(now - 600_000)]);
+
+    let mut registry = StrategyRegistry::with_builtin();
+    registry.register("always_buy", |_| Ok(Box::new(AlwaysBuy)));
+    let manager = manager_for(&server).with_strategy_registry(registry);
+    manager.add_currency(CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20)
+        .with_strategy(StrategyConfig::new("always_buy"))
+        .with_cooldown(600)).await.unwrap();
+
+    assert!(manager.cooldown_remaining("BTC-USDT").await.is_none());
+    manager.monitor_once().await;
+    assert_eq!(server.orders().len(), 1);
+    assert!(manager.get_currency_status("BTC-USDT").await.unwrap().last_trade_at.is_some());
+
+    let remaining = manager.cooldown_remaining("BTC-USDT").await.unwrap();
+    assert!(remaining > 0 && remaining <= 600_000);
+
+    // 平掉持仓后再次出现开仓信号, 冷却期内不下单
+    manager.execute_decision("BTC-USDT", 100.0, &Decision::exit(1.0)).await.unwrap();
+    assert_eq!(server.orders().len(), 2);
+    server.set_klines("BTC-USDT", vec![bar(now - 600_000), bar(now - 300_000)]);
+    manager.monitor_once().await;
+    assert_eq!(server.orders().len(), 2);
+}
+
+#[tokio::test]
+async fn rejected_orders_are_not_recorded_as_signals() {
+    let server = bingx_server().await;
+    let now = Utc::now().timestamp_millis();
+    server.set_klines("BTC-USDT", vec![Kline {
+        open_time: now - 600_000,
+        open: 100.0,
+        high: 101.0,
+        low: 99.0,
+        close: 100.0,
+        volume: 1.0,
+        close_time: now - 300_001,
+    }]);
+
+    let signals = Arc::new(Mutex::new(Vec::new()));
+    let mut registry = StrategyRegistry::with_builtin();
+    let recorded = signals.clone();
+    registry.register("recording_buy", move |_| Ok(Box::new(RecordingBuy { signals: recorded.clone() })));
+    let manager = manager_for(&server).with_strategy_registry(registry);
+    manager.add_currency(CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20)
+        .with_strategy(StrategyConfig::new("recording_buy"))).await.unwrap();
+
+    // 交易所拒绝开仓单: 没有持仓, 策略也不记录信号
+    let order_path = "/openApi/swap/v2/trade/order";
+    server.inject_fault(order_path, MockFault::ApiError { code: 101204, msg: "Insufficient margin".to_string() });
+    manager.monitor_once().await;
+    assert!(server.orders().is_empty());
+    assert!(signals.lock().unwrap().is_empty());
+    assert!(manager.get_currency_status("BTC-USDT").await.unwrap().current_position.is_none());
+
+    // 反手时平仓被拒绝, 不开反向仓位
+    manager.place_order("BTC-USDT", OrderSide::Sell, 100.0).await.unwrap();
+    server.inject_fault(order_path, MockFault::ApiError { code: 101204, msg: "Insufficient margin".to_string() });
+    let err = manager.execute_decision("BTC-USDT", 100.0, &Decision::enter_long(1.0)).await.unwrap_err();
+    assert!(err.to_string().contains("Insufficient margin"));
+    assert_eq!(server.orders().len(), 1);
+    let position = manager.get_currency_status("BTC-USDT").await.unwrap().current_position.unwrap();
+    assert_eq!(position.side, OrderSide::Sell);
+}
@@ -0,0 +1,109 @@
+use crypto_trading_bot::strategy::{
+    Action, DepthFilter, FilterSet, FilterVerdict, MACDStrategy, MarketDepth, MarketTicker,
+    SignalFilter, StrategyContext, StrategyRegistry, TickerFilter, TradingStrategy,
+};
+use crypto_trading_bot::types::StrategyConfig;
+
+// 横盘后加速上涨, 最后一个价格触发 MACD 买入
+fn rising_strategy() -> (MACDStrategy, f64) {
+    let mut strategy = MACDStrategy::new(12, 26, 9);
+    let mut price = 100.0;
+    for i in 0..43 {
+        price = if i < 40 { 100.0 } else { 100.0 + 0.05 * ((i - 40) as f64).powi(2) };
+        strategy.add_price(price);
+    }
+    (strategy, price)
+}
+
+fn ticker(last_price: f64, change: f64, bid: f64, ask: f64) -> MarketTicker {
+    MarketTicker {
+        price_change_percent: change,
+        high_price: 110.0,
+        low_price: 100.0,
+        last_price,
+        volume: 1000.0,
+        bid_price: bid,
+        ask_price: ask,
+    }
+}
+
+// 否决所有开仓的过滤器
+struct Blackout;
+
+impl SignalFilter for Blackout {
+    fn name(&self) -> &str {
+        "停牌"
+    }
+
+    fn check(&self, _ctx: &StrategyContext) -> Option<FilterVerdict> {
+        Some(FilterVerdict {
+            filter: self.name().to_string(),
+            long: false,
+            short: false,
+            reason: "维护窗口".to_string(),
+        })
+    }
+}
+
+#[test]
+fn depth_filter_thresholds_are_tunable() {
+    let depth = MarketDepth {
+        asks: vec![(100.5, 4.0), (105.0, 50.0)],
+        bids: vec![(99.5, 6.0)],
+    };
+
+    let verdict = DepthFilter::new().analyze(&depth, 100.0);
+    assert!(verdict.long && !verdict.short);
+
+    assert!(!DepthFilter::new().with_pressure_ratio(2.0).analyze(&depth, 100.0).long);
+    // 放宽价格范围后远处的卖单也计入
+    let verdict = DepthFilter::new().with_band_pct(10.0).analyze(&depth, 100.0);
+    assert!(!verdict.long && verdict.short);
+}
+
+#[test]
+fn ticker_filter_checks_position_and_spread() {
+    let near_low = ticker(101.0, 0.5, 100.99, 101.0);
+    let verdict = TickerFilter::new().analyze(&near_low);
+    assert!(verdict.long && !verdict.short);
+
+    let wide_spread = ticker(101.0, 0.5, 100.0, 101.0);
+    let verdict = TickerFilter::new().analyze(&wide_spread);
+    assert!(!verdict.long && !verdict.short);
+    assert!(TickerFilter::new().with_max_spread_pct(2.0).analyze(&wide_spread).long);
+}
+
+#[test]
+fn filter_params_come_from_strategy_config() {
+    let config: StrategyConfig = "macd:depth_filter=0;max_spread_pct=0.5".parse().unwrap();
+    assert_eq!(FilterSet::from_config(&config).unwrap().names(), vec!["行情"]);
+    assert_eq!(FilterSet::from_config(&StrategyConfig::default()).unwrap().names(), vec!["深度", "行情"]);
+
+    let registry = StrategyRegistry::with_builtin();
+    assert!(registry.build(&config).is_ok());
+    assert!(registry.build(&"macd:depth_ratio=0".parse().unwrap()).is_err());
+    assert!(registry.build(&"macd:low_zone_pct=70".parse().unwrap()).is_err());
+}
+
+#[test]
+fn strategies_compose_filters_and_report_verdicts() {
+    let (strategy, price) = rising_strategy();
+    let ctx = StrategyContext::new("BTC-USDT", price);
+
+    // 默认过滤器缺少数据时只计一半分数
+    let decision = strategy.evaluate(&ctx);
+    assert_eq!(decision.action, Action::EnterLong);
+    assert_eq!(decision.verdicts.len(), 2);
+
+    let strategy = strategy.with_filters(FilterSet::new());
+    let unfiltered = strategy.evaluate(&ctx);
+    assert_eq!(unfiltered.action, Action::EnterLong);
+    assert!(unfiltered.confidence > decision.confidence);
+    assert!(unfiltered.verdicts.is_empty());
+
+    let strategy = strategy.with_filters(FilterSet::standard().with_filter(Blackout));
+    let blocked = strategy.evaluate(&ctx);
+    assert_eq!(blocked.action, Action::Hold);
+    assert!(blocked.reasons.iter().any(|r| r.contains("停牌未确认")));
+    assert!(blocked.verdicts.iter().any(|v| v.filter == "停牌" && v.to_string().contains("维护窗口")));
+}
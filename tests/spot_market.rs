@@ -0,0 +1,125 @@
+mod common;
+
+use common::{bingx_client, bingx_server, manager_for};
+use chrono::Utc;
+use crypto_trading_bot::exchange::mock::MockBingXServer;
+use crypto_trading_bot::strategy::{MarketDepth, MarketTicker};
+use crypto_trading_bot::types::{CurrencyConfig, Interval, Kline, MarketType, OrderSide};
+
+async fn start_server() -> MockBingXServer {
+    let server = bingx_server().await;
+    let start = Utc::now().timestamp_millis() - 5 * 300_000;
+    server.set_klines("ETH-USDT", (0..5).map(|i| Kline {
+        open_time: start + i * 300_000,
+        open: 2000.0,
+        high: 2010.0,
+        low: 1990.0,
+        close: 2000.0 + i as f64,
+        volume: 3.0,
+        close_time: start + (i + 1) * 300_000 - 1,
+    }).collect());
+    server.set_depth("ETH-USDT", MarketDepth {
+        asks: vec![(2004.5, 1.0)],
+        bids: vec![(2003.5, 2.0)],
+    });
+    server.set_ticker("ETH-USDT", MarketTicker {
+        price_change_percent: -1.25,
+        high_price: 2050.0,
+        low_price: 1950.0,
+        last_price: 2000.0,
+        volume: 500.0,
+        bid_price: 1999.5,
+        ask_price: 2000.5,
+    });
+    server
+}
+
+fn spot_config() -> CurrencyConfig {
+    CurrencyConfig::new("ETH-USDT", "ETH", "USDT", 0.5, 2, 4, 5.0, 20).with_market(MarketType::Spot)
+}
+
+#[tokio::test]
+async fn parses_spot_market_data() {
+    let server = start_server().await;
+    let client = bingx_client(&server);
+
+    let klines = client.get_spot_klines("ETH-USDT", Interval::FiveMinutes, None, None, Some(2)).await.unwrap();
+    assert_eq!(klines.iter().map(|k| k.close).collect::<Vec<_>>(), vec![2003.0, 2004.0]);
+    assert!(klines[0].close_time > klines[0].open_time);
+
+    let depth = client.get_spot_depth("ETH-USDT", None).await.unwrap();
+    assert_eq!(depth.bids, vec![(2003.5, 2.0)]);
+
+    let ticker = &client.get_spot_ticker(Some("ETH-USDT")).await.unwrap()[0];
+    assert_eq!(ticker.price_change_percent, -1.25);
+    assert_eq!(ticker.last_price, 2000.0);
+
+    server.set_balance("USDT", 1234.5);
+    assert_eq!(client.get_spot_free_balance("USDT").await.unwrap(), 1234.5);
+    assert_eq!(client.get_spot_free_balance("ETH").await.unwrap(), 0.0);
+}
+
+#[tokio::test]
+async fn spot_buys_accumulate_and_sells_reduce_holdings() {
+    let server = start_server().await;
+    server.set_balance("USDT", 10_000.0);
+    let manager = manager_for(&server);
+    let config = spot_config();
+    assert_eq!(config.leverage, 1);
+    manager.add_currency(config).await.unwrap();
+
+    manager.place_order("ETH-USDT", OrderSide::Buy, 2000.0).await.unwrap();
+    server.set_price("ETH-USDT", 2100.0);
+    manager.place_order("ETH-USDT", OrderSide::Buy, 2100.0).await.unwrap();
+
+    let position = manager.get_currency_status("ETH-USDT").await.unwrap().current_position.unwrap();
+    assert_eq!(position.quantity, 1.0);
+    assert!((position.entry_price - 2050.0).abs() < 1e-9);
+    assert_eq!(server.balance("ETH"), 1.0);
+
+    manager.place_order("ETH-USDT", OrderSide::Sell, 2100.0).await.unwrap();
+    let position = manager.get_currency_status("ETH-USDT").await.unwrap().current_position.unwrap();
+    assert_eq!(position.quantity, 0.5);
+
+    let orders = server.orders();
+    assert_eq!(orders.len(), 3);
+    assert!(orders.iter().all(|o| o.market == MarketType::Spot));
+    assert!(orders.iter().all(|o| o.take_profit.is_none()));
+}
+
+#[tokio::test]
+async fn spot_sell_without_holdings_is_rejected_locally() {
+    let server = start_server().await;
+    let manager = manager_for(&server);
+    manager.add_currency(spot_config()).await.unwrap();
+
+    assert!(manager.place_order("ETH-USDT", OrderSide::Sell, 2000.0).await.is_err());
+    assert!(server.orders().is_empty());
+}
+
+#[tokio::test]
+async fn monitor_routes_spot_symbols_to_spot_endpoints() {
+    let server = start_server().await;
+    let manager = manager_for(&server);
+    manager.add_currency(spot_config()).await.unwrap();
+
+    manager.monitor_once().await;
+
+    // 回填一次, 监控一次
+    assert_eq!(server.request_count("/openApi/spot/v2/market/kline"), 2);
+    assert_eq!(server.request_count("/openApi/spot/v1/market/depth"), 1);
+    assert_eq!(server.request_count("/openApi/spot/v1/ticker/24hr"), 1);
+    assert_eq!(server.request_count("/openApi/swap/v3/quote/klines"), 0);
+}
+
+#[tokio::test]
+async fn rejects_resting_order_strategies_on_spot() {
+    let server = start_server().await;
+    let manager = manager_for(&server);
+    for strategy in ["dca", "grid:lower=1900;upper=2100;levels=4"] {
+        let err = manager.add_currency(spot_config().with_strategy(strategy.parse().unwrap())).await.unwrap_err();
+        assert!(err.to_string().contains("现货暂不支持限价挂单"), "{}", err);
+    }
+    assert!(manager.get_currency_status("ETH-USDT").await.is_none());
+    assert_eq!(server.request_count("/openApi/spot/v2/market/kline"), 0);
+}
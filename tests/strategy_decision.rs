@@ -0,0 +1,133 @@
+mod common;
+
+use common::{bingx_server, manager_for, position};
+use crypto_trading_bot::strategy::{Action, Decision, MACDStrategy, MarketDepth, StrategyContext, TradingStrategy};
+use crypto_trading_bot::types::{CurrencyConfig, OrderSide};
+
+// 横盘后加速上涨, 最后一个价格触发 MACD 买入
+fn rising_strategy() -> (MACDStrategy, f64) {
+    let mut strategy = MACDStrategy::new(12, 26, 9);
+    let mut price = 100.0;
+    for i in 0..43 {
+        price = if i < 40 { 100.0 } else { 100.0 + 0.05 * ((i - 40) as f64).powi(2) };
+        strategy.add_price(price);
+    }
+    (strategy, price)
+}
+
+#[test]
+fn macd_decision_carries_confidence_levels_and_reasons() {
+    let (strategy, price) = rising_strategy();
+
+    let ctx = StrategyContext::new("BTC-USDT", price);
+    let decision = strategy.evaluate(&ctx);
+    assert_eq!(decision.action, Action::EnterLong);
+    assert!(decision.confidence > 0.0 && decision.confidence <= 1.0);
+    assert!(decision.stop_loss.unwrap() < price && decision.take_profit.unwrap() > price);
+    assert!(decision.reasons.iter().any(|r| r.contains("MACD")));
+
+    // 卖盘明显更厚时深度不确认, 决策为观望并说明原因
+    let heavy_asks = MarketDepth {
+        asks: vec![(price + 0.1, 10.0)],
+        bids: vec![(price - 0.1, 1.0)],
+    };
+    let decision = strategy.evaluate(&ctx.with_depth(Some(&heavy_asks)));
+    assert_eq!(decision.action, Action::Hold);
+    assert!(decision.reasons.iter().any(|r| r.contains("深度")));
+
+    let decision = MACDStrategy::new(12, 26, 9).evaluate(&ctx);
+    assert_eq!(decision.action, Action::Hold);
+    assert!(decision.reasons[0].contains("数据不足"));
+}
+
+#[test]
+fn order_quantity_scales_with_confidence() {
+    let config = CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20);
+    assert_eq!(config.order_quantity(1.0), 0.01);
+
+    let config = config.with_max_size_multiplier(3.0);
+    assert_eq!(config.order_quantity(0.0), 0.01);
+    assert_eq!(config.order_quantity(0.5), 0.02);
+    assert_eq!(config.order_quantity(1.0), 0.03);
+}
+
+#[tokio::test]
+async fn manager_sizes_entries_and_executes_exits() {
+    let server = bingx_server().await;
+    server.set_price("BTC-USDT", 100.0);
+    let manager = manager_for(&server);
+    manager.add_currency(CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20)
+        .with_max_size_multiplier(2.0)).await.unwrap();
+
+    let entry = Decision::enter_long(1.0)
+        .with_stop_loss(97.0)
+        .with_take_profit(108.0)
+        .with_reason("测试");
+    manager.execute_decision("BTC-USDT", 100.0, &entry).await.unwrap();
+
+    let orders = server.orders();
+    assert_eq!(orders[0].quantity, 0.02);
+    assert!(orders[0].stop_loss.as_ref().unwrap().contains("97"));
+    assert!(orders[0].take_profit.as_ref().unwrap().contains("108"));
+
+    manager.execute_decision("BTC-USDT", 101.0, &Decision::exit(1.0)).await.unwrap();
+    let orders = server.orders();
+    assert_eq!(orders.len(), 2);
+    assert_eq!(orders[1].side, "SELL");
+    assert_eq!(orders[1].quantity, 0.02);
+    assert!(orders[1].take_profit.is_none());
+    assert!(manager.get_currency_status("BTC-USDT").await.unwrap().current_position.is_none());
+
+    // 无持仓时平仓决策不下单
+    manager.execute_decision("BTC-USDT", 101.0, &Decision::exit(1.0)).await.unwrap();
+    assert_eq!(server.orders().len(), 2);
+}
+
+#[test]
+fn macd_exits_positions_against_the_signal() {
+    let (strategy, price) = rising_strategy();
+    let heavy_asks = MarketDepth {
+        asks: vec![(price + 0.1, 10.0)],
+        bids: vec![(price - 0.1, 1.0)],
+    };
+
+    let long = position("BTC-USDT", OrderSide::Buy);
+    let decision = strategy.evaluate(&StrategyContext::new("BTC-USDT", price).with_position(Some(&long)));
+    assert_eq!(decision.action, Action::Hold);
+
+    // 持空仓遇到向上动量: 未确认时平仓, 确认时反手
+    let short = position("BTC-USDT", OrderSide::Sell);
+    let ctx = StrategyContext::new("BTC-USDT", price).with_position(Some(&short));
+    assert_eq!(strategy.evaluate(&ctx.with_depth(Some(&heavy_asks))).action, Action::Exit);
+    assert_eq!(strategy.evaluate(&ctx).action, Action::EnterLong);
+}
+
+#[tokio::test]
+async fn manager_sends_reduce_only_partial_closes_and_reverses() {
+    let server = bingx_server().await;
+    server.set_price("BTC-USDT", 100.0);
+    let manager = manager_for(&server);
+    manager.add_currency(CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.04, 1, 3, 5.0, 20)).await.unwrap();
+
+    manager.place_order("BTC-USDT", OrderSide::Buy, 100.0).await.unwrap();
+    // 同向信号不加仓
+    manager.place_order("BTC-USDT", OrderSide::Buy, 100.0).await.unwrap();
+    assert_eq!(server.orders().len(), 1);
+
+    manager.execute_decision("BTC-USDT", 100.0, &Decision::reduce(0.5, 1.0)).await.unwrap();
+    let close = server.orders()[1].clone();
+    assert!(close.reduce_only);
+    assert_eq!((close.side.as_str(), close.position_side.as_str(), close.quantity), ("SELL", "LONG", 0.02));
+    let held = manager.get_currency_status("BTC-USDT").await.unwrap().current_position.unwrap();
+    assert_eq!(held.quantity, 0.02);
+
+    // 反向信号: 先只减仓平掉剩余多仓, 再开空
+    manager.place_order("BTC-USDT", OrderSide::Sell, 100.0).await.unwrap();
+    let orders = server.orders();
+    assert_eq!(orders.len(), 4);
+    assert!(orders[2].reduce_only && orders[2].quantity == 0.02);
+    assert!(!orders[3].reduce_only);
+    assert_eq!((orders[3].side.as_str(), orders[3].position_side.as_str()), ("SELL", "SHORT"));
+    let held = manager.get_currency_status("BTC-USDT").await.unwrap().current_position.unwrap();
+    assert_eq!((held.side, held.quantity), (OrderSide::Sell, 0.04));
+}
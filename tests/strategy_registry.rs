@@ -0,0 +1,98 @@
+mod common;
+
+use common::{bingx_server, manager_for};
+use crypto_trading_bot::strategy::{Decision, StrategyContext, StrategyRegistry, TradingStrategy};
+use crypto_trading_bot::types::{CurrencyConfig, Kline, StrategyConfig};
+use chrono::Utc;
+
+// 收到任意价格后立即发出买入信号
+struct AlwaysBuy {
+    prices: usize,
+}
+
+impl TradingStrategy for AlwaysBuy {
+    fn name(&self) -> &str {
+        "always_buy"
+    }
+
+    fn add_price(&mut self, _price: f64) {
+        self.prices += 1;
+    }
+
+    fn evaluate(&self, _ctx: &StrategyContext) -> Decision {
+        if self.prices > 0 {
+            Decision::enter_long(1.0)
+        } else {
+            Decision::hold()
+        }
+    }
+}
+
+#[test]
+fn parses_strategy_config_text() {
+    let config: StrategyConfig = "MACD:fast=8; slow=21;signal=5".parse().unwrap();
+    assert_eq!(config.name, "macd");
+    assert_eq!(config.param("slow", 0.0), 21.0);
+    assert_eq!(config.to_string(), "macd:fast=8;signal=5;slow=21");
+    assert_eq!("macd".parse::<StrategyConfig>().unwrap(), StrategyConfig::default());
+
+    assert!("macd:fast".parse::<StrategyConfig>().is_err());
+    assert!("macd:fast=abc".parse::<StrategyConfig>().is_err());
+    assert!(":fast=1".parse::<StrategyConfig>().is_err());
+}
+
+#[test]
+fn builds_builtin_strategies_and_validates_params() {
+    let registry = StrategyRegistry::with_builtin();
+    assert_eq!(registry.names(), vec!["dca", "donchian", "filtered", "grid", "macd", "majority", "mean_reversion", "pairs", "unanimous", "weighted"]);
+
+    let strategy = registry.build(&"macd:fast=5;slow=10;signal=3".parse().unwrap()).unwrap();
+    assert_eq!(strategy.name(), "macd");
+
+    let err = registry.build(&StrategyConfig::new("turtle")).err().unwrap();
+    assert!(err.to_string().contains("turtle"));
+    assert!(registry.build(&"macd:fast=26;slow=12".parse().unwrap()).is_err());
+    assert!(registry.build(&"macd:fast=2.5".parse().unwrap()).is_err());
+    assert!(registry.build(&"macd:fsat=8".parse().unwrap()).is_err());
+
+    // 过大的周期返回错误, 而不是在分配缓冲区时溢出
+    let err = registry.build(&"mean_reversion:period=1e20".parse().unwrap()).err().unwrap();
+    assert!(err.to_string().contains("不能超过 1000"), "{}", err);
+    assert!(registry.build(&"donchian:entry=1e20".parse().unwrap()).is_err());
+    assert!(registry.build(&"dca:safety_orders=1e20".parse().unwrap()).is_err());
+    assert!(registry.build(&"donchian:entry=1000".parse().unwrap()).is_ok());
+}
+
+#[tokio::test]
+async fn manager_uses_strategy_selected_per_symbol() {
+    let server = bingx_server().await;
+    let now = Utc::now().timestamp_millis();
+    for symbol in ["BTC-USDT", "ETH-USDT"] {
+        server.set_klines(symbol, vec![Kline {
+            open_time: now - 300_000,
+            open: 100.0,
+            high: 101.0,
+            low: 99.0,
+            close: 100.0,
+            volume: 1.0,
+            close_time: now - 1,
+        }]);
+    }
+
+    let mut registry = StrategyRegistry::with_builtin();
+    registry.register("always_buy", |_| Ok(Box::new(AlwaysBuy { prices: 0 })));
+    let manager = manager_for(&server).with_strategy_registry(registry);
+
+    manager.add_currency(CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20)
+        .with_strategy(StrategyConfig::new("always_buy"))).await.unwrap();
+    manager.add_currency(CurrencyConfig::new("ETH-USDT", "ETH", "USDT", 0.1, 2, 3, 5.0, 20)).await.unwrap();
+    assert!(manager.add_currency(CurrencyConfig::new("SOL-USDT", "SOL", "USDT", 1.0, 3, 1, 5.0, 20)
+        .with_strategy(StrategyConfig::new("unknown"))).await.is_err());
+    assert!(manager.get_currency_status("SOL-USDT").await.is_none());
+
+    manager.monitor_once().await;
+
+    let orders = server.orders();
+    assert_eq!(orders.len(), 1);
+    assert_eq!(orders[0].symbol, "BTC-USDT");
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use common::{bar, bingx_client, bingx_server};
+use chrono::Utc;
+use crypto_trading_bot::exchange::mock::MockBingXServer;
+use crypto_trading_bot::strategy::{Decision, StrategyContext, StrategyRegistry, TradingStrategy};
+use crypto_trading_bot::trading::TradingManager;
+use crypto_trading_bot::types::{CurrencyConfig, Interval, Kline, StrategyConfig};
+
+const KLINES_PATH: &str = "/openApi/swap/v3/quote/klines";
+
+// 最近 n 根已收盘的 5 分钟K线
+fn closed_bars(n: i64) -> Vec<Kline> {
+    let current = Interval::FiveMinutes.align(Utc::now().timestamp_millis());
+    (0..n).map(|i| bar(current - (n - i) * 300_000, 100.0 + i as f64)).collect()
+}
+
+// 需要 3 根K线预热, 预热后总是开多
+struct NeedsThree {
+    prices: usize,
+}
+
+impl TradingStrategy for NeedsThree {
+    fn name(&self) -> &str {
+        "needs_three"
+    }
+
+    fn add_price(&mut self, _price: f64) {
+        self.prices += 1;
+    }
+
+    fn lookback(&self, _interval: Interval) -> usize {
+        3
+    }
+
+    fn is_ready(&self) -> bool {
+        self.prices >= 3
+    }
+
+    fn evaluate(&self, _ctx: &StrategyContext) -> Decision {
+        Decision::enter_long(1.0)
+    }
+}
+
+async fn manager_for(server: &MockBingXServer) -> TradingManager {
+    let mut registry = StrategyRegistry::with_builtin();
+    registry.register("needs_three", |_| Ok(Box::new(NeedsThree { prices: 0 })));
+    TradingManager::new(bingx_client(server)).with_strategy_registry(registry)
+}
+
+fn config() -> CurrencyConfig {
+    CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20)
+        .with_strategy(StrategyConfig::new("needs_three"))
+}
+
+#[tokio::test]
+async fn macd_is_backfilled_to_its_lookback() {
+    let server = bingx_server().await;
+    server.set_klines("BTC-USDT", closed_bars(60));
+    let manager = manager_for(&server).await;
+
+    manager.add_currency(CurrencyConfig::new("BTC-USDT", "BTC", "USDT", 0.01, 1, 3, 5.0, 20)).await.unwrap();
+
+    let warm_up = manager.get_currency_status("BTC-USDT").await.unwrap().warm_up;
+    assert_eq!(warm_up.required, 36);
+    assert_eq!(warm_up.loaded, 36);
+    assert!(warm_up.ready);
+    assert_eq!(server.requests()[0].params.get("limit").map(String::as_str), Some("37"));
+}
+
+#[tokio::test]
+async fn live_signals_wait_until_warm_up_completes() {
+    let server = bingx_server().await;
+    let bars = closed_bars(3);
+    server.set_klines("BTC-USDT", bars[..2].to_vec());
+    let manager = manager_for(&server).await;
+
+    manager.add_currency(config()).await.unwrap();
+    let warm_up = manager.get_currency_status("BTC-USDT").await.unwrap().warm_up;
+    assert_eq!((warm_up.required, warm_up.loaded, warm_up.ready), (3, 2, false));
+
+    manager.monitor_once().await;
+    assert!(server.orders().is_empty());
+
+    // 第三根K线收盘后预热完成并开始评估
+    server.set_klines("BTC-USDT", bars);
+    manager.monitor_once().await;
+    assert!(manager.get_currency_status("BTC-USDT").await.unwrap().warm_up.ready);
+    assert_eq!(server.orders().len(), 1);
+}
+
+#[tokio::test]
+async fn re_adding_a_currency_reuses_cached_candles() {
+    let server = bingx_server().await;
+    server.set_klines("BTC-USDT", closed_bars(5));
+    let manager = manager_for(&server).await;
+
+    manager.add_currency(config()).await.unwrap();
+    assert_eq!(server.request_count(KLINES_PATH), 1);
+
+    // 更换策略参数时用缓存的K线预热, 不再请求交易所
+    manager.add_currency(config()).await.unwrap();
+    assert_eq!(server.request_count(KLINES_PATH), 1);
+    let warm_up = manager.get_currency_status("BTC-USDT").await.unwrap().warm_up;
+    assert!(warm_up.ready);
+    assert_eq!(warm_up.loaded, 3);
+}